@@ -14,7 +14,7 @@ pub(crate) fn derive_lens_impl(
         Data::Struct(_) => derive_struct(&input),
         Data::Enum(e) => Err(syn::Error::new(
             e.enum_token.span(),
-            "Lens implementations cannot be derived from enums",
+            "Lens implementations cannot be derived from enums, derive Prism instead",
         )),
         Data::Union(u) => Err(syn::Error::new(
             u.union_token.span(),
@@ -169,7 +169,7 @@ fn char_has_case(c: char) -> bool {
     c.is_lowercase() || c.is_uppercase()
 }
 
-fn is_camel_case(name: &str) -> bool {
+pub(crate) fn is_camel_case(name: &str) -> bool {
     let name = name.trim_matches('_');
     if name.is_empty() {
         return true;
@@ -185,7 +185,7 @@ fn is_camel_case(name: &str) -> bool {
         })
 }
 
-fn to_snake_case(mut str: &str) -> String {
+pub(crate) fn to_snake_case(mut str: &str) -> String {
     let mut words = vec![];
     // Preserve leading underscores
     str = str.trim_start_matches(|c: char| {
@@ -13,6 +13,7 @@ extern crate proc_macro;
 mod attr;
 mod data;
 mod lens;
+mod prism;
 
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
@@ -89,3 +90,45 @@ pub fn derive_lens(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Generates prisms to access the variants of an enum.
+///
+/// An associated constant is defined on the enum for each variant, having
+/// the name of the variant in snake_case (with a trailing underscore if that
+/// would be a keyword). A variant with a single field gives access to that
+/// field, and a variant without fields gives access to `()`.
+///
+/// This macro supports a `prism` variant attribute with the following arguments:
+///
+/// - `#[prism(ignore)]` skips creating a prism for one variant. Variants with
+///   more than one field must be ignored.
+/// - `#[prism(name="foo")]` gives the prism the specified name (instead of the default).
+///
+/// # Example
+///
+/// ```rust
+/// use druid_derive::Prism;
+///
+/// #[derive(Prism)]
+/// enum State {
+///     // The Prism derive will create a `State::loading` constant implementing
+///     // `druid::Prism<State, ()>`
+///     Loading,
+///     // The Prism derive will create a `State::ready` constant implementing
+///     // `druid::Prism<State, String>`
+///     Ready(String),
+///     // The Prism derive will create a `State::failed` constant implementing
+///     // `druid::Prism<State, u32>`
+///     Failed { code: u32 },
+///     // The Prism derive won't create anything for this variant.
+///     #[prism(ignore)]
+///     Retrying(u32, String),
+/// }
+/// ```
+#[proc_macro_derive(Prism, attributes(prism))]
+pub fn derive_prism(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    prism::derive_prism_impl(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -0,0 +1,217 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::attr::{FieldKind, Fields, LensAttrs, PrismAttrs};
+use super::lens::{is_camel_case, to_snake_case};
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{spanned::Spanned, Data, GenericParam, TypeParam};
+
+/// Words that cannot be used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+pub(crate) fn derive_prism_impl(
+    input: syn::DeriveInput,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    match &input.data {
+        Data::Enum(e) => derive_enum(&input, e),
+        Data::Struct(s) => Err(syn::Error::new(
+            s.struct_token.span(),
+            "Prism implementations cannot be derived from structs, derive Lens instead",
+        )),
+        Data::Union(u) => Err(syn::Error::new(
+            u.union_token.span(),
+            "Prism implementations cannot be derived from unions",
+        )),
+    }
+}
+
+fn derive_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let ty = &input.ident;
+
+    let twizzled_name = if is_camel_case(&ty.to_string()) {
+        let temp_name = format!("{}_derived_prisms", to_snake_case(&ty.to_string()));
+        proc_macro2::Ident::new(&temp_name, proc_macro2::Span::call_site())
+    } else {
+        return Err(syn::Error::new(
+            ty.span(),
+            "Prism implementations can only be derived from CamelCase types",
+        ));
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut variants = Vec::new();
+    for variant in data.variants.iter() {
+        let attrs = PrismAttrs::parse_ast(variant)?;
+        if attrs.ignore {
+            continue;
+        }
+        let fields = Fields::<LensAttrs>::parse_ast(&variant.fields)?;
+        if fields.len() > 1 {
+            return Err(syn::Error::new(
+                variant.span(),
+                "Prisms can only be derived for variants with at most one field; \
+                use #[prism(ignore)] to skip this variant",
+            ));
+        }
+        variants.push((variant, attrs, fields));
+    }
+
+    let mut prism_ty_idents = Vec::new();
+    let mut phantom_decls = Vec::new();
+    let mut phantom_inits = Vec::new();
+
+    for gp in input.generics.params.iter() {
+        if let GenericParam::Type(TypeParam { ident, .. }) = gp {
+            prism_ty_idents.push(quote! {#ident});
+            phantom_decls.push(quote! {std::marker::PhantomData<*const #ident>});
+            phantom_inits.push(quote! {std::marker::PhantomData});
+        }
+    }
+
+    let prism_ty_generics = quote! {
+        <#(#prism_ty_idents),*>
+    };
+
+    let prism_name = |variant: &syn::Variant| {
+        let mut name = to_snake_case(&variant.ident.to_string());
+        // `enum Token { Type, .. }` would otherwise produce `Token::type`.
+        if KEYWORDS.contains(&name.as_str()) {
+            name.push('_');
+        }
+        Ident::new(&name, variant.ident.span())
+    };
+
+    // Define prism types for each variant
+    let defs = variants.iter().map(|(variant, _, _)| {
+        let variant_name = &variant.ident;
+        let prism_name = prism_name(variant);
+        let struct_docs =
+            format!("Prism for the variant `{variant_name}` of [`{ty}`](super::{ty}).");
+
+        let fn_docs = format!(
+            "Creates a new prism for the variant `{variant_name}` of [`{ty}`](super::{ty}). \
+            Use [`{ty}::{prism_name}`](super::{ty}::{prism_name}) instead."
+        );
+
+        quote! {
+            #[doc = #struct_docs]
+            #[allow(non_camel_case_types)]
+            #[derive(Debug, Copy, Clone)]
+            pub struct #prism_name#prism_ty_generics(#(#phantom_decls),*);
+
+            impl #prism_ty_generics #prism_name#prism_ty_generics{
+                #[doc = #fn_docs]
+                pub const fn new()->Self{
+                    Self(#(#phantom_inits),*)
+                }
+            }
+        }
+    });
+
+    let used_params: HashSet<String> = input
+        .generics
+        .params
+        .iter()
+        .flat_map(|gp: &GenericParam| match gp {
+            GenericParam::Type(TypeParam { ident, .. }) => Some(ident.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let gen_new_param = |name: &str| {
+        let mut candidate: String = name.into();
+        let mut count = 1usize;
+        while used_params.contains(&candidate) {
+            candidate = format!("{name}_{count}");
+            count += 1;
+        }
+        Ident::new(&candidate, Span::call_site())
+    };
+
+    let func_ty_par = gen_new_param("F");
+    let val_ty_par = gen_new_param("V");
+
+    let impls = variants.iter().map(|(variant, _, fields)| {
+        let variant_name = &variant.ident;
+        let prism_name = prism_name(variant);
+
+        // Unit variants are treated as containing `()`.
+        let (variant_ty, pattern, value, value_mut) = match fields.iter().next() {
+            None => (
+                quote!(()),
+                quote!(#ty::#variant_name),
+                quote!(&()),
+                quote!(&mut ()),
+            ),
+            Some(field) => {
+                let field_ty = &field.ty;
+                let pattern = if fields.kind == FieldKind::Named {
+                    let field_name = field.ident_tokens();
+                    quote!(#ty::#variant_name { #field_name: inner })
+                } else {
+                    quote!(#ty::#variant_name(inner))
+                };
+                (quote!(#field_ty), pattern, quote!(inner), quote!(inner))
+            }
+        };
+
+        quote! {
+            impl #impl_generics druid::Prism<#ty#ty_generics, #variant_ty> for #twizzled_name::#prism_name#prism_ty_generics #where_clause {
+                #[allow(unreachable_patterns)]
+                fn with<#val_ty_par, #func_ty_par: FnOnce(&#variant_ty) -> #val_ty_par>(&self, data: &#ty#ty_generics, f: #func_ty_par) -> ::std::option::Option<#val_ty_par> {
+                    match data {
+                        #pattern => ::std::option::Option::Some(f(#value)),
+                        _ => ::std::option::Option::None,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn with_mut<#val_ty_par, #func_ty_par: FnOnce(&mut #variant_ty) -> #val_ty_par>(&self, data: &mut #ty#ty_generics, f: #func_ty_par) -> ::std::option::Option<#val_ty_par> {
+                    match data {
+                        #pattern => ::std::option::Option::Some(f(#value_mut)),
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+
+    let associated_items = variants.iter().map(|(variant, attrs, _)| {
+        let prism_name = prism_name(variant);
+        let prism_const_name = attrs.prism_name_override.as_ref().unwrap_or(&prism_name);
+
+        quote! {
+            /// Prism for the corresponding variant.
+            pub const #prism_const_name: #twizzled_name::#prism_name#prism_ty_generics = #twizzled_name::#prism_name::new();
+        }
+    });
+
+    let mod_docs = format!("Derived prisms for [`{ty}`].");
+
+    let expanded = quote! {
+        #[doc = #mod_docs]
+        pub mod #twizzled_name {
+            #(#defs)*
+        }
+
+        #(#impls)*
+
+        #[allow(non_upper_case_globals)]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #(#associated_items)*
+        }
+    };
+
+    Ok(expanded)
+}
@@ -0,0 +1,69 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use druid::Prism;
+
+#[derive(Debug, PartialEq, Prism)]
+enum State {
+    Loading,
+    Ready(String),
+    Failed {
+        code: u32,
+    },
+    #[prism(name = "retry")]
+    Retrying(u32),
+    #[prism(ignore)]
+    #[allow(dead_code)]
+    Other(u32, u32),
+}
+
+#[test]
+fn variant_prisms() {
+    let ready = State::Ready("hello".into());
+    assert_eq!(
+        State::ready.with(&ready, |text| text.clone()),
+        Some("hello".into())
+    );
+    assert_eq!(State::loading.with(&ready, |_| ()), None);
+    assert!(State::loading.matches(&State::Loading));
+    assert_eq!(
+        State::failed.with(&State::Failed { code: 3 }, |code| *code),
+        Some(3)
+    );
+    assert!(State::retry.matches(&State::Retrying(1)));
+}
+
+#[test]
+fn with_mut() {
+    let mut state = State::Failed { code: 1 };
+    assert_eq!(
+        State::failed.with_mut(&mut state, |code| *code += 1),
+        Some(())
+    );
+    assert_eq!(state, State::Failed { code: 2 });
+    assert_eq!(State::ready.with_mut(&mut state, |text| text.clear()), None);
+}
+
+#[derive(Prism)]
+enum Generic<T> {
+    Value(T),
+    Empty,
+}
+
+#[test]
+fn generic_enum() {
+    let value = Generic::Value(7u64);
+    assert_eq!(Generic::<u64>::value.with(&value, |v| *v), Some(7));
+    assert!(!Generic::<u64>::empty.matches(&value));
+    assert!(Generic::<u64>::empty.matches(&Generic::Empty));
+}
+
+#[derive(Prism)]
+enum Token {
+    Type,
+}
+
+#[test]
+fn keyword_variant() {
+    assert!(Token::type_.matches(&Token::Type));
+}
@@ -19,14 +19,15 @@ use gtk::glib::source::Continue;
 use gtk::glib::translate::FromGlib;
 use gtk::prelude::*;
 use gtk::traits::SettingsExt;
-use gtk::{AccelGroup, ApplicationWindow, DrawingArea};
+use gtk::{AccelGroup, ApplicationWindow, DestDefaults, DrawingArea, TargetEntry, TargetFlags};
 
 use gdk_sys::GdkKeymapKey;
 
 use anyhow::anyhow;
 use cairo::Surface;
 use gtk::gdk::{
-    EventKey, EventMask, EventType, ModifierType, ScrollDirection, Window, WindowTypeHint,
+    Atom, DragAction, DragContext, EventKey, EventMask, EventType, ModifierType, ScrollDirection,
+    Window, WindowTypeHint,
 };
 
 use instant::Duration;
@@ -40,6 +41,7 @@ use crate::piet::{Piet, PietText, RenderContext};
 
 use crate::common_util::{ClickCounter, IdleCallback};
 use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
+use crate::drop::{DropEvent, TEXT_FORMATS, URI_LIST_FORMAT};
 use crate::error::Error as ShellError;
 use crate::keyboard::{KbKey, KeyEvent, KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
@@ -193,6 +195,8 @@ pub(crate) struct WindowState {
 
     request_animation: Cell<bool>,
     in_draw: Cell<bool>,
+    /// Whether data dragged from another application is over the window.
+    drag_entered: Cell<bool>,
 
     parent: Option<crate::WindowHandle>,
 }
@@ -361,6 +365,7 @@ impl WindowBuilder {
             click_counter: ClickCounter::default(),
             active_text_input: Cell::new(None),
             deferred_queue: RefCell::new(Vec::new()),
+            drag_entered: Cell::new(false),
             request_animation: Cell::new(false),
             in_draw: Cell::new(false),
             parent,
@@ -774,6 +779,91 @@ impl WindowBuilder {
                 }
             }));
 
+        // We handle drag and drop ourselves instead of using the defaults, so that the
+        // handler can decide whether to accept the data.
+        let drop_targets: Vec<TargetEntry> = std::iter::once(URI_LIST_FORMAT)
+            .chain(TEXT_FORMATS.iter().copied())
+            .map(|format| TargetEntry::new(format, TargetFlags::OTHER_APP, 0))
+            .collect();
+        win_state.drawing_area.drag_dest_set(
+            DestDefaults::empty(),
+            &drop_targets,
+            DragAction::COPY,
+        );
+
+        win_state.drawing_area.connect_drag_motion(
+            clone!(handle => move |_widget, context, x, y, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    let event = drop_event(&state, context, x, y);
+                    let entered = state.drag_entered.replace(true);
+                    let accepted = state
+                        .with_handler(|h| {
+                            if entered {
+                                h.drag_over(&event)
+                            } else {
+                                h.drag_enter(&event)
+                            }
+                        })
+                        .unwrap_or(false);
+                    let action = if accepted {
+                        DragAction::COPY
+                    } else {
+                        DragAction::empty()
+                    };
+                    context.drag_status(action, time);
+                }
+                true
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_leave(
+            clone!(handle => move |_widget, _context, _time| {
+                if let Some(state) = handle.state.upgrade() {
+                    if state.drag_entered.replace(false) {
+                        state.with_handler(|h| h.drag_leave());
+                    }
+                }
+            }),
+        );
+
+        win_state
+            .drawing_area
+            .connect_drag_drop(|widget, context, _x, _y, time| {
+                // The data arrives in `drag-data-received`, where we let the handler know.
+                let formats = drop_formats(context);
+                match DropEvent::preferred_format(&formats) {
+                    Some(format) => {
+                        widget.drag_get_data(context, &Atom::intern(format), time);
+                        true
+                    }
+                    None => {
+                        context.drag_finish(false, false, time);
+                        false
+                    }
+                }
+            });
+
+        win_state.drawing_area.connect_drag_data_received(
+            clone!(handle => move |_widget, context, x, y, selection, _info, time| {
+                let mut accepted = false;
+                if let Some(state) = handle.state.upgrade() {
+                    let mut event = drop_event(&state, context, x, y);
+                    if selection.target().name().as_str() == URI_LIST_FORMAT {
+                        event.files = selection
+                            .uris()
+                            .iter()
+                            .filter_map(|uri| gtk::glib::filename_from_uri(uri).ok())
+                            .map(|(path, _host)| FileInfo { path, format: None })
+                            .collect();
+                    } else {
+                        event.text = selection.text().map(String::from);
+                    }
+                    accepted = state.with_handler(|h| h.drag_drop(&event)).unwrap_or(false);
+                }
+                context.drag_finish(accepted, false, time);
+            }),
+        );
+
         vbox.pack_end(&win_state.drawing_area, true, true, 0);
         win_state.drawing_area.realize();
         win_state
@@ -1427,6 +1517,21 @@ fn make_gdk_cursor(cursor: &Cursor, gdk_window: &Window) -> Option<gtk::gdk::Cur
     }
 }
 
+/// The formats that data dragged from another application is offered in.
+fn drop_formats(context: &DragContext) -> Vec<String> {
+    context
+        .list_targets()
+        .into_iter()
+        .map(|atom| atom.name().to_string())
+        .collect()
+}
+
+/// Build a [`DropEvent`] without contents for a drag at (`x`, `y`) in pixels.
+fn drop_event(state: &WindowState, context: &DragContext, x: i32, y: i32) -> DropEvent {
+    let pos = Point::new(x as f64, y as f64).to_dp(state.scale.get());
+    DropEvent::new(pos, drop_formats(context))
+}
+
 fn get_mouse_button(button: u32) -> Option<MouseButton> {
     match button {
         1 => Some(MouseButton::Left),
@@ -0,0 +1,150 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Headless implementation of features at the application scope.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex};
+
+use instant::Instant;
+
+use crate::application::AppHandler;
+
+use super::clipboard::Clipboard;
+use super::error::Error;
+use super::window::WindowState;
+
+#[derive(Clone)]
+pub(crate) struct Application {
+    state: Rc<AppState>,
+}
+
+struct AppState {
+    windows: RefCell<Vec<Rc<WindowState>>>,
+    quitting: Cell<bool>,
+    clipboard: Clipboard,
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    primary: Clipboard,
+    waker: Arc<Waker>,
+}
+
+/// Wakes up the run loop when there is new work, possibly from another thread.
+#[derive(Default)]
+pub(crate) struct Waker {
+    woken: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Waker {
+    pub(crate) fn wake(&self) {
+        *self.woken.lock().expect("Waker::wake") = true;
+        self.condvar.notify_one();
+    }
+
+    /// Blocks until [`wake`](Self::wake) is called or `deadline` passes.
+    fn wait(&self, deadline: Option<Instant>) {
+        let mut woken = self.woken.lock().expect("Waker::wait");
+        while !*woken {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline <= now {
+                        break;
+                    }
+                    woken = self
+                        .condvar
+                        .wait_timeout(woken, deadline - now)
+                        .expect("Waker::wait")
+                        .0;
+                }
+                None => woken = self.condvar.wait(woken).expect("Waker::wait"),
+            }
+        }
+        *woken = false;
+    }
+}
+
+impl Application {
+    pub fn new() -> Result<Application, Error> {
+        Ok(Application {
+            state: Rc::new(AppState {
+                windows: Default::default(),
+                quitting: Cell::new(false),
+                clipboard: Default::default(),
+                #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+                primary: Default::default(),
+                waker: Default::default(),
+            }),
+        })
+    }
+
+    pub(crate) fn waker(&self) -> Arc<Waker> {
+        self.state.waker.clone()
+    }
+
+    pub(crate) fn add_window(&self, window: Rc<WindowState>) {
+        self.state.windows.borrow_mut().push(window);
+    }
+
+    pub fn run(self, _handler: Option<Box<dyn AppHandler>>) {
+        while !self.state.quitting.get() {
+            // Windows can be added while we call into their handlers, so work on a copy.
+            let windows = self.state.windows.borrow().clone();
+            let next_wake = windows.iter().filter_map(|w| w.run_pending()).min();
+            self.state
+                .windows
+                .borrow_mut()
+                .retain(|w| !w.is_destroyed());
+
+            if self.state.quitting.get() {
+                break;
+            }
+            self.state.waker.wait(next_wake);
+        }
+        self.state.windows.borrow_mut().clear();
+    }
+
+    pub fn quit(&self) {
+        self.state.quitting.set(true);
+        self.state.waker.wake();
+    }
+
+    pub fn clipboard(&self) -> Clipboard {
+        self.state.clipboard.clone()
+    }
+
+    pub fn get_locale() -> String {
+        // Follow the POSIX precedence, but don't depend on any system facilities.
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX");
+        match locale {
+            Some(mut locale) => {
+                // The locale parsing library we use expects a unicode locale, not an ISO one.
+                if let Some(idx) = locale.find(['.', '@']) {
+                    locale.truncate(idx);
+                }
+                locale.replace('_', "-")
+            }
+            None => "en-US".into(),
+        }
+    }
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+impl crate::platform::linux::ApplicationExt for crate::Application {
+    fn primary_clipboard(&self) -> crate::Clipboard {
+        self.backend_app.state.primary.clone().into()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl crate::platform::mac::ApplicationExt for crate::Application {
+    fn hide(&self) {}
+
+    fn hide_others(&self) {}
+
+    fn set_menu(&self, _menu: crate::Menu) {}
+}
@@ -0,0 +1,65 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-memory clipboard for the headless backend.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::clipboard::{ClipboardFormat, FormatId};
+
+/// The headless clipboard.
+///
+/// It is shared between all clones, but never leaves the process.
+#[derive(Debug, Clone, Default)]
+pub struct Clipboard {
+    contents: Rc<RefCell<Vec<ClipboardFormat>>>,
+}
+
+impl Clipboard {
+    /// Put a string onto the clipboard.
+    pub fn put_string(&mut self, s: impl AsRef<str>) {
+        self.put_formats(&[ClipboardFormat::from(s.as_ref())]);
+    }
+
+    /// Put multi-format data on the clipboard.
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
+        *self.contents.borrow_mut() = formats.to_vec();
+    }
+
+    /// Get a string from the clipboard, if one is available.
+    pub fn get_string(&self) -> Option<String> {
+        self.get_format(ClipboardFormat::TEXT)
+            .and_then(|data| String::from_utf8(data).ok())
+    }
+
+    /// Given a list of supported clipboard types, returns the supported type which has
+    /// highest priority on the clipboard, or `None` if no types are supported.
+    pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
+        let contents = self.contents.borrow();
+        contents
+            .iter()
+            .find(|fmt| formats.contains(&fmt.identifier))
+            .map(|fmt| fmt.identifier)
+    }
+
+    /// Return data in a given format, if available.
+    ///
+    /// It is recommended that the `fmt` argument be a format returned by
+    /// [`Clipboard::preferred_format`]
+    pub fn get_format(&self, format: FormatId) -> Option<Vec<u8>> {
+        self.contents
+            .borrow()
+            .iter()
+            .find(|fmt| fmt.identifier == format)
+            .map(|fmt| fmt.data.clone())
+    }
+
+    pub fn available_type_names(&self) -> Vec<String> {
+        self.contents
+            .borrow()
+            .iter()
+            .map(|fmt| fmt.identifier.to_string())
+            .collect()
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Headless backend errors.
+
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The window was built without a handler.
+    NoHandler,
+    /// Rendering into the offscreen bitmap failed.
+    Piet(String),
+    /// A frame was to be saved before the window was painted.
+    NoFrame,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Error::NoHandler => write!(f, "Window was built without a handler"),
+            Error::Piet(err) => write!(f, "Offscreen render error: {err}"),
+            Error::NoFrame => write!(f, "The window has not been painted yet"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<piet_common::Error> for Error {
+    fn from(err: piet_common::Error) -> Error {
+        Error::Piet(err.to_string())
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Menus for the headless backend.
+//!
+//! There is nothing to display them on, so all operations are no-ops.
+
+use crate::hotkey::HotKey;
+
+/// A menu object, which can be either a top-level menubar or a
+/// submenu.
+pub struct Menu;
+
+impl Menu {
+    pub fn new() -> Menu {
+        Menu
+    }
+
+    pub fn new_for_popup() -> Menu {
+        Menu
+    }
+
+    pub fn add_dropdown(&mut self, _menu: Menu, _text: &str, _enabled: bool) {}
+
+    pub fn add_item(
+        &mut self,
+        _id: u32,
+        _text: &str,
+        _key: Option<&HotKey>,
+        _selected: Option<bool>,
+        _enabled: bool,
+    ) {
+    }
+
+    pub fn add_separator(&mut self) {}
+}
@@ -0,0 +1,15 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Headless backend support, for running without a display server.
+//!
+//! Windows are never shown on screen; instead each window is painted into an
+//! offscreen piet bitmap, which can be retrieved with
+//! [`WindowHandleExt`](crate::platform::headless::WindowHandleExt).
+
+pub mod application;
+pub mod clipboard;
+pub mod error;
+pub mod menu;
+pub mod screen;
+pub mod window;
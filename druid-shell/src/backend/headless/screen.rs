@@ -0,0 +1,14 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Monitor and Screen information for the headless backend.
+
+use crate::kurbo::Rect;
+use crate::screen::Monitor;
+
+/// The size of the single virtual monitor, in display points.
+const VIRTUAL_MONITOR: Rect = Rect::new(0.0, 0.0, 1920.0, 1080.0);
+
+pub(crate) fn get_monitors() -> Vec<Monitor> {
+    vec![Monitor::new(true, VIRTUAL_MONITOR, VIRTUAL_MONITOR)]
+}
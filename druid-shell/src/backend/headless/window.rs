@@ -0,0 +1,767 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Headless window creation and management.
+
+use std::cell::{Cell, RefCell};
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use instant::Instant;
+use tracing::{error, warn};
+
+#[cfg(feature = "raw-win-handle")]
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, WebWindowHandle};
+
+use crate::kurbo::{Insets, Point, Rect, Size};
+use crate::piet::{
+    BitmapTarget, Device, ImageBuf, ImageFormat, InterpolationMode, PietText, RenderContext,
+};
+
+use super::application::{Application, Waker};
+use super::error::Error;
+use super::menu::Menu;
+use crate::common_util::IdleCallback;
+use crate::dialog::FileDialogOptions;
+use crate::error::Error as ShellError;
+use crate::mouse::{Cursor, CursorDesc};
+use crate::region::Region;
+use crate::scale::{Scale, ScaledArea};
+use crate::text::Event;
+use crate::window;
+use crate::window::{
+    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+};
+
+/// The size used for windows that never had one set.
+const DEFAULT_SIZE: Size = Size::new(500.0, 400.0);
+
+/// The minimum time between two frames of the same window.
+///
+/// There is no display to synchronize with, so this stands in for vsync.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The environment variable naming a directory that every rendered frame is saved to.
+const FRAME_DIR_ENV_VAR: &str = "DRUID_SHELL_HEADLESS_FRAME_DIR";
+
+/// Builder abstraction for creating new windows.
+pub(crate) struct WindowBuilder {
+    app: Application,
+    handler: Option<Box<dyn WinHandler>>,
+    title: String,
+    size: Size,
+    min_size: Size,
+    position: Option<Point>,
+    state: window::WindowState,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct WindowHandle(Weak<WindowState>);
+
+impl PartialEq for WindowHandle {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.0.upgrade(), other.0.upgrade()) {
+            (None, None) => true,
+            (Some(s), Some(o)) => Rc::ptr_eq(&s, &o),
+            (_, _) => false,
+        }
+    }
+}
+
+impl Eq for WindowHandle {}
+
+#[cfg(feature = "raw-win-handle")]
+unsafe impl HasRawWindowHandle for WindowHandle {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        error!("HasRawWindowHandle trait not implemented for headless windows.");
+        RawWindowHandle::Web(WebWindowHandle::empty())
+    }
+}
+
+/// A handle that can get used to schedule an idle handler. Note that
+/// this handle is thread safe.
+#[derive(Clone)]
+pub struct IdleHandle {
+    queue: Arc<Mutex<Vec<IdleKind>>>,
+    waker: Arc<Waker>,
+}
+
+/// Operations that need the `WinHandler`, and so have to wait until it is not borrowed.
+enum DeferredOp {
+    SetSize(Size),
+    SaveFrame(PathBuf),
+    Close,
+}
+
+enum IdleKind {
+    Callback(Box<dyn IdleCallback>),
+    Token(IdleToken),
+    DeferredOp(DeferredOp),
+}
+
+pub(crate) struct WindowState {
+    id: u64,
+    handler: RefCell<Box<dyn WinHandler>>,
+    idle_queue: Arc<Mutex<Vec<IdleKind>>>,
+    waker: Arc<Waker>,
+    timers: RefCell<Vec<(Instant, TimerToken)>>,
+    scale: Cell<Scale>,
+    area: Cell<ScaledArea>,
+    min_size: Cell<Size>,
+    position: Cell<Point>,
+    title: RefCell<String>,
+    window_state: Cell<window::WindowState>,
+    visible: Cell<bool>,
+    destroyed: Cell<bool>,
+    needs_render: Cell<bool>,
+    last_render: Cell<Option<Instant>>,
+    frame_count: Cell<u64>,
+    last_frame: RefCell<Option<ImageBuf>>,
+    active_text_field: Cell<Option<TextFieldToken>>,
+    text: PietText,
+}
+
+// TODO: support custom cursors
+#[derive(Clone, PartialEq, Eq)]
+pub struct CustomCursor;
+
+impl WindowBuilder {
+    pub fn new(app: Application) -> WindowBuilder {
+        WindowBuilder {
+            app,
+            handler: None,
+            title: String::new(),
+            size: DEFAULT_SIZE,
+            min_size: Size::ZERO,
+            position: None,
+            state: window::WindowState::Restored,
+        }
+    }
+
+    pub fn set_handler(&mut self, handler: Box<dyn WinHandler>) {
+        self.handler = Some(handler);
+    }
+
+    pub fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    pub fn set_min_size(&mut self, size: Size) {
+        self.min_size = size;
+    }
+
+    pub fn resizable(&mut self, _resizable: bool) {
+        // Ignored
+    }
+
+    pub fn show_titlebar(&mut self, _show_titlebar: bool) {
+        // Ignored
+    }
+
+    pub fn set_transparent(&mut self, _transparent: bool) {
+        // Ignored
+    }
+
+    pub fn set_position(&mut self, position: Point) {
+        self.position = Some(position);
+    }
+
+    pub fn set_always_on_top(&mut self, _always_on_top: bool) {
+        // Ignored
+    }
+
+    pub fn set_level(&mut self, _level: WindowLevel) {
+        // Ignored
+    }
+
+    pub fn set_window_state(&mut self, state: window::WindowState) {
+        self.state = state;
+    }
+
+    pub fn set_title<S: Into<String>>(&mut self, title: S) {
+        self.title = title.into();
+    }
+
+    pub fn set_menu(&mut self, _menu: Menu) {
+        // Ignored
+    }
+
+    pub fn build(self) -> Result<WindowHandle, Error> {
+        static WINDOW_ID: AtomicU64 = AtomicU64::new(0);
+
+        let handler = self.handler.ok_or(Error::NoHandler)?;
+        let scale = Scale::default();
+        let size = at_least(self.size, self.min_size);
+        let area = ScaledArea::from_dp(size, scale);
+        let waker = self.app.waker();
+
+        let state = Rc::new(WindowState {
+            id: WINDOW_ID.fetch_add(1, Ordering::Relaxed),
+            handler: RefCell::new(handler),
+            idle_queue: Default::default(),
+            waker,
+            timers: Default::default(),
+            scale: Cell::new(scale),
+            area: Cell::new(area),
+            min_size: Cell::new(self.min_size),
+            position: Cell::new(self.position.unwrap_or_default()),
+            title: RefCell::new(self.title),
+            window_state: Cell::new(self.state),
+            visible: Cell::new(false),
+            destroyed: Cell::new(false),
+            needs_render: Cell::new(true),
+            last_render: Cell::new(None),
+            frame_count: Cell::new(0),
+            last_frame: RefCell::new(None),
+            active_text_field: Cell::new(None),
+            text: make_text(),
+        });
+        self.app.add_window(state.clone());
+
+        let handle = WindowHandle(Rc::downgrade(&state));
+        state.with_handler(|h| {
+            h.connect(&handle.clone().into());
+            h.scale(scale);
+            h.size(area.size_dp());
+        });
+
+        Ok(handle)
+    }
+}
+
+impl WindowState {
+    #[track_caller]
+    fn with_handler<T, F: FnOnce(&mut dyn WinHandler) -> T>(&self, f: F) -> Option<T> {
+        match self.handler.try_borrow_mut() {
+            Ok(mut h) => Some(f(&mut **h)),
+            Err(_) => {
+                error!("failed to borrow WinHandler at {}", Location::caller());
+                None
+            }
+        }
+    }
+
+    fn schedule(&self, op: DeferredOp) {
+        self.idle_queue
+            .lock()
+            .expect("WindowState::schedule queue")
+            .push(IdleKind::DeferredOp(op));
+        self.waker.wake();
+    }
+
+    fn request_render(&self) {
+        self.needs_render.set(true);
+        self.waker.wake();
+    }
+
+    pub(crate) fn is_destroyed(&self) -> bool {
+        self.destroyed.get()
+    }
+
+    /// Does all the work that is currently due for this window.
+    ///
+    /// Returns the next time this window needs attention, if it has more work
+    /// scheduled.
+    pub(crate) fn run_pending(&self) -> Option<Instant> {
+        self.process_idle_queue();
+        self.process_timers();
+        if self.destroyed.get() {
+            return None;
+        }
+
+        let next_frame = self.next_frame();
+        if let Some(next_frame) = next_frame {
+            if next_frame <= Instant::now() {
+                self.render();
+            }
+        }
+
+        let next_timer = self
+            .timers
+            .borrow()
+            .iter()
+            .map(|(deadline, _)| *deadline)
+            .min();
+        match (self.next_frame(), next_timer) {
+            (Some(frame), Some(timer)) => Some(frame.min(timer)),
+            (frame, timer) => frame.or(timer),
+        }
+    }
+
+    fn next_frame(&self) -> Option<Instant> {
+        if !self.visible.get() || !self.needs_render.get() {
+            return None;
+        }
+        Some(match self.last_render.get() {
+            Some(last) => last + FRAME_INTERVAL,
+            None => Instant::now(),
+        })
+    }
+
+    fn process_idle_queue(&self) {
+        let queue = std::mem::take(&mut *self.idle_queue.lock().expect("process_idle_queue"));
+        for item in queue {
+            if self.destroyed.get() {
+                break;
+            }
+            match item {
+                IdleKind::Callback(cb) => {
+                    self.with_handler(|h| cb.call(h));
+                }
+                IdleKind::Token(tok) => {
+                    self.with_handler(|h| h.idle(tok));
+                }
+                IdleKind::DeferredOp(op) => self.run_deferred(op),
+            }
+        }
+    }
+
+    fn run_deferred(&self, op: DeferredOp) {
+        match op {
+            DeferredOp::SetSize(size) => {
+                let size = at_least(size, self.min_size.get());
+                self.area.set(ScaledArea::from_dp(size, self.scale.get()));
+                self.with_handler(|h| h.size(size));
+                self.request_render();
+            }
+            DeferredOp::SaveFrame(path) => {
+                if let Err(e) = self.save_frame(&path) {
+                    error!("failed to save frame to {}: {}", path.display(), e);
+                }
+            }
+            DeferredOp::Close => {
+                self.visible.set(false);
+                self.with_handler(|h| h.destroy());
+                self.destroyed.set(true);
+            }
+        }
+    }
+
+    fn process_timers(&self) {
+        let now = Instant::now();
+        let expired: Vec<TimerToken> = {
+            let mut timers = self.timers.borrow_mut();
+            timers.sort_by_key(|(deadline, _)| *deadline);
+            let split = timers.partition_point(|(deadline, _)| *deadline <= now);
+            timers.drain(..split).map(|(_, token)| token).collect()
+        };
+        for token in expired {
+            if self.destroyed.get() {
+                break;
+            }
+            self.with_handler(|h| h.timer(token));
+        }
+    }
+
+    fn render(&self) {
+        self.needs_render.set(false);
+        self.last_render.set(Some(Instant::now()));
+        self.with_handler(|h| h.prepare_paint());
+
+        let frame =
+            self.paint_frame(|mut target| Ok(target.to_image_buf(ImageFormat::RgbaPremul)?));
+        match frame {
+            Ok(frame) => *self.last_frame.borrow_mut() = Some(frame),
+            Err(e) => error!("failed to render headless window: {}", e),
+        }
+
+        let frame_count = self.frame_count.get();
+        self.frame_count.set(frame_count + 1);
+        if let Some(dir) = std::env::var_os(FRAME_DIR_ENV_VAR) {
+            let path = Path::new(&dir).join(format!("window-{}-{:05}.png", self.id, frame_count));
+            if let Err(e) = self.save_frame(&path) {
+                error!("failed to save frame to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Encodes the last painted frame as a PNG file, without painting again.
+    fn save_frame(&self, path: &Path) -> Result<(), Error> {
+        let frame = self.last_frame.borrow().clone().ok_or(Error::NoFrame)?;
+        let mut device = Device::new()?;
+        let mut target = device.bitmap_target(frame.width(), frame.height(), 1.0)?;
+        {
+            let mut piet = target.render_context();
+            let image = frame.to_image(&mut piet);
+            let rect = Rect::new(0.0, 0.0, frame.width() as f64, frame.height() as f64);
+            piet.draw_image(&image, rect, InterpolationMode::NearestNeighbor);
+            piet.finish()?;
+        }
+        Ok(target.save_to_file(path)?)
+    }
+
+    /// Paints the whole window into a new bitmap, and hands that to `f`.
+    fn paint_frame<T>(&self, f: impl FnOnce(BitmapTarget) -> Result<T, Error>) -> Result<T, Error> {
+        let size_dp = self.area.get().size_dp();
+        let size_px = self.area.get().size_px();
+        let mut device = Device::new()?;
+        let mut target = device.bitmap_target(
+            size_px.width as usize,
+            size_px.height as usize,
+            self.scale.get().x(),
+        )?;
+        {
+            let mut piet = target.render_context();
+            self.with_handler(|h| h.paint(&mut piet, &size_dp.to_rect().into()));
+            piet.finish()?;
+        }
+        f(target)
+    }
+}
+
+/// Grows `size` so that it is at least `min_size` in both dimensions.
+fn at_least(size: Size, min_size: Size) -> Size {
+    Size::new(
+        size.width.max(min_size.width),
+        size.height.max(min_size.height),
+    )
+}
+
+/// Creates a text context that is not tied to any particular render target.
+fn make_text() -> PietText {
+    let mut device = Device::new().expect("failed to create piet device");
+    let mut target = device
+        .bitmap_target(1, 1, 1.0)
+        .expect("failed to create bitmap target");
+    let mut piet = target.render_context();
+    let text = piet.text().clone();
+    if let Err(e) = piet.finish() {
+        warn!("piet error finishing text context: {}", e);
+    }
+    text
+}
+
+impl WindowHandle {
+    pub fn show(&self) {
+        if let Some(state) = self.0.upgrade() {
+            state.visible.set(true);
+            state.request_render();
+        }
+    }
+
+    pub fn resizable(&self, _resizable: bool) {
+        // Ignored
+    }
+
+    pub fn show_titlebar(&self, _show_titlebar: bool) {
+        // Ignored
+    }
+
+    pub fn set_position(&self, position: Point) {
+        if let Some(state) = self.0.upgrade() {
+            state.position.set(position);
+        }
+    }
+
+    pub fn set_input_region(&self, _region: Option<Region>) {
+        // Ignored
+    }
+
+    pub fn set_always_on_top(&self, _always_on_top: bool) {
+        // Ignored
+    }
+
+    pub fn set_mouse_pass_through(&self, _mouse_pass_through: bool) {
+        // Ignored
+    }
+
+    pub fn get_position(&self) -> Point {
+        self.0
+            .upgrade()
+            .map(|state| state.position.get())
+            .unwrap_or_default()
+    }
+
+    pub fn set_size(&self, size: Size) {
+        if let Some(state) = self.0.upgrade() {
+            state.schedule(DeferredOp::SetSize(size));
+        }
+    }
+
+    pub fn get_size(&self) -> Size {
+        self.0
+            .upgrade()
+            .map(|state| state.area.get().size_dp())
+            .unwrap_or_default()
+    }
+
+    pub fn is_foreground_window(&self) -> bool {
+        true
+    }
+
+    pub fn content_insets(&self) -> Insets {
+        Insets::ZERO
+    }
+
+    pub fn set_window_state(&self, state: window::WindowState) {
+        if let Some(s) = self.0.upgrade() {
+            s.window_state.set(state);
+        }
+    }
+
+    pub fn get_window_state(&self) -> window::WindowState {
+        self.0
+            .upgrade()
+            .map(|state| state.window_state.get())
+            .unwrap_or(window::WindowState::Restored)
+    }
+
+    pub fn handle_titlebar(&self, _val: bool) {
+        // Ignored
+    }
+
+    pub fn close(&self) {
+        if let Some(state) = self.0.upgrade() {
+            state.schedule(DeferredOp::Close);
+        }
+    }
+
+    pub fn hide(&self) {
+        if let Some(state) = self.0.upgrade() {
+            state.visible.set(false);
+        }
+    }
+
+    pub fn bring_to_front_and_focus(&self) {
+        self.show();
+    }
+
+    pub fn request_anim_frame(&self) {
+        if let Some(state) = self.0.upgrade() {
+            state.request_render();
+        }
+    }
+
+    pub fn invalidate_rect(&self, _rect: Rect) {
+        // Every frame repaints the whole window, so there is no need to track the region.
+        self.invalidate();
+    }
+
+    pub fn invalidate(&self) {
+        if let Some(state) = self.0.upgrade() {
+            state.request_render();
+        }
+    }
+
+    pub fn text(&self) -> PietText {
+        match self.0.upgrade() {
+            Some(state) => state.text.clone(),
+            None => make_text(),
+        }
+    }
+
+    pub fn add_text_field(&self) -> TextFieldToken {
+        TextFieldToken::next()
+    }
+
+    pub fn remove_text_field(&self, token: TextFieldToken) {
+        if let Some(state) = self.0.upgrade() {
+            if state.active_text_field.get() == Some(token) {
+                state.active_text_field.set(None);
+            }
+        }
+    }
+
+    pub fn set_focused_text_field(&self, active_field: Option<TextFieldToken>) {
+        if let Some(state) = self.0.upgrade() {
+            state.active_text_field.set(active_field);
+        }
+    }
+
+    pub fn update_text_field(&self, _token: TextFieldToken, _update: Event) {
+        // no-op, there is no platform IME to inform
+    }
+
+    pub fn request_timer(&self, deadline: Instant) -> TimerToken {
+        let token = TimerToken::next();
+        if let Some(state) = self.0.upgrade() {
+            state.timers.borrow_mut().push((deadline, token));
+            state.waker.wake();
+        }
+        token
+    }
+
+    pub fn set_cursor(&mut self, _cursor: &Cursor) {
+        // Ignored
+    }
+
+    pub fn make_cursor(&self, _cursor_desc: &CursorDesc) -> Option<Cursor> {
+        warn!("Custom cursors are not supported in the headless backend");
+        None
+    }
+
+    pub fn open_file(&mut self, _options: FileDialogOptions) -> Option<FileDialogToken> {
+        warn!("open_file is not supported in the headless backend.");
+        None
+    }
+
+    pub fn save_as(&mut self, _options: FileDialogOptions) -> Option<FileDialogToken> {
+        warn!("save_as is not supported in the headless backend.");
+        None
+    }
+
+    /// Get a handle that can be used to schedule an idle task.
+    pub fn get_idle_handle(&self) -> Option<IdleHandle> {
+        self.0.upgrade().map(|state| IdleHandle {
+            queue: state.idle_queue.clone(),
+            waker: state.waker.clone(),
+        })
+    }
+
+    /// Get the `Scale` of the window.
+    pub fn get_scale(&self) -> Result<Scale, ShellError> {
+        Ok(self
+            .0
+            .upgrade()
+            .ok_or(ShellError::WindowDropped)?
+            .scale
+            .get())
+    }
+
+    pub fn set_menu(&self, _menu: Menu) {
+        // Ignored
+    }
+
+    pub fn show_context_menu(&self, _menu: Menu, _pos: Point) {
+        warn!("show_context_menu is not supported in the headless backend");
+    }
+
+    pub fn set_title(&self, title: impl Into<String>) {
+        if let Some(state) = self.0.upgrade() {
+            *state.title.borrow_mut() = title.into();
+        }
+    }
+}
+
+impl crate::platform::headless::WindowHandleExt for crate::WindowHandle {
+    fn title(&self) -> String {
+        self.0
+             .0
+            .upgrade()
+            .map(|state| state.title.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.0
+             .0
+            .upgrade()
+            .map(|state| state.visible.get() && !state.destroyed.get())
+            .unwrap_or(false)
+    }
+
+    fn last_frame(&self) -> Option<ImageBuf> {
+        self.0
+             .0
+            .upgrade()
+            .and_then(|state| state.last_frame.borrow().clone())
+    }
+
+    fn save_frame(&self, path: impl Into<PathBuf>) {
+        if let Some(state) = self.0 .0.upgrade() {
+            state.schedule(DeferredOp::SaveFrame(path.into()));
+        }
+    }
+}
+
+impl IdleHandle {
+    /// Add an idle handler, which is called (once) when the main thread is idle.
+    pub fn add_idle_callback<F>(&self, callback: F)
+    where
+        F: FnOnce(&mut dyn WinHandler) + Send + 'static,
+    {
+        let mut queue = self.queue.lock().expect("IdleHandle::add_idle queue");
+        queue.push(IdleKind::Callback(Box::new(callback)));
+        self.waker.wake();
+    }
+
+    pub fn add_idle_token(&self, token: IdleToken) {
+        let mut queue = self.queue.lock().expect("IdleHandle::add_idle queue");
+        queue.push(IdleKind::Token(token));
+        self.waker.wake();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+
+    use super::*;
+    use crate::platform::headless::WindowHandleExt;
+
+    /// Saves a frame and closes its window when its timer fires, and quits
+    /// when the window is gone.
+    struct SmokeHandler {
+        app: Application,
+        handle: crate::WindowHandle,
+        timer: Option<TimerToken>,
+        frame_path: PathBuf,
+        events: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl WinHandler for SmokeHandler {
+        fn connect(&mut self, handle: &crate::WindowHandle) {
+            self.handle = handle.clone();
+            self.timer = Some(handle.request_timer(Duration::from_millis(10)));
+            self.events.borrow_mut().push("connect");
+        }
+
+        fn prepare_paint(&mut self) {}
+
+        fn paint(&mut self, _piet: &mut crate::piet::Piet, _invalid: &Region) {
+            self.events.borrow_mut().push("paint");
+        }
+
+        fn timer(&mut self, token: TimerToken) {
+            assert_eq!(Some(token), self.timer);
+            assert!(self.handle.is_visible());
+            assert!(self.handle.last_frame().is_some());
+            self.events.borrow_mut().push("timer");
+            self.handle.save_frame(self.frame_path.clone());
+            self.handle.close();
+        }
+
+        fn destroy(&mut self) {
+            self.events.borrow_mut().push("destroy");
+            self.app.quit();
+        }
+
+        fn as_any(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn window_timer_and_close() {
+        let app = Application::new().unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let frame_path =
+            std::env::temp_dir().join(format!("druid-headless-{}.png", std::process::id()));
+        let mut builder = WindowBuilder::new(app.clone());
+        builder.set_handler(Box::new(SmokeHandler {
+            app: app.clone(),
+            handle: Default::default(),
+            timer: None,
+            frame_path: frame_path.clone(),
+            events: events.clone(),
+        }));
+        let handle = builder.build().unwrap();
+        handle.show();
+        app.run(None);
+
+        let events = events.borrow();
+        assert_eq!(events.first(), Some(&"connect"));
+        assert!(events.contains(&"paint"));
+        // Saving the frame encodes the last one instead of painting again.
+        assert_eq!(events[events.len() - 2..], ["timer", "destroy"]);
+        assert!(frame_path.exists());
+        std::fs::remove_file(&frame_path).unwrap();
+
+        let handle: crate::WindowHandle = handle.into();
+        assert!(!handle.is_visible());
+    }
+}
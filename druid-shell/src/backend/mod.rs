@@ -5,70 +5,85 @@
 
 // It would be clearer to use cfg_if! macros here, but that breaks rustfmt.
 
-#[cfg(target_os = "windows")]
+// The headless backend takes precedence over all platform backends when enabled.
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "headless")]
+pub use headless::*;
+
+#[cfg(all(not(feature = "headless"), target_os = "windows"))]
 mod windows;
-#[cfg(target_os = "windows")]
+#[cfg(all(not(feature = "headless"), target_os = "windows"))]
 pub use windows::*;
 
-#[cfg(target_os = "macos")]
+#[cfg(all(not(feature = "headless"), target_os = "macos"))]
 mod mac;
-#[cfg(target_os = "macos")]
+#[cfg(all(not(feature = "headless"), target_os = "macos"))]
 pub use mac::*;
-#[cfg(target_os = "macos")]
+#[cfg(all(not(feature = "headless"), target_os = "macos"))]
 pub(crate) mod shared;
 
 #[cfg(all(
+    not(feature = "headless"),
     feature = "x11",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 mod x11;
 #[cfg(all(
+    not(feature = "headless"),
     feature = "x11",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub use x11::*;
 #[cfg(all(
+    not(feature = "headless"),
     feature = "x11",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub(crate) mod shared;
 
 #[cfg(all(
+    not(feature = "headless"),
     feature = "wayland",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 mod wayland;
 #[cfg(all(
+    not(feature = "headless"),
     feature = "wayland",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub use wayland::*;
 #[cfg(all(
+    not(feature = "headless"),
     feature = "wayland",
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub(crate) mod shared;
 
 #[cfg(all(
+    not(feature = "headless"),
     not(feature = "x11"),
     not(feature = "wayland"),
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 mod gtk;
 #[cfg(all(
+    not(feature = "headless"),
     not(feature = "x11"),
     not(feature = "wayland"),
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub use self::gtk::*;
 #[cfg(all(
+    not(feature = "headless"),
     not(feature = "x11"),
     not(feature = "wayland"),
     any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
 ))]
 pub(crate) mod shared;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(not(feature = "headless"), target_arch = "wasm32"))]
 mod web;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(not(feature = "headless"), target_arch = "wasm32"))]
 pub use web::*;
@@ -5,7 +5,7 @@
 
 use super::{
     clipboard, display, error::Error, events::WaylandSource, keyboard, outputs, pointers, surfaces,
-    window::WindowHandle,
+    touches, window::WindowHandle,
 };
 
 use crate::{backend, mouse, AppHandler, TimerToken};
@@ -31,6 +31,7 @@ use wayland_client::{
         wl_seat::{self, WlSeat},
         wl_shm::{self, WlShm},
         wl_surface::WlSurface,
+        wl_touch::WlTouch,
     },
 };
 use wayland_cursor::CursorTheme;
@@ -121,6 +122,8 @@ pub(crate) struct Data {
     pub(super) display_flushed: RefCell<bool>,
     /// reference to the pointer events manager.
     pub(super) pointer: pointers::Pointer,
+    /// reference to the touch events manager.
+    pub(super) touch: touches::Touch,
     /// reference to the keyboard events manager.
     keyboard: keyboard::Manager,
     clipboard: clipboard::Manager,
@@ -243,6 +246,7 @@ impl Application {
             timers: RefCell::new(BinaryHeap::new()),
             display_flushed: RefCell::new(false),
             pointer,
+            touch: touches::Touch::default(),
             keyboard: keyboard::Manager::default(),
             clipboard: clipboard::Manager::new(&env.display, &env.registry)?,
             roundtrip_requested: RefCell::new(false),
@@ -288,6 +292,18 @@ impl Application {
                             });
                             seat.pointer = Some(pointer);
                         }
+                        if capabilities.contains(wl_seat::Capability::Touch)
+                            && seat.touch.is_none()
+                        {
+                            let touch = seat.wl_seat.get_touch();
+                            touch.quick_assign({
+                                let app = appdata.clone();
+                                move |_, event, _| {
+                                    touches::Touch::consume(app.clone(), event);
+                                }
+                            });
+                            seat.touch = Some(touch);
+                        }
 
                         // TODO: We should react to capability removal, 
                         // "if a seat regains the pointer capability 
@@ -461,6 +477,13 @@ impl Data {
             .cloned()
     }
 
+    /// The window that shows the given surface, if any.
+    pub(super) fn window_for_surface(&self, surface: &WlSurface) -> Option<WindowHandle> {
+        self.handles_iter()
+            .map(|(_, win)| win)
+            .find(|win| win.data().map_or(false, |data| data.is_surface(surface)))
+    }
+
     fn handle_timer_event(&self, _token: TimerToken) {
         // Don't borrow the timers in case the callbacks want to add more.
         let mut expired_timers = Vec::with_capacity(1);
@@ -553,6 +576,7 @@ pub struct Seat {
     capabilities: wl_seat::Capability,
     keyboard: Option<wl::Main<WlKeyboard>>,
     pointer: Option<wl::Main<WlPointer>>,
+    touch: Option<wl::Main<WlTouch>>,
 }
 
 impl Seat {
@@ -563,6 +587,7 @@ impl Seat {
             capabilities: wl_seat::Capability::empty(),
             keyboard: None,
             pointer: None,
+            touch: None,
         }
     }
 }
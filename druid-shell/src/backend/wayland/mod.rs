@@ -17,6 +17,7 @@ mod outputs;
 pub mod pointers;
 pub mod screen;
 pub mod surfaces;
+mod touches;
 pub mod window;
 
 /// Little enum to make it clearer what some return values mean.
@@ -262,6 +262,11 @@ pub struct Data {
 }
 
 impl Data {
+    /// Returns `true` if this is the data of the given surface.
+    pub(crate) fn is_surface(&self, surface: &wl_surface::WlSurface) -> bool {
+        **self.wl_surface.borrow() == *surface
+    }
+
     pub(crate) fn output(&self) -> Option<outputs::Meta> {
         match self.outputs.borrow().iter().find(|_| true) {
             None => None,
@@ -0,0 +1,218 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Touch screen input.
+//!
+//! Pen input needs the tablet protocol, which isn't supported yet.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use wayland_client::protocol::wl_touch;
+
+use crate::keyboard::Modifiers;
+use crate::kurbo::{Point, Vec2};
+use crate::mouse::{MouseButton, MouseButtons};
+use crate::pointer::{PointerEvent, PointerId, PointerType};
+
+use super::application::Data;
+
+/// Collect up touch events then emit them together on a touch frame.
+#[derive(Default)]
+pub(crate) struct Touch {
+    /// Events that have occurred since the last frame.
+    queued_events: RefCell<VecDeque<TouchEvent>>,
+    /// The touch points that are down, with the window they went down on and
+    /// their last position.
+    points: RefCell<Vec<(i32, u64, Point)>>,
+    /// The touch point that is also reported as the mouse.
+    primary: Cell<Option<i32>>,
+}
+
+/// Raw wayland touch events.
+#[derive(Debug)]
+enum TouchEvent {
+    /// A touch point appeared on a window.
+    Down { id: i32, window: u64, point: Point },
+    /// A touch point moved.
+    Motion { id: i32, point: Point },
+    /// A touch point was lifted.
+    Up { id: i32 },
+    /// The compositor took over all the touch points.
+    Cancel,
+}
+
+/// An enum that we will convert into the different callbacks.
+#[derive(Debug)]
+enum TouchEvtKind {
+    Down(PointerEvent),
+    Move(PointerEvent),
+    Up(PointerEvent),
+    Cancel(PointerEvent),
+}
+
+impl Touch {
+    #[inline]
+    fn push(&self, event: TouchEvent) {
+        self.queued_events.borrow_mut().push_back(event);
+    }
+
+    pub(super) fn consume(appdata: std::sync::Arc<Data>, event: wl_touch::Event) {
+        match event {
+            wl_touch::Event::Down {
+                surface, id, x, y, ..
+            } => {
+                // The later events of the point go to the same surface.
+                match appdata.window_for_surface(&surface) {
+                    Some(window) => appdata.touch.push(TouchEvent::Down {
+                        id,
+                        window: window.id(),
+                        point: Point::new(x, y),
+                    }),
+                    None => tracing::warn!("dropping touch point, no window for its surface"),
+                }
+            }
+            wl_touch::Event::Motion { id, x, y, .. } => {
+                appdata.touch.push(TouchEvent::Motion {
+                    id,
+                    point: Point::new(x, y),
+                });
+            }
+            wl_touch::Event::Up { id, .. } => {
+                appdata.touch.push(TouchEvent::Up { id });
+            }
+            wl_touch::Event::Cancel => {
+                // No frame follows a cancel, so emit it right away.
+                appdata.touch.push(TouchEvent::Cancel);
+                Touch::flush(&appdata);
+            }
+            wl_touch::Event::Frame => Touch::flush(&appdata),
+            evt => {
+                tracing::trace!("Unhandled touch event: {:?}", evt);
+            }
+        }
+    }
+
+    fn flush(appdata: &Data) {
+        // (re-entrancy) call user code
+        while let Some((window, event)) = appdata.touch.dequeue() {
+            let data = appdata.handles.borrow().get(&window).and_then(|w| w.data());
+            let winhandle = match data {
+                Some(w) => w,
+                None => {
+                    tracing::warn!("dropping touch event, its window is gone");
+                    continue;
+                }
+            };
+            let mut winhandle = winhandle.handler.borrow_mut();
+
+            // The primary touch point is also reported as the mouse.
+            match event {
+                TouchEvtKind::Down(evt) => {
+                    winhandle.pointer_down(&evt);
+                    if evt.is_primary {
+                        winhandle.mouse_down(&evt.to_mouse_event(1));
+                    }
+                }
+                TouchEvtKind::Move(evt) => {
+                    winhandle.pointer_move(&evt);
+                    if evt.is_primary {
+                        winhandle.mouse_move(&evt.to_mouse_event(0));
+                    }
+                }
+                TouchEvtKind::Up(evt) => {
+                    winhandle.pointer_up(&evt);
+                    if evt.is_primary {
+                        winhandle.mouse_up(&evt.to_mouse_event(0));
+                    }
+                }
+                TouchEvtKind::Cancel(evt) => {
+                    winhandle.pointer_cancel(&evt);
+                    if evt.is_primary {
+                        winhandle.mouse_leave();
+                    }
+                }
+            }
+        }
+    }
+
+    /// The next event to emit, with the id of the window it goes to.
+    fn dequeue(&self) -> Option<(u64, TouchEvtKind)> {
+        // sometimes we need to ignore an event and move on
+        loop {
+            let event = self.queued_events.borrow_mut().pop_front()?;
+            tracing::trace!("touch event {:?}", event);
+            let mut points = self.points.borrow_mut();
+            match event {
+                TouchEvent::Down { id, window, point } => {
+                    if points.is_empty() {
+                        self.primary.set(Some(id));
+                    }
+                    points.push((id, window, point));
+                    let evt = self.pointer_event(id, point, MouseButton::Left, true);
+                    return Some((window, TouchEvtKind::Down(evt)));
+                }
+                TouchEvent::Motion { id, point } => {
+                    let window = match points.iter_mut().find(|(p, _, _)| *p == id) {
+                        Some(entry) => {
+                            entry.2 = point;
+                            entry.1
+                        }
+                        None => continue,
+                    };
+                    let evt = self.pointer_event(id, point, MouseButton::None, true);
+                    return Some((window, TouchEvtKind::Move(evt)));
+                }
+                TouchEvent::Up { id } => {
+                    let (_, window, point) = match points.iter().position(|(p, _, _)| *p == id) {
+                        Some(idx) => points.remove(idx),
+                        None => continue,
+                    };
+                    let evt = self.pointer_event(id, point, MouseButton::Left, false);
+                    if evt.is_primary {
+                        self.primary.set(None);
+                    }
+                    return Some((window, TouchEvtKind::Up(evt)));
+                }
+                TouchEvent::Cancel => {
+                    // Cancel the points one at a time.
+                    let (id, window, point) = match points.pop() {
+                        Some(entry) => entry,
+                        None => continue,
+                    };
+                    if !points.is_empty() {
+                        self.queued_events
+                            .borrow_mut()
+                            .push_front(TouchEvent::Cancel);
+                    }
+                    let evt = self.pointer_event(id, point, MouseButton::None, false);
+                    if evt.is_primary {
+                        self.primary.set(None);
+                    }
+                    return Some((window, TouchEvtKind::Cancel(evt)));
+                }
+            }
+        }
+    }
+
+    fn pointer_event(&self, id: i32, pos: Point, button: MouseButton, down: bool) -> PointerEvent {
+        let (buttons, pressure) = if down {
+            (
+                MouseButtons::new().with(MouseButton::Left),
+                PointerEvent::DEFAULT_PRESSURE,
+            )
+        } else {
+            (MouseButtons::new(), 0.0)
+        };
+        PointerEvent {
+            pointer_id: PointerId::from_raw(id as u32 as u64),
+            pointer_type: PointerType::Touch,
+            is_primary: self.primary.get() == Some(id),
+            pos,
+            buttons,
+            button,
+            mods: Modifiers::empty(),
+            pressure,
+            tilt: Vec2::ZERO,
+        }
+    }
+}
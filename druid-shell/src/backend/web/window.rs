@@ -31,6 +31,7 @@ use crate::scale::{Scale, ScaledArea};
 
 use crate::keyboard::{KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
+use crate::pointer::{PointerEvent, PointerId, PointerType};
 use crate::region::Region;
 use crate::text::{simulate_input, Event};
 use crate::window;
@@ -242,6 +243,50 @@ fn setup_mouse_move_callback(ws: &Rc<WindowState>) {
     });
 }
 
+fn setup_pointer_callbacks(ws: &Rc<WindowState>) {
+    for event_type in ["pointerdown", "pointermove", "pointerup", "pointercancel"] {
+        let state = ws.clone();
+        register_canvas_event_listener(ws, event_type, move |event: web_sys::PointerEvent| {
+            // The mouse is handled by the mouse callbacks.
+            let pointer = match pointer_event(&event) {
+                Some(pointer) => pointer,
+                None => return,
+            };
+            // Browsers only send their compatibility mouse events for some touch
+            // input, so we suppress them and report the primary pointer ourselves.
+            event.prevent_default();
+            let mut handler = state.handler.borrow_mut();
+            match event_type {
+                "pointerdown" => {
+                    handler.pointer_down(&pointer);
+                    if pointer.is_primary {
+                        let count = state.click_counter.count_for_click(pointer.pos);
+                        handler.mouse_down(&pointer.to_mouse_event(count));
+                    }
+                }
+                "pointermove" => {
+                    handler.pointer_move(&pointer);
+                    if pointer.is_primary {
+                        handler.mouse_move(&pointer.to_mouse_event(0));
+                    }
+                }
+                "pointerup" => {
+                    handler.pointer_up(&pointer);
+                    if pointer.is_primary {
+                        handler.mouse_up(&pointer.to_mouse_event(0));
+                    }
+                }
+                _ => {
+                    handler.pointer_cancel(&pointer);
+                    if pointer.is_primary {
+                        handler.mouse_leave();
+                    }
+                }
+            }
+        });
+    }
+}
+
 fn setup_scroll_callback(ws: &Rc<WindowState>) {
     let state = ws.clone();
     register_canvas_event_listener(ws, "wheel", move |event: web_sys::WheelEvent| {
@@ -340,6 +385,7 @@ fn setup_web_callbacks(window_state: &Rc<WindowState>) {
     setup_mouse_down_callback(window_state);
     setup_mouse_move_callback(window_state);
     setup_mouse_up_callback(window_state);
+    setup_pointer_callbacks(window_state);
     setup_resize_callback(window_state);
     setup_scroll_callback(window_state);
     setup_keyup_callback(window_state);
@@ -448,6 +494,11 @@ impl WindowBuilder {
         let size_dp = area.size_dp();
 
         set_cursor(&canvas, &self.cursor);
+        // Let touch input reach us instead of scrolling or zooming the page.
+        canvas
+            .style()
+            .set_property("touch-action", "none")
+            .unwrap_or_else(|_| warn!("Failed to set touch-action"));
 
         let handler = self.handler.unwrap();
 
@@ -791,6 +842,35 @@ fn mouse_buttons(mask: u16) -> MouseButtons {
     buttons
 }
 
+/// Converts a touch or pen event, returning `None` for the mouse.
+fn pointer_event(event: &web_sys::PointerEvent) -> Option<PointerEvent> {
+    let pointer_type = match event.pointer_type().as_str() {
+        "touch" => PointerType::Touch,
+        "pen" => PointerType::Pen,
+        _ => return None,
+    };
+    let button = match event.button() {
+        // The eraser end of a pen.
+        5 => MouseButton::Left,
+        button => mouse_button(button).unwrap_or(MouseButton::None),
+    };
+    let mut buttons = mouse_buttons(event.buttons());
+    if event.buttons() & 1 << 5 != 0 {
+        buttons.insert(MouseButton::Left);
+    }
+    Some(PointerEvent {
+        pointer_id: PointerId::from_raw(event.pointer_id() as u64),
+        pointer_type,
+        is_primary: event.is_primary(),
+        pos: Point::new(event.offset_x() as f64, event.offset_y() as f64),
+        buttons,
+        button,
+        mods: get_modifiers!(event),
+        pressure: event.pressure() as f64,
+        tilt: Vec2::new(event.tilt_x() as f64, event.tilt_y() as f64),
+    })
+}
+
 fn set_cursor(canvas: &web_sys::HtmlCanvasElement, cursor: &Cursor) {
     canvas
         .style()
@@ -29,6 +29,7 @@ use crate::application::AppHandler;
 use super::clipboard::Clipboard;
 use super::util;
 use super::window::Window;
+use super::xinput::{PointerPhase, XInput};
 use crate::backend::shared::linux;
 use crate::backend::shared::xkb;
 
@@ -83,6 +84,14 @@ use crate::backend::shared::xkb;
 // INCR
 //
 // Type used for incremental selection transfers
+//
+// XdndAware, XdndEnter, XdndPosition, XdndStatus, XdndLeave, XdndDrop, XdndFinished,
+// XdndSelection, XdndTypeList, XdndActionCopy
+//
+// The property, messages, selection and action of the XDND protocol, which we use to receive
+// data dragged from other applications
+//
+// https://www.freedesktop.org/wiki/Specifications/XDND/
 x11rb::atom_manager! {
     pub(crate) AppAtoms: AppAtomsCookie {
         WM_PROTOCOLS,
@@ -99,6 +108,16 @@ x11rb::atom_manager! {
         PRIMARY,
         TARGETS,
         INCR,
+        XdndAware,
+        XdndEnter,
+        XdndPosition,
+        XdndStatus,
+        XdndLeave,
+        XdndDrop,
+        XdndFinished,
+        XdndSelection,
+        XdndTypeList,
+        XdndActionCopy,
     }
 }
 
@@ -166,6 +185,8 @@ pub(crate) struct Application {
     present_opcode: Option<u8>,
     /// Support for the render extension in at least version 0.5?
     render_argb32_pictformat_cursor: Option<Pictformat>,
+    /// The touch and pen devices, if XInput 2.2 is supported.
+    xinput: Option<Rc<XInput>>,
     /// Newest timestamp that we received
     timestamp: Rc<Cell<Timestamp>>,
 }
@@ -240,6 +261,14 @@ impl Application {
             }
         };
 
+        let xinput = match XInput::new(&connection) {
+            Ok(xinput) => xinput.map(Rc::new),
+            Err(e) => {
+                tracing::info!("failed to initialize XInput: {}", e);
+                None
+            }
+        };
+
         let pictformats = connection.render_query_pict_formats()?;
         let render_create_cursor_supported = matches!(connection
             .extension_information(render::X11_EXTENSION_NAME)?
@@ -340,6 +369,7 @@ impl Application {
             pending_events: Default::default(),
             marker: std::marker::PhantomData,
             render_argb32_pictformat_cursor,
+            xinput,
             timestamp,
         })
     }
@@ -397,6 +427,12 @@ impl Application {
         self.render_argb32_pictformat_cursor
     }
 
+    /// The touch and pen devices, if XInput 2.2 is supported.
+    #[inline]
+    pub(crate) fn xinput(&self) -> Option<&XInput> {
+        self.xinput.as_deref()
+    }
+
     fn create_event_window(conn: &Rc<XCBConnection>, screen_num: usize) -> Result<u32, Error> {
         let id = conn.generate_id()?;
         let setup = conn.setup();
@@ -587,6 +623,42 @@ impl Application {
                     .context("MOTION_NOTIFY - failed to get window")?;
                 w.handle_motion_notify(ev)?;
             }
+            Event::XinputTouchBegin(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("TOUCH_BEGIN - failed to get window")?;
+                w.handle_touch(ev, PointerPhase::Down);
+            }
+            Event::XinputTouchUpdate(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("TOUCH_UPDATE - failed to get window")?;
+                w.handle_touch(ev, PointerPhase::Move);
+            }
+            Event::XinputTouchEnd(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("TOUCH_END - failed to get window")?;
+                w.handle_touch(ev, PointerPhase::Up);
+            }
+            Event::XinputButtonPress(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("XI_BUTTON_PRESS - failed to get window")?;
+                w.handle_pen(ev, PointerPhase::Down);
+            }
+            Event::XinputMotion(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("XI_MOTION - failed to get window")?;
+                w.handle_pen(ev, PointerPhase::Move);
+            }
+            Event::XinputButtonRelease(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("XI_BUTTON_RELEASE - failed to get window")?;
+                w.handle_pen(ev, PointerPhase::Up);
+            }
             Event::ClientMessage(ev) => {
                 let w = self
                     .window(ev.window)
@@ -653,6 +725,13 @@ impl Application {
                     .handle_request(ev)
                     .context("SELECTION_REQUEST event handling for primary")?;
             }
+            Event::SelectionNotify(ev) => {
+                // The clipboard receives its selections on windows of its own.
+                if let Ok(w) = self.window(ev.requestor) {
+                    w.handle_selection_notify(ev)
+                        .context("SELECTION_NOTIFY - failed to handle")?;
+                }
+            }
             Event::PropertyNotify(ev) => {
                 self.clipboard
                     .handle_property_notify(*ev)
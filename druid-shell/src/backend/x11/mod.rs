@@ -29,3 +29,4 @@ pub mod error;
 pub mod menu;
 pub mod screen;
 pub mod window;
+mod xinput;
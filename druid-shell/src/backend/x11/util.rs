@@ -3,6 +3,9 @@
 
 //! Miscellaneous utility functions for working with X11.
 
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 use anyhow::{anyhow, Error};
@@ -13,6 +16,8 @@ use x11rb::protocol::render::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::{Screen, Visualid, Visualtype, Window};
 use x11rb::xcb_ffi::XCBConnection;
 
+use crate::dialog::FileInfo;
+
 // See: https://github.com/rtbo/rust-xcb/blob/master/examples/randr_screen_modes.rs
 pub fn refresh_rate(conn: &Rc<XCBConnection>, window_id: Window) -> Option<f64> {
     let try_refresh_rate = || -> Result<f64, Error> {
@@ -127,6 +132,50 @@ pub fn get_argb_visual_type(
     }
 }
 
+/// Parse a `text/uri-list`, as used for dragging files, keeping the local files.
+pub fn parse_uri_list(list: &[u8]) -> Vec<FileInfo> {
+    String::from_utf8_lossy(list)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|uri| uri.strip_prefix("file://"))
+        .filter_map(|path| {
+            // Skip the host name, which is usually empty or `localhost`.
+            let path = &path[path.find('/')?..];
+            Some(FileInfo {
+                path: PathBuf::from(OsString::from_vec(percent_decode(path))),
+                format: None,
+            })
+        })
+        .collect()
+}
+
+/// Decode the `%XX` escapes in a URI.
+fn percent_decode(uri: &str) -> Vec<u8> {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            b'%' => uri
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
 macro_rules! log_x11 {
     ($val:expr) => {
         if let Err(e) = $val {
@@ -138,3 +187,21 @@ macro_rules! log_x11 {
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uri_list() {
+        let list = b"# dropped files\r\nfile:///tmp/a%20b.txt\r\nfile://localhost/home/%C3%A9\r\nhttps://example.com/c\r\n";
+        let paths: Vec<_> = parse_uri_list(list)
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("/tmp/a b.txt"), PathBuf::from("/home/é")]
+        );
+    }
+}
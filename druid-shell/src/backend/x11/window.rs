@@ -22,6 +22,7 @@ use x11rb::properties::{WmHints, WmHintsState, WmSizeHints};
 use x11rb::protocol::present::{CompleteNotifyEvent, ConnectionExt as _, IdleNotifyEvent};
 use x11rb::protocol::render::{ConnectionExt as _, Pictformat};
 use x11rb::protocol::xfixes::{ConnectionExt as _, Region as XRegion};
+use x11rb::protocol::xinput as xi;
 use x11rb::protocol::xproto::{
     self, AtomEnum, ChangeWindowAttributesAux, ColormapAlloc, ConfigureNotifyEvent,
     ConfigureWindowAux, ConnectionExt, CreateGCAux, EventMask, Gcontext, ImageFormat,
@@ -36,11 +37,13 @@ use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, XcbWindowHandle};
 use crate::backend::shared::Timer;
 use crate::common_util::IdleCallback;
 use crate::dialog::FileDialogOptions;
+use crate::drop::{DropEvent, URI_LIST_FORMAT};
 use crate::error::Error as ShellError;
 use crate::keyboard::{KeyState, Modifiers};
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::piet::{Piet, PietText, RenderContext};
+use crate::pointer::{PointerEvent, PointerId, PointerType};
 use crate::region::Region;
 use crate::scale::Scale;
 use crate::text::{simulate_input, Event};
@@ -52,6 +55,8 @@ use crate::{window, KeyEvent, ScaledArea};
 use super::application::Application;
 use super::dialog;
 use super::menu::Menu;
+use super::util;
+use super::xinput::{self, PointerPhase};
 
 /// A version of XCB's `xcb_visualtype_t` struct. This was copied from the [example] in x11rb; it
 /// is used to interoperate with cairo.
@@ -420,6 +425,19 @@ impl WindowBuilder {
         .check()
         .context("set WM_PROTOCOLS")?;
 
+        // Accept data dragged from other applications, with version 5 of the XDND protocol.
+        log_x11!(conn.change_property32(
+            PropMode::REPLACE,
+            id,
+            atoms.XdndAware,
+            AtomEnum::ATOM,
+            &[XDND_VERSION],
+        ));
+
+        if let Some(xinput) = self.app.xinput() {
+            log_x11!(xinput.select_events(conn, id));
+        }
+
         let min_size = self.min_size.to_px(scale);
         log_x11!(size_hints(self.resizable, size_px, min_size)
             .set_normal_hints(conn.as_ref(), id)
@@ -482,6 +500,7 @@ impl WindowBuilder {
             buffers,
             active_text_field: Cell::new(None),
             parent,
+            xdnd: RefCell::new(None),
         });
 
         window.set_title(&self.title);
@@ -601,6 +620,39 @@ pub(crate) struct Window {
     buffers: RefCell<Buffers>,
     active_text_field: Cell<Option<TextFieldToken>>,
     parent: Weak<Window>,
+    /// Data that another application is dragging over the window.
+    xdnd: RefCell<Option<XdndDrag>>,
+}
+
+/// The version of the XDND protocol that we support.
+const XDND_VERSION: u32 = 5;
+
+/// A drag from another application, received with the [XDND protocol].
+///
+/// [XDND protocol]: https://www.freedesktop.org/wiki/Specifications/XDND/
+struct XdndDrag {
+    /// The window the data is dragged from.
+    source: u32,
+    /// The types the data is offered in, with their names.
+    types: Vec<(xproto::Atom, String)>,
+    /// The last position of the pointer, or `None` before the first `XdndPosition` message.
+    pos: Option<Point>,
+}
+
+impl XdndDrag {
+    /// A [`DropEvent`] for this drag, without contents.
+    fn event(&self) -> DropEvent {
+        let formats = self.types.iter().map(|(_, name)| name.clone()).collect();
+        DropEvent::new(self.pos.unwrap_or_default(), formats)
+    }
+
+    /// The type with the given name, if the data is offered in it.
+    fn type_named(&self, name: &str) -> Option<xproto::Atom> {
+        self.types
+            .iter()
+            .find(|(_, type_name)| type_name == name)
+            .map(|(atom, _)| *atom)
+    }
 }
 
 /// A collection of pixmaps for rendering to. This gets used in two different ways: if the present
@@ -1138,6 +1190,108 @@ impl Window {
         Ok(())
     }
 
+    pub fn handle_touch(&self, touch: &xi::TouchBeginEvent, phase: PointerPhase) {
+        let (buttons, button, pressure) = match phase {
+            PointerPhase::Down => (
+                MouseButtons::new().with(MouseButton::Left),
+                MouseButton::Left,
+                PointerEvent::DEFAULT_PRESSURE,
+            ),
+            PointerPhase::Move => (
+                MouseButtons::new().with(MouseButton::Left),
+                MouseButton::None,
+                PointerEvent::DEFAULT_PRESSURE,
+            ),
+            PointerPhase::Up => (MouseButtons::new(), MouseButton::Left, 0.0),
+        };
+        let pos = Point::new(xinput::fp1616(touch.event_x), xinput::fp1616(touch.event_y));
+        let event = PointerEvent {
+            pointer_id: PointerId::from_raw(touch.detail.into()),
+            pointer_type: PointerType::Touch,
+            is_primary: xinput::is_emulating_pointer(u32::from(touch.flags)),
+            pos: pos.to_dp(self.scale.get()),
+            buttons,
+            button,
+            mods: key_mods(touch.mods.effective as u16),
+            pressure,
+            tilt: Vec2::ZERO,
+        };
+        // The server doesn't emulate the core pointer for clients that select touch
+        // events, so we report the primary touch as the mouse ourselves.
+        self.dispatch_pointer_event(&event, phase, event.is_primary);
+    }
+
+    pub fn handle_pen(&self, pen_event: &xi::ButtonPressEvent, phase: PointerPhase) {
+        let pen = match self.app.xinput().and_then(|x| x.pen(pen_event.sourceid)) {
+            Some(pen) => pen,
+            None => return,
+        };
+        // The button mask doesn't include the button that was just pressed, but does
+        // include the one that was just released, like the core state.
+        let mut buttons = MouseButtons::new();
+        for index in 1..=3 {
+            if xinput::mask_contains(&pen_event.button_mask, index) {
+                buttons.insert(mouse_button(index as u8));
+            }
+        }
+        let button = match phase {
+            // Buttons 4 through 7 are for scrolling, which the core events already report.
+            PointerPhase::Down | PointerPhase::Up if (4..=7).contains(&pen_event.detail) => return,
+            PointerPhase::Down => {
+                let button = mouse_button(pen_event.detail as u8);
+                buttons.insert(button);
+                button
+            }
+            PointerPhase::Up => {
+                let button = mouse_button(pen_event.detail as u8);
+                buttons.remove(button);
+                button
+            }
+            PointerPhase::Move => MouseButton::None,
+        };
+        let (pressure, tilt) = pen.update(&pen_event.valuator_mask, &pen_event.axisvalues);
+        let pos = Point::new(
+            xinput::fp1616(pen_event.event_x),
+            xinput::fp1616(pen_event.event_y),
+        );
+        let event = PointerEvent {
+            pointer_id: PointerId::from_raw(u64::from(pen_event.sourceid) << 32),
+            pointer_type: PointerType::Pen,
+            is_primary: true,
+            pos: pos.to_dp(self.scale.get()),
+            buttons,
+            button,
+            mods: key_mods(pen_event.mods.effective as u16),
+            pressure,
+            tilt,
+        };
+        // The pen also moves the core pointer, so the mouse events are already sent.
+        self.dispatch_pointer_event(&event, phase, false);
+    }
+
+    fn dispatch_pointer_event(&self, event: &PointerEvent, phase: PointerPhase, as_mouse: bool) {
+        self.with_handler(|h| match phase {
+            PointerPhase::Down => {
+                h.pointer_down(event);
+                if as_mouse {
+                    h.mouse_down(&event.to_mouse_event(1));
+                }
+            }
+            PointerPhase::Move => {
+                h.pointer_move(event);
+                if as_mouse {
+                    h.mouse_move(&event.to_mouse_event(0));
+                }
+            }
+            PointerPhase::Up => {
+                h.pointer_up(event);
+                if as_mouse {
+                    h.mouse_up(&event.to_mouse_event(0));
+                }
+            }
+        });
+    }
+
     pub fn handle_wheel(&self, event: &xproto::ButtonPressEvent) -> Result<(), Error> {
         let button = event.detail;
         let mods = key_mods(event.state);
@@ -1204,9 +1358,209 @@ impl Window {
             if protocol == atoms.WM_DELETE_WINDOW {
                 self.with_handler(|h| h.request_close());
             }
+        } else if client_message.format == 32 {
+            let data = client_message.data.as_data32();
+            let type_ = client_message.type_;
+            if type_ == atoms.XdndEnter {
+                log_x11!(self.handle_xdnd_enter(data));
+            } else if type_ == atoms.XdndPosition {
+                log_x11!(self.handle_xdnd_position(data));
+            } else if type_ == atoms.XdndLeave {
+                log_x11!(self.handle_xdnd_leave());
+            } else if type_ == atoms.XdndDrop {
+                log_x11!(self.handle_xdnd_drop(data));
+            }
         }
     }
 
+    fn handle_xdnd_enter(&self, data: [u32; 5]) -> Result<(), Error> {
+        let conn = self.app.connection();
+        let source = data[0];
+        let types: Vec<xproto::Atom> = if data[1] & 1 != 0 {
+            // There are more than three types, so the full list is in a property of the source.
+            conn.get_property(
+                false,
+                source,
+                self.app.atoms().XdndTypeList,
+                AtomEnum::ATOM,
+                0,
+                u32::MAX,
+            )?
+            .reply()?
+            .value32()
+            .map(|types| types.collect())
+            .unwrap_or_default()
+        } else {
+            data[2..]
+                .iter()
+                .copied()
+                .filter(|&atom| atom != x11rb::NONE)
+                .collect()
+        };
+        let types = types
+            .into_iter()
+            .map(|atom| {
+                let name = conn.get_atom_name(atom)?.reply()?.name;
+                Ok((atom, String::from_utf8_lossy(&name).into_owned()))
+            })
+            .collect::<Result<_, Error>>()?;
+        *borrow_mut!(self.xdnd)? = Some(XdndDrag {
+            source,
+            types,
+            pos: None,
+        });
+        Ok(())
+    }
+
+    fn handle_xdnd_position(&self, data: [u32; 5]) -> Result<(), Error> {
+        let conn = self.app.connection();
+        let atoms = self.app.atoms();
+        // The position is given in root window coordinates.
+        let (root_x, root_y) = ((data[2] >> 16) as i16, (data[2] & 0xffff) as i16);
+        let root = conn.setup().roots[self.app.screen_num()].root;
+        let reply = conn
+            .translate_coordinates(root, self.id, root_x, root_y)?
+            .reply()?;
+        let pos = Point::new(reply.dst_x as f64, reply.dst_y as f64).to_dp(self.scale.get());
+
+        let (event, entered, source) = match borrow_mut!(self.xdnd)?.as_mut() {
+            Some(drag) => {
+                let entered = drag.pos.replace(pos).is_some();
+                (drag.event(), entered, drag.source)
+            }
+            None => return Ok(()),
+        };
+        let accepted = self
+            .with_handler(|h| {
+                if entered {
+                    h.drag_over(&event)
+                } else {
+                    h.drag_enter(&event)
+                }
+            })
+            .unwrap_or(false);
+
+        // Setting the second bit asks for a position message whenever the pointer moves.
+        let action = if accepted {
+            atoms.XdndActionCopy
+        } else {
+            x11rb::NONE
+        };
+        let flags = accepted as u32 | 2;
+        self.send_xdnd_message(source, atoms.XdndStatus, [self.id, flags, 0, 0, action])
+    }
+
+    fn handle_xdnd_leave(&self) -> Result<(), Error> {
+        let drag = borrow_mut!(self.xdnd)?.take();
+        if drag.map_or(false, |drag| drag.pos.is_some()) {
+            self.with_handler(|h| h.drag_leave());
+        }
+        Ok(())
+    }
+
+    fn handle_xdnd_drop(&self, data: [u32; 5]) -> Result<(), Error> {
+        let atoms = self.app.atoms();
+        let time = data[2];
+        let (target, source) = match borrow!(self.xdnd)?.as_ref() {
+            Some(drag) => {
+                let format = DropEvent::preferred_format(&drag.event().formats);
+                (
+                    format.and_then(|format| drag.type_named(format)),
+                    drag.source,
+                )
+            }
+            None => return Ok(()),
+        };
+        match target {
+            Some(target) => {
+                // The data arrives with a `SelectionNotify` event.
+                let conn = self.app.connection();
+                conn.convert_selection(
+                    self.id,
+                    atoms.XdndSelection,
+                    target,
+                    atoms.XdndSelection,
+                    time,
+                )?;
+                conn.flush()?;
+                Ok(())
+            }
+            None => {
+                // We don't understand any of the types, so refuse the drop.
+                self.handle_xdnd_leave()?;
+                self.send_xdnd_message(source, atoms.XdndFinished, [self.id, 0, 0, 0, 0])
+            }
+        }
+    }
+
+    pub fn handle_selection_notify(
+        &self,
+        event: &xproto::SelectionNotifyEvent,
+    ) -> Result<(), Error> {
+        let atoms = self.app.atoms();
+        if event.selection != atoms.XdndSelection {
+            return Ok(());
+        }
+        let drag = match borrow_mut!(self.xdnd)?.take() {
+            Some(drag) => drag,
+            None => return Ok(()),
+        };
+        let mut drop_event = drag.event();
+        if event.property != x11rb::NONE {
+            let reply = self
+                .app
+                .connection()
+                .get_property(true, self.id, event.property, AtomEnum::ANY, 0, u32::MAX)?
+                .reply()?;
+            if reply.type_ == atoms.INCR {
+                // Lists of files and dragged text are small enough to not need this.
+                warn!("Incremental transfers of dropped data are not supported");
+            } else if Some(event.target) == drag.type_named(URI_LIST_FORMAT) {
+                drop_event.files = util::parse_uri_list(&reply.value);
+            } else if Some(event.target) == drag.type_named("STRING") {
+                // STRING is Latin-1, whose bytes are the first 256 code points.
+                drop_event.text = Some(reply.value.iter().map(|&byte| char::from(byte)).collect());
+            } else {
+                drop_event.text = Some(String::from_utf8_lossy(&reply.value).into_owned());
+            }
+        }
+        let accepted = self
+            .with_handler(|h| h.drag_drop(&drop_event))
+            .unwrap_or(false);
+        // The pointer stays grabbed by the source until the drop is finished, so
+        // without this the widgets under it would stay hot.
+        self.with_handler(|h| h.drag_leave());
+
+        let action = if accepted {
+            atoms.XdndActionCopy
+        } else {
+            x11rb::NONE
+        };
+        let data = [self.id, accepted as u32, action, 0, 0];
+        self.send_xdnd_message(drag.source, atoms.XdndFinished, data)
+    }
+
+    /// Send an XDND message to the window that data is dragged from.
+    fn send_xdnd_message(
+        &self,
+        source: u32,
+        type_: xproto::Atom,
+        data: [u32; 5],
+    ) -> Result<(), Error> {
+        let conn = self.app.connection();
+        let event = xproto::ClientMessageEvent {
+            response_type: xproto::CLIENT_MESSAGE_EVENT,
+            format: 32,
+            sequence: 0,
+            window: source,
+            type_,
+            data: data.into(),
+        };
+        conn.send_event(false, source, EventMask::NO_EVENT, event)?;
+        conn.flush()?;
+        Ok(())
+    }
+
     #[allow(clippy::trivially_copy_pass_by_ref)]
     pub fn handle_destroy_notify(&self, _destroy_notify: &xproto::DestroyNotifyEvent) {
         self.with_handler(|h| h.destroy());
@@ -0,0 +1,224 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Touch and pen input, using the XInput 2 extension.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use anyhow::{Context, Error};
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{self, ConnectionExt as _};
+use x11rb::xcb_ffi::XCBConnection;
+
+use crate::kurbo::Vec2;
+
+/// The first XInput version with touch events.
+const XI_VERSION: (u16, u16) = (2, 2);
+
+/// The flag set on the touch that the server also reports as the core pointer.
+const XI_TOUCH_EMULATING_POINTER: u32 = 1 << 17;
+
+/// Whether a pointer went down, moved, or went up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PointerPhase {
+    Down,
+    Move,
+    Up,
+}
+
+/// The touch and pen devices of the X server.
+///
+/// Touch screens need no setup: their events are selected on the master
+/// devices. Pens are slave pointer devices that report pressure; their core
+/// pointer events still reach us as mouse events, and their XInput events add
+/// the pressure and tilt.
+pub(crate) struct XInput {
+    /// The pens, by device id.
+    pens: HashMap<xinput::DeviceId, Pen>,
+}
+
+/// A pen, with the valuators it reports.
+#[derive(Default)]
+pub(crate) struct Pen {
+    pressure: Option<Axis>,
+    tilt_x: Option<Axis>,
+    tilt_y: Option<Axis>,
+    /// The last values reported, because events only include the valuators that changed.
+    state: Cell<(f64, Vec2)>,
+}
+
+/// A valuator of a device, with its range.
+#[derive(Clone, Copy)]
+struct Axis {
+    number: u16,
+    min: f64,
+    max: f64,
+}
+
+impl XInput {
+    /// Returns `None` if the X server doesn't support XInput 2.2.
+    pub(crate) fn new(conn: &XCBConnection) -> Result<Option<XInput>, Error> {
+        if conn
+            .extension_information(xinput::X11_EXTENSION_NAME)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let version = conn
+            .xinput_xi_query_version(XI_VERSION.0, XI_VERSION.1)?
+            .reply()
+            .context("query XInput version")?;
+        tracing::info!(
+            "X server supports XInput version {}.{}",
+            version.major_version,
+            version.minor_version,
+        );
+        if (version.major_version, version.minor_version) < XI_VERSION {
+            return Ok(None);
+        }
+
+        // The labels that the common tablet drivers give to the valuators we use.
+        let label = |name: &[u8]| -> Result<xproto::Atom, Error> {
+            let atom = conn
+                .intern_atom(true, name)?
+                .reply()
+                .context("intern valuator label")?
+                .atom;
+            Ok(atom)
+        };
+        let pressure = label(b"Abs Pressure")?;
+        let tilt_x = label(b"Abs Tilt X")?;
+        let tilt_y = label(b"Abs Tilt Y")?;
+
+        // TODO: react to devices being plugged in later, with XI_HierarchyChanged.
+        let devices = conn
+            .xinput_xi_query_device(xinput::Device::ALL)?
+            .reply()
+            .context("query XInput devices")?;
+        let mut pens = HashMap::new();
+        for info in devices.infos {
+            if info.type_ != xinput::DeviceType::SLAVE_POINTER {
+                continue;
+            }
+            let mut pen = Pen::default();
+            for class in &info.classes {
+                if let xinput::DeviceClassData::Valuator(valuator) = &class.data {
+                    if valuator.label == x11rb::NONE {
+                        continue;
+                    }
+                    let axis = Some(Axis {
+                        number: valuator.number,
+                        min: fp3232(valuator.min),
+                        max: fp3232(valuator.max),
+                    });
+                    if valuator.label == pressure {
+                        pen.pressure = axis;
+                    } else if valuator.label == tilt_x {
+                        pen.tilt_x = axis;
+                    } else if valuator.label == tilt_y {
+                        pen.tilt_y = axis;
+                    }
+                }
+            }
+            if pen.pressure.is_some() {
+                tracing::debug!("found pen {}", String::from_utf8_lossy(&info.name));
+                pens.insert(info.deviceid, pen);
+            }
+        }
+        Ok(Some(XInput { pens }))
+    }
+
+    /// Select the touch and pen events of `window`.
+    pub(crate) fn select_events(
+        &self,
+        conn: &XCBConnection,
+        window: xproto::Window,
+    ) -> Result<(), Error> {
+        let touch = xinput::XIEventMask::TOUCH_BEGIN
+            | xinput::XIEventMask::TOUCH_UPDATE
+            | xinput::XIEventMask::TOUCH_END;
+        let pen = xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE;
+        let mut masks = vec![xinput::EventMask {
+            deviceid: xinput::Device::ALL_MASTER.into(),
+            mask: vec![touch.into()],
+        }];
+        masks.extend(self.pens.keys().map(|&deviceid| xinput::EventMask {
+            deviceid,
+            mask: vec![pen.into()],
+        }));
+        conn.xinput_xi_select_events(window, &masks)?
+            .check()
+            .context("select XInput events")?;
+        Ok(())
+    }
+
+    /// The pen with the given device id.
+    pub(crate) fn pen(&self, deviceid: xinput::DeviceId) -> Option<&Pen> {
+        self.pens.get(&deviceid)
+    }
+}
+
+impl Pen {
+    /// Updates and returns the pressure, from `0.0` to `1.0`, and the tilt in degrees.
+    pub(crate) fn update(&self, valuator_mask: &[u32], values: &[xinput::Fp3232]) -> (f64, Vec2) {
+        let (mut pressure, mut tilt) = self.state.get();
+        let value = |axis: Option<Axis>| {
+            axis.and_then(|axis| {
+                valuator(valuator_mask, values, axis.number).map(|value| (axis, value))
+            })
+        };
+        if let Some((axis, value)) = value(self.pressure) {
+            pressure = if axis.max > axis.min {
+                ((value - axis.min) / (axis.max - axis.min)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+        }
+        // The common drivers report tilt in degrees.
+        if let Some((_, value)) = value(self.tilt_x) {
+            tilt.x = value.clamp(-90.0, 90.0);
+        }
+        if let Some((_, value)) = value(self.tilt_y) {
+            tilt.y = value.clamp(-90.0, 90.0);
+        }
+        self.state.set((pressure, tilt));
+        (pressure, tilt)
+    }
+}
+
+/// Returns `true` if `flags` mark the touch that is also reported as the core pointer.
+pub(crate) fn is_emulating_pointer(flags: u32) -> bool {
+    flags & XI_TOUCH_EMULATING_POINTER != 0
+}
+
+/// Returns `true` if the bit for `index` is set in an XInput mask.
+pub(crate) fn mask_contains(mask: &[u32], index: u32) -> bool {
+    mask.get(index as usize / 32)
+        .map_or(false, |word| word & (1 << (index % 32)) != 0)
+}
+
+/// The value of valuator `number`, if the event includes it.
+///
+/// Events only carry the values of the valuators set in their mask, in order.
+fn valuator(mask: &[u32], values: &[xinput::Fp3232], number: u16) -> Option<f64> {
+    let number = u32::from(number);
+    if !mask_contains(mask, number) {
+        return None;
+    }
+    let index = (0..number).filter(|&n| mask_contains(mask, n)).count();
+    values.get(index).copied().map(fp3232)
+}
+
+/// Converts a 16.16 fixed point number.
+pub(crate) fn fp1616(value: xinput::Fp1616) -> f64 {
+    value as f64 / 65536.0
+}
+
+/// Converts a 32.32 fixed point number.
+fn fp3232(value: xinput::Fp3232) -> f64 {
+    value.integral as f64 + value.frac as f64 / (1u64 << 32) as f64
+}
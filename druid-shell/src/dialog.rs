@@ -31,7 +31,8 @@ pub struct FileInfo {
         feature = "x11",
         any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
     ),
-    feature = "wayland"
+    feature = "wayland",
+    feature = "headless"
 )))]
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum FileDialogType {
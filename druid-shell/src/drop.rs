@@ -0,0 +1,76 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Drag and drop from other applications.
+
+use crate::dialog::FileInfo;
+use crate::kurbo::Point;
+
+/// The MIME type used for lists of files.
+pub(crate) const URI_LIST_FORMAT: &str = "text/uri-list";
+
+/// The formats that dragged text can be requested in, in order of preference.
+pub(crate) const TEXT_FORMATS: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+];
+
+/// Data dragged over a window from another application, or from the system.
+///
+/// While the data is being dragged only the [`formats`] it is offered in are
+/// known; the [`files`] and [`text`] are filled in when it is dropped.
+///
+/// [`formats`]: DropEvent::formats
+/// [`files`]: DropEvent::files
+/// [`text`]: DropEvent::text
+#[derive(Debug, Clone)]
+pub struct DropEvent {
+    /// The position of the pointer, in [display points](crate::Scale) relative to the window.
+    pub pos: Point,
+    /// The formats the data is offered in.
+    ///
+    /// These are usually MIME types, such as `text/uri-list` for files, but
+    /// some platforms also use their own names.
+    pub formats: Vec<String>,
+    /// The dropped files.
+    pub files: Vec<FileInfo>,
+    /// The dropped text.
+    pub text: Option<String>,
+}
+
+impl DropEvent {
+    /// Create a new event for data offered in `formats`, with no contents.
+    pub fn new(pos: Point, formats: Vec<String>) -> Self {
+        DropEvent {
+            pos,
+            formats,
+            files: Vec::new(),
+            text: None,
+        }
+    }
+
+    /// Returns `true` if the data includes files.
+    pub fn has_files(&self) -> bool {
+        self.formats.iter().any(|format| format == URI_LIST_FORMAT)
+    }
+
+    /// Returns `true` if the data includes text.
+    pub fn has_text(&self) -> bool {
+        self.formats
+            .iter()
+            .any(|format| TEXT_FORMATS.contains(&format.as_str()))
+    }
+
+    /// The format to request dropped data in, given the formats it is offered in,
+    /// if it is one we understand.
+    ///
+    /// Files are preferred over text, because file managers usually offer both.
+    #[allow(dead_code)] // Not yet used on all platforms.
+    pub(crate) fn preferred_format(formats: &[String]) -> Option<&'static str> {
+        std::iter::once(URI_LIST_FORMAT)
+            .chain(TEXT_FORMATS.iter().copied())
+            .find(|preferred| formats.iter().any(|format| format == preferred))
+    }
+}
@@ -14,6 +14,8 @@
 //!
 //! - `DRUID_SHELL_DISABLE_X11_PRESENT`: if this is set and `druid-shell` is using the `x11`
 //!   backend, it will avoid using the Present extension.
+//! - `DRUID_SHELL_HEADLESS_FRAME_DIR`: if this is set and `druid-shell` is using the `headless`
+//!   backend, every frame that is painted will be saved as a PNG file in this directory.
 
 #![warn(rustdoc::broken_intra_doc_links)]
 #![allow(clippy::new_without_default)]
@@ -50,11 +52,13 @@ mod backend;
 mod clipboard;
 mod common_util;
 mod dialog;
+mod drop;
 mod error;
 mod hotkey;
 mod keyboard;
 mod menu;
 mod mouse;
+mod pointer;
 mod region;
 mod scale;
 mod screen;
@@ -67,11 +71,13 @@ pub use application::{AppHandler, Application};
 pub use clipboard::{Clipboard, ClipboardFormat, FormatId};
 pub use common_util::Counter;
 pub use dialog::{FileDialogOptions, FileInfo, FileSpec};
+pub use drop::DropEvent;
 pub use error::Error;
 pub use hotkey::{HotKey, RawMods, SysMods};
 pub use keyboard::{Code, IntoKey, KbKey, KeyEvent, KeyState, Location, Modifiers};
 pub use menu::Menu;
 pub use mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
+pub use pointer::{PointerEvent, PointerId, PointerType};
 pub use region::Region;
 pub use scale::{Scalable, Scale, ScaledArea};
 pub use screen::{Monitor, Screen};
@@ -0,0 +1,35 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Headless backend specific extensions.
+
+use std::path::PathBuf;
+
+use crate::piet::ImageBuf;
+
+/// Headless backend specific extensions to [`WindowHandle`]
+///
+/// [`WindowHandle`]: crate::WindowHandle
+pub trait WindowHandleExt {
+    /// Returns the window's current title.
+    fn title(&self) -> String;
+
+    /// Returns `true` if the window has been shown and not hidden or closed since.
+    fn is_visible(&self) -> bool;
+
+    /// Returns the most recently painted frame of this window.
+    ///
+    /// The pixels are in [`ImageFormat::RgbaPremul`] and cover the whole window,
+    /// at the window's scale. Returns `None` if the window has not been painted yet.
+    ///
+    /// [`ImageFormat::RgbaPremul`]: crate::piet::ImageFormat::RgbaPremul
+    fn last_frame(&self) -> Option<ImageBuf>;
+
+    /// Saves the most recently painted frame of this window as a PNG file at `path`.
+    ///
+    /// The window isn't painted again, so saving a frame doesn't run the
+    /// handler's paint code. The file is written once control returns to the
+    /// run loop, so this can be called from inside the window's own handler.
+    /// Failures, including a window that has not been painted yet, are logged.
+    fn save_frame(&self, path: impl Into<PathBuf>);
+}
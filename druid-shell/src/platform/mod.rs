@@ -11,3 +11,6 @@ pub mod linux;
 
 #[cfg(any(doc, target_os = "macos"))]
 pub mod mac;
+
+#[cfg(any(doc, feature = "headless"))]
+pub mod headless;
@@ -0,0 +1,101 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Common types for representing touch and pen input.
+
+use crate::kurbo::{Point, Vec2};
+use crate::mouse::{MouseButton, MouseButtons, MouseEvent};
+use crate::Modifiers;
+
+/// Identifies a pointer for as long as it is in contact with, or hovering over, the window.
+///
+/// Every finger touching the screen has its own id. Ids are only unique among the
+/// pointers that are currently down, and may be reused afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointerId(u64);
+
+impl PointerId {
+    /// Create a new `PointerId` with the given raw `u64` id.
+    pub const fn from_raw(id: u64) -> PointerId {
+        PointerId(id)
+    }
+
+    /// Get the raw `u64` id.
+    pub const fn into_raw(self) -> u64 {
+        self.0
+    }
+}
+
+/// The kind of device that produced a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerType {
+    /// A finger on a touch screen.
+    Touch,
+    /// A stylus on a tablet or screen.
+    Pen,
+}
+
+/// Information about a touch or pen event.
+///
+/// Pointer events are sent in addition to [`MouseEvent`]s: the [primary]
+/// pointer is also reported as a mouse, so that code that only handles the
+/// mouse keeps working with a touch screen or a pen. The mouse itself is only
+/// reported through [`MouseEvent`]s.
+///
+/// [`MouseEvent`]: crate::MouseEvent
+/// [primary]: PointerEvent::is_primary
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointerEvent {
+    /// Identifies the pointer among the ones that are currently down.
+    pub pointer_id: PointerId,
+    /// The kind of device that produced this event.
+    pub pointer_type: PointerType,
+    /// Whether this is the pointer that is also reported as the mouse.
+    ///
+    /// For touch input this is the first finger that touched the screen.
+    pub is_primary: bool,
+    /// The location of the pointer in [display points] in relation to the current window.
+    ///
+    /// [display points]: crate::Scale
+    pub pos: Point,
+    /// Buttons being held down during a move or after a down event.
+    ///
+    /// A finger or pen in contact with the surface counts as [`MouseButton::Left`],
+    /// and a pen's barrel button as [`MouseButton::Right`].
+    pub buttons: MouseButtons,
+    /// The button that was pressed down in the case of pointer-down,
+    /// or the button that was released in the case of pointer-up.
+    /// This will always be `MouseButton::None` in the case of pointer-move.
+    pub button: MouseButton,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+    /// The normalized pressure of the pointer, from `0.0` to `1.0`.
+    ///
+    /// Devices that don't report pressure use `0.5` while in contact and `0.0` otherwise.
+    pub pressure: f64,
+    /// The tilt of a pen, in degrees from the surface's normal.
+    ///
+    /// The `x` component is the tilt towards the right edge of the screen and
+    /// the `y` component the tilt towards the bottom edge, both between `-90.0`
+    /// and `90.0`. This is zero for devices that don't report tilt.
+    pub tilt: Vec2,
+}
+
+impl PointerEvent {
+    /// The pressure used for devices that don't report it, while in contact.
+    pub const DEFAULT_PRESSURE: f64 = 0.5;
+
+    /// The event that reports this pointer as the mouse, with the given click count.
+    #[allow(dead_code)] // Not yet used on all platforms.
+    pub(crate) fn to_mouse_event(&self, count: u8) -> MouseEvent {
+        MouseEvent {
+            pos: self.pos,
+            buttons: self.buttons,
+            mods: self.mods,
+            count,
+            focus: false,
+            button: self.button,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+}
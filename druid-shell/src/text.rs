@@ -458,10 +458,21 @@ pub fn simulate_input<H: WinHandler + ?Sized>(
         None => return false,
     };
     let mut input_handler = handler.acquire_input_lock(token, true);
-    match event.key {
+    let handled = simulate_text_input(&mut *input_handler, &event);
+    handler.release_input_lock(token);
+    handled
+}
+
+/// Applies the editing that a keypress `event` stands for to a text field,
+/// through a mutable lock on its `InputHandler`.
+///
+/// Returns `false` if the key doesn't edit text; this is what [`simulate_input`]
+/// does after the [`WinHandler`] didn't handle the key.
+pub fn simulate_text_input(input_handler: &mut dyn InputHandler, event: &KeyEvent) -> bool {
+    match &event.key {
         KbKey::Character(c) if !event.mods.ctrl() && !event.mods.meta() && !event.mods.alt() => {
             let selection = input_handler.selection();
-            input_handler.replace_range(selection.range(), &c);
+            input_handler.replace_range(selection.range(), c);
             let new_caret_index = selection.min() + c.len();
             input_handler.set_selection(Selection::caret(new_caret_index));
         }
@@ -578,12 +589,8 @@ pub fn simulate_input<H: WinHandler + ?Sized>(
                 input_handler.handle_action(Action::Move(movement));
             }
         }
-        _ => {
-            handler.release_input_lock(token);
-            return false;
-        }
+        _ => return false,
     };
-    handler.release_input_lock(token);
     true
 }
 
@@ -10,11 +10,13 @@ use crate::application::Application;
 use crate::backend::window as backend;
 use crate::common_util::Counter;
 use crate::dialog::{FileDialogOptions, FileInfo};
+use crate::drop::DropEvent;
 use crate::error::Error;
 use crate::keyboard::KeyEvent;
 use crate::kurbo::{Insets, Point, Rect, Size};
 use crate::menu::Menu;
 use crate::mouse::{Cursor, CursorDesc, MouseEvent};
+use crate::pointer::PointerEvent;
 use crate::region::Region;
 use crate::scale::Scale;
 use crate::text::{Event, InputHandler};
@@ -700,6 +702,61 @@ pub trait WinHandler {
     /// Called when the mouse cursor has left the application window
     fn mouse_leave(&mut self) {}
 
+    /// Called when a finger touches the window, or a pen presses it or one of its buttons.
+    ///
+    /// The [primary] pointer is also reported through [`mouse_down`].
+    ///
+    /// [primary]: PointerEvent::is_primary
+    /// [`mouse_down`]: WinHandler::mouse_down
+    #[allow(unused_variables)]
+    fn pointer_down(&mut self, event: &PointerEvent) {}
+
+    /// Called when a touch or pen pointer moves.
+    #[allow(unused_variables)]
+    fn pointer_move(&mut self, event: &PointerEvent) {}
+
+    /// Called when a finger is lifted, or a pen or one of its buttons is released.
+    #[allow(unused_variables)]
+    fn pointer_up(&mut self, event: &PointerEvent) {}
+
+    /// Called when the platform stops reporting a pointer without it being
+    /// released, for example because a system gesture took over.
+    #[allow(unused_variables)]
+    fn pointer_cancel(&mut self, event: &PointerEvent) {}
+
+    /// Called when data dragged from another application enters the window.
+    ///
+    /// Return `true` if the data would be accepted if it were dropped at this
+    /// position; the platform uses this to give feedback to the user.
+    #[allow(unused_variables)]
+    fn drag_enter(&mut self, event: &DropEvent) -> bool {
+        false
+    }
+
+    /// Called when data dragged from another application moves over the window.
+    ///
+    /// Return `true` if the data would be accepted if it were dropped at this position.
+    #[allow(unused_variables)]
+    fn drag_over(&mut self, event: &DropEvent) -> bool {
+        false
+    }
+
+    /// Called when data dragged from another application leaves the window, or
+    /// the drag is cancelled.
+    ///
+    /// On some platforms this is also called right before [`drag_drop`].
+    ///
+    /// [`drag_drop`]: WinHandler::drag_drop
+    fn drag_leave(&mut self) {}
+
+    /// Called when data dragged from another application is dropped on the window.
+    ///
+    /// Return `true` if the data was accepted.
+    #[allow(unused_variables)]
+    fn drag_drop(&mut self, event: &DropEvent) -> bool {
+        false
+    }
+
     /// Called on timer event.
     ///
     /// This is called at (approximately) the requested deadline by a
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! An example of an animating widget. It is just a widget that
-//! requests an animation frame when it needs to, and draws the frame in the
-//! `paint` method.
+//! requests an animation frame when it needs to, advances a `Tween` by the
+//! frame interval, and draws the frame in the `paint` method.
 //! Once the animation is over it simply stops requesting animation frames.
 //! Usually we would put the state in the `Data`, but for things like animation
 //! we don't. This is because the animation state is not useful to know for the
@@ -14,32 +14,33 @@
 #![windows_subsystem = "windows"]
 
 use std::f64::consts::PI;
+use std::time::Duration;
 
+use druid::animation::{Animation, Easing, Tween};
 use druid::kurbo::{Circle, Line};
 use druid::widget::prelude::*;
 use druid::{AppLauncher, Color, LocalizedString, Point, Vec2, WindowDesc};
 
 struct AnimWidget {
-    t: f64,
+    t: Tween<f64>,
+}
+
+fn spin() -> Tween<f64> {
+    Tween::new(0.0, 1.0, Duration::from_secs(1)).with_easing(Easing::EaseInOut)
 }
 
 impl Widget<()> for AnimWidget {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut (), _env: &Env) {
         match event {
             Event::MouseDown(_) => {
-                self.t = 0.0;
+                self.t.restart();
                 ctx.request_anim_frame();
             }
             Event::AnimFrame(interval) => {
                 ctx.request_paint();
-                self.t += (*interval as f64) * 1e-9;
-                if self.t < 1.0 {
+                self.t.advance(*interval);
+                if !self.t.is_finished() {
                     ctx.request_anim_frame();
-                } else {
-                    // We might have t>1.0 at the end of the animation,
-                    // we want to make sure the line points up at the
-                    // end of the animation.
-                    self.t = 0.0;
                 }
             }
             _ => (),
@@ -61,7 +62,7 @@ impl Widget<()> for AnimWidget {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &(), _env: &Env) {
-        let t = self.t;
+        let t = self.t.value();
         let center = Point::new(50.0, 50.0);
         ctx.paint_with_z_index(1, move |ctx| {
             let ambit = center + 45.0 * Vec2::from_angle((0.75 + t) * 2.0 * PI);
@@ -73,7 +74,7 @@ impl Widget<()> for AnimWidget {
 }
 
 pub fn main() {
-    let window = WindowDesc::new(AnimWidget { t: 0.0 }).title(
+    let window = WindowDesc::new(AnimWidget { t: spin() }).title(
         LocalizedString::new("anim-demo-window-title")
             .with_placeholder("You spin me right round..."),
     );
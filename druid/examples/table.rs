@@ -0,0 +1,86 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Demos the table widget with sorting, column resizing and row selection.
+
+// On Windows platform, don't show a console when opening the app.
+#![windows_subsystem = "windows"]
+
+use std::sync::Arc;
+
+use druid::widget::{ColumnWidth, Flex, Label, Table, TableColumn, TableState};
+use druid::{AppLauncher, Data, Lens, LocalizedString, Widget, WidgetExt, WindowDesc};
+
+#[derive(Clone, Data, Lens)]
+struct Planet {
+    name: String,
+    moons: u32,
+    radius_km: u32,
+}
+
+#[derive(Clone, Data, Lens)]
+struct AppData {
+    planets: Arc<Vec<Planet>>,
+    table: TableState,
+}
+
+pub fn main() {
+    let main_window = WindowDesc::new(ui_builder())
+        .title(LocalizedString::new("table-demo-window-title").with_placeholder("Table Demo"));
+    let planets = [
+        ("Mercury", 0, 2440),
+        ("Venus", 0, 6052),
+        ("Earth", 1, 6371),
+        ("Mars", 2, 3390),
+        ("Jupiter", 95, 69911),
+        ("Saturn", 146, 58232),
+        ("Uranus", 28, 25362),
+        ("Neptune", 16, 24622),
+    ];
+    let data = AppData {
+        planets: Arc::new(
+            planets
+                .iter()
+                .map(|(name, moons, radius_km)| Planet {
+                    name: name.to_string(),
+                    moons: *moons,
+                    radius_km: *radius_km,
+                })
+                .collect(),
+        ),
+        table: TableState::default(),
+    };
+    AppLauncher::with_window(main_window)
+        .log_to_console()
+        .launch(data)
+        .expect("launch failed");
+}
+
+fn ui_builder() -> impl Widget<AppData> {
+    let table = Table::new()
+        .with_column(
+            TableColumn::with_lens("Name", Planet::name)
+                .with_width(ColumnWidth::Flex(2.0))
+                .sort_by(|a, b| a.name.cmp(&b.name)),
+        )
+        .with_column(
+            TableColumn::with_lens("Moons", Planet::moons).sort_by(|a, b| a.moons.cmp(&b.moons)),
+        )
+        .with_column(
+            TableColumn::with_lens("Radius (km)", Planet::radius_km)
+                .with_width(ColumnWidth::Fixed(120.))
+                .sort_by(|a, b| a.radius_km.cmp(&b.radius_km)),
+        )
+        .lens((AppData::planets, AppData::table));
+
+    let selection = Label::dynamic(|data: &AppData, _| match data.table.selection {
+        Some(index) => format!("Selected: {}", data.planets[index].name),
+        None => "Click a row to select it".to_string(),
+    });
+
+    Flex::column()
+        .with_flex_child(table, 1.0)
+        .with_spacer(8.0)
+        .with_child(selection)
+        .padding(8.0)
+}
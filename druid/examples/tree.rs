@@ -0,0 +1,131 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Demos the tree widget, with children that are loaded when a node is first expanded.
+
+// On Windows platform, don't show a console when opening the app.
+#![windows_subsystem = "windows"]
+
+use std::sync::Arc;
+
+use druid::widget::{Flex, Label, Scroll, Tree, TreeNode, TreeState};
+use druid::{
+    AppDelegate, AppLauncher, Command, Data, DelegateCtx, Env, Handled, Lens, LocalizedString,
+    Target, Widget, WidgetExt, WindowDesc,
+};
+
+/// A number, whose children are its multiples up to ten times.
+#[derive(Clone, Data, Lens)]
+struct Number {
+    value: u64,
+    expanded: bool,
+    children: Option<Arc<Vec<Number>>>,
+}
+
+#[derive(Clone, Data, Lens)]
+struct AppData {
+    root: Number,
+    tree: TreeState,
+}
+
+impl Number {
+    fn new(value: u64) -> Self {
+        Number {
+            value,
+            expanded: false,
+            children: None,
+        }
+    }
+
+    fn at_path(&mut self, path: &[usize]) -> Option<&mut Number> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => Arc::make_mut(self.children.as_mut()?)
+                .get_mut(*first)?
+                .at_path(rest),
+        }
+    }
+}
+
+impl TreeNode for Number {
+    fn children_count(&self) -> usize {
+        self.children.as_ref().map_or(0, |children| children.len())
+    }
+
+    fn get_child(&self, index: usize) -> &Self {
+        &self.children.as_ref().unwrap()[index]
+    }
+
+    fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Self, usize)) {
+        let children = self.children.as_mut().unwrap();
+        let mut child = children[index].clone();
+        cb(&mut child, index);
+        if !child.same(&children[index]) {
+            Arc::make_mut(children)[index] = child;
+        }
+    }
+
+    fn is_branch(&self) -> bool {
+        true
+    }
+
+    fn needs_loading(&self) -> bool {
+        self.children.is_none()
+    }
+}
+
+struct Delegate;
+
+impl AppDelegate<AppData> for Delegate {
+    fn command(
+        &mut self,
+        _ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        data: &mut AppData,
+        _env: &Env,
+    ) -> Handled {
+        if let Some(path) = cmd.get(Tree::LOAD_CHILDREN) {
+            if let Some(node) = data.root.at_path(path) {
+                let children = (2..=10).map(|n| Number::new(node.value * n)).collect();
+                node.children = Some(Arc::new(children));
+            }
+            Handled::Yes
+        } else {
+            Handled::No
+        }
+    }
+}
+
+pub fn main() {
+    let main_window = WindowDesc::new(ui_builder())
+        .title(LocalizedString::new("tree-demo-window-title").with_placeholder("Tree Demo"));
+    let data = AppData {
+        root: Number::new(1),
+        tree: TreeState::default(),
+    };
+    AppLauncher::with_window(main_window)
+        .delegate(Delegate)
+        .log_to_console()
+        .launch(data)
+        .expect("launch failed");
+}
+
+fn ui_builder() -> impl Widget<AppData> {
+    let tree = Tree::new(
+        || Label::dynamic(|number: &Number, _| number.value.to_string()),
+        Number::expanded,
+    )
+    .lens((AppData::root, AppData::tree));
+
+    let selection = Label::dynamic(|data: &AppData, _| match &data.tree.selection {
+        Some(path) => format!("Selected: {path:?}"),
+        None => "Click a number to select it".to_string(),
+    });
+
+    Flex::column()
+        .with_flex_child(Scroll::new(tree).vertical().expand_width(), 1.0)
+        .with_spacer(8.0)
+        .with_child(selection)
+        .padding(8.0)
+}
@@ -0,0 +1,228 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Values that animate when they change.
+
+use std::time::Duration;
+
+use super::{Animation, Easing, Interpolate, Spring, SpringParams, Tween};
+use crate::Data;
+
+/// How an [`Animated`] value moves to a new target.
+#[derive(Debug, Clone, Copy)]
+enum Motion {
+    Tween(u64, Easing),
+    Spring(SpringParams),
+}
+
+#[derive(Debug, Clone)]
+enum Running<T> {
+    Tween(Tween<T>),
+    Spring(Spring<T>),
+}
+
+/// A value that animates towards its target whenever the target changes.
+///
+/// This is meant to be kept in a widget, to show some part of the widget's
+/// [`Data`] smoothly changing instead of jumping. Set the target in
+/// [`Widget::update`], and advance the animation on [`Event::AnimFrame`]:
+///
+/// ```
+/// use druid::animation::Animated;
+/// use druid::widget::prelude::*;
+/// use druid::Color;
+///
+/// struct Meter {
+///     level: Animated<f64>,
+/// }
+///
+/// impl Widget<f64> for Meter {
+///     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut f64, _env: &Env) {
+///         if let Event::AnimFrame(interval) = event {
+///             self.level.advance(*interval);
+///             if self.level.is_running() {
+///                 ctx.request_anim_frame();
+///             }
+///             ctx.request_paint();
+///         }
+///     }
+///
+///     fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &f64, _env: &Env) {
+///         if let LifeCycle::WidgetAdded = event {
+///             self.level.jump_to(*data);
+///         }
+///     }
+///
+///     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, data: &f64, _env: &Env) {
+///         if self.level.animate_to(*data) {
+///             ctx.request_anim_frame();
+///         }
+///     }
+///
+///     fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &f64, _env: &Env) -> Size {
+///         bc.constrain((100.0, 10.0))
+///     }
+///
+///     fn paint(&mut self, ctx: &mut PaintCtx, _data: &f64, _env: &Env) {
+///         let size = ctx.size();
+///         let rect = Size::new(size.width * self.level.value(), size.height).to_rect();
+///         ctx.fill(rect, &Color::WHITE);
+///     }
+/// }
+/// ```
+///
+/// [`Widget::update`]: crate::Widget::update
+/// [`Event::AnimFrame`]: crate::Event::AnimFrame
+#[derive(Debug, Clone)]
+pub struct Animated<T> {
+    value: T,
+    target: T,
+    motion: Motion,
+    running: Option<Running<T>>,
+}
+
+impl<T: Interpolate + Data> Animated<T> {
+    /// The default duration of the animation.
+    pub const DEFAULT_DURATION: Duration = Duration::from_millis(250);
+
+    /// Create a new animated value, resting at `value`.
+    ///
+    /// Changes are animated over [`DEFAULT_DURATION`](Self::DEFAULT_DURATION)
+    /// with [`Easing::EaseInOut`].
+    pub fn new(value: T) -> Self {
+        Animated {
+            target: value.clone(),
+            value,
+            motion: Motion::Tween(Self::DEFAULT_DURATION.as_nanos() as u64, Easing::default()),
+            running: None,
+        }
+    }
+
+    /// Builder-style method to animate changes with a tween of the given
+    /// duration and easing curve.
+    pub fn with_tween(mut self, duration: Duration, easing: Easing) -> Self {
+        self.motion = Motion::Tween(duration.as_nanos() as u64, easing);
+        self
+    }
+
+    /// Builder-style method to animate changes with a spring.
+    pub fn with_spring(mut self, params: SpringParams) -> Self {
+        self.motion = Motion::Spring(params);
+        self
+    }
+
+    /// The current value.
+    pub fn value(&self) -> T {
+        self.value.clone()
+    }
+
+    /// The value being animated towards.
+    ///
+    /// When the animation is not running, this is the same as the current value.
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    /// Returns `true` if the value is still moving towards its target.
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Start animating from the current value to `target`.
+    ///
+    /// Returns `true` if an animation was started, which is the case if
+    /// `target` is different from the current target. The caller should
+    /// then request an animation frame.
+    pub fn animate_to(&mut self, target: T) -> bool {
+        if self.target.same(&target) {
+            return false;
+        }
+        self.target = target.clone();
+        self.running = Some(match (self.motion, self.running.take()) {
+            (Motion::Spring(_), Some(Running::Spring(mut spring))) => {
+                spring.retarget(target);
+                Running::Spring(spring)
+            }
+            (Motion::Spring(params), _) => {
+                Running::Spring(Spring::new(self.value.clone(), target).with_params(params))
+            }
+            (Motion::Tween(duration, easing), _) => Running::Tween(
+                Tween::new(self.value.clone(), target, Duration::from_nanos(duration))
+                    .with_easing(easing),
+            ),
+        });
+        true
+    }
+
+    /// Set the value immediately, without animating.
+    pub fn jump_to(&mut self, value: T) {
+        self.target = value.clone();
+        self.value = value;
+        self.running = None;
+    }
+
+    /// Advance the animation by `nanos` nanoseconds, the interval of an
+    /// [`Event::AnimFrame`](crate::Event::AnimFrame).
+    pub fn advance(&mut self, nanos: u64) {
+        let finished = match &mut self.running {
+            None => return,
+            Some(Running::Tween(tween)) => {
+                tween.advance(nanos);
+                self.value = tween.value();
+                tween.is_finished()
+            }
+            Some(Running::Spring(spring)) => {
+                spring.advance(nanos);
+                self.value = spring.value();
+                spring.is_finished()
+            }
+        };
+        if finished {
+            self.value = self.target.clone();
+            self.running = None;
+        }
+    }
+}
+
+impl<T: Interpolate + Data + Default> Default for Animated<T> {
+    fn default() -> Self {
+        Animated::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use test_log::test;
+
+    #[test]
+    fn animates_changes() {
+        let mut animated =
+            Animated::new(0.0).with_tween(Duration::from_millis(100), Easing::Linear);
+        assert!(!animated.animate_to(0.0));
+        assert!(animated.animate_to(10.0));
+        animated.advance(50_000_000);
+        assert_eq!(animated.value(), 5.0);
+        assert_eq!(*animated.target(), 10.0);
+
+        // Retargeting continues from the current value.
+        assert!(animated.animate_to(0.0));
+        animated.advance(50_000_000);
+        assert_eq!(animated.value(), 2.5);
+        animated.advance(50_000_000);
+        assert!(!animated.is_running());
+        assert_eq!(animated.value(), 0.0);
+    }
+
+    #[test]
+    fn spring_settles_on_target() {
+        let mut animated = Animated::new(Color::BLACK).with_spring(SpringParams::STIFF);
+        animated.animate_to(Color::WHITE);
+        for _ in 0..120 {
+            animated.advance(16_666_667);
+        }
+        assert!(!animated.is_running());
+        assert!(animated.value().same(&Color::WHITE));
+    }
+}
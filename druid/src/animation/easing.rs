@@ -0,0 +1,165 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Easing curves.
+
+/// A curve that maps the linear progress of an animation to eased progress.
+///
+/// Both the input and the output are usually in the range `0.0..=1.0`, but
+/// some curves, such as [`Easing::BackOut`], overshoot past `1.0` before
+/// settling.
+///
+/// The named cubic curves match the CSS `ease`, `ease-in`, `ease-out` and
+/// `ease-in-out` timing functions.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+    /// Progress at a constant rate.
+    Linear,
+    /// Start slowly, speed up and then slow down; the CSS `ease` curve.
+    Ease,
+    /// Start slowly and end at full speed.
+    EaseIn,
+    /// Start at full speed and slow down at the end.
+    EaseOut,
+    /// Start and end slowly.
+    #[default]
+    EaseInOut,
+    /// Overshoot the end slightly before settling.
+    BackOut,
+    /// Bounce against the end a few times before settling.
+    BounceOut,
+    /// A cubic Bézier curve from `(0, 0)` to `(1, 1)` with the two given
+    /// control points `(x1, y1)` and `(x2, y2)`, like the CSS `cubic-bezier`
+    /// function.
+    ///
+    /// The x coordinates of the control points should be within `0.0..=1.0`.
+    CubicBezier(f64, f64, f64, f64),
+    /// Jump between the given number of discrete steps.
+    Steps(u32),
+    /// A custom curve.
+    Custom(fn(f64) -> f64),
+}
+
+impl Easing {
+    /// Map the linear progress `t` to eased progress.
+    ///
+    /// `t` is clamped to `0.0..=1.0`.
+    pub fn eval(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::BackOut => {
+                const C1: f64 = 1.70158;
+                const C3: f64 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+            Easing::BounceOut => bounce_out(t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            Easing::Steps(0) => t,
+            Easing::Steps(steps) => {
+                let steps = steps as f64;
+                (t * steps).floor().min(steps) / steps
+            }
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+/// Evaluate the y coordinate of a cubic Bézier timing curve at `x`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    // Polynomial coefficients of one coordinate of the curve, given the
+    // coordinates of the two control points.
+    let coefficients = |p1: f64, p2: f64| {
+        let c = 3.0 * p1;
+        let b = 3.0 * (p2 - p1) - c;
+        let a = 1.0 - c - b;
+        (a, b, c)
+    };
+    let (ax, bx, cx) = coefficients(x1, x2);
+    let (ay, by, cy) = coefficients(y1, y2);
+    let sample_x = |s: f64| ((ax * s + bx) * s + cx) * s;
+    let sample_dx = |s: f64| (3.0 * ax * s + 2.0 * bx) * s + cx;
+
+    // Newton's method usually converges in a few iterations ...
+    let mut s = x;
+    for _ in 0..8 {
+        let error = sample_x(s) - x;
+        if error.abs() < 1e-7 {
+            return ((ay * s + by) * s + cy) * s;
+        }
+        let dx = sample_dx(s);
+        if dx.abs() < 1e-6 {
+            break;
+        }
+        s -= error / dx;
+    }
+
+    // ... but falls back to bisection where the slope is too flat.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = x;
+    while hi - lo > 1e-7 {
+        if sample_x(s) < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    ((ay * s + by) * s + cy) * s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn curves_start_and_end() {
+        let curves = [
+            Easing::Linear,
+            Easing::Ease,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::BackOut,
+            Easing::BounceOut,
+            Easing::CubicBezier(0.1, 0.7, 1.0, 0.1),
+            Easing::Steps(4),
+        ];
+        for curve in curves {
+            assert!(curve.eval(0.0).abs() < 1e-6, "{curve:?}");
+            assert!((curve.eval(1.0) - 1.0).abs() < 1e-6, "{curve:?}");
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_shape() {
+        // A symmetric curve passes through the middle.
+        assert!((Easing::EaseInOut.eval(0.5) - 0.5).abs() < 1e-6);
+        assert!(Easing::EaseIn.eval(0.25) < 0.25);
+        assert!(Easing::EaseOut.eval(0.25) > 0.25);
+        assert!(Easing::BackOut.eval(0.7) > 1.0);
+        assert_eq!(Easing::Steps(4).eval(0.3), 0.25);
+    }
+}
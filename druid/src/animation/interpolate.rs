@@ -0,0 +1,133 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interpolation between values.
+
+use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
+use crate::Color;
+
+/// A value that can be smoothly interpolated between two end points.
+///
+/// This is implemented for the numeric types and for the geometry types
+/// and [`Color`].
+pub trait Interpolate: Clone {
+    /// Return the value a fraction `t` of the way from `self` to `other`.
+    ///
+    /// `t` is `0.0` at `self` and `1.0` at `other`. It may fall slightly
+    /// outside of that range, for easing curves and springs that overshoot.
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        lerp(*self, *other, t)
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        lerp(*self as f64, *other as f64, t) as f32
+    }
+}
+
+macro_rules! impl_interpolate_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                /// Integers are rounded to the nearest value, and saturate at
+                /// the bounds of the type.
+                fn interpolate(&self, other: &Self, t: f64) -> Self {
+                    lerp(*self as f64, *other as f64, t).round() as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Interpolate for Point {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Size {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Size::new(
+            lerp(self.width, other.width, t),
+            lerp(self.height, other.height, t),
+        )
+    }
+}
+
+impl Interpolate for Rect {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Rect::new(
+            lerp(self.x0, other.x0, t),
+            lerp(self.y0, other.y0, t),
+            lerp(self.x1, other.x1, t),
+            lerp(self.y1, other.y1, t),
+        )
+    }
+}
+
+impl Interpolate for Insets {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Insets::new(
+            lerp(self.x0, other.x0, t),
+            lerp(self.y0, other.y0, t),
+            lerp(self.x1, other.x1, t),
+            lerp(self.y1, other.y1, t),
+        )
+    }
+}
+
+impl Interpolate for Color {
+    /// Colors are interpolated component-wise in RGBA space.
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let (r0, g0, b0, a0) = self.as_rgba();
+        let (r1, g1, b1, a1) = other.as_rgba();
+        Color::rgba(
+            lerp(r0, r1, t),
+            lerp(g0, g1, t),
+            lerp(b0, b1, t),
+            lerp(a0, a1, t),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn interpolate_values() {
+        assert_eq!(2.0.interpolate(&4.0, 0.5), 3.0);
+        assert_eq!(0u8.interpolate(&10, 0.26), 3);
+        assert_eq!(0u8.interpolate(&255, 1.5), 255);
+        assert_eq!(
+            Point::ORIGIN.interpolate(&Point::new(10.0, 20.0), 0.5),
+            Point::new(5.0, 10.0)
+        );
+        assert_eq!(
+            Rect::ZERO.interpolate(&Rect::new(0.0, 0.0, 10.0, 10.0), 0.1),
+            Rect::new(0.0, 0.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            Color::BLACK.interpolate(&Color::WHITE, 0.5).as_rgba8(),
+            (128, 128, 128, 255)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Animation of values over time.
+//!
+//! Widgets animate by requesting an animation frame with
+//! [`request_anim_frame`], and then updating their state when they receive
+//! [`Event::AnimFrame`], which carries the time in nanoseconds since the
+//! previous frame. The types in this module take care of turning that
+//! stream of intervals into smoothly changing values:
+//!
+//! - a [`Tween`] animates between two values over a fixed duration, following
+//!   an [`Easing`] curve;
+//! - a [`Spring`] moves a value towards a target with simple physics;
+//! - a [`Timeline`] animates a value through a sequence of keyframes;
+//! - an [`Animated`] value animates towards a new target whenever it changes,
+//!   which is useful for showing changes to a widget's [`Data`].
+//!
+//! All of these work on values that implement [`Interpolate`], including the
+//! numeric types, [`Color`], [`Point`], [`Size`] and [`Rect`].
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use druid::animation::{Animation, Easing, Tween};
+//! use druid::widget::prelude::*;
+//!
+//! struct Spinner {
+//!     angle: Tween<f64>,
+//! }
+//!
+//! impl Spinner {
+//!     fn event(&mut self, ctx: &mut EventCtx, event: &Event) {
+//!         match event {
+//!             Event::MouseDown(_) => {
+//!                 self.angle = Tween::new(0.0, 360.0, Duration::from_secs(1))
+//!                     .with_easing(Easing::EaseOut);
+//!                 ctx.request_anim_frame();
+//!             }
+//!             Event::AnimFrame(interval) => {
+//!                 self.angle.advance(*interval);
+//!                 if !self.angle.is_finished() {
+//!                     ctx.request_anim_frame();
+//!                 }
+//!                 ctx.request_paint();
+//!             }
+//!             _ => (),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`request_anim_frame`]: crate::EventCtx::request_anim_frame
+//! [`Event::AnimFrame`]: crate::Event::AnimFrame
+//! [`Data`]: crate::Data
+//! [`Color`]: crate::Color
+//! [`Point`]: crate::Point
+//! [`Size`]: crate::Size
+//! [`Rect`]: crate::Rect
+
+mod animated;
+mod easing;
+mod interpolate;
+mod spring;
+mod timeline;
+mod tween;
+
+pub use animated::Animated;
+pub use easing::Easing;
+pub use interpolate::Interpolate;
+pub use spring::{Spring, SpringParams};
+pub use timeline::Timeline;
+pub use tween::Tween;
+
+/// A value that changes over time.
+///
+/// Animations are driven by the intervals of [`Event::AnimFrame`]; a widget
+/// should keep requesting animation frames until the animation is finished.
+///
+/// [`Event::AnimFrame`]: crate::Event::AnimFrame
+pub trait Animation {
+    /// The type of value that is animated.
+    type Value;
+
+    /// Advance the animation by `nanos` nanoseconds.
+    fn advance(&mut self, nanos: u64);
+
+    /// The current value of the animation.
+    fn value(&self) -> Self::Value;
+
+    /// Returns `true` if the animation has come to its end.
+    ///
+    /// Once an animation is finished, its value does not change any more.
+    fn is_finished(&self) -> bool;
+}
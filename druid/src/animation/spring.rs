@@ -0,0 +1,205 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Physically based spring animations.
+
+use super::{Animation, Interpolate};
+
+/// The longest step, in seconds, that the simulation takes at once.
+const MAX_STEP: f64 = 0.001;
+/// How close to rest, in fractions of the distance travelled, the spring
+/// has to be before it is considered finished.
+const REST_THRESHOLD: f64 = 1e-3;
+
+/// The physical parameters of a [`Spring`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpringParams {
+    /// How strongly the spring pulls towards its target.
+    pub stiffness: f64,
+    /// How strongly the motion is slowed down.
+    ///
+    /// With less damping than `2 * sqrt(stiffness * mass)` the spring
+    /// overshoots and oscillates around its target before settling.
+    pub damping: f64,
+    /// The mass attached to the spring.
+    pub mass: f64,
+}
+
+impl SpringParams {
+    /// A spring that settles quickly without overshooting.
+    pub const STIFF: SpringParams = SpringParams {
+        stiffness: 210.0,
+        damping: 29.0,
+        mass: 1.0,
+    };
+    /// A spring that overshoots its target a little.
+    pub const GENTLE: SpringParams = SpringParams {
+        stiffness: 120.0,
+        damping: 14.0,
+        mass: 1.0,
+    };
+    /// A spring that oscillates noticeably before settling.
+    pub const WOBBLY: SpringParams = SpringParams {
+        stiffness: 180.0,
+        damping: 12.0,
+        mass: 1.0,
+    };
+
+    /// Create parameters with the given stiffness and damping, and a mass of `1.0`.
+    pub const fn new(stiffness: f64, damping: f64) -> Self {
+        SpringParams {
+            stiffness,
+            damping,
+            mass: 1.0,
+        }
+    }
+}
+
+impl Default for SpringParams {
+    fn default() -> Self {
+        SpringParams::new(170.0, 26.0)
+    }
+}
+
+/// An animation that moves a value towards a target as if it were attached
+/// to it by a spring.
+///
+/// Unlike a [`Tween`], a spring has no fixed duration; it runs until it comes
+/// to rest.
+///
+/// The simulation runs on the progress from the start value to the target,
+/// so any [`Interpolate`] value can be animated.
+///
+/// [`Tween`]: super::Tween
+#[derive(Debug, Clone)]
+pub struct Spring<T> {
+    from: T,
+    to: T,
+    params: SpringParams,
+    /// Progress from `from` (`0.0`) to `to` (`1.0`).
+    position: f64,
+    /// Velocity in progress per second.
+    velocity: f64,
+    at_rest: bool,
+}
+
+impl<T: Interpolate> Spring<T> {
+    /// Create a spring that moves from `from` to `to`, with the default parameters.
+    pub fn new(from: T, to: T) -> Self {
+        Spring {
+            from,
+            to,
+            params: SpringParams::default(),
+            position: 0.0,
+            velocity: 0.0,
+            at_rest: false,
+        }
+    }
+
+    /// Builder-style method to set the physical parameters of the spring.
+    pub fn with_params(mut self, params: SpringParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Builder-style method to set the initial velocity.
+    ///
+    /// The velocity is measured in the distance between the start and the
+    /// target per second, so `1.0` would cover the whole distance in one
+    /// second if the spring did not pull.
+    pub fn with_velocity(mut self, velocity: f64) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// The value the spring is moving towards.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// The current velocity, in the distance between the start and the target per second.
+    pub fn velocity(&self) -> f64 {
+        self.velocity
+    }
+
+    /// Move the target, continuing from the current value.
+    ///
+    /// Because the velocity is relative to the distance travelled, and that
+    /// changes, the spring starts from rest again.
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.position = 0.0;
+        self.velocity = 0.0;
+        self.at_rest = false;
+    }
+}
+
+impl<T: Interpolate> Animation for Spring<T> {
+    type Value = T;
+
+    fn advance(&mut self, nanos: u64) {
+        if self.at_rest {
+            return;
+        }
+        let SpringParams {
+            stiffness,
+            damping,
+            mass,
+        } = self.params;
+        let mut remaining = nanos as f64 * 1e-9;
+        while remaining > 0.0 {
+            let dt = remaining.min(MAX_STEP);
+            let force = -stiffness * (self.position - 1.0) - damping * self.velocity;
+            self.velocity += force / mass * dt;
+            self.position += self.velocity * dt;
+            remaining -= dt;
+        }
+        if (self.position - 1.0).abs() < REST_THRESHOLD && self.velocity.abs() < REST_THRESHOLD {
+            self.position = 1.0;
+            self.velocity = 0.0;
+            self.at_rest = true;
+        }
+    }
+
+    fn value(&self) -> T {
+        if self.at_rest {
+            self.to.clone()
+        } else {
+            self.from.interpolate(&self.to, self.position)
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.at_rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn spring_comes_to_rest() {
+        let mut spring = Spring::new(0.0, 10.0).with_params(SpringParams::WOBBLY);
+        let mut overshot = false;
+        for _ in 0..600 {
+            spring.advance(16_666_667);
+            overshot |= spring.value() > 10.0;
+        }
+        assert!(overshot);
+        assert!(spring.is_finished());
+        assert_eq!(spring.value(), 10.0);
+    }
+
+    #[test]
+    fn retarget_keeps_value() {
+        let mut spring = Spring::new(0.0, 10.0);
+        spring.advance(100_000_000);
+        let value = spring.value();
+        spring.retarget(-10.0);
+        assert_eq!(spring.value(), value);
+        assert!(!spring.is_finished());
+    }
+}
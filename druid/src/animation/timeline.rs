@@ -0,0 +1,166 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sequences of animations.
+
+use std::time::Duration;
+
+use super::{Animation, Easing, Interpolate};
+
+/// A sequence of segments that animate a value through a series of
+/// keyframes.
+///
+/// # Examples
+///
+/// A value that grows, pauses, and shrinks again, forever:
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::animation::{Easing, Timeline};
+///
+/// let pulse = Timeline::new(1.0)
+///     .then(1.2, Duration::from_millis(150), Easing::EaseOut)
+///     .hold(Duration::from_millis(100))
+///     .then(1.0, Duration::from_millis(300), Easing::EaseIn)
+///     .looping();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Timeline<T> {
+    start: T,
+    segments: Vec<Segment<T>>,
+    elapsed: u64,
+    looping: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Segment<T> {
+    to: T,
+    duration: u64,
+    easing: Easing,
+}
+
+impl<T: Interpolate> Timeline<T> {
+    /// Create an empty timeline that starts at `start`.
+    pub fn new(start: T) -> Self {
+        Timeline {
+            start,
+            segments: Vec::new(),
+            elapsed: 0,
+            looping: false,
+        }
+    }
+
+    /// Builder-style method to add a segment that animates to `to`.
+    pub fn then(mut self, to: T, duration: Duration, easing: Easing) -> Self {
+        self.segments.push(Segment {
+            to,
+            duration: duration.as_nanos() as u64,
+            easing,
+        });
+        self
+    }
+
+    /// Builder-style method to add a segment that keeps the current value.
+    pub fn hold(self, duration: Duration) -> Self {
+        let value = self.end().clone();
+        self.then(value, duration, Easing::Linear)
+    }
+
+    /// Builder-style method to make the timeline start over when it ends.
+    ///
+    /// A looping timeline is never finished.
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// The total duration of one pass through the timeline.
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.total())
+    }
+
+    /// Start the timeline again from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = 0;
+    }
+
+    fn total(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+
+    fn end(&self) -> &T {
+        self.segments
+            .last()
+            .map(|segment| &segment.to)
+            .unwrap_or(&self.start)
+    }
+}
+
+impl<T: Interpolate> Animation for Timeline<T> {
+    type Value = T;
+
+    fn advance(&mut self, nanos: u64) {
+        let total = self.total();
+        self.elapsed = self.elapsed.saturating_add(nanos);
+        if self.looping && total > 0 {
+            self.elapsed %= total;
+        } else {
+            self.elapsed = self.elapsed.min(total);
+        }
+    }
+
+    fn value(&self) -> T {
+        let mut from = &self.start;
+        let mut start_time = 0;
+        for segment in &self.segments {
+            if self.elapsed < start_time + segment.duration {
+                let t = (self.elapsed - start_time) as f64 / segment.duration as f64;
+                return from.interpolate(&segment.to, segment.easing.eval(t));
+            }
+            from = &segment.to;
+            start_time += segment.duration;
+        }
+        self.end().clone()
+    }
+
+    fn is_finished(&self) -> bool {
+        !self.looping && self.elapsed >= self.total()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn segments_in_sequence() {
+        let mut timeline = Timeline::new(0.0)
+            .then(10.0, Duration::from_millis(100), Easing::Linear)
+            .hold(Duration::from_millis(100))
+            .then(0.0, Duration::from_millis(100), Easing::Linear);
+        assert_eq!(timeline.duration(), Duration::from_millis(300));
+
+        timeline.advance(50 * MS);
+        assert_eq!(timeline.value(), 5.0);
+        timeline.advance(100 * MS);
+        assert_eq!(timeline.value(), 10.0);
+        timeline.advance(100 * MS);
+        assert_eq!(timeline.value(), 5.0);
+        timeline.advance(100 * MS);
+        assert!(timeline.is_finished());
+        assert_eq!(timeline.value(), 0.0);
+    }
+
+    #[test]
+    fn looping_wraps_around() {
+        let mut timeline = Timeline::new(0.0)
+            .then(10.0, Duration::from_millis(100), Easing::Linear)
+            .looping();
+        timeline.advance(130 * MS);
+        assert!(!timeline.is_finished());
+        assert_eq!(timeline.value(), 3.0);
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Animations between two values over a fixed duration.
+
+use std::time::Duration;
+
+use super::{Animation, Easing, Interpolate};
+
+/// An animation from one value to another over a fixed duration, following
+/// an [`Easing`] curve.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::animation::{Animation, Easing, Tween};
+///
+/// let mut tween = Tween::new(0.0, 100.0, Duration::from_millis(200))
+///     .with_easing(Easing::Linear);
+/// tween.advance(50_000_000);
+/// assert_eq!(tween.value(), 25.0);
+/// tween.advance(200_000_000);
+/// assert!(tween.is_finished());
+/// assert_eq!(tween.value(), 100.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: u64,
+    elapsed: u64,
+    easing: Easing,
+}
+
+impl<T: Interpolate> Tween<T> {
+    /// Create a new tween from `from` to `to`, taking `duration`.
+    ///
+    /// The default easing curve is [`Easing::EaseInOut`].
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Tween {
+            from,
+            to,
+            duration: duration.as_nanos() as u64,
+            elapsed: 0,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Builder-style method to set the easing curve.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// The value the tween starts at.
+    pub fn start(&self) -> &T {
+        &self.from
+    }
+
+    /// The value the tween ends at.
+    pub fn end(&self) -> &T {
+        &self.to
+    }
+
+    /// The linear progress of the tween, between `0.0` and `1.0`.
+    pub fn progress(&self) -> f64 {
+        if self.duration == 0 {
+            1.0
+        } else {
+            (self.elapsed as f64 / self.duration as f64).min(1.0)
+        }
+    }
+
+    /// Start the tween again from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = 0;
+    }
+}
+
+impl<T: Interpolate> Animation for Tween<T> {
+    type Value = T;
+
+    fn advance(&mut self, nanos: u64) {
+        self.elapsed = self.elapsed.saturating_add(nanos).min(self.duration);
+    }
+
+    fn value(&self) -> T {
+        if self.is_finished() {
+            return self.to.clone();
+        }
+        let t = self.easing.eval(self.progress());
+        self.from.interpolate(&self.to, t)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
@@ -159,7 +159,7 @@ pub mod sys {
     use super::Selector;
     use crate::{
         sub_window::{SubWindowDesc, SubWindowUpdate},
-        FileDialogOptions, FileInfo, Rect, SingleUse, WidgetId, WindowConfig,
+        FileDialogOptions, FileInfo, FocusDirection, Rect, SingleUse, WidgetId, WindowConfig,
     };
 
     /// Quit the running application. This command is handled by the Druid library.
@@ -342,6 +342,17 @@ pub mod sys {
     /// [`Viewport::default_scroll_to_view_handling`]: crate::widget::Viewport::default_scroll_to_view_handling()
     pub const SCROLL_TO_VIEW: Selector<Rect> = Selector::new("druid-builtin.scroll-to");
 
+    /// Move keyboard focus to the nearest focusable widget in the given direction.
+    ///
+    /// Candidates are chosen using the layout rects of the widgets in the focus chain,
+    /// restricted to the innermost open focus trap, if any. This command is handled by
+    /// the window it is submitted to, unless a widget handles it first.
+    ///
+    /// See [`EventCtx::move_focus`] for moving focus from inside a widget.
+    ///
+    /// [`EventCtx::move_focus`]: crate::EventCtx::move_focus
+    pub const MOVE_FOCUS: Selector<FocusDirection> = Selector::new("druid-builtin.move-focus");
+
     /// A change that has occurred to text state, and needs to be
     /// communicated to the platform.
     pub(crate) struct ImeInvalidation {
@@ -5,7 +5,7 @@
 
 use std::{
     any::{Any, TypeId},
-    collections::{HashMap, VecDeque},
+    collections::VecDeque,
     ops::{Deref, DerefMut},
     rc::Rc,
     time::Duration,
@@ -13,17 +13,19 @@ use std::{
 use tracing::{error, trace, warn};
 
 use crate::commands::SCROLL_TO_VIEW;
-use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
+use crate::core::{CommandQueue, CursorChange, FocusChange, FocusDirection, WidgetState};
+use crate::drag::DragSession;
 use crate::env::KeyLike;
 use crate::menu::ContextMenu;
 use crate::piet::{Piet, PietText, RenderContext};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
+use crate::window::Timers;
 use crate::{
     commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
-    ExtEventSink, Insets, Menu, Notification, Point, Rect, Scale, SingleUse, Size, Target,
-    TimerToken, Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
+    ExtEventSink, Insets, Menu, Notification, Point, Rect, Scale, Selector, SingleUse, Size,
+    Target, TimerToken, Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -61,8 +63,10 @@ pub(crate) struct ContextState<'a> {
     /// The id of the widget that currently has focus.
     pub(crate) focus_widget: Option<WidgetId>,
     pub(crate) root_app_data_type: TypeId,
-    pub(crate) timers: &'a mut HashMap<TimerToken, WidgetId>,
+    pub(crate) timers: &'a mut Timers,
     pub(crate) text_registrations: &'a mut Vec<TextFieldRegistration>,
+    /// The drag in progress in this window, if any.
+    pub(crate) drag: &'a mut Option<DragSession>,
 }
 
 /// A mutable context provided to event handling methods of widgets.
@@ -759,6 +763,77 @@ impl EventCtx<'_, '_> {
         // TODO: plumb mouse grab through to platform (through druid-shell)
     }
 
+    /// Start dragging `payload`, identified by `selector`.
+    ///
+    /// The drag follows the mouse until the mouse button is released, and
+    /// other widgets can inspect the payload with [`drag_payload`] in the
+    /// meantime. `paint_preview` paints the preview that follows the mouse
+    /// above the window's content, with the origin at the mouse position;
+    /// `preview_bounds` is the area it covers, relative to that origin.
+    ///
+    /// This is usually done by a [`DragSource`] rather than directly.
+    ///
+    /// [`drag_payload`]: EventCtx::drag_payload
+    /// [`DragSource`]: crate::widget::DragSource
+    pub fn start_drag<P: Any>(
+        &mut self,
+        selector: Selector<P>,
+        payload: P,
+        preview_bounds: Rect,
+        paint_preview: impl Fn(&mut PaintCtx) + 'static,
+    ) {
+        trace!("start_drag {}", selector.symbol());
+        let payload = Command::new(selector, payload, Target::Auto);
+        self.start_drag_command(payload, preview_bounds, paint_preview);
+    }
+
+    /// Start dragging a payload that is already wrapped in a [`Command`].
+    pub(crate) fn start_drag_command(
+        &mut self,
+        payload: Command,
+        preview_bounds: Rect,
+        paint_preview: impl Fn(&mut PaintCtx) + 'static,
+    ) {
+        *self.state.drag = Some(DragSession {
+            source: self.widget_id(),
+            payload,
+            preview: Rc::new(paint_preview),
+            preview_bounds,
+            mouse_pos: Point::ZERO,
+        });
+    }
+
+    /// The payload of the drag in progress, wrapped in a [`Command`].
+    pub(crate) fn drag_command(&self) -> Option<Command> {
+        self.state.drag.as_ref().map(|drag| drag.payload.clone())
+    }
+
+    /// The payload of the drag in progress, if there is one identified by `selector`.
+    pub fn drag_payload<P: Any>(&self, selector: Selector<P>) -> Option<&P> {
+        self.state.drag.as_ref()?.payload.get(selector)
+    }
+
+    /// Returns `true` if a drag is in progress in this window.
+    pub fn is_dragging(&self) -> bool {
+        self.state.drag.is_some()
+    }
+
+    /// Returns `true` if a drag is in progress that was started by this widget.
+    pub fn is_drag_source(&self) -> bool {
+        self.state
+            .drag
+            .as_ref()
+            .map_or(false, |drag| drag.source == self.widget_id())
+    }
+
+    /// Cancel the drag in progress, if any.
+    ///
+    /// No widget receives the payload.
+    pub fn cancel_drag(&mut self) {
+        trace!("cancel_drag");
+        *self.state.drag = None;
+    }
+
     /// Create a new window.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
@@ -875,6 +950,26 @@ impl EventCtx<'_, '_> {
         }
     }
 
+    /// Transfer focus to the nearest focusable widget in the given direction,
+    /// based on the layout rects of the widgets in the focus chain.
+    ///
+    /// This should only be called by a widget that currently has focus.
+    ///
+    /// See [`is_focused`] for more information about focus.
+    ///
+    /// [`is_focused`]: struct.EventCtx.html#method.is_focused
+    pub fn move_focus(&mut self, direction: FocusDirection) {
+        trace!("move_focus direction={:?}", direction);
+        if self.has_focus() {
+            self.widget_state.request_focus = Some(FocusChange::Direction(direction));
+        } else {
+            warn!(
+                "move_focus can only be called by the currently \
+                            focused widget or one of its ancestors."
+            );
+        }
+    }
+
     /// Give up focus.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -1166,8 +1261,9 @@ impl<'a> ContextState<'a> {
         window: &'a WindowHandle,
         window_id: WindowId,
         focus_widget: Option<WidgetId>,
-        timers: &'a mut HashMap<TimerToken, WidgetId>,
+        timers: &'a mut Timers,
         text_registrations: &'a mut Vec<TextFieldRegistration>,
+        drag: &'a mut Option<DragSession>,
     ) -> Self {
         ContextState {
             command_queue,
@@ -1177,6 +1273,7 @@ impl<'a> ContextState<'a> {
             focus_widget,
             timers,
             text_registrations,
+            drag,
             text: window.text(),
             root_app_data_type: TypeId::of::<T>(),
         }
@@ -1190,9 +1287,7 @@ impl<'a> ContextState<'a> {
 
     fn request_timer(&mut self, widget_id: WidgetId, deadline: Duration) -> TimerToken {
         trace!("request_timer deadline={:?}", deadline);
-        let timer_token = self.window.request_timer(deadline);
-        self.timers.insert(timer_token, widget_id);
-        timer_token
+        self.timers.request(self.window, widget_id, deadline)
     }
 }
 
@@ -3,7 +3,7 @@
 
 //! The fundamental Druid types.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use tracing::{trace, trace_span, warn};
 
 use crate::bloom::Bloom;
@@ -139,6 +139,13 @@ pub struct WidgetState {
     pub(crate) update_focus_chain: bool,
 
     pub(crate) focus_chain: Vec<WidgetId>,
+    /// Explicit tab indices of widgets in `focus_chain` that are not yet
+    /// ordered by an enclosing [`FocusScope`].
+    ///
+    /// [`FocusScope`]: crate::widget::FocusScope
+    pub(crate) tab_indices: Vec<(WidgetId, u32)>,
+    /// The focus traps in this subtree, innermost first.
+    pub(crate) focus_traps: Vec<FocusTrap>,
     pub(crate) request_focus: Option<FocusChange>,
     pub(crate) children: Bloom<WidgetId>,
     pub(crate) children_changed: bool,
@@ -163,6 +170,53 @@ pub(crate) enum FocusChange {
     Next,
     /// Focus should pass to the previous focusable widget
     Previous,
+    /// Focus should pass to the nearest focusable widget in a direction
+    Direction(FocusDirection),
+}
+
+/// A direction in which keyboard focus can be moved.
+///
+/// See [`EventCtx::move_focus`] and [`commands::MOVE_FOCUS`].
+///
+/// [`EventCtx::move_focus`]: crate::EventCtx::move_focus
+/// [`commands::MOVE_FOCUS`]: crate::commands::MOVE_FOCUS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusDirection {
+    /// Towards the top of the window.
+    Up,
+    /// Towards the bottom of the window.
+    Down,
+    /// Towards the left edge of the window.
+    Left,
+    /// Towards the right edge of the window.
+    Right,
+}
+
+/// A [`FocusScope`] that keeps keyboard focus within its members.
+///
+/// [`FocusScope`]: crate::widget::FocusScope
+#[derive(Debug, Clone)]
+pub(crate) struct FocusTrap {
+    /// The id of the scope widget.
+    pub(crate) scope: WidgetId,
+    /// The focusable widgets inside the scope, in tab order.
+    pub(crate) members: Vec<WidgetId>,
+    /// The widgets inside the scope, which may include false positives.
+    pub(crate) descendants: Bloom<WidgetId>,
+}
+
+/// Stable-sort `chain` so that widgets with an explicit tab index come first, in
+/// ascending order of their index, followed by the others in tree order.
+pub(crate) fn sort_by_tab_index(chain: &mut [WidgetId], indices: &[(WidgetId, u32)]) {
+    if indices.is_empty() {
+        return;
+    }
+    // The first index given to a widget wins.
+    let indices: HashMap<WidgetId, u32> = indices.iter().rev().copied().collect();
+    chain.sort_by_key(|id| match indices.get(id) {
+        Some(index) => (false, *index),
+        None => (true, 0),
+    });
 }
 
 /// The possible cursor states for a widget.
@@ -621,7 +675,8 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         // a follow_up event should reach the widget which handled the first event.
         // in this case we dont discard events when ctx.is_handled is set but just dont set our hot
         // state to true
-        let follow_up_event = event.is_pointer_event() && self.state.has_active;
+        let follow_up_event =
+            (event.is_pointer_event() || event.pointer().is_some()) && self.state.has_active;
         if ctx.is_handled && !follow_up_event {
             // This function is called by containers to propagate an event from
             // containers to children. Non-recurse events will be invoked directly
@@ -773,6 +828,34 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     false
                 }
             }
+            Event::PointerDown(pointer_event)
+            | Event::PointerMove(pointer_event)
+            | Event::PointerUp(pointer_event)
+            | Event::PointerCancel(pointer_event) => {
+                // Unlike the mouse, pointers don't change the hot state.
+                if had_active || (!ctx.is_handled && rect.winding(pointer_event.pos) != 0) {
+                    modified_event = event.translated_pointer(-rect.origin().to_vec2());
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::ExternalDrag(drag) => {
+                let hot_changed = self.set_hot_state(
+                    ctx.state,
+                    if !ctx.is_handled { drag.pos() } else { None },
+                    data,
+                    env,
+                );
+                // Like `MouseMove`, this is also sent to widgets that just stopped being hot.
+                if self.state.is_hot || hot_changed {
+                    let drag = drag.translated(-rect.origin().to_vec2());
+                    modified_event = Some(Event::ExternalDrag(drag));
+                    true
+                } else {
+                    false
+                }
+            }
             Event::AnimFrame(_) => {
                 let r = self.state.request_anim;
                 self.state.request_anim = false;
@@ -994,6 +1077,29 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     f.call(&self.state);
                     true
                 }
+                InternalLifeCycle::DebugCollectWidgets(widgets) => {
+                    if let Some(data) = &self.old_data {
+                        widgets.enter(
+                            self.id(),
+                            self.state.layout_rect(),
+                            Rect::from_origin_size(self.state.window_origin(), self.state.size),
+                            self.inner.debug_state(data),
+                        );
+                        true
+                    } else {
+                        false
+                    }
+                }
+                InternalLifeCycle::CollectFocusRects(rects) => {
+                    if self.state.focus_chain.contains(&self.state.id) {
+                        rects.push(
+                            self.state.id,
+                            Rect::from_origin_size(self.state.window_origin(), self.state.size),
+                        );
+                    }
+                    // Only descend into subtrees that contain focusable widgets.
+                    !self.state.focus_chain.is_empty()
+                }
             },
             LifeCycle::WidgetAdded => {
                 assert!(self.old_data.is_none());
@@ -1047,6 +1153,8 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     self.state.has_focus = is_focused;
 
                     self.state.focus_chain.clear();
+                    self.state.tab_indices.clear();
+                    self.state.focus_traps.clear();
                     true
                 } else {
                     false
@@ -1119,8 +1227,16 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
                 if !self.state.is_disabled() {
                     ctx.widget_state.focus_chain.extend(&self.state.focus_chain);
+                    ctx.widget_state.tab_indices.extend(&self.state.tab_indices);
+                    ctx.widget_state
+                        .focus_traps
+                        .extend(self.state.focus_traps.iter().cloned());
                 }
             }
+            // Our descendants have been collected
+            LifeCycle::Internal(InternalLifeCycle::DebugCollectWidgets(widgets)) => {
+                widgets.leave(self.id());
+            }
             _ => (),
         }
 
@@ -1239,6 +1355,8 @@ impl WidgetState {
             request_update: false,
             request_focus: None,
             focus_chain: Vec::new(),
+            tab_indices: Vec::new(),
+            focus_traps: Vec::new(),
             children: Bloom::new(),
             children_changed: false,
             cursor_change: CursorChange::Default,
@@ -1259,6 +1377,33 @@ impl WidgetState {
             || self.is_explicitly_disabled != self.is_explicitly_disabled_new
     }
 
+    /// Give every widget added to the focus chain since `start` the tab index `index`,
+    /// unless it already has one.
+    pub(crate) fn assign_tab_index(&mut self, start: usize, index: u32) {
+        for id in &self.focus_chain[start..] {
+            if !self.tab_indices.iter().any(|(w, _)| w == id) {
+                self.tab_indices.push((*id, index));
+            }
+        }
+    }
+
+    /// Close a focus scope whose members were added to the focus chain since `start`.
+    ///
+    /// The members are ordered by their tab indices, which are consumed. If `trap` is
+    /// set, the members are recorded as a focus trap owned by `scope`.
+    pub(crate) fn close_focus_scope(&mut self, start: usize, scope: WidgetId, trap: bool) {
+        let members = &mut self.focus_chain[start..];
+        sort_by_tab_index(members, &self.tab_indices);
+        self.tab_indices.retain(|(id, _)| !members.contains(id));
+        if trap {
+            self.focus_traps.push(FocusTrap {
+                scope,
+                members: members.to_vec(),
+                descendants: self.children,
+            });
+        }
+    }
+
     /// Update to incorporate state changes from a child.
     ///
     /// This will also clear some requests in the child state.
@@ -1362,8 +1507,8 @@ mod tests {
     use crate::ext_event::ExtEventHost;
     use crate::text::ParseFormatter;
     use crate::widget::{Button, Flex, Scroll, Split, TextBox};
+    use crate::window::Timers;
     use crate::{WidgetExt, WindowHandle, WindowId};
-    use std::collections::HashMap;
     use test_log::test;
 
     const ID_1: WidgetId = WidgetId::reserved(0);
@@ -1402,16 +1547,18 @@ mod tests {
         let window = WindowHandle::default();
         let ext_host = ExtEventHost::default();
         let ext_handle = ext_host.make_sink();
-        let mut timers = Vec::new();
-        let mut text_registrations = HashMap::new();
+        let mut timers = Timers::default();
+        let mut text_registrations = Vec::new();
+        let mut drag = None;
         let mut state = ContextState::new::<Option<u32>>(
             &mut command_queue,
             &ext_handle,
             &window,
             WindowId::next(),
             None,
-            &mut text_registrations,
             &mut timers,
+            &mut text_registrations,
+            &mut drag,
         );
 
         let mut ctx = LifeCycleCtx {
@@ -1438,16 +1585,18 @@ mod tests {
         let window = WindowHandle::default();
         let ext_host = ExtEventHost::default();
         let ext_handle = ext_host.make_sink();
-        let mut timers = Vec::new();
-        let mut text_registrations = HashMap::new();
+        let mut timers = Timers::default();
+        let mut text_registrations = Vec::new();
+        let mut drag = None;
         let mut state = ContextState::new::<Option<u32>>(
             &mut command_queue,
             &ext_handle,
             &window,
             WindowId::next(),
             None,
-            &mut text_registrations,
             &mut timers,
+            &mut text_registrations,
+            &mut drag,
         );
 
         let mut ctx = EventCtx {
@@ -5,6 +5,9 @@
 
 use std::collections::HashMap;
 
+use crate::kurbo::Rect;
+use crate::WidgetId;
+
 /// A description widget and its children, clonable and comparable, meant
 /// for testing and debugging. This is extremely not optimized.
 #[derive(Default, Clone, PartialEq, Eq)]
@@ -20,6 +23,53 @@ pub struct DebugState {
     pub children: Vec<DebugState>,
 }
 
+/// The `DebugState` of the widget in a `WidgetPod`, with the pod's id and
+/// position in the widget tree.
+///
+/// The `state` includes the states of the descendants, which are also
+/// collected on their own if they are in a `WidgetPod`.
+#[derive(Debug, Clone)]
+pub(crate) struct DebugWidget {
+    pub(crate) id: WidgetId,
+    /// The index of the closest ancestor in the collected list.
+    pub(crate) parent: Option<usize>,
+    /// The position of `state` in the state of the parent, as indices into
+    /// the `children` of each level, or `None` if it isn't in there.
+    pub(crate) path: Option<Vec<usize>>,
+    pub(crate) layout_rect: Rect,
+    pub(crate) window_rect: Rect,
+    pub(crate) state: DebugState,
+}
+
+impl DebugState {
+    /// The position of the first descendant that is equal to `state`, as
+    /// indices into the `children` of each level, skipping the descendants
+    /// at the `claimed` positions and their children.
+    ///
+    /// Descendants are searched in tree order, so identical siblings are found
+    /// in the order they are claimed in.
+    pub(crate) fn find_descendant(
+        &self,
+        state: &DebugState,
+        claimed: &[&[usize]],
+    ) -> Option<Vec<usize>> {
+        let mut stack = vec![(Vec::new(), self)];
+        while let Some((path, node)) = stack.pop() {
+            if !path.is_empty() && node == state {
+                return Some(path);
+            }
+            for (index, child) in node.children.iter().enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                if !claimed.contains(&child_path.as_slice()) {
+                    stack.push((child_path, child));
+                }
+            }
+        }
+        None
+    }
+}
+
 impl std::fmt::Debug for DebugState {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         if self.other_values.is_empty() && self.children.is_empty() && self.main_value.is_empty() {
@@ -0,0 +1,40 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-app drag and drop.
+
+use std::rc::Rc;
+
+use crate::kurbo::{Point, Rect};
+use crate::{Command, PaintCtx, Selector, WidgetId};
+
+/// Sent to the widget that started a drag when the drag is cancelled with
+/// <kbd>Escape</kbd>, so that it can reset its state.
+pub(crate) const DRAG_CANCELLED: Selector = Selector::new("druid-builtin.drag-cancelled");
+
+/// A drag in progress in a window.
+///
+/// This is started with [`EventCtx::start_drag`] and lives in the window until
+/// the mouse button is released or the drag is cancelled.
+///
+/// [`EventCtx::start_drag`]: crate::EventCtx::start_drag
+pub(crate) struct DragSession {
+    /// The widget that started the drag.
+    pub(crate) source: WidgetId,
+    /// The dragged payload, stored with the selector that identifies its type.
+    pub(crate) payload: Command,
+    /// Paints the preview, with the origin at the mouse position.
+    pub(crate) preview: Rc<dyn Fn(&mut PaintCtx)>,
+    /// The area covered by the preview, relative to the mouse position.
+    pub(crate) preview_bounds: Rect,
+    /// The last known mouse position, in window coordinates.
+    pub(crate) mouse_pos: Point,
+}
+
+impl DragSession {
+    /// The area covered by the preview, in window coordinates.
+    pub(crate) fn preview_rect(&self) -> Rect {
+        // Leave some room for strokes along the edge of the bounds.
+        (self.preview_bounds + self.mouse_pos.to_vec2()).inflate(2.0, 2.0)
+    }
+}
@@ -15,7 +15,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use crate::kurbo::RoundedRectRadii;
-use crate::localization::L10nManager;
+use crate::localization::{L10nManager, LayoutDirection};
 use crate::text::FontDescriptor;
 use crate::{ArcStr, Color, Data, Insets, Point, Rect, Size};
 
@@ -97,6 +97,7 @@ pub enum Value {
     String(ArcStr),
     Font(FontDescriptor),
     RoundedRectRadii(RoundedRectRadii),
+    LayoutDirection(LayoutDirection),
     Other(Arc<dyn Any + Send + Sync>),
 }
 // ANCHOR_END: value_type
@@ -215,6 +216,16 @@ impl Env {
     /// [`WidgetExt::debug_widget`]: crate::WidgetExt::debug_widget
     pub const DEBUG_WIDGET: Key<bool> = Key::new("org.linebender.druid.built-in.debug-widget");
 
+    /// The direction in which widgets lay out their content horizontally.
+    ///
+    /// This is set from the current locale when the application starts, and
+    /// can be overridden for part of the widget tree with
+    /// [`WidgetExt::layout_direction`].
+    ///
+    /// [`WidgetExt::layout_direction`]: crate::WidgetExt::layout_direction
+    pub const LAYOUT_DIRECTION: Key<LayoutDirection> =
+        Key::new("org.linebender.druid.built-in.layout-direction");
+
     /// Gets a value from the environment, expecting it to be present.
     ///
     /// Note that the return value is a reference for "expensive" types such
@@ -426,6 +437,7 @@ impl Value {
                 | (String(_), String(_))
                 | (Font(_), Font(_))
                 | (RoundedRectRadii(_), RoundedRectRadii(_))
+                | (LayoutDirection(_), LayoutDirection(_))
         )
     }
 }
@@ -444,6 +456,7 @@ impl Debug for Value {
             Value::String(s) => write!(f, "String {s:?}"),
             Value::Font(font) => write!(f, "Font {font:?}"),
             Value::RoundedRectRadii(radius) => write!(f, "RoundedRectRadii {radius:?}"),
+            Value::LayoutDirection(direction) => write!(f, "LayoutDirection {direction:?}"),
             Value::Other(other) => write!(f, "{other:?}"),
         }
     }
@@ -505,6 +518,7 @@ impl Env {
 
     pub(crate) fn with_i10n(resources: Vec<String>, base_dir: &str) -> Self {
         let l10n = L10nManager::new(resources, base_dir);
+        let layout_direction = l10n.layout_direction();
 
         let inner = EnvImpl {
             l10n: Some(Arc::new(l10n)),
@@ -514,7 +528,8 @@ impl Env {
         let env = Env(Arc::new(inner))
             .adding(Env::DEBUG_PAINT, false)
             .adding(Env::DEBUG_WIDGET_ID, false)
-            .adding(Env::DEBUG_WIDGET, false);
+            .adding(Env::DEBUG_WIDGET, false)
+            .adding(Env::LAYOUT_DIRECTION, layout_direction);
 
         crate::theme::add_to_env(env)
     }
@@ -588,6 +603,7 @@ impl_value_type!(Insets, Insets);
 impl_value_type!(ArcStr, String);
 impl_value_type!(FontDescriptor, Font);
 impl_value_type!(RoundedRectRadii, RoundedRectRadii);
+impl_value_type!(LayoutDirection, LayoutDirection);
 
 impl<T: 'static + Send + Sync> From<Arc<T>> for Value {
     fn from(this: Arc<T>) -> Value {
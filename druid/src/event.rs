@@ -5,10 +5,11 @@
 
 use std::ops::{Add, Sub};
 
-use druid_shell::{Clipboard, KeyEvent, TimerToken};
+use druid_shell::{Clipboard, DropEvent, KeyEvent, TimerToken};
 
-use crate::kurbo::{Rect, Size};
+use crate::kurbo::{Rect, Size, Vec2};
 use crate::mouse::MouseEvent;
+use crate::pointer::PointerEvent;
 use crate::{Command, Notification, Point, Scale, WidgetId};
 
 /// An event, propagated downwards during event flow.
@@ -101,6 +102,20 @@ pub enum Event {
     MouseMove(MouseEvent),
     /// Called when the mouse wheel or trackpad is scrolled.
     Wheel(MouseEvent),
+    /// Called when a finger touches the window, or a pen is pressed against it.
+    ///
+    /// Pointer events are propagated like mouse events, to the active widgets
+    /// and to the widgets under the pointer, but they don't change the hot
+    /// status. The primary pointer is also reported with mouse events; see
+    /// [`PointerEvent`] for details.
+    PointerDown(PointerEvent),
+    /// Called when a finger or a pen moves.
+    PointerMove(PointerEvent),
+    /// Called when a finger is lifted, or a pen is released.
+    PointerUp(PointerEvent),
+    /// Called when the platform stops reporting a pointer without it being
+    /// released, for example because a system gesture took over.
+    PointerCancel(PointerEvent),
     /// Called when a key is pressed.
     KeyDown(KeyEvent),
     /// Called when a key is released.
@@ -114,6 +129,12 @@ pub enum Event {
     ///
     /// The value is a delta.
     Zoom(f64),
+    /// Called when data is dragged over the window from another application,
+    /// or dropped on it.
+    ///
+    /// Like [`Event::MouseMove`], this is propagated to the widgets under the
+    /// pointer, and updates their hot status. See [`ExternalDrag`] for details.
+    ExternalDrag(ExternalDrag),
     /// Called on a timer event.
     ///
     /// Request a timer event through [`EventCtx::request_timer`]. That will
@@ -201,6 +222,48 @@ pub enum Event {
     Internal(InternalEvent),
 }
 
+/// Data dragged over a window from another application, or from the system.
+///
+/// Widgets that would accept the data, or that accept it when it is dropped,
+/// should call [`set_handled`]; the platform uses this to show feedback to
+/// the user, and to tell the other application whether the drop succeeded.
+///
+/// [`set_handled`]: crate::EventCtx::set_handled
+#[derive(Debug, Clone)]
+pub enum ExternalDrag {
+    /// The data was dragged into the window, or moved within it.
+    ///
+    /// Only the formats of the data are known at this point.
+    Over(DropEvent),
+    /// The data was dragged out of the window, or the drag was cancelled.
+    ///
+    /// This is sent to the widgets that were under the pointer.
+    Leave,
+    /// The data was dropped.
+    ///
+    /// This carries the dropped files, or text.
+    Drop(DropEvent),
+}
+
+impl ExternalDrag {
+    /// The position of the pointer, in the receiving widget's coordinate space.
+    pub fn pos(&self) -> Option<Point> {
+        match self {
+            ExternalDrag::Over(event) | ExternalDrag::Drop(event) => Some(event.pos),
+            ExternalDrag::Leave => None,
+        }
+    }
+
+    /// The same event, with the position moved by `offset`.
+    pub(crate) fn translated(&self, offset: Vec2) -> ExternalDrag {
+        let mut drag = self.clone();
+        if let ExternalDrag::Over(event) | ExternalDrag::Drop(event) = &mut drag {
+            event.pos += offset;
+        }
+        drag
+    }
+}
+
 /// Internal events used by Druid inside [`WidgetPod`].
 ///
 /// These events are translated into regular [`Event`]s
@@ -365,6 +428,15 @@ pub enum InternalLifeCycle {
     },
     /// For testing: apply the given function on every widget.
     DebugInspectState(StateCheckFn),
+    /// For testing: collect the `DebugState`, id and layout rect of every widget.
+    ///
+    /// Every `WidgetPod` adds its widget to the provided cell, in tree order.
+    DebugCollectWidgets(DebugWidgetsCell),
+    /// Used to collect the layout rects of focusable widgets, in window coordinates.
+    ///
+    /// Every widget in the focus chain adds its rect to the provided cell. This is
+    /// used to move focus by direction.
+    CollectFocusRects(FocusRectsCell),
 }
 
 /// Information about the widget's surroundings.
@@ -423,11 +495,16 @@ impl Event {
             | Event::MouseUp(_)
             | Event::MouseMove(_)
             | Event::Wheel(_)
+            | Event::PointerDown(_)
+            | Event::PointerMove(_)
+            | Event::PointerUp(_)
+            | Event::PointerCancel(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::Paste(_)
             | Event::ImeStateChange
-            | Event::Zoom(_) => false,
+            | Event::Zoom(_)
+            | Event::ExternalDrag(_) => false,
         }
     }
 
@@ -440,6 +517,29 @@ impl Event {
             Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
         )
     }
+
+    /// The touch or pen pointer of a pointer event.
+    pub fn pointer(&self) -> Option<&PointerEvent> {
+        match self {
+            Event::PointerDown(pointer)
+            | Event::PointerMove(pointer)
+            | Event::PointerUp(pointer)
+            | Event::PointerCancel(pointer) => Some(pointer),
+            _ => None,
+        }
+    }
+
+    /// A pointer event with the position moved by `offset`, or `None` for other events.
+    pub(crate) fn translated_pointer(&self, offset: Vec2) -> Option<Event> {
+        let mut pointer = self.pointer()?.clone();
+        pointer.pos += offset;
+        Some(match self {
+            Event::PointerDown(_) => Event::PointerDown(pointer),
+            Event::PointerMove(_) => Event::PointerMove(pointer),
+            Event::PointerUp(_) => Event::PointerUp(pointer),
+            _ => Event::PointerCancel(pointer),
+        })
+    }
 }
 
 impl LifeCycle {
@@ -502,10 +602,12 @@ impl InternalLifeCycle {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
             | InternalLifeCycle::RouteDisabledChanged => true,
-            InternalLifeCycle::RouteViewContextChanged { .. } => false,
+            InternalLifeCycle::RouteViewContextChanged { .. }
+            | InternalLifeCycle::CollectFocusRects(_) => false,
             InternalLifeCycle::DebugRequestState { .. }
             | InternalLifeCycle::DebugRequestDebugState { .. }
-            | InternalLifeCycle::DebugInspectState(_) => true,
+            | InternalLifeCycle::DebugInspectState(_)
+            | InternalLifeCycle::DebugCollectWidgets(_) => true,
         }
     }
 }
@@ -522,11 +624,14 @@ impl ViewContext {
     }
 }
 
-pub(crate) use state_cell::{DebugStateCell, StateCell, StateCheckFn};
+pub(crate) use state_cell::{
+    DebugStateCell, DebugWidgetsCell, FocusRectsCell, StateCell, StateCheckFn,
+};
 
 mod state_cell {
     use crate::core::WidgetState;
-    use crate::debug_state::DebugState;
+    use crate::debug_state::{DebugState, DebugWidget};
+    use crate::kurbo::Rect;
     use crate::WidgetId;
     use std::{cell::RefCell, rc::Rc};
 
@@ -538,6 +643,21 @@ mod state_cell {
     #[derive(Clone, Default)]
     pub struct DebugStateCell(Rc<RefCell<Option<DebugState>>>);
 
+    /// An interior-mutable list of the window rects of focusable widgets.
+    #[derive(Clone, Default)]
+    pub struct FocusRectsCell(Rc<RefCell<Vec<(WidgetId, Rect)>>>);
+
+    /// An interior-mutable list of the widgets in a tree, for querying them.
+    #[derive(Clone, Default)]
+    pub struct DebugWidgetsCell(Rc<RefCell<DebugWidgets>>);
+
+    #[derive(Default)]
+    struct DebugWidgets {
+        widgets: Vec<DebugWidget>,
+        /// The indices of the widgets whose descendants are being collected.
+        ancestors: Vec<usize>,
+    }
+
     #[derive(Clone)]
     pub struct StateCheckFn(Rc<dyn Fn(&WidgetState)>);
 
@@ -582,6 +702,70 @@ mod state_cell {
         }
     }
 
+    impl FocusRectsCell {
+        pub(crate) fn push(&self, widget: WidgetId, rect: Rect) {
+            self.0.borrow_mut().push((widget, rect));
+        }
+
+        pub(crate) fn take(&self) -> Vec<(WidgetId, Rect)> {
+            std::mem::take(&mut *self.0.borrow_mut())
+        }
+    }
+
+    impl DebugWidgetsCell {
+        /// Add a widget, whose descendants are added next until [`leave`] is called.
+        ///
+        /// [`leave`]: DebugWidgetsCell::leave
+        pub(crate) fn enter(
+            &self,
+            id: WidgetId,
+            layout_rect: Rect,
+            window_rect: Rect,
+            state: DebugState,
+        ) {
+            let mut inner = self.0.borrow_mut();
+            let parent = inner.ancestors.last().copied();
+            // Siblings are entered in tree order, so an identical sibling that
+            // comes first has already claimed its position.
+            let path = parent.and_then(|parent| {
+                let claimed: Vec<_> = inner
+                    .widgets
+                    .iter()
+                    .filter(|widget| widget.parent == Some(parent))
+                    .filter_map(|widget| widget.path.as_deref())
+                    .collect();
+                inner.widgets[parent]
+                    .state
+                    .find_descendant(&state, &claimed)
+            });
+            let index = inner.widgets.len();
+            inner.widgets.push(DebugWidget {
+                id,
+                parent,
+                path,
+                layout_rect,
+                window_rect,
+                state,
+            });
+            inner.ancestors.push(index);
+        }
+
+        /// Stop adding descendants to `id`, if it was entered.
+        pub(crate) fn leave(&self, id: WidgetId) {
+            let mut inner = self.0.borrow_mut();
+            if let Some(&index) = inner.ancestors.last() {
+                if inner.widgets[index].id == id {
+                    inner.ancestors.pop();
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        pub(crate) fn take(&self) -> Vec<DebugWidget> {
+            std::mem::take(&mut self.0.borrow_mut().widgets)
+        }
+    }
+
     impl StateCheckFn {
         #[cfg(not(target_arch = "wasm32"))]
         pub(crate) fn new(f: impl Fn(&WidgetState) + 'static) -> Self {
@@ -619,6 +803,18 @@ mod state_cell {
         }
     }
 
+    impl std::fmt::Debug for DebugWidgetsCell {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "DebugWidgetsCell({})", self.0.borrow().widgets.len())
+        }
+    }
+
+    impl std::fmt::Debug for FocusRectsCell {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "FocusRectsCell({})", self.0.borrow().len())
+        }
+    }
+
     impl std::fmt::Debug for StateCheckFn {
         fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
             write!(f, "StateCheckFn")
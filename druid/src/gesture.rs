@@ -0,0 +1,384 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recognizing gestures in touch and pen input.
+
+use std::f64::consts::{PI, TAU};
+use std::time::Duration;
+
+use crate::kurbo::{Point, Vec2};
+use crate::{Event, EventCtx, MouseButton, PointerId, TimerToken};
+
+/// How far a pointer can move before a press stops being a tap or a long press.
+const TAP_SLOP: f64 = 8.0;
+
+/// How long a pointer has to be held still for a long press.
+const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// The stage of a continuous gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GesturePhase {
+    /// The gesture was recognized.
+    Began,
+    /// The gesture continued.
+    Changed,
+    /// The pointers were released.
+    Ended,
+    /// The platform stopped reporting the pointers.
+    Cancelled,
+}
+
+/// A gesture recognized by a [`GestureRecognizer`].
+///
+/// Positions are in the coordinate space of the widget that received the events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gesture {
+    /// A pointer was pressed and released without moving.
+    Tap {
+        /// Where the pointer was released.
+        pos: Point,
+    },
+    /// A pointer was pressed and held without moving.
+    LongPress {
+        /// Where the pointer is held.
+        pos: Point,
+    },
+    /// A single pointer was dragged.
+    Pan {
+        /// The stage of the gesture.
+        phase: GesturePhase,
+        /// The current position of the pointer.
+        pos: Point,
+        /// How far the pointer has moved since it was pressed.
+        translation: Vec2,
+        /// How far the pointer has moved since the last event.
+        delta: Vec2,
+    },
+    /// Two pointers moved towards or away from each other.
+    Pinch {
+        /// The stage of the gesture.
+        phase: GesturePhase,
+        /// The point halfway between the pointers.
+        center: Point,
+        /// The distance between the pointers, relative to when the second was pressed.
+        scale: f64,
+    },
+    /// Two pointers rotated around each other.
+    Rotate {
+        /// The stage of the gesture.
+        phase: GesturePhase,
+        /// The point halfway between the pointers.
+        center: Point,
+        /// The clockwise rotation in radians since the second pointer was pressed,
+        /// between `-PI` and `PI`.
+        angle: f64,
+    },
+}
+
+/// A pointer that is down.
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    id: PointerId,
+    start: Point,
+    pos: Point,
+}
+
+/// What the pointers that are down are doing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum State {
+    /// Waiting for all pointers to be released.
+    #[default]
+    Idle,
+    /// One pointer is down and hasn't moved far.
+    Pressed,
+    /// One pointer was held long enough for a long press.
+    LongPressed,
+    /// One pointer is being dragged.
+    Panning,
+    /// Two pointers are down, with their distance and angle when the second was pressed.
+    Transforming {
+        distance: f64,
+        angle: f64,
+        began: bool,
+    },
+}
+
+/// A component for embedding in a widget to recognize gestures in its
+/// [pointer events].
+///
+/// Only the gestures that are enabled with the builder-style methods are
+/// recognized. Pass every event to [`event`], which returns the gestures that
+/// were recognized, if any; the [`Gestures`] controller does this for you.
+///
+/// While pointers are down the widget is kept [active], so that it receives
+/// their events even when they move outside of it. At most two pointers are
+/// tracked at a time. One pointer can make a tap, a long press or a pan; a
+/// second pointer ends a pan, and starts a pinch and a rotation.
+///
+/// The mouse is not used: it has [`Event::MouseDown`] and friends, and the
+/// primary pointer is reported as the mouse too.
+///
+/// [pointer events]: crate::PointerEvent
+/// [`event`]: GestureRecognizer::event
+/// [`Gestures`]: crate::widget::Gestures
+/// [active]: crate::EventCtx::set_active
+#[derive(Debug, Clone, Default)]
+pub struct GestureRecognizer {
+    tap: bool,
+    long_press: bool,
+    pan: bool,
+    pinch: bool,
+    rotate: bool,
+    pointers: Vec<Tracked>,
+    state: State,
+    long_press_timer: Option<TimerToken>,
+}
+
+impl GestureRecognizer {
+    /// Create a recognizer with no gestures enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to recognize [`Gesture::Tap`].
+    pub fn tap(mut self) -> Self {
+        self.tap = true;
+        self
+    }
+
+    /// Builder-style method to recognize [`Gesture::LongPress`].
+    pub fn long_press(mut self) -> Self {
+        self.long_press = true;
+        self
+    }
+
+    /// Builder-style method to recognize [`Gesture::Pan`].
+    pub fn pan(mut self) -> Self {
+        self.pan = true;
+        self
+    }
+
+    /// Builder-style method to recognize [`Gesture::Pinch`].
+    pub fn pinch(mut self) -> Self {
+        self.pinch = true;
+        self
+    }
+
+    /// Builder-style method to recognize [`Gesture::Rotate`].
+    pub fn rotate(mut self) -> Self {
+        self.rotate = true;
+        self
+    }
+
+    /// Returns `true` if any pointers are down.
+    pub fn is_tracking(&self) -> bool {
+        !self.pointers.is_empty()
+    }
+
+    /// Update the recognizer with an event, returning the gestures it completes.
+    pub fn event(&mut self, ctx: &mut EventCtx, event: &Event) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        match event {
+            Event::PointerDown(pointer)
+                if pointer.button == MouseButton::Left
+                    && self.pointers.len() < 2
+                    && !ctx.is_disabled() =>
+            {
+                self.pointer_down(ctx, pointer.pointer_id, pointer.pos, &mut gestures);
+            }
+            Event::PointerMove(pointer) => {
+                self.pointer_move(pointer.pointer_id, pointer.pos, &mut gestures);
+            }
+            Event::PointerUp(pointer) => {
+                self.pointer_up(ctx, pointer.pointer_id, pointer.pos, &mut gestures);
+            }
+            Event::PointerCancel(pointer) => {
+                self.pointer_cancel(ctx, pointer.pointer_id, &mut gestures);
+            }
+            Event::Timer(token) if self.long_press_timer == Some(*token) => {
+                self.long_press_timer = None;
+                if self.state == State::Pressed {
+                    self.state = State::LongPressed;
+                    gestures.push(Gesture::LongPress {
+                        pos: self.pointers[0].pos,
+                    });
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+        gestures
+    }
+
+    fn pointer_down(
+        &mut self,
+        ctx: &mut EventCtx,
+        id: PointerId,
+        pos: Point,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        if self.pointers.is_empty() {
+            ctx.set_active(true);
+        }
+        self.pointers.push(Tracked {
+            id,
+            start: pos,
+            pos,
+        });
+        if self.pointers.len() == 1 {
+            self.state = State::Pressed;
+            if self.long_press {
+                self.long_press_timer = Some(ctx.request_timer(LONG_PRESS_DELAY));
+            }
+            return;
+        }
+
+        self.long_press_timer = None;
+        if self.state == State::Panning {
+            gestures.push(self.pan_gesture(GesturePhase::Ended, Vec2::ZERO));
+        }
+        self.state = if self.pinch || self.rotate {
+            let (a, b) = (self.pointers[0].pos, self.pointers[1].pos);
+            State::Transforming {
+                distance: a.distance(b),
+                angle: (b - a).atan2(),
+                began: false,
+            }
+        } else {
+            State::Idle
+        };
+    }
+
+    fn pointer_move(&mut self, id: PointerId, pos: Point, gestures: &mut Vec<Gesture>) {
+        let pointer = match self.pointers.iter_mut().find(|p| p.id == id) {
+            Some(pointer) => pointer,
+            None => return,
+        };
+        let delta = pos - pointer.pos;
+        pointer.pos = pos;
+        let moved_far = pos.distance(pointer.start) > TAP_SLOP;
+
+        match self.state {
+            State::Pressed if moved_far => {
+                self.long_press_timer = None;
+                if self.pan {
+                    self.state = State::Panning;
+                    let translation = self.pointers[0].pos - self.pointers[0].start;
+                    gestures.push(self.pan_gesture(GesturePhase::Began, translation));
+                } else {
+                    self.state = State::Idle;
+                }
+            }
+            State::Panning => gestures.push(self.pan_gesture(GesturePhase::Changed, delta)),
+            State::Transforming {
+                distance,
+                angle,
+                began,
+            } => {
+                self.state = State::Transforming {
+                    distance,
+                    angle,
+                    began: true,
+                };
+                let phase = if began {
+                    GesturePhase::Changed
+                } else {
+                    GesturePhase::Began
+                };
+                self.transform_gestures(phase, gestures);
+            }
+            _ => (),
+        }
+    }
+
+    fn pointer_up(
+        &mut self,
+        ctx: &mut EventCtx,
+        id: PointerId,
+        pos: Point,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        let index = match self.pointers.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            None => return,
+        };
+        let delta = pos - self.pointers[index].pos;
+        self.pointers[index].pos = pos;
+
+        match self.state {
+            State::Pressed if self.tap => gestures.push(Gesture::Tap { pos }),
+            State::Panning => gestures.push(self.pan_gesture(GesturePhase::Ended, delta)),
+            State::Transforming { began: true, .. } => {
+                self.transform_gestures(GesturePhase::Ended, gestures);
+            }
+            _ => (),
+        }
+
+        self.pointers.remove(index);
+        self.state = State::Idle;
+        self.long_press_timer = None;
+        if self.pointers.is_empty() {
+            ctx.set_active(false);
+        }
+    }
+
+    fn pointer_cancel(&mut self, ctx: &mut EventCtx, id: PointerId, gestures: &mut Vec<Gesture>) {
+        if !self.pointers.iter().any(|p| p.id == id) {
+            return;
+        }
+        match self.state {
+            State::Panning => gestures.push(self.pan_gesture(GesturePhase::Cancelled, Vec2::ZERO)),
+            State::Transforming { began: true, .. } => {
+                self.transform_gestures(GesturePhase::Cancelled, gestures);
+            }
+            _ => (),
+        }
+
+        // The other pointer is most likely taken over as well, so we start over.
+        self.pointers.clear();
+        self.state = State::Idle;
+        self.long_press_timer = None;
+        ctx.set_active(false);
+    }
+
+    fn pan_gesture(&self, phase: GesturePhase, delta: Vec2) -> Gesture {
+        let pointer = self.pointers[0];
+        Gesture::Pan {
+            phase,
+            pos: pointer.pos,
+            translation: pointer.pos - pointer.start,
+            delta,
+        }
+    }
+
+    fn transform_gestures(&self, phase: GesturePhase, gestures: &mut Vec<Gesture>) {
+        let (start_distance, start_angle) = match self.state {
+            State::Transforming {
+                distance, angle, ..
+            } => (distance, angle),
+            _ => return,
+        };
+        let (a, b) = (self.pointers[0].pos, self.pointers[1].pos);
+        let center = a.midpoint(b);
+        if self.pinch {
+            let scale = if start_distance > 0.0 {
+                a.distance(b) / start_distance
+            } else {
+                1.0
+            };
+            gestures.push(Gesture::Pinch {
+                phase,
+                center,
+                scale,
+            });
+        }
+        if self.rotate {
+            let angle = ((b - a).atan2() - start_angle + PI).rem_euclid(TAU) - PI;
+            gestures.push(Gesture::Rotate {
+                phase,
+                center,
+                angle,
+            });
+        }
+    }
+}
@@ -37,6 +37,9 @@
 //!
 //! Most of the time, if you want to create your own lenses, you need to use
 //! [`#[derive(Lens)]`](druid_derive::Lens).
+//!
+//! The data of an enum can be accessed in a similar way with a [`Prism`], which
+//! can be derived with [`#[derive(Prism)]`](druid_derive::Prism).
 
 #[allow(clippy::module_inception)]
 #[macro_use]
@@ -44,3 +47,6 @@ mod lens;
 pub use lens::{
     Constant, Deref, Field, Identity, InArc, Index, Lens, LensExt, Map, Ref, Then, Unit,
 };
+
+mod prism;
+pub use prism::Prism;
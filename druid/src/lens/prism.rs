@@ -0,0 +1,34 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+/// A prism is a datatype that gives access to a part of a larger
+/// data structure that may not be present.
+///
+/// Where a [`Lens`] focuses on something that is always there, like the
+/// field of a struct, a prism focuses on something that is only there
+/// some of the time, like the contents of one variant of an enum.
+///
+/// The most common way to create `Prism` implementations is to use
+/// [`#[derive(Prism)]`](druid_derive::Prism) on an enum. To show a widget
+/// for whichever variant the data currently is, use a [`Matcher`].
+///
+/// [`Lens`]: super::Lens
+/// [`Matcher`]: crate::widget::Matcher
+pub trait Prism<T: ?Sized, U: ?Sized> {
+    /// Get non-mut access to the part, if it is present.
+    ///
+    /// Runs the supplied closure with a reference to the part and returns
+    /// its result, or returns `None` without running it.
+    fn with<V, F: FnOnce(&U) -> V>(&self, data: &T, f: F) -> Option<V>;
+
+    /// Get mutable access to the part, if it is present.
+    ///
+    /// Runs the supplied closure with a mutable reference to the part and
+    /// returns its result, or returns `None` without running it.
+    fn with_mut<V, F: FnOnce(&mut U) -> V>(&self, data: &mut T, f: F) -> Option<V>;
+
+    /// Returns `true` if the part is present in `data`.
+    fn matches(&self, data: &T) -> bool {
+        self.with(data, |_| ()).is_some()
+    }
+}
@@ -143,7 +143,7 @@
 
 // Allows to use macros from druid_derive in this crate
 extern crate self as druid;
-pub use druid_derive::Lens;
+pub use druid_derive::{Lens, Prism};
 
 use druid_shell as shell;
 #[doc(inline)]
@@ -160,6 +160,7 @@ pub mod lens;
 #[macro_use]
 mod util;
 
+pub mod animation;
 mod app;
 mod app_delegate;
 mod bloom;
@@ -170,12 +171,15 @@ mod core;
 mod data;
 pub mod debug_state;
 mod dialog;
+mod drag;
 pub mod env;
 mod event;
 mod ext_event;
+mod gesture;
 mod localization;
 pub mod menu;
 mod mouse;
+mod pointer;
 pub mod scroll_component;
 mod sub_window;
 #[cfg(not(target_arch = "wasm32"))]
@@ -195,16 +199,17 @@ pub use piet::{Color, ImageBuf, LinearGradient, RadialGradient, RenderContext, U
 pub use shell::image;
 pub use shell::keyboard_types;
 pub use shell::{
-    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, Error as PlatformError,
-    FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location, Modifiers, Monitor,
-    MouseButton, MouseButtons, RawMods, Region, Scalable, Scale, ScaledArea, Screen, SysMods,
-    TimerToken, WindowHandle, WindowLevel, WindowState,
+    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, DropEvent,
+    Error as PlatformError, FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location,
+    Modifiers, Monitor, MouseButton, MouseButtons, PointerId, PointerType, RawMods, Region,
+    Scalable, Scale, ScaledArea, Screen, SysMods, TimerToken, WindowHandle, WindowLevel,
+    WindowState,
 };
 
 #[cfg(feature = "raw-win-handle")]
 pub use crate::shell::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 
-pub use crate::core::{WidgetPod, WidgetState};
+pub use crate::core::{FocusDirection, WidgetPod, WidgetState};
 pub use app::{AppLauncher, WindowConfig, WindowDesc, WindowSizePolicy};
 pub use app_delegate::{AppDelegate, DelegateCtx};
 pub use box_constraints::BoxConstraints;
@@ -214,20 +219,22 @@ pub use data::*; // Wildcard because rustdoc has trouble inlining docs of two th
 pub use dialog::FileDialogOptions;
 #[doc(inline)]
 pub use env::{Env, Key, KeyOrValue, Value, ValueType, ValueTypeError};
-pub use event::{Event, InternalEvent, InternalLifeCycle, LifeCycle, ViewContext};
+pub use event::{Event, ExternalDrag, InternalEvent, InternalLifeCycle, LifeCycle, ViewContext};
 pub use ext_event::{ExtEventError, ExtEventSink};
-pub use lens::{Lens, LensExt};
-pub use localization::LocalizedString;
+pub use gesture::{Gesture, GesturePhase, GestureRecognizer};
+pub use lens::{Lens, LensExt, Prism};
+pub use localization::{LayoutDirection, LocalizedString};
 #[doc(inline)]
 pub use menu::{sys as platform_menus, Menu, MenuItem};
 pub use mouse::MouseEvent;
+pub use pointer::PointerEvent;
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
 pub use window::{Window, WindowId};
 
 #[cfg(not(target_arch = "wasm32"))]
-pub(crate) use event::{DebugStateCell, StateCell, StateCheckFn};
+pub(crate) use event::{DebugStateCell, DebugWidgetsCell, StateCell, StateCheckFn};
 
 #[doc(hidden)]
 #[deprecated(since = "0.8.0", note = "import from druid::text module instead")]
@@ -27,14 +27,14 @@ use std::{fs, io};
 
 use tracing::{debug, error, warn};
 
-use crate::{Application, ArcStr, Env};
+use crate::{Application, ArcStr, Data, Env};
 
 use fluent_bundle::{
     FluentArgs, FluentBundle, FluentError, FluentMessage, FluentResource, FluentValue,
 };
 use fluent_langneg::{negotiate_languages, NegotiationStrategy};
 use fluent_syntax::ast::Pattern as FluentPattern;
-use unic_langid::LanguageIdentifier;
+use unic_langid::{CharacterDirection, LanguageIdentifier};
 
 // Localization looks for string files in druid/resources, but this path is hardcoded;
 // it will only work if you're running an example from the druid/ directory.
@@ -53,6 +53,27 @@ pub(crate) struct L10nManager {
     current_locale: LanguageIdentifier,
 }
 
+/// The direction in which content is laid out horizontally.
+///
+/// The direction for the current locale is set in the [`Env`] as
+/// [`Env::LAYOUT_DIRECTION`], and direction-aware widgets such as [`Flex`]
+/// and [`Scroll`] mirror their layout when it is right-to-left. It can be
+/// overridden for part of the widget tree with
+/// [`WidgetExt::layout_direction`], for instance to keep a media timeline
+/// left-to-right.
+///
+/// [`Flex`]: crate::widget::Flex
+/// [`Scroll`]: crate::widget::Scroll
+/// [`WidgetExt::layout_direction`]: crate::WidgetExt::layout_direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data, Default)]
+pub enum LayoutDirection {
+    /// Content starts on the left, as in English.
+    #[default]
+    LeftToRight,
+    /// Content starts on the right, as in Arabic or Hebrew.
+    RightToLeft,
+}
+
 /// Manages a collection of localization files.
 struct ResourceManager {
     resources: HashMap<String, Arc<FluentResource>>,
@@ -165,6 +186,33 @@ impl ResourceManager {
     }
 }
 
+impl LayoutDirection {
+    /// The layout direction of the script used for `locale`.
+    fn for_locale(locale: &LanguageIdentifier) -> Self {
+        match locale.character_direction() {
+            CharacterDirection::RTL => LayoutDirection::RightToLeft,
+            _ => LayoutDirection::LeftToRight,
+        }
+    }
+
+    /// Returns `true` if this is [`RightToLeft`](LayoutDirection::RightToLeft).
+    pub fn is_rtl(self) -> bool {
+        self == LayoutDirection::RightToLeft
+    }
+
+    /// Mirror a horizontal position for this direction.
+    ///
+    /// Given the `x` position of something `width` wide in a left-to-right
+    /// layout of a container `container_width` wide, returns its position in
+    /// this direction.
+    pub fn mirror_x(self, x: f64, width: f64, container_width: f64) -> f64 {
+        match self {
+            LayoutDirection::LeftToRight => x,
+            LayoutDirection::RightToLeft => container_width - x - width,
+        }
+    }
+}
+
 impl L10nManager {
     /// Create a new localization manager.
     ///
@@ -271,6 +319,11 @@ impl L10nManager {
             Some(result.into())
         }
     }
+
+    /// The layout direction of the current locale.
+    pub(crate) fn layout_direction(&self) -> LayoutDirection {
+        LayoutDirection::for_locale(&self.current_locale)
+    }
     //TODO: handle locale change
 }
 
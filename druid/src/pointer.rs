@@ -0,0 +1,54 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Touch and pen input.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::kurbo::Point;
+use crate::shell;
+
+/// The state of a touch or pen pointer for a down, move, up, or cancel event.
+///
+/// This wraps the [`druid_shell::PointerEvent`] and derefs to it, with its
+/// `pos` in the coordinate space of the receiver. Like [`MouseEvent`], it
+/// also has the position in the window's.
+///
+/// Pointer events are sent in addition to mouse events: the [primary] pointer
+/// is also reported as the mouse, so widgets that only handle the mouse work
+/// with touch screens and pens too. Handle pointer events to support more
+/// than one finger, or to use the pressure and tilt of a pen; the
+/// [`GestureRecognizer`] does this for common gestures.
+///
+/// [`MouseEvent`]: crate::MouseEvent
+/// [primary]: druid_shell::PointerEvent::is_primary
+/// [`GestureRecognizer`]: crate::GestureRecognizer
+#[derive(Debug, Clone)]
+pub struct PointerEvent {
+    /// The event, with the position in the coordinate space of the receiver.
+    pub event: shell::PointerEvent,
+    /// The position of the pointer in the coordinate space of the window.
+    pub window_pos: Point,
+}
+
+impl Deref for PointerEvent {
+    type Target = shell::PointerEvent;
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+impl DerefMut for PointerEvent {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.event
+    }
+}
+
+impl From<shell::PointerEvent> for PointerEvent {
+    fn from(event: shell::PointerEvent) -> PointerEvent {
+        PointerEvent {
+            window_pos: event.pos,
+            event,
+        }
+    }
+}
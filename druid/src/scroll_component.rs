@@ -216,6 +216,10 @@ impl ScrollComponent {
         let bar_width = env.get(theme::SCROLLBAR_WIDTH);
         let bar_pad = env.get(theme::SCROLLBAR_PAD);
         let bar_min_size = env.get(theme::SCROLLBAR_MIN_SIZE);
+        let rtl = env
+            .try_get(Env::LAYOUT_DIRECTION)
+            .unwrap_or_default()
+            .is_rtl();
 
         let percent_visible = viewport_major / content_major;
         let percent_scrolled = axis.major_vec(scroll_offset) / (content_major - viewport_major);
@@ -231,15 +235,21 @@ impl ScrollComponent {
         #[allow(clippy::manual_clamp)] // Usable space could be below the minimum bar size.
         let length = length.max(bar_min_size).min(usable_space);
 
-        let left_x_offset = bar_pad + ((usable_space - length) * percent_scrolled).ceil();
+        let mut left_x_offset = bar_pad + ((usable_space - length) * percent_scrolled).ceil();
+        // In right-to-left layouts the vertical bar is on the left, and the
+        // horizontal bar leaves room for it there.
+        if rtl && axis == Axis::Horizontal && self.enabled.is_enabled(Axis::Vertical) {
+            left_x_offset += bar_width;
+        }
         let right_x_offset = left_x_offset + length;
 
-        let (x0, y0) = axis.pack(
-            left_x_offset,
-            axis.minor(viewport_size) - bar_width - bar_pad,
-        );
-
-        let (x1, y1) = axis.pack(right_x_offset, axis.minor(viewport_size) - bar_pad);
+        let minor_offset = if rtl && axis == Axis::Vertical {
+            bar_pad
+        } else {
+            axis.minor(viewport_size) - bar_width - bar_pad
+        };
+        let (x0, y0) = axis.pack(left_x_offset, minor_offset);
+        let (x1, y1) = axis.pack(right_x_offset, minor_offset + bar_width);
 
         if x0 >= x1 || y0 >= y1 {
             return None;
@@ -302,7 +312,15 @@ impl ScrollComponent {
 
         if let Some(mut bounds) = self.calc_vertical_bar_bounds(port, env) {
             // Stretch hitbox to edge of widget
-            bounds.x1 = scroll_offset.x + viewport_size.width;
+            if env
+                .try_get(Env::LAYOUT_DIRECTION)
+                .unwrap_or_default()
+                .is_rtl()
+            {
+                bounds.x0 = scroll_offset.x;
+            } else {
+                bounds.x1 = scroll_offset.x + viewport_size.width;
+            }
             bounds.contains(pos)
         } else {
             false
@@ -515,6 +533,7 @@ mod tests {
 
     use super::*;
     use crate::kurbo::Size;
+    use crate::LayoutDirection;
 
     const TEST_SCROLLBAR_WIDTH: f64 = 11.0;
     const TEST_SCROLLBAR_PAD: f64 = 3.0;
@@ -659,6 +678,32 @@ mod tests {
         assert_eq!(scrollbar_rect, Rect::new(86.0, 61.0, 97.0, 86.0));
     }
 
+    #[test]
+    fn scrollbar_layout_rtl() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Both;
+        let viewport = Viewport {
+            content_size: Size::new(200.0, 100.0),
+            view_origin: (0.0, 0.0).into(),
+            view_size: (100.0, 50.0).into(),
+        };
+        let env = test_env().adding(Env::LAYOUT_DIRECTION, LayoutDirection::RightToLeft);
+
+        let vertical = scroll_component
+            .calc_vertical_bar_bounds(&viewport, &env)
+            .unwrap();
+        let horizontal = scroll_component
+            .calc_horizontal_bar_bounds(&viewport, &env)
+            .unwrap();
+
+        assert_eq!(vertical, Rect::new(3.0, 3.0, 14.0, 28.0));
+        assert_eq!(
+            horizontal,
+            Rect::new(14.0, 36.0, 64.0, 47.0),
+            "horizontal scrollbar should leave space for the vertical scrollbar on the left"
+        );
+    }
+
     #[test]
     fn scrollbar_layout_min_bar_size() {
         let mut scroll_component = ScrollComponent::new();
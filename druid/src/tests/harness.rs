@@ -3,19 +3,28 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::any::TypeId;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::app::PendingWindow;
 use crate::core::{CommandQueue, WidgetState};
 use crate::ext_event::ExtEventHost;
+use crate::lens::Unit;
 use crate::piet::{BitmapTarget, Device, Error, ImageFormat, Piet};
+use crate::shell::text::{simulate_text_input, InputHandler, Selection};
+use crate::shell::{IntoKey, KeyState, TextFieldToken};
 use crate::*;
 
+use super::query::{FoundWidget, WidgetQuery};
 use crate::debug_state::DebugState;
 
 pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
 
+/// The number of mouse moves that [`Harness::drag`] sends between its start and end.
+const DRAG_STEPS: usize = 4;
+
 /// A type that tries very hard to provide a comforting and safe environment
 /// for widgets who are trying to find their way.
 ///
@@ -35,7 +44,24 @@ pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
 ///
 /// if you want those functions run you will need to call them yourself.
 ///
-/// Also, timers don't work.  ¯\_(ツ)_/¯
+/// Time is kept by a virtual clock, which only moves when you call
+/// [`advance_time`]; timers fire when the clock passes their deadline.
+/// Likewise, [`AnimFrame`] events are only sent by [`animation_frame`] and
+/// [`run_animation`], with the interval you choose.
+///
+/// The harness can hold multiple windows. Commands that open and close windows,
+/// such as those of [`new_sub_window`] and [`CLOSE_WINDOW`], are handled like
+/// the application would, and commands are routed to the windows they target.
+/// Events, layout and paint go to the window chosen with [`select_window`],
+/// which starts out as the window with the root widget.
+///
+/// [`advance_time`]: Harness::advance_time
+/// [`new_sub_window`]: EventCtx::new_sub_window
+/// [`CLOSE_WINDOW`]: commands::CLOSE_WINDOW
+/// [`select_window`]: Harness::select_window
+/// [`AnimFrame`]: Event::AnimFrame
+/// [`animation_frame`]: Harness::animation_frame
+/// [`run_animation`]: Harness::run_animation
 pub struct Harness<'a, T> {
     piet: Piet<'a>,
     mock_app: MockAppState<T>,
@@ -47,8 +73,19 @@ pub struct Harness<'a, T> {
 struct MockAppState<T> {
     data: T,
     env: Env,
-    window: Window<T>,
+    /// The open windows, in the order they were opened.
+    windows: Vec<Window<T>>,
+    /// The window that events, layout and paint go to.
+    current: WindowId,
+    delegate: Option<Box<dyn AppDelegate<T>>>,
     cmds: CommandQueue,
+    ext_host: ExtEventHost,
+    /// The time on the virtual clock, which is shared by all windows.
+    now: Duration,
+    /// The windows that were opened after the harness was created.
+    opened: Vec<WindowId>,
+    /// The windows that were closed.
+    closed: Vec<WindowId>,
 }
 
 /// A way to clean up resources when our target goes out of scope.
@@ -67,6 +104,13 @@ impl<'a> TargetGuard<'a> {
             .raw_pixels_shared()
     }
 
+    /// Turns the TargetGuard into an image, with premultiplied alpha.
+    #[allow(dead_code)]
+    pub fn into_image_buf(mut self) -> ImageBuf {
+        let mut raw_target = self.0.take().unwrap();
+        raw_target.to_image_buf(ImageFormat::RgbaPremul).unwrap()
+    }
+
     /// Saves the TargetGuard into a png
     #[allow(dead_code)]
     pub fn into_png<P: AsRef<Path>>(mut self, path: P) -> Result<(), Error> {
@@ -127,27 +171,62 @@ impl<T: Data> Harness<'_, T> {
         data: T,
         root: impl Widget<T> + 'static,
         window_size: Size,
+        harness_closure: impl FnMut(&mut Harness<T>),
+        render_context_closure: impl FnMut(TargetGuard),
+    ) {
+        Self::create_with_render_scaled(
+            data,
+            root,
+            window_size,
+            1.0,
+            harness_closure,
+            render_context_closure,
+        )
+    }
+
+    /// Like [create_with_render](Harness::create_with_render), but renders at the
+    /// given `scale`, the number of pixels per display point.
+    ///
+    /// The window keeps its `window_size` in display points; the `TargetGuard`
+    /// is `window_size * scale` pixels.
+    pub fn create_with_render_scaled(
+        data: T,
+        root: impl Widget<T> + 'static,
+        window_size: Size,
+        scale: f64,
         mut harness_closure: impl FnMut(&mut Harness<T>),
         mut render_context_closure: impl FnMut(TargetGuard),
     ) {
         let ext_host = ExtEventHost::default();
-        let ext_handle = ext_host.make_sink();
         let mut device = Device::new().expect("harness failed to get device");
+        let pixel_size = (window_size * scale).round();
         let target = device
-            .bitmap_target(window_size.width as usize, window_size.height as usize, 1.0)
+            .bitmap_target(pixel_size.width as usize, pixel_size.height as usize, scale)
             .expect("bitmap_target");
         let mut target = TargetGuard(Some(target));
         {
             let piet = target.0.as_mut().unwrap().render_context();
 
             let pending = PendingWindow::new(root);
-            let window = Window::new(WindowId::next(), Default::default(), pending, ext_handle);
+            let mut window = Window::new(
+                WindowId::next(),
+                Default::default(),
+                pending,
+                ext_host.make_sink(),
+            );
+            window.timers.virtual_clock();
 
             let mock_app = MockAppState {
                 data,
                 env: Env::with_default_i10n(),
-                window,
+                current: window.id,
+                windows: vec![window],
+                delegate: None,
                 cmds: Default::default(),
+                ext_host,
+                now: Duration::ZERO,
+                opened: Vec::new(),
+                closed: Vec::new(),
             };
 
             let mut harness = Harness {
@@ -167,12 +246,12 @@ impl<T: Data> Harness<'_, T> {
     }
 
     pub fn window(&self) -> &Window<T> {
-        &self.mock_app.window
+        self.mock_app.window()
     }
 
     #[allow(dead_code)]
     pub fn window_mut(&mut self) -> &mut Window<T> {
-        &mut self.mock_app.window
+        self.mock_app.window_mut()
     }
 
     #[allow(dead_code)]
@@ -236,10 +315,13 @@ impl<T: Data> Harness<'_, T> {
     }
 
     /// Send a command to a target.
+    ///
+    /// Commands without a target are sent to the current window.
     pub fn submit_command(&mut self, cmd: impl Into<Command>) {
-        let command = cmd.into().default_to(self.mock_app.window.id.into());
-        let event = Event::Internal(InternalEvent::TargetedCommand(command));
-        self.event(event);
+        let command = cmd.into().default_to(self.mock_app.current.into());
+        self.mock_app.handle_cmd(command);
+        self.process_commands();
+        self.update();
     }
 
     /// Send the events that would normally be sent when the app starts.
@@ -249,6 +331,69 @@ impl<T: Data> Harness<'_, T> {
         self.event(Event::WindowSize(self.window_size));
     }
 
+    /// The time that has passed on the virtual clock.
+    pub fn elapsed(&mut self) -> Duration {
+        self.mock_app.now
+    }
+
+    /// Returns `true` if there are timers that haven't fired yet, in any window.
+    #[allow(dead_code)]
+    pub fn has_pending_timers(&mut self) -> bool {
+        self.mock_app
+            .windows
+            .iter_mut()
+            .any(|window| window.timers.virtual_clock().next_deadline().is_some())
+    }
+
+    /// Move the virtual clock forward by `duration`.
+    ///
+    /// Each timer whose deadline is passed fires, in the order of their
+    /// deadlines, with the clock set to its deadline. This includes timers
+    /// that are requested while handling earlier ones, and timers of windows
+    /// other than the current one.
+    pub fn advance_time(&mut self, duration: Duration) {
+        let target = self.elapsed() + duration;
+        while let Some((window, token)) = self.mock_app.pop_due_timer(target) {
+            self.window_event(window, Event::Timer(token));
+        }
+        self.mock_app.set_now(target);
+    }
+
+    /// Returns `true` if a widget in the current window requested an animation frame.
+    pub fn wants_animation_frame(&self) -> bool {
+        self.mock_app.window().wants_animation_frame()
+    }
+
+    /// Send an [`AnimFrame`] event, if a widget requested an animation frame.
+    ///
+    /// The virtual clock is first moved forward by `interval`, which is also
+    /// the time passed to the widgets. Returns `true` if the event was sent.
+    ///
+    /// [`AnimFrame`]: Event::AnimFrame
+    pub fn animation_frame(&mut self, interval: Duration) -> bool {
+        if !self.wants_animation_frame() {
+            return false;
+        }
+        self.advance_time(interval);
+        // A timer might have completed the animation.
+        if !self.wants_animation_frame() {
+            return false;
+        }
+        self.event(Event::AnimFrame(interval.as_nanos() as u64));
+        true
+    }
+
+    /// Send animation frames `interval` apart until the widgets stop requesting
+    /// them, or `max_frames` were sent. Returns the number of frames sent.
+    #[allow(dead_code)]
+    pub fn run_animation(&mut self, interval: Duration, max_frames: usize) -> usize {
+        let mut frames = 0;
+        while frames < max_frames && self.animation_frame(interval) {
+            frames += 1;
+        }
+        frames
+    }
+
     /// Send an event to the widget.
     ///
     /// If this event triggers lifecycle events, they will also be dispatched,
@@ -256,16 +401,33 @@ impl<T: Data> Harness<'_, T> {
     ///
     /// Commands dispatched during `update` will not be sent?
     pub fn event(&mut self, event: Event) {
-        self.mock_app.event(event);
+        self.event_handled(event);
+    }
+
+    /// Like [`event`](Harness::event), returning whether a widget handled the event.
+    pub fn event_handled(&mut self, event: Event) -> Handled {
+        self.window_event(self.mock_app.current, event)
+    }
+
+    /// Send an event to the window `id`, instead of the current window.
+    ///
+    /// Like the events from the platform, this is first passed to the
+    /// [`AppDelegate`], if there is one.
+    pub fn window_event(&mut self, id: WindowId, event: Event) -> Handled {
+        let handled = self.mock_app.window_event(id, event);
         self.process_commands();
         self.update();
+        handled
     }
 
     fn process_commands(&mut self) {
         loop {
             let cmd = self.mock_app.cmds.pop_front();
             match cmd {
-                Some(cmd) => self.event(Event::Internal(InternalEvent::TargetedCommand(cmd))),
+                Some(cmd) => {
+                    self.mock_app.handle_cmd(cmd);
+                    self.update();
+                }
                 None => break,
             }
         }
@@ -277,7 +439,19 @@ impl<T: Data> Harness<'_, T> {
 
     //TODO: should we expose this? I don't think so?
     fn update(&mut self) {
-        self.mock_app.update()
+        self.mock_app.update();
+        // An `ExtEventSink` asks the application to handle its commands on idle.
+        if self.mock_app.ext_host.has_pending_items() {
+            self.process_ext_events();
+        }
+    }
+
+    fn process_ext_events(&mut self) {
+        while let Some(cmd) = self.mock_app.ext_host.recv() {
+            self.mock_app.handle_cmd(cmd);
+        }
+        self.process_commands();
+        self.update();
     }
 
     /// Only do a layout pass, without painting
@@ -308,34 +482,552 @@ impl<T: Data> Harness<'_, T> {
     }
 }
 
+/// Multiple windows.
+///
+/// Windows are opened by the commands of [`DelegateCtx::new_window`] and
+/// [`EventCtx::new_sub_window`], and closed by [`CLOSE_WINDOW`] and
+/// [`CLOSE_ALL_WINDOWS`]. A new window is sent [`WindowConnected`] and a
+/// [`WindowSize`] event with the size in its [`WindowConfig`], or the default
+/// size of the harness. As in the application, a window that is sent
+/// [`CLOSE_WINDOW`] gets [`WindowCloseRequested`], and stays open if that is
+/// handled, while [`CLOSE_ALL_WINDOWS`] closes every window right away.
+///
+/// [`CLOSE_WINDOW`]: commands::CLOSE_WINDOW
+/// [`CLOSE_ALL_WINDOWS`]: commands::CLOSE_ALL_WINDOWS
+/// [`WindowConnected`]: Event::WindowConnected
+/// [`WindowSize`]: Event::WindowSize
+/// [`WindowCloseRequested`]: Event::WindowCloseRequested
+impl<T: Data> Harness<'_, T> {
+    /// Set the [`AppDelegate`], which sees the events, commands and window
+    /// changes from now on.
+    pub fn set_delegate(&mut self, delegate: impl AppDelegate<T> + 'static) {
+        self.mock_app.delegate = Some(Box::new(delegate));
+    }
+
+    /// Send events, layout and paint to the window `id` from now on.
+    ///
+    /// Panics if the window isn't open.
+    pub fn select_window(&mut self, id: WindowId) {
+        assert!(
+            self.mock_app.get(id).is_some(),
+            "can't select {id:?}: the window isn't open"
+        );
+        self.mock_app.current = id;
+    }
+
+    /// The ids of the open windows, in the order they were opened.
+    pub fn window_ids(&self) -> Vec<WindowId> {
+        self.mock_app
+            .windows
+            .iter()
+            .map(|window| window.id)
+            .collect()
+    }
+
+    /// The windows that were opened after the harness was created, in order,
+    /// including those that were closed since.
+    pub fn opened_windows(&self) -> &[WindowId] {
+        &self.mock_app.opened
+    }
+
+    /// The windows that were closed, in order.
+    pub fn closed_windows(&self) -> &[WindowId] {
+        &self.mock_app.closed
+    }
+}
+
+/// Querying widgets.
+///
+/// These methods find widgets by their [`DebugState`], as described by a
+/// [`WidgetQuery`], so that tests can interact with them without assigning
+/// them ids in advance.
+impl<T: Data> Harness<'_, T> {
+    /// Find all widgets that match `query`, in tree order.
+    pub fn find_all(&mut self, query: &WidgetQuery) -> Vec<FoundWidget> {
+        self.run_requested_passes();
+        let widgets = DebugWidgetsCell::default();
+        self.lifecycle(LifeCycle::Internal(InternalLifeCycle::DebugCollectWidgets(
+            widgets.clone(),
+        )));
+        query.find_in(&widgets.take())
+    }
+
+    /// Find the first widget that matches `query`, in tree order.
+    pub fn find(&mut self, query: &WidgetQuery) -> Option<FoundWidget> {
+        self.find_all(query).into_iter().next()
+    }
+
+    /// Assert that exactly one widget matches `query`, and return it.
+    pub fn assert_one(&mut self, query: &WidgetQuery) -> FoundWidget {
+        self.assert_count(query, 1).remove(0)
+    }
+
+    /// Assert that no widget matches `query`.
+    pub fn assert_none(&mut self, query: &WidgetQuery) {
+        self.assert_count(query, 0);
+    }
+
+    /// Assert that exactly `count` widgets match `query`, and return them.
+    pub fn assert_count(&mut self, query: &WidgetQuery, count: usize) -> Vec<FoundWidget> {
+        let found = self.find_all(query);
+        if found.len() != count {
+            let found_states: Vec<_> = found.iter().map(|widget| &widget.state).collect();
+            panic!(
+                "expected {count} of {query}, found {}: {found_states:?}\nin {:?}",
+                found.len(),
+                self.get_root_debug_state(),
+            );
+        }
+        found
+    }
+}
+
+/// Scripted user input.
+///
+/// These methods send the events that the platform would send for common
+/// interactions, so tests don't have to build them by hand. Like the
+/// platform, they run layout and paint the invalid region afterwards, if a
+/// widget requested it.
+impl<T: Data> Harness<'_, T> {
+    /// The layout rect of a widget, in window coordinates.
+    pub fn window_rect(&mut self, widget: WidgetId) -> Rect {
+        self.run_requested_passes();
+        let state = self.get_state(widget);
+        state.layout_rect().with_origin(state.window_origin())
+    }
+
+    /// Click the center of a widget with the left mouse button.
+    ///
+    /// Panics if the widget doesn't become hot when the mouse is moved there,
+    /// for instance because another widget covers it.
+    pub fn click(&mut self, widget: WidgetId) {
+        let pos = self.window_rect(widget).center();
+        self.mouse_move(pos);
+        assert!(
+            self.get_state(widget).is_hot,
+            "can't click {widget:?}: it is not under the mouse at {pos:?}"
+        );
+        self.click_at(pos);
+    }
+
+    /// Click a point in the window with the left mouse button.
+    pub fn click_at(&mut self, pos: impl Into<Point>) {
+        let pos = pos.into();
+        let left = MouseButtons::new().with(MouseButton::Left);
+        self.mouse_move(pos);
+        self.mouse_event(Event::MouseDown, pos, left, MouseButton::Left, Vec2::ZERO);
+        self.mouse_event(
+            Event::MouseUp,
+            pos,
+            MouseButtons::new(),
+            MouseButton::Left,
+            Vec2::ZERO,
+        );
+    }
+
+    /// Move the mouse to a point in the window, with no buttons held.
+    pub fn mouse_move(&mut self, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.mouse_event(
+            Event::MouseMove,
+            pos,
+            MouseButtons::new(),
+            MouseButton::None,
+            Vec2::ZERO,
+        );
+    }
+
+    /// Press the left mouse button at `from`, move the mouse to `to` in a few
+    /// steps, and release the button there.
+    pub fn drag(&mut self, from: impl Into<Point>, to: impl Into<Point>) {
+        let (from, to) = (from.into(), to.into());
+        let left = MouseButtons::new().with(MouseButton::Left);
+        self.mouse_move(from);
+        self.mouse_event(Event::MouseDown, from, left, MouseButton::Left, Vec2::ZERO);
+        for step in 1..=DRAG_STEPS {
+            let pos = from.lerp(to, step as f64 / DRAG_STEPS as f64);
+            self.mouse_event(Event::MouseMove, pos, left, MouseButton::None, Vec2::ZERO);
+        }
+        self.mouse_event(
+            Event::MouseUp,
+            to,
+            MouseButtons::new(),
+            MouseButton::Left,
+            Vec2::ZERO,
+        );
+    }
+
+    /// Move the mouse to a point in the window, and scroll the wheel by `delta`.
+    pub fn scroll(&mut self, pos: impl Into<Point>, delta: impl Into<Vec2>) {
+        let pos = pos.into();
+        self.mouse_move(pos);
+        self.mouse_event(
+            Event::Wheel,
+            pos,
+            MouseButtons::new(),
+            MouseButton::None,
+            delta.into(),
+        );
+    }
+
+    /// Press and release a key, such as a hotkey.
+    ///
+    /// If no widget handles the key and the focused widget is a text field,
+    /// the key edits it, as it would on most platforms.
+    ///
+    /// ```ignore
+    /// harness.press_key(RawMods::Ctrl, "a");
+    /// harness.press_key(Modifiers::empty(), KbKey::Backspace);
+    /// ```
+    pub fn press_key(&mut self, mods: impl Into<Modifiers>, key: impl IntoKey) {
+        let mut event = KeyEvent::for_test(mods, key);
+        let handled = self
+            .event_handled(Event::KeyDown(event.clone()))
+            .is_handled();
+        if !handled && self.focused_text_field().is_some() {
+            self.edit_text_field(|handler| {
+                simulate_text_input(handler, &event);
+            });
+        }
+        event.state = KeyState::Up;
+        self.event(Event::KeyUp(event));
+        self.run_requested_passes();
+    }
+
+    /// Type `text` into the focused widget, one key at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' => self.press_key(Modifiers::empty(), KbKey::Enter),
+                '\t' => self.press_key(Modifiers::empty(), KbKey::Tab),
+                c => self.press_key(Modifiers::empty(), c.to_string().as_str()),
+            }
+        }
+    }
+
+    /// Compose `text` with an input method in the focused text field.
+    ///
+    /// The text replaces the previous composition, or the selection if there
+    /// is none, and is marked as the composition until [`ime_commit`].
+    ///
+    /// [`ime_commit`]: Harness::ime_commit
+    pub fn ime_compose(&mut self, text: &str) {
+        self.edit_text_field(|handler| {
+            let range = handler
+                .composition_range()
+                .unwrap_or_else(|| handler.selection().range());
+            let end = range.start + text.len();
+            handler.replace_range(range.clone(), text);
+            handler.set_composition_range((!text.is_empty()).then_some(range.start..end));
+            handler.set_selection(Selection::caret(end));
+        });
+        self.run_requested_passes();
+    }
+
+    /// Finish the composition in the focused text field, replacing it with `text`.
+    pub fn ime_commit(&mut self, text: &str) {
+        self.edit_text_field(|handler| {
+            let range = handler
+                .composition_range()
+                .unwrap_or_else(|| handler.selection().range());
+            handler.replace_range(range.clone(), text);
+            handler.set_composition_range(None);
+            handler.set_selection(Selection::caret(range.start + text.len()));
+        });
+        self.run_requested_passes();
+    }
+
+    /// Run layout and paint the invalid region, if a widget requested it.
+    pub fn run_requested_passes(&mut self) {
+        if self.mock_app.window().root.state().needs_layout {
+            self.just_layout();
+        }
+        // A window processes the commands submitted during layout on idle,
+        // and those may request another layout.
+        if !self.mock_app.cmds.is_empty() {
+            self.process_commands();
+            if self.mock_app.window().root.state().needs_layout {
+                self.just_layout();
+            }
+        }
+        if !self.window().invalid().is_empty() {
+            self.paint_invalid();
+        }
+    }
+
+    fn mouse_event(
+        &mut self,
+        kind: fn(MouseEvent) -> Event,
+        pos: Point,
+        buttons: MouseButtons,
+        button: MouseButton,
+        wheel_delta: Vec2,
+    ) {
+        let count = u8::from(button != MouseButton::None);
+        self.event(kind(MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons,
+            mods: Modifiers::default(),
+            count,
+            focus: false,
+            button,
+            wheel_delta,
+        }));
+        self.run_requested_passes();
+    }
+
+    /// Edit the focused text field through a mutable lock on its input handler.
+    ///
+    /// Panics if the focused widget isn't a text field.
+    fn edit_text_field(&mut self, f: impl FnOnce(&mut dyn InputHandler)) {
+        let token = self
+            .focused_text_field()
+            .expect("the focused widget is not a text field");
+        let window = self.mock_app.window_mut();
+        let mut handler = window.get_ime_handler(token, true);
+        f(&mut *handler);
+        drop(handler);
+        if let Some(widget) = window.release_ime_lock(token) {
+            self.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+        }
+    }
+
+    /// The token of the focused widget's text field, if it has one.
+    fn focused_text_field(&self) -> Option<TextFieldToken> {
+        let window = self.mock_app.window();
+        let focus = window.focus?;
+        window
+            .ime_handlers
+            .iter()
+            .find(|(_, reg)| reg.widget_id == focus)
+            .map(|(token, _)| *token)
+    }
+}
+
 impl<T: Data> MockAppState<T> {
-    fn event(&mut self, event: Event) {
-        self.window
-            .event(&mut self.cmds, event, &mut self.data, &self.env);
+    fn get(&self, id: WindowId) -> Option<&Window<T>> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn window(&self) -> &Window<T> {
+        self.get(self.current)
+            .expect("the current window was closed")
+    }
+
+    fn window_mut(&mut self) -> &mut Window<T> {
+        let current = self.current;
+        self.windows
+            .iter_mut()
+            .find(|window| window.id == current)
+            .expect("the current window was closed")
+    }
+
+    /// Send an event to a window, after passing it to the delegate.
+    fn window_event(&mut self, id: WindowId, event: Event) -> Handled {
+        let event = if self.delegate.is_some() {
+            match self
+                .with_delegate(|del, data, env, ctx| del.event(ctx, id, event, data, env))
+                .unwrap()
+            {
+                Some(event) => event,
+                None => return Handled::Yes,
+            }
+        } else {
+            event
+        };
+        self.raw_window_event(id, event)
+    }
+
+    fn raw_window_event(&mut self, id: WindowId, event: Event) -> Handled {
+        match self.windows.iter_mut().find(|window| window.id == id) {
+            Some(window) => window.event(&mut self.cmds, event, &mut self.data, &self.env),
+            None => Handled::No,
+        }
+    }
+
+    /// Handle a command like the application does: commands that open or
+    /// close windows are handled here, others are dispatched to the windows.
+    fn handle_cmd(&mut self, cmd: Command) {
+        match cmd.target() {
+            _ if cmd.is(commands::NEW_WINDOW) => {
+                let desc = cmd
+                    .get_unchecked(commands::NEW_WINDOW)
+                    .take()
+                    .unwrap()
+                    .downcast::<WindowDesc<T>>()
+                    .unwrap();
+                self.open_window(desc.id, desc.pending, desc.config);
+            }
+            _ if cmd.is(commands::NEW_SUB_WINDOW) => {
+                let desc = cmd
+                    .get_unchecked(commands::NEW_SUB_WINDOW)
+                    .take()
+                    .expect("NEW_SUB_WINDOW must carry a SubWindowDesc");
+                let pending = PendingWindow::new(desc.sub_window_root.lens(Unit));
+                self.open_window(desc.window_id, pending, desc.window_config);
+            }
+            _ if cmd.is(commands::CLOSE_ALL_WINDOWS) => {
+                let ids: Vec<_> = self.windows.iter().map(|window| window.id).collect();
+                for id in ids {
+                    self.close_window(id);
+                }
+            }
+            Target::Window(id) if cmd.is(commands::CLOSE_WINDOW) => {
+                if !self.dispatch_cmd(cmd).is_handled() {
+                    self.close_window(id);
+                }
+            }
+            _ => {
+                self.dispatch_cmd(cmd);
+            }
+        }
+    }
+
+    /// Pass a command to the delegate, and then to the windows it targets.
+    fn dispatch_cmd(&mut self, cmd: Command) -> Handled {
+        let handled = self
+            .with_delegate(|del, data, env, ctx| del.command(ctx, cmd.target(), &cmd, data, env))
+            .unwrap_or(Handled::No);
+        if handled.is_handled() {
+            return handled;
+        }
+
+        let targets: Vec<_> = match cmd.target() {
+            Target::Window(id) if cmd.is(commands::CLOSE_WINDOW) => {
+                let handled = self.raw_window_event(id, Event::WindowCloseRequested);
+                if !handled.is_handled() {
+                    self.raw_window_event(id, Event::WindowDisconnected);
+                }
+                return handled;
+            }
+            Target::Window(id) => vec![id],
+            Target::Widget(widget) => self
+                .windows
+                .iter()
+                .filter(|window| window.may_contain_widget(widget))
+                .map(|window| window.id)
+                .collect(),
+            Target::Global => self.windows.iter().map(|window| window.id).collect(),
+            Target::Auto => {
+                tracing::error!("{:?} reached the harness with `Target::Auto`", cmd);
+                Vec::new()
+            }
+        };
+        for id in targets {
+            let event = Event::Internal(InternalEvent::TargetedCommand(cmd.clone()));
+            if self.raw_window_event(id, event).is_handled() {
+                return Handled::Yes;
+            }
+        }
+        Handled::No
+    }
+
+    fn open_window(&mut self, id: WindowId, mut pending: PendingWindow<T>, config: WindowConfig) {
+        pending.size_policy = config.size_policy;
+        let mut window = Window::new(id, Default::default(), pending, self.ext_host.make_sink());
+        window.timers.virtual_clock().set_now(self.now);
+        self.windows.push(window);
+        self.opened.push(id);
+
+        self.with_delegate(|del, data, env, ctx| {
+            del.window_added(id, Default::default(), data, env, ctx)
+        });
+        self.window_event(id, Event::WindowConnected);
+        self.window_event(id, Event::WindowSize(config.size.unwrap_or(DEFAULT_SIZE)));
+    }
+
+    /// Remove a window, as the platform does after closing it.
+    ///
+    /// If it was the current window, the most recently opened window that is
+    /// still open becomes the current one.
+    fn close_window(&mut self, id: WindowId) {
+        if let Some(index) = self.windows.iter().position(|window| window.id == id) {
+            self.with_delegate(|del, data, env, ctx| del.window_removed(id, data, env, ctx));
+            self.windows.remove(index);
+            self.closed.push(id);
+            if self.current == id {
+                if let Some(window) = self.windows.last() {
+                    self.current = window.id;
+                }
+            }
+        }
+    }
+
+    /// A helper fn for setting up the `DelegateCtx`, like the one of `AppState`.
+    fn with_delegate<R>(
+        &mut self,
+        f: impl FnOnce(&mut dyn AppDelegate<T>, &mut T, &Env, &mut DelegateCtx) -> R,
+    ) -> Option<R> {
+        let mut ctx = DelegateCtx {
+            command_queue: &mut self.cmds,
+            app_data_type: TypeId::of::<T>(),
+            ext_event_host: &self.ext_host,
+        };
+        self.delegate
+            .as_deref_mut()
+            .map(|delegate| f(delegate, &mut self.data, &self.env, &mut ctx))
+    }
+
+    /// Remove the timer with the earliest deadline that is due at `time`, from
+    /// any window, and move the clock to its deadline.
+    fn pop_due_timer(&mut self, time: Duration) -> Option<(WindowId, TimerToken)> {
+        let (index, deadline) = self
+            .windows
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, window)| {
+                let deadline = window.timers.virtual_clock().next_deadline()?;
+                Some((index, deadline))
+            })
+            .filter(|(_, deadline)| *deadline <= time)
+            .min_by_key(|(_, deadline)| *deadline)?;
+        let window = &mut self.windows[index];
+        let token = window.timers.virtual_clock().pop_due(time)?;
+        let id = window.id;
+        self.set_now(deadline);
+        Some((id, token))
+    }
+
+    /// Move the clocks of all windows forward to `time`.
+    fn set_now(&mut self, time: Duration) {
+        self.now = self.now.max(time);
+        for window in &mut self.windows {
+            window.timers.virtual_clock().set_now(self.now);
+        }
     }
 
     fn lifecycle(&mut self, event: LifeCycle) {
-        self.window
-            .lifecycle(&mut self.cmds, &event, &self.data, &self.env, false);
+        let current = self.current;
+        if let Some(window) = self.windows.iter_mut().find(|window| window.id == current) {
+            window.lifecycle(&mut self.cmds, &event, &self.data, &self.env, false);
+        }
     }
 
+    /// Update all windows, like the application does after each event.
     fn update(&mut self) {
-        self.window.update(&mut self.cmds, &self.data, &self.env);
+        for window in &mut self.windows {
+            window.update(&mut self.cmds, &self.data, &self.env);
+        }
     }
 
     fn layout(&mut self) {
-        self.window
-            .just_layout(&mut self.cmds, &self.data, &self.env);
+        let current = self.current;
+        if let Some(window) = self.windows.iter_mut().find(|window| window.id == current) {
+            window.just_layout(&mut self.cmds, &self.data, &self.env);
+        }
     }
 
     #[allow(dead_code)]
     fn paint_region(&mut self, piet: &mut Piet, invalid: &Region) {
-        self.window
-            .do_paint(piet, invalid, &mut self.cmds, &self.data, &self.env);
+        let current = self.current;
+        if let Some(window) = self.windows.iter_mut().find(|window| window.id == current) {
+            window.do_paint(piet, invalid, &mut self.cmds, &self.data, &self.env);
+        }
     }
 
     pub fn root_debug_state(&self) -> DebugState {
-        self.window.root_debug_state(&self.data)
+        self.window().root_debug_state(&self.data)
     }
 }
 
@@ -14,6 +14,7 @@ use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use crate::widget::Controller;
 use crate::*;
 
 pub type EventFn<S, T> = dyn FnMut(&mut S, &mut EventCtx, &Event, &mut T, &Env);
@@ -24,6 +25,9 @@ pub type PaintFn<S, T> = dyn FnMut(&mut S, &mut PaintCtx, &T, &Env);
 
 pub const REPLACE_CHILD: Selector = Selector::new("druid-test.replace-child");
 
+/// Sent to a widget wrapped in [`TakeFocus`] to give it keyboard focus.
+pub const TAKE_FOCUS: Selector = Selector::new("druid-test.take-focus");
+
 /// A widget that can be constructed from individual functions, builder-style.
 ///
 /// This widget is generic over its state, which is passed in at construction time.
@@ -42,6 +46,9 @@ pub struct ReplaceChild<T> {
     replacer: Box<dyn Fn() -> Box<dyn Widget<T>>>,
 }
 
+/// A controller that gives its child keyboard focus when sent [`TAKE_FOCUS`].
+pub struct TakeFocus;
+
 /// A widget that records each time one of its methods is called.
 ///
 /// Make one like this:
@@ -187,6 +194,15 @@ impl<S, T: Data> Widget<T> for ModularWidget<S, T> {
     }
 }
 
+impl<T, W: Widget<T>> Controller<T, W> for TakeFocus {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if matches!(event, Event::Command(cmd) if cmd.is(TAKE_FOCUS)) {
+            ctx.request_focus();
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
 impl<T: Data> ReplaceChild<T> {
     pub fn new<W: Widget<T> + 'static>(
         child: impl Widget<T> + 'static,
@@ -274,6 +290,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
             event,
             LifeCycle::Internal(InternalLifeCycle::DebugRequestState { .. })
                 | LifeCycle::Internal(InternalLifeCycle::DebugInspectState(_))
+                | LifeCycle::Internal(InternalLifeCycle::DebugCollectWidgets(_))
         );
 
         if should_record {
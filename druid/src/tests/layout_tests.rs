@@ -262,3 +262,172 @@ fn aspect_ratio() {
         assert_eq!(state.layout_rect().size(), Size::new(1000., 500.));
     });
 }
+
+#[test]
+fn grid_columns_line_up() {
+    let [short, long, field, footer] = widget_ids();
+    let widget = Grid::new()
+        .with_column(GridTrack::Intrinsic)
+        .with_column(GridTrack::Flex(1.0))
+        .gap(10.0)
+        .with_child(
+            SizedBox::empty().width(50.).height(20.).with_id(short),
+            (0, 0),
+        )
+        .with_child(
+            SizedBox::empty().width(80.).height(20.).with_id(long),
+            (1, 0),
+        )
+        .with_child(
+            SizedBox::empty().height(20.).with_id(field),
+            GridParams::new(0, 1).align(CrossAxisAlignment::Fill, None),
+        )
+        .with_child(
+            SizedBox::empty().width(300.).height(30.).with_id(footer),
+            GridParams::new(2, 0).span(1, 2),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // The first column is as wide as its widest label, and the flex
+        // column takes the rest of the window.
+        let field = harness.get_state(field).layout_rect();
+        assert_eq!(field.origin(), Point::new(90., 0.));
+        assert_eq!(field.width(), DEFAULT_SIZE.width - 90.);
+
+        let long = harness.get_state(long).layout_rect();
+        assert_eq!(long.origin(), Point::new(0., 30.));
+        let footer = harness.get_state(footer).layout_rect();
+        assert_eq!(footer.origin(), Point::new(0., 60.));
+        assert_eq!(harness.get_state(short).layout_rect().width(), 50.);
+    });
+}
+
+#[test]
+fn wrap_moves_children_to_new_runs() {
+    let ids = widget_ids::<5>();
+    let mut widget = Wrap::row()
+        .spacing(10.0)
+        .run_spacing(5.0)
+        .main_axis_alignment(MainAxisAlignment::Center);
+    for id in ids {
+        widget.add_child(SizedBox::empty().width(100.).height(20.).with_id(id));
+    }
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // Three children fit in the first run, and each run is centered
+        // in the window.
+        let third = harness.get_state(ids[2]).layout_rect();
+        assert_eq!(third.origin(), Point::new(260., 0.));
+        let fourth = harness.get_state(ids[3]).layout_rect();
+        assert_eq!(fourth.origin(), Point::new(95., 25.));
+        let fifth = harness.get_state(ids[4]).layout_rect();
+        assert_eq!(fifth.origin(), Point::new(205., 25.));
+    });
+}
+
+#[test]
+fn flex_row_mirrors_right_to_left() {
+    let [first, second, trailing] = widget_ids();
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().width(100.).height(20.).with_id(first))
+        .with_child(SizedBox::empty().width(50.).height(20.).with_id(second))
+        .with_flex_spacer(1.0)
+        .with_child(SizedBox::empty().width(30.).height(20.).with_id(trailing))
+        .layout_direction(LayoutDirection::RightToLeft);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // The first child hugs the right edge, and the trailing one the left.
+        let first = harness.get_state(first).layout_rect();
+        assert_eq!(first.x0, DEFAULT_SIZE.width - 100.);
+        let second = harness.get_state(second).layout_rect();
+        assert_eq!(second.x0, DEFAULT_SIZE.width - 150.);
+        let trailing = harness.get_state(trailing).layout_rect();
+        assert_eq!(trailing.x0, 0.);
+    });
+}
+
+#[test]
+fn split_mirrors_right_to_left() {
+    let [first, second] = widget_ids();
+    let widget = Split::columns(
+        SizedBox::empty().expand().with_id(first),
+        SizedBox::empty().expand().with_id(second),
+    )
+    .layout_direction(LayoutDirection::RightToLeft);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let first = harness.window_rect(first);
+        let second = harness.window_rect(second);
+        assert_eq!(first.x1, DEFAULT_SIZE.width);
+        assert_eq!(second.x0, 0.);
+        assert!(second.x1 <= first.x0);
+    });
+}
+
+#[test]
+fn tabs_mirror_right_to_left() {
+    let widget = Tabs::new()
+        .with_tab("One", Label::new("First page"))
+        .with_tab("Two", Label::new("Second page"))
+        .layout_direction(LayoutDirection::RightToLeft);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // The first tab hugs the right edge of the tab bar.
+        let one = harness.assert_one(&WidgetQuery::new().label("One"));
+        let two = harness.assert_one(&WidgetQuery::new().label("Two"));
+        assert_eq!(one.window_rect.x1, DEFAULT_SIZE.width);
+        assert!(two.window_rect.x1 <= one.window_rect.x0);
+    });
+}
+
+#[test]
+fn slider_mirrors_right_to_left() {
+    let slider = WidgetId::next();
+    let widget = Slider::new()
+        .fix_width(200.)
+        .with_id(slider)
+        .center()
+        .layout_direction(LayoutDirection::RightToLeft);
+
+    Harness::create_simple(0.5, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // The lowest value is at the right.
+        let rect = harness.window_rect(slider);
+        harness.click_at((rect.x1 - 1., rect.center().y));
+        assert_eq!(*harness.data(), 0.);
+        harness.click_at((rect.x0 + 1., rect.center().y));
+        assert_eq!(*harness.data(), 1.);
+    });
+}
+
+#[test]
+fn align_mirrors_right_to_left() {
+    let child = WidgetId::next();
+    let widget = Align::left(SizedBox::empty().width(100.).height(20.).with_id(child))
+        .layout_direction(LayoutDirection::RightToLeft);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let child = harness.get_state(child).layout_rect();
+        assert_eq!(child.x0, DEFAULT_SIZE.width - 100.);
+    });
+}
@@ -7,23 +7,29 @@
 
 pub mod harness;
 pub mod helpers;
+pub mod query;
 
 #[cfg(test)]
 mod invalidation_tests;
 #[cfg(test)]
 mod layout_tests;
+#[cfg(test)]
+pub mod snapshot;
 
 use std::cell::Cell;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::widget::*;
 use crate::*;
 use harness::*;
 use helpers::*;
 use kurbo::Vec2;
+use query::*;
 
 /// Helper function to construct a "move to this position" mouse event.
 pub fn move_mouse(p: impl Into<Point>) -> MouseEvent {
@@ -835,6 +841,974 @@ fn participate_in_autofocus() {
     })
 }
 
+#[test]
+fn keyboard_focus_and_activation() {
+    fn key_down(mods: Modifiers, key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(mods, key))
+    }
+    let space = || key_down(Modifiers::empty(), KbKey::Character(" ".into()));
+
+    let [button_id, checkbox_id] = widget_ids();
+    let clicks = Rc::new(Cell::new(0));
+    let button = Button::new("Go")
+        .on_click({
+            let clicks = clicks.clone();
+            move |_, _, _| clicks.set(clicks.get() + 1)
+        })
+        .controller(TakeFocus)
+        .with_id(button_id);
+    let widget = Flex::column()
+        .with_child(button)
+        .with_child(Checkbox::new("Check").with_id(checkbox_id));
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        assert_eq!(harness.window().focus_chain(), &[button_id, checkbox_id]);
+
+        harness.submit_command(TAKE_FOCUS.to(button_id));
+        harness.event(space());
+        assert_eq!(clicks.get(), 1);
+
+        // Holding the key down doesn't click again.
+        let mut held = KeyEvent::for_test(Modifiers::empty(), KbKey::Character(" ".into()));
+        held.repeat = true;
+        harness.event(Event::KeyDown(held));
+        assert_eq!(clicks.get(), 1);
+
+        harness.event(key_down(Modifiers::empty(), KbKey::Tab));
+        assert_eq!(harness.window().focus, Some(checkbox_id));
+        harness.event(space());
+        assert!(*harness.data());
+
+        harness.event(key_down(Modifiers::SHIFT, KbKey::Tab));
+        assert_eq!(harness.window().focus, Some(button_id));
+    })
+}
+
+#[test]
+fn keyboard_activation_is_opt_in() {
+    let [text_box, custom] = widget_ids();
+    let clicks = Rc::new(Cell::new(0));
+    let count = |clicks: &Rc<Cell<u32>>| {
+        let clicks = clicks.clone();
+        move |_: &mut EventCtx, _: &mut String, _: &Env| clicks.set(clicks.get() + 1)
+    };
+    // A focusable widget that uses Enter itself.
+    let enter_handler = ModularWidget::new(())
+        .lifecycle_fn(|_, ctx, event, _, _| {
+            if let LifeCycle::BuildFocusChain = event {
+                ctx.register_for_focus();
+            }
+        })
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::KeyDown(key) = event {
+                if key.key == KbKey::Enter {
+                    ctx.set_handled();
+                }
+            }
+        })
+        .controller(Click::new(count(&clicks)).with_keyboard_activation())
+        .controller(TakeFocus)
+        .with_id(custom);
+    let widget = Flex::column()
+        .with_child(TextBox::new().on_click(count(&clicks)).with_id(text_box))
+        .with_child(enter_handler);
+
+    Harness::create_simple(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // Typing a space in a text field doesn't click it.
+        harness.click(text_box);
+        assert_eq!(clicks.get(), 1);
+        harness.press_key(Modifiers::empty(), " ");
+        assert_eq!(harness.data(), " ");
+        assert_eq!(clicks.get(), 1);
+
+        // Keys the child handles don't click it either.
+        harness.submit_command(TAKE_FOCUS.to(custom));
+        harness.press_key(Modifiers::empty(), KbKey::Enter);
+        assert_eq!(clicks.get(), 1);
+        harness.press_key(Modifiers::empty(), " ");
+        assert_eq!(clicks.get(), 2);
+    })
+}
+
+#[test]
+fn keyboard_value_controls() {
+    #[derive(Clone, Data, Lens)]
+    struct Controls {
+        value: f64,
+        count: f64,
+        choice: u32,
+        on: bool,
+    }
+
+    let press = |harness: &mut Harness<Controls>, key: KbKey| {
+        harness.event(Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key)));
+    };
+
+    let slider = WidgetId::next();
+    let widget = Flex::column()
+        .with_child(
+            Slider::new()
+                .with_step(0.25)
+                .lens(Controls::value)
+                .controller(TakeFocus)
+                .with_id(slider),
+        )
+        .with_child(Stepper::new().lens(Controls::count))
+        .with_child(RadioGroup::column([("A", 0), ("B", 1), ("C", 2)]).lens(Controls::choice))
+        .with_child(Switch::new().lens(Controls::on));
+    let controls = Controls {
+        value: 0.5,
+        count: 0.,
+        choice: 0,
+        on: false,
+    };
+
+    Harness::create_simple(controls, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(TAKE_FOCUS.to(slider));
+
+        press(harness, KbKey::ArrowRight);
+        assert_eq!(harness.data().value, 0.75);
+        press(harness, KbKey::ArrowDown);
+        assert_eq!(harness.data().value, 0.5);
+        press(harness, KbKey::End);
+        assert_eq!(harness.data().value, 1.);
+        press(harness, KbKey::Home);
+        assert_eq!(harness.data().value, 0.);
+
+        press(harness, KbKey::Tab);
+        press(harness, KbKey::ArrowUp);
+        press(harness, KbKey::ArrowUp);
+        press(harness, KbKey::ArrowDown);
+        assert_eq!(harness.data().count, 1.);
+
+        // The arrows move through the radio group, wrapping at either end.
+        press(harness, KbKey::Tab);
+        press(harness, KbKey::ArrowDown);
+        assert_eq!(harness.data().choice, 1);
+        press(harness, KbKey::ArrowUp);
+        press(harness, KbKey::ArrowUp);
+        assert_eq!(harness.data().choice, 2);
+
+        // Focus followed the selection to the last radio button.
+        press(harness, KbKey::Tab);
+        press(harness, KbKey::Character(" ".into()));
+        assert!(harness.data().on);
+        let mut held = KeyEvent::for_test(Modifiers::empty(), KbKey::Character(" ".into()));
+        held.repeat = true;
+        harness.event(Event::KeyDown(held));
+        assert!(harness.data().on);
+    })
+}
+
+#[test]
+fn tab_bar_arrows() {
+    /// A tab body that sets the data to `page` when the mouse moves over it.
+    fn page(page: u32) -> impl Widget<u32> {
+        ModularWidget::new(())
+            .event_fn(move |_, _, event, data: &mut u32, _| {
+                if let Event::MouseMove(_) = event {
+                    *data = page;
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.max())
+    }
+
+    let button = WidgetId::next();
+    let widget = Flex::column()
+        .with_child(Button::new("Before").controller(TakeFocus).with_id(button))
+        .with_flex_child(
+            Tabs::new()
+                .with_tab("One", page(1))
+                .with_tab("Two", page(2)),
+            1.0,
+        );
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(TAKE_FOCUS.to(button));
+        harness.event(Event::KeyDown(KeyEvent::for_test(
+            Modifiers::empty(),
+            KbKey::Tab,
+        )));
+
+        // Only the selected tab's body gets the mouse.
+        let selected = |harness: &mut Harness<u32>, key: KbKey| {
+            harness.event(Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), key)));
+            harness.mouse_move((200., 300.));
+            *harness.data()
+        };
+        assert_eq!(selected(harness, KbKey::ArrowRight), 2);
+        assert_eq!(selected(harness, KbKey::ArrowRight), 1);
+        assert_eq!(selected(harness, KbKey::ArrowLeft), 2);
+    })
+}
+
+#[test]
+fn focus_scopes_and_directional_focus() {
+    const SET_OPEN: Selector<bool> = Selector::new("druid-tests.scope-set-open");
+
+    /// Opens or closes the dialog when sent a particular command.
+    struct Open;
+
+    impl<W: Widget<bool>> Controller<bool, W> for Open {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut bool,
+            env: &Env,
+        ) {
+            if let Event::Command(cmd) = event {
+                if let Some(open) = cmd.get(SET_OPEN) {
+                    *data = *open;
+                }
+            }
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    let tab = |mods: Modifiers| Event::KeyDown(KeyEvent::for_test(mods, KbKey::Tab));
+    let move_focus = |direction: FocusDirection| commands::MOVE_FOCUS.with(direction);
+
+    let [root, a, b, c, d, e, title] = widget_ids();
+    let dialog = FocusScope::new(
+        Flex::row()
+            .with_child(Button::new("D").with_id(d))
+            .with_child(Button::new("E").with_id(e))
+            .with_child(Label::new("Title").controller(TakeFocus).with_id(title)),
+    )
+    .trap_focus();
+    let widget = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("A").controller(TakeFocus).with_id(a))
+                .with_child(Button::new("B").with_id(b).tab_index(1)),
+        )
+        .with_child(Button::new("C").with_id(c))
+        .with_child(Either::new(
+            |open: &bool, _| *open,
+            dialog,
+            SizedBox::empty(),
+        ))
+        .controller(Open)
+        .with_id(root);
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.window().focus_chain(), &[b, a, c]);
+
+        harness.submit_command(TAKE_FOCUS.to(a));
+        harness.event(tab(Modifiers::empty()));
+        assert_eq!(harness.window().focus, Some(c));
+        harness.event(tab(Modifiers::SHIFT));
+        assert_eq!(harness.window().focus, Some(a));
+
+        harness.submit_command(move_focus(FocusDirection::Right));
+        assert_eq!(harness.window().focus, Some(b));
+        harness.submit_command(move_focus(FocusDirection::Down));
+        assert_eq!(harness.window().focus, Some(c));
+        harness.submit_command(TAKE_FOCUS.to(a));
+
+        // Opening the dialog moves focus into it, and keeps it there.
+        harness.submit_command(SET_OPEN.with(true).to(root));
+        harness.just_layout();
+        assert_eq!(harness.window().focus_chain(), &[b, a, c, d, e]);
+        assert_eq!(harness.window().focus, Some(d));
+        harness.event(tab(Modifiers::SHIFT));
+        assert_eq!(harness.window().focus, Some(e));
+        harness.event(tab(Modifiers::empty()));
+        assert_eq!(harness.window().focus, Some(d));
+        harness.submit_command(TAKE_FOCUS.to(a));
+        assert_eq!(harness.window().focus, Some(d));
+        harness.submit_command(move_focus(FocusDirection::Up));
+        assert_eq!(harness.window().focus, Some(d));
+        harness.submit_command(move_focus(FocusDirection::Right));
+        assert_eq!(harness.window().focus, Some(e));
+
+        // Any widget inside the dialog may take focus, not just its members.
+        harness.submit_command(TAKE_FOCUS.to(title));
+        assert_eq!(harness.window().focus, Some(title));
+
+        // Closing it gives focus back to where it was before.
+        harness.submit_command(SET_OPEN.with(false).to(root));
+        assert_eq!(harness.window().focus, Some(a));
+        assert_eq!(harness.window().focus_chain(), &[b, a, c]);
+    })
+}
+
+#[test]
+fn drag_and_drop() {
+    const NUMBER: Selector<u32> = Selector::new("druid-tests.drag-number");
+
+    fn mouse_button(pos: (f64, f64), buttons: MouseButtons) -> MouseEvent {
+        MouseEvent {
+            buttons,
+            count: 1,
+            button: MouseButton::Left,
+            ..move_mouse(pos)
+        }
+    }
+
+    /// Press the left button at `from`, move to `to` and release it there.
+    fn drag<T: Data>(harness: &mut Harness<T>, from: (f64, f64), to: (f64, f64)) {
+        let pressed = MouseButtons::new().with(MouseButton::Left);
+        harness.event(Event::MouseMove(move_mouse(from)));
+        harness.event(Event::MouseDown(mouse_button(from, pressed)));
+        harness.event(Event::MouseMove(move_mouse(to)));
+        assert!(harness.window().drag.is_some());
+        harness.event(Event::MouseUp(mouse_button(to, MouseButtons::new())));
+        assert!(harness.window().drag.is_none());
+    }
+
+    let escape = || Event::KeyDown(KeyEvent::for_test(Modifiers::empty(), KbKey::Escape));
+
+    let [source, list] = widget_ids();
+    let rejected = Rc::new(Cell::new(false));
+    let rejected_inner = rejected.clone();
+    let widget = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            SizedBox::empty()
+                .fix_size(50., 50.)
+                .drag_source(NUMBER, |data: &u32, _| *data + 1)
+                .with_id(source),
+        )
+        .with_child(
+            SizedBox::empty()
+                .fix_size(50., 50.)
+                .drop_target(NUMBER, |_, payload, data: &mut u32, _| *data = *payload)
+                .accept_if(|payload, _, _| *payload < 3)
+                .on_reject(move |_, _, _, _| rejected_inner.set(true)),
+        );
+
+    Harness::create_simple(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        drag(harness, (10., 10.), (70., 10.));
+        assert_eq!(*harness.data(), 1);
+        drag(harness, (10., 10.), (70., 10.));
+        assert_eq!(*harness.data(), 2);
+        assert!(!rejected.get());
+
+        drag(harness, (10., 10.), (70., 10.));
+        assert_eq!(*harness.data(), 2);
+        assert!(rejected.get());
+
+        // Dropping outside of the target does nothing.
+        drag(harness, (10., 10.), (150., 10.));
+        assert_eq!(*harness.data(), 2);
+
+        // Escape cancels the drag, and the source lets go of the mouse.
+        let pressed = MouseButtons::new().with(MouseButton::Left);
+        harness.event(Event::MouseDown(mouse_button((10., 10.), pressed)));
+        harness.event(Event::MouseMove(move_mouse((70., 10.))));
+        harness.event(escape());
+        assert!(harness.window().drag.is_none());
+        assert!(!harness.get_state(source).is_active);
+    });
+
+    let widget = List::new(|| SizedBox::empty().fix_height(20.))
+        .reorderable()
+        .with_id(list);
+    Harness::create_simple(Arc::new(vec![0u32, 1, 2]), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        drag(harness, (10., 5.), (10., 55.));
+        assert_eq!(**harness.data(), vec![1, 2, 0]);
+        drag(harness, (10., 45.), (10., 5.));
+        assert_eq!(**harness.data(), vec![0, 1, 2]);
+        drag(harness, (10., 5.), (10., 25.));
+        assert_eq!(**harness.data(), vec![0, 1, 2]);
+
+        // Escape cancels the drag.
+        let pressed = MouseButtons::new().with(MouseButton::Left);
+        harness.event(Event::MouseDown(mouse_button((10., 5.), pressed)));
+        harness.event(Event::MouseMove(move_mouse((10., 55.))));
+        harness.event(escape());
+        assert!(harness.window().drag.is_none());
+        assert!(!harness.get_state(list).is_active);
+        harness.event(Event::MouseUp(mouse_button(
+            (10., 55.),
+            MouseButtons::new(),
+        )));
+        assert_eq!(**harness.data(), vec![0, 1, 2]);
+    });
+}
+
+#[test]
+fn external_drag_and_drop() {
+    /// Accepts dropped files, counting them.
+    struct FileDrop;
+
+    impl<W: Widget<usize>> Controller<usize, W> for FileDrop {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut usize,
+            env: &Env,
+        ) {
+            match event {
+                Event::ExternalDrag(ExternalDrag::Over(drop)) if drop.has_files() => {
+                    ctx.set_handled();
+                }
+                Event::ExternalDrag(ExternalDrag::Drop(drop)) => {
+                    *data += drop.files.len();
+                    ctx.set_handled();
+                }
+                _ => (),
+            }
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    let files = |pos: (f64, f64)| {
+        let mut drop = DropEvent::new(pos.into(), vec!["text/uri-list".to_string()]);
+        drop.files = vec![
+            FileInfo {
+                path: "/tmp/a.txt".into(),
+                format: None,
+            },
+            FileInfo {
+                path: "/tmp/b.txt".into(),
+                format: None,
+            },
+        ];
+        drop
+    };
+
+    let [other, target] = widget_ids();
+    let widget = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(SizedBox::empty().fix_size(50., 50.).with_id(other))
+        .with_child(
+            SizedBox::empty()
+                .fix_size(50., 50.)
+                .controller(FileDrop)
+                .with_id(target),
+        );
+
+    Harness::create_simple(0usize, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let over = DropEvent::new((70., 10.).into(), vec!["text/uri-list".to_string()]);
+        harness.event(Event::ExternalDrag(ExternalDrag::Over(over)));
+        assert!(harness.get_state(target).is_hot);
+        assert!(!harness.get_state(other).is_hot);
+        harness.event(Event::ExternalDrag(ExternalDrag::Leave));
+        assert!(!harness.get_state(target).is_hot);
+
+        harness.event(Event::ExternalDrag(ExternalDrag::Drop(files((10., 10.)))));
+        assert_eq!(*harness.data(), 0);
+        harness.event(Event::ExternalDrag(ExternalDrag::Drop(files((70., 10.)))));
+        assert_eq!(*harness.data(), 2);
+    });
+}
+
+#[test]
+fn gestures() {
+    let touch = |id: u64, pos: (f64, f64), down: bool| -> PointerEvent {
+        druid_shell::PointerEvent {
+            pointer_id: PointerId::from_raw(id),
+            pointer_type: PointerType::Touch,
+            is_primary: id == 0,
+            pos: pos.into(),
+            buttons: if down {
+                MouseButtons::new().with(MouseButton::Left)
+            } else {
+                MouseButtons::new()
+            },
+            button: MouseButton::Left,
+            mods: Modifiers::default(),
+            pressure: if down { 0.5 } else { 0.0 },
+            tilt: Vec2::ZERO,
+        }
+        .into()
+    };
+
+    let recognized = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let id = WidgetId::next();
+    let widget = SizedBox::empty()
+        .fix_size(100., 100.)
+        .on_gesture(
+            GestureRecognizer::new()
+                .tap()
+                .long_press()
+                .pan()
+                .pinch()
+                .rotate(),
+            {
+                let recognized = recognized.clone();
+                move |_, gesture, _, _| recognized.borrow_mut().push(gesture.clone())
+            },
+        )
+        .with_id(id);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // A press that doesn't move far is a tap.
+        harness.event(Event::PointerDown(touch(0, (20., 20.), true)));
+        assert!(harness.get_state(id).is_active);
+        harness.event(Event::PointerMove(touch(0, (22., 20.), true)));
+        harness.event(Event::PointerUp(touch(0, (22., 20.), false)));
+        assert!(!harness.get_state(id).is_active);
+        assert_eq!(
+            recognized.replace(Vec::new()),
+            vec![Gesture::Tap {
+                pos: Point::new(22., 20.)
+            }]
+        );
+
+        // Dragging a single pointer pans, even outside of the widget.
+        harness.event(Event::PointerDown(touch(0, (10., 10.), true)));
+        harness.event(Event::PointerMove(touch(0, (30., 10.), true)));
+        harness.event(Event::PointerMove(touch(0, (130., 10.), true)));
+        harness.event(Event::PointerUp(touch(0, (130., 10.), false)));
+        assert_eq!(
+            recognized.replace(Vec::new()),
+            vec![
+                Gesture::Pan {
+                    phase: GesturePhase::Began,
+                    pos: Point::new(30., 10.),
+                    translation: Vec2::new(20., 0.),
+                    delta: Vec2::new(20., 0.),
+                },
+                Gesture::Pan {
+                    phase: GesturePhase::Changed,
+                    pos: Point::new(130., 10.),
+                    translation: Vec2::new(120., 0.),
+                    delta: Vec2::new(100., 0.),
+                },
+                Gesture::Pan {
+                    phase: GesturePhase::Ended,
+                    pos: Point::new(130., 10.),
+                    translation: Vec2::new(120., 0.),
+                    delta: Vec2::ZERO,
+                },
+            ]
+        );
+
+        // A press that is held still is a long press once the delay has
+        // passed, and isn't a tap when it is released.
+        harness.event(Event::PointerDown(touch(0, (20., 20.), true)));
+        harness.advance_time(Duration::from_millis(499));
+        assert!(recognized.borrow().is_empty());
+        harness.advance_time(Duration::from_millis(1));
+        assert_eq!(
+            recognized.replace(Vec::new()),
+            vec![Gesture::LongPress {
+                pos: Point::new(20., 20.)
+            }]
+        );
+        harness.event(Event::PointerUp(touch(0, (20., 20.), false)));
+        assert!(recognized.borrow().is_empty());
+
+        // Moving away before the delay cancels the long press.
+        harness.event(Event::PointerDown(touch(0, (20., 20.), true)));
+        harness.event(Event::PointerMove(touch(0, (20., 40.), true)));
+        harness.event(Event::PointerUp(touch(0, (20., 40.), false)));
+        recognized.replace(Vec::new());
+        harness.advance_time(Duration::from_millis(500));
+        assert!(recognized.borrow().is_empty());
+
+        // Two pointers pinch and rotate.
+        harness.event(Event::PointerDown(touch(0, (20., 50.), true)));
+        harness.event(Event::PointerDown(touch(1, (60., 50.), true)));
+        harness.event(Event::PointerMove(touch(1, (20., 130.), true)));
+        harness.event(Event::PointerUp(touch(1, (20., 130.), false)));
+        harness.event(Event::PointerUp(touch(0, (20., 50.), false)));
+        assert!(!harness.get_state(id).is_active);
+        let recognized = recognized.replace(Vec::new());
+        assert_eq!(recognized.len(), 4);
+        for (gesture, expected_phase) in recognized.iter().zip([
+            GesturePhase::Began,
+            GesturePhase::Began,
+            GesturePhase::Ended,
+            GesturePhase::Ended,
+        ]) {
+            match *gesture {
+                Gesture::Pinch {
+                    phase,
+                    center,
+                    scale,
+                } => {
+                    assert_eq!(phase, expected_phase);
+                    assert_eq!(center, Point::new(20., 90.));
+                    assert!((scale - 2.0).abs() < 1e-9);
+                }
+                Gesture::Rotate {
+                    phase,
+                    center,
+                    angle,
+                } => {
+                    assert_eq!(phase, expected_phase);
+                    assert_eq!(center, Point::new(20., 90.));
+                    assert!((angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+                }
+                ref other => panic!("unexpected gesture {other:?}"),
+            }
+        }
+    });
+}
+
+#[test]
+fn render_snapshots() {
+    let centered_box = |color: Color| {
+        Painter::new(move |ctx, _: &(), _| ctx.fill(ctx.size().to_rect(), &color))
+            .fix_size(20., 10.)
+            .center()
+    };
+    let snapshot = snapshot::Snapshot::new("centered_box")
+        .size(Size::new(40., 30.))
+        .scale(2.0);
+
+    snapshot.assert_widget((), centered_box(Color::rgb8(0xff, 0, 0)));
+    // Small differences are within the tolerance.
+    snapshot.assert_widget((), centered_box(Color::rgb8(0xfe, 0x01, 0)));
+}
+
+#[test]
+fn virtual_clock() {
+    const START: Selector = Selector::new("druid-tests.start-timers");
+    const FRAME_TIME: Duration = Duration::from_millis(16);
+
+    // Each timer records the time it was requested with; the 50ms timer
+    // requests another one that fires before the 100ms one.
+    let fired = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let widget = ModularWidget::new((HashMap::new(), fired.clone())).event_fn(
+        |(timers, fired), ctx, event, data: &mut u64, _| match event {
+            Event::Command(cmd) if cmd.is(START) => {
+                for ms in [100, 50] {
+                    let token = ctx.request_timer(Duration::from_millis(ms));
+                    timers.insert(token, ms);
+                }
+                ctx.request_anim_frame();
+            }
+            Event::Timer(token) => {
+                let ms = timers.remove(token).unwrap();
+                fired.borrow_mut().push(ms);
+                if ms == 50 {
+                    timers.insert(ctx.request_timer(Duration::from_millis(30)), 30);
+                }
+            }
+            Event::AnimFrame(interval) => {
+                *data += interval;
+                if *data < 50_000_000 {
+                    ctx.request_anim_frame();
+                }
+            }
+            _ => (),
+        },
+    );
+
+    Harness::create_simple(0u64, widget, |harness| {
+        harness.send_initial_events();
+        assert!(!harness.animation_frame(FRAME_TIME));
+
+        harness.submit_command(START);
+        harness.advance_time(Duration::from_millis(49));
+        assert!(fired.borrow().is_empty());
+        harness.advance_time(Duration::from_millis(1));
+        assert_eq!(*fired.borrow(), [50]);
+        harness.advance_time(Duration::from_millis(100));
+        assert_eq!(*fired.borrow(), [50, 30, 100]);
+        assert!(!harness.has_pending_timers());
+        assert_eq!(harness.elapsed(), Duration::from_millis(150));
+
+        assert!(harness.wants_animation_frame());
+        assert_eq!(harness.run_animation(FRAME_TIME, 10), 4);
+        assert_eq!(*harness.data(), 4 * 16_000_000);
+        assert!(!harness.wants_animation_frame());
+        assert_eq!(harness.elapsed(), Duration::from_millis(150 + 4 * 16));
+    });
+}
+
+#[test]
+fn scripted_interactions() {
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        clicks: u32,
+        text: String,
+        value: f64,
+    }
+
+    let [button, text_box, slider] = widget_ids();
+    let widget = Flex::column()
+        .with_child(
+            Button::new("Add")
+                .on_click(|_, data: &mut Form, _| data.clicks += 1)
+                .with_id(button),
+        )
+        .with_child(TextBox::new().lens(Form::text).with_id(text_box))
+        .with_child(
+            Slider::new()
+                .lens(Form::value)
+                .fix_width(100.)
+                .with_id(slider),
+        );
+    let form = Form {
+        clicks: 0,
+        text: String::new(),
+        value: 0.5,
+    };
+
+    Harness::create_simple(form, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // Keys that nothing handles are ignored without a focused text field.
+        harness.press_key(Modifiers::empty(), "x");
+        assert_eq!(harness.data().text, "");
+
+        harness.click(button);
+        harness.click(button);
+        assert_eq!(harness.data().clicks, 2);
+
+        harness.click(text_box);
+        harness.type_text("hello");
+        assert_eq!(harness.data().text, "hello");
+        harness.press_key(Modifiers::empty(), KbKey::Backspace);
+        assert_eq!(harness.data().text, "hell");
+
+        // The composition is replaced until it is committed.
+        harness.ime_compose("w");
+        harness.ime_compose("wo");
+        assert_eq!(harness.data().text, "hellwo");
+        harness.ime_commit("世界");
+        assert_eq!(harness.data().text, "hell世界");
+
+        let rect = harness.window_rect(slider);
+        let start = Point::new(rect.x0 + 1., rect.center().y);
+        harness.drag(start, start + Vec2::new(rect.width() + 20., 0.));
+        assert_eq!(harness.data().value, 1.0);
+        assert_eq!(harness.data().text, "hell世界");
+    });
+}
+
+#[test]
+fn widget_queries() {
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        name: String,
+        agree: bool,
+        submitted: u32,
+    }
+
+    let widget = Flex::column()
+        .with_child(Label::new("Name"))
+        .with_child(TextBox::new().lens(Form::name))
+        .with_child(Checkbox::new("Agree").lens(Form::agree))
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("OK").on_click(|_, data: &mut Form, _| data.submitted += 1))
+                .with_child(Button::new("Cancel").debug_tag("cancel")),
+        )
+        .with_child(Label::new("Note"))
+        .with_child(Label::new("Note"));
+    let form = Form {
+        name: String::new(),
+        agree: false,
+        submitted: 0,
+    };
+
+    Harness::create_simple(form, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let button = || WidgetQuery::new().type_name("Button");
+        harness.assert_count(&button(), 2);
+        harness.assert_none(&button().label("Apply"));
+
+        // Identical siblings are told apart by their position in the tree.
+        let notes = harness.find_all(&WidgetQuery::new().label("Note"));
+        assert_eq!(notes.len(), 2);
+        assert_ne!(notes[0].id, notes[1].id);
+        assert!(notes[0].window_rect.y1 <= notes[1].window_rect.y0);
+
+        let ok = harness.assert_one(&button().label("OK"));
+        assert_eq!(ok.window_rect, harness.window_rect(ok.id));
+        harness.click(ok.id);
+        assert_eq!(harness.data().submitted, 1);
+
+        // Tags and other conditions can match different wrappers of a widget.
+        let cancel = harness.assert_one(&button().tag("cancel"));
+        assert_eq!(cancel.state.main_value, "Cancel");
+        assert!(cancel.window_rect.x0 >= ok.window_rect.x1);
+
+        let agree = harness.assert_one(&WidgetQuery::new().label("Agree"));
+        assert_eq!(agree.state.display_name, "Checkbox");
+        harness.click(agree.id);
+        harness.assert_one(&WidgetQuery::new().main_value("[X] Agree"));
+
+        let text_box = harness.assert_one(&WidgetQuery::new().type_name("TextBox"));
+        harness.click(text_box.id);
+        harness.type_text("Ada");
+        let name = WidgetQuery::new().type_name("TextBox").main_value("Ada");
+        assert_eq!(harness.assert_one(&name).id, text_box.id);
+        assert_eq!(
+            harness
+                .assert_one(&WidgetQuery::new().label("Name"))
+                .state
+                .display_name,
+            "Label"
+        );
+    });
+}
+
+#[test]
+fn multiple_windows() {
+    const NEW_WINDOW: Selector = Selector::new("druid-tests.new-window");
+    const NEW_SUB_WINDOW: Selector = Selector::new("druid-tests.new-sub-window");
+    const PING: Selector = Selector::new("druid-tests.ping");
+
+    type Log = Rc<std::cell::RefCell<Vec<(&'static str, WindowId)>>>;
+
+    struct Delegate(Log);
+
+    impl AppDelegate<u32> for Delegate {
+        fn command(
+            &mut self,
+            ctx: &mut DelegateCtx,
+            _: Target,
+            cmd: &Command,
+            _: &mut u32,
+            _: &Env,
+        ) -> Handled {
+            if cmd.is(NEW_WINDOW) {
+                ctx.new_window(WindowDesc::new(pinged(10)));
+                Handled::Yes
+            } else {
+                Handled::No
+            }
+        }
+
+        fn window_added(
+            &mut self,
+            id: WindowId,
+            _: WindowHandle,
+            _: &mut u32,
+            _: &Env,
+            _: &mut DelegateCtx,
+        ) {
+            self.0.borrow_mut().push(("added", id));
+        }
+
+        fn window_removed(&mut self, id: WindowId, _: &mut u32, _: &Env, _: &mut DelegateCtx) {
+            self.0.borrow_mut().push(("removed", id));
+        }
+    }
+
+    // Each window adds to the data when pinged, without handling the command.
+    fn pinged(amount: u32) -> impl Widget<u32> {
+        ModularWidget::new(()).event_fn(move |_, ctx, event, data: &mut u32, env| match event {
+            Event::Command(cmd) if cmd.is(PING) => *data += amount,
+            Event::Command(cmd) if cmd.is(NEW_SUB_WINDOW) => {
+                ctx.new_sub_window(WindowConfig::default(), Label::new("Sub"), (), env.clone());
+            }
+            _ => (),
+        })
+    }
+
+    let log = Log::default();
+    Harness::create_simple(0, pinged(1), |harness| {
+        harness.send_initial_events();
+        harness.set_delegate(Delegate(log.clone()));
+        let main = harness.window().id;
+
+        harness.submit_command(NEW_WINDOW.to(Target::Global));
+        let second = harness.opened_windows()[0];
+        assert_eq!(harness.window_ids(), [main, second]);
+        assert_eq!(*log.borrow(), [("added", second)]);
+
+        harness.submit_command(PING.to(second));
+        assert_eq!(*harness.data(), 10);
+        harness.submit_command(PING.to(Target::Global));
+        assert_eq!(*harness.data(), 21);
+
+        harness.submit_command(NEW_SUB_WINDOW);
+        let sub = harness.opened_windows()[1];
+        harness.select_window(sub);
+        harness.just_layout();
+        harness.assert_one(&WidgetQuery::new().type_name("Label").label("Sub"));
+        harness.submit_command(PING);
+        assert_eq!(*harness.data(), 21);
+
+        harness.submit_command(commands::CLOSE_WINDOW.to(second));
+        assert_eq!(harness.window_ids(), [main, sub]);
+        // Closing the current window selects the last window that is still open.
+        harness.submit_command(commands::CLOSE_WINDOW);
+        assert_eq!(harness.closed_windows(), [second, sub]);
+        assert_eq!(harness.window().id, main);
+        assert_eq!(
+            *log.borrow(),
+            [
+                ("added", second),
+                ("added", sub),
+                ("removed", second),
+                ("removed", sub)
+            ]
+        );
+    });
+}
+
+#[test]
+fn window_close_veto() {
+    const NEW_SUB_WINDOW: Selector = Selector::new("druid-tests.new-sub-window");
+
+    // The sub window refuses to close while it has unsaved changes.
+    let unsaved = Rc::new(Cell::new(true));
+    let disconnected = Rc::new(Cell::new(false));
+    let sub_window = ModularWidget::new((unsaved.clone(), disconnected.clone())).event_fn(
+        |(unsaved, disconnected), ctx, event, _: &mut (), _| match event {
+            Event::WindowCloseRequested if unsaved.get() => ctx.set_handled(),
+            Event::WindowDisconnected => disconnected.set(true),
+            _ => (),
+        },
+    );
+    let widget = ModularWidget::new(Some(sub_window)).event_fn(
+        |sub_window, ctx, event, _: &mut u32, env| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(NEW_SUB_WINDOW) {
+                    let root = sub_window.take().unwrap();
+                    ctx.new_sub_window(WindowConfig::default(), root, (), env.clone());
+                }
+            }
+        },
+    );
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        let main = harness.window().id;
+        harness.submit_command(NEW_SUB_WINDOW);
+        let sub = harness.opened_windows()[0];
+
+        harness.submit_command(commands::CLOSE_WINDOW.to(sub));
+        assert_eq!(harness.window_ids(), [main, sub]);
+        assert!(!disconnected.get());
+
+        unsaved.set(false);
+        harness.submit_command(commands::CLOSE_WINDOW.to(sub));
+        assert_eq!(harness.window_ids(), [main]);
+        assert_eq!(harness.closed_windows(), [sub]);
+        assert!(disconnected.get());
+    });
+}
+
 #[test]
 fn child_tracking() {
     let [id_1, id_2, id_3, id_4] = widget_ids();
@@ -0,0 +1,192 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Finding widgets in a widget tree by their [`DebugState`].
+//!
+//! Use the [`Harness`]'s [`find_all`] and [`assert_one`] with a [`WidgetQuery`]
+//! to get the ids and rects of widgets, without assigning ids in advance.
+//!
+//! [`Harness`]: super::harness::Harness
+//! [`find_all`]: super::harness::Harness::find_all
+//! [`assert_one`]: super::harness::Harness::assert_one
+
+use std::fmt;
+
+use crate::debug_state::{DebugState, DebugWidget};
+use crate::widget::DebugTag;
+use crate::{Rect, WidgetId};
+
+/// A description of the widgets to find, built from conditions on their
+/// [`DebugState`].
+///
+/// A widget matches if all of the conditions hold. The conditions are checked
+/// against the states of the widget in a `WidgetPod` and of the wrappers
+/// around it that aren't in a `WidgetPod` of their own, so a [`Button`] that
+/// was given an id with [`with_id`] is found with its id.
+///
+/// ```ignore
+/// let ok = harness.assert_one(&WidgetQuery::new().type_name("Button").label("OK"));
+/// harness.click(ok.id);
+/// ```
+///
+/// [`Button`]: crate::widget::Button
+/// [`with_id`]: crate::WidgetExt::with_id
+#[derive(Debug, Clone, Default)]
+pub struct WidgetQuery {
+    type_name: Option<String>,
+    main_value: Option<String>,
+    label: Option<String>,
+    other_values: Vec<(String, String)>,
+    tag: Option<String>,
+}
+
+/// A widget that matched a [`WidgetQuery`].
+#[derive(Debug, Clone)]
+pub struct FoundWidget {
+    /// The id of the widget's `WidgetPod`.
+    pub id: WidgetId,
+    /// The layout rect of the widget, in its parent's coordinate space.
+    pub layout_rect: Rect,
+    /// The layout rect of the widget, in window coordinates.
+    pub window_rect: Rect,
+    /// The state that matched the query, with the states of its children.
+    pub state: DebugState,
+}
+
+impl WidgetQuery {
+    /// Create a query that matches every widget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to match widgets with the given short type name,
+    /// such as `"Button"`.
+    pub fn type_name(mut self, name: impl Into<String>) -> Self {
+        self.type_name = Some(name.into());
+        self
+    }
+
+    /// Builder-style method to match widgets with the given main value, such
+    /// as the text of a `TextBox` or the value of a `Slider`.
+    pub fn main_value(mut self, value: impl Into<String>) -> Self {
+        self.main_value = Some(value.into());
+        self
+    }
+
+    /// Builder-style method to match widgets with the given label text.
+    ///
+    /// This is the main value of labels and buttons, without the `"[X] "` or
+    /// `"[_] "` that checkboxes and radio buttons put in front of their label.
+    pub fn label(mut self, text: impl Into<String>) -> Self {
+        self.label = Some(text.into());
+        self
+    }
+
+    /// Builder-style method to match widgets with the given value for `key`
+    /// in their `other_values`.
+    pub fn other_value(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.other_values.push((key.into(), value.into()));
+        self
+    }
+
+    /// Builder-style method to match widgets that were tagged with
+    /// [`debug_tag`].
+    ///
+    /// [`debug_tag`]: crate::WidgetExt::debug_tag
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Find the matching widgets in a list collected from a widget tree.
+    pub(crate) fn find_in(&self, widgets: &[DebugWidget]) -> Vec<FoundWidget> {
+        (0..widgets.len())
+            .filter_map(|index| {
+                let nodes = own_states(widgets, index);
+                if let Some(tag) = &self.tag {
+                    let tagged = |node: &&DebugState| {
+                        node.other_values.get(DebugTag::<()>::KEY) == Some(tag)
+                    };
+                    nodes.iter().find(tagged)?;
+                }
+                let state = nodes.into_iter().find(|node| self.matches(node))?;
+                let widget = &widgets[index];
+                Some(FoundWidget {
+                    id: widget.id,
+                    layout_rect: widget.layout_rect,
+                    window_rect: widget.window_rect,
+                    state: state.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns `true` if a single state matches the conditions other than the tag.
+    fn matches(&self, state: &DebugState) -> bool {
+        let label = ["[X] ", "[_] "]
+            .iter()
+            .find_map(|mark| state.main_value.strip_prefix(mark))
+            .unwrap_or(&state.main_value);
+
+        self.type_name
+            .iter()
+            .all(|name| *name == state.display_name)
+            && self
+                .main_value
+                .iter()
+                .all(|value| *value == state.main_value)
+            && self.label.iter().all(|text| text == label)
+            && self
+                .other_values
+                .iter()
+                .all(|(key, value)| state.other_values.get(key) == Some(value))
+    }
+}
+
+/// The states in the tree of a collected widget that belong to it, rather
+/// than to one of the widgets collected as its descendants, in tree order.
+fn own_states(widgets: &[DebugWidget], index: usize) -> Vec<&DebugState> {
+    let children: Vec<_> = widgets
+        .iter()
+        .filter(|widget| widget.parent == Some(index))
+        .filter_map(|widget| widget.path.as_deref())
+        .collect();
+
+    let mut states = Vec::new();
+    let mut stack = vec![(Vec::new(), &widgets[index].state)];
+    while let Some((path, state)) = stack.pop() {
+        states.push(state);
+        for (position, child) in state.children.iter().enumerate().rev() {
+            let mut child_path = path.clone();
+            child_path.push(position);
+            if !children.contains(&child_path.as_slice()) {
+                stack.push((child_path, child));
+            }
+        }
+    }
+    states
+}
+
+impl fmt::Display for WidgetQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.type_name.as_deref().unwrap_or("widget"))?;
+
+        let mut conditions = Vec::new();
+        if let Some(value) = &self.main_value {
+            conditions.push(format!("main value {value:?}"));
+        }
+        if let Some(text) = &self.label {
+            conditions.push(format!("label {text:?}"));
+        }
+        for (key, value) in &self.other_values {
+            conditions.push(format!("{key} {value:?}"));
+        }
+        if let Some(tag) = &self.tag {
+            conditions.push(format!("tag {tag:?}"));
+        }
+        if !conditions.is_empty() {
+            write!(f, " with {}", conditions.join(" and "))?;
+        }
+        Ok(())
+    }
+}
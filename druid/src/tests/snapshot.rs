@@ -0,0 +1,453 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparing renders of widgets with reference images.
+//!
+//! The reference images are stored in `src/tests/snapshots`. Run the tests with
+//! the `DRUID_BLESS_SNAPSHOTS` environment variable set to create or update them
+//! from the current renders.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use super::harness::{Harness, DEFAULT_SIZE};
+use crate::piet::ImageFormat;
+use crate::{Data, ImageBuf, Size, Widget};
+
+/// Set this environment variable to write the renders as the new reference images.
+pub const BLESS_VAR: &str = "DRUID_BLESS_SNAPSHOTS";
+
+/// The largest difference of a color channel for pixels that are still equal.
+const DEFAULT_TOLERANCE: u8 = 2;
+
+/// The largest fraction of pixels that may differ in a render that still matches.
+const DEFAULT_THRESHOLD: f64 = 0.0;
+
+/// The largest perceptual difference of two pixels, black and white.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// A named reference image, that renders of a widget have to match.
+///
+/// A render matches if its pixels are equal to those of the reference,
+/// ignoring color channels that differ by at most the [`tolerance`]. Pixels
+/// with a channel that differs by more count as different, and at most the
+/// [`threshold`], a fraction of all pixels, may be different.
+///
+/// If the render doesn't match, the render, the reference and an image
+/// highlighting the differences are written to `target/snapshots`, and the
+/// test panics.
+///
+/// ```ignore
+/// Snapshot::new("red_box")
+///     .size(Size::new(40., 30.))
+///     .scale(2.0)
+///     .assert_widget((), SizedBox::empty().background(Color::RED));
+/// ```
+///
+/// [`tolerance`]: Snapshot::tolerance
+/// [`threshold`]: Snapshot::threshold
+pub struct Snapshot {
+    name: String,
+    size: Size,
+    scale: f64,
+    tolerance: u8,
+    threshold: f64,
+    reference_dir: PathBuf,
+}
+
+/// An image with separate alpha, in RGBA order.
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+/// How a render differs from its reference image.
+struct Comparison {
+    /// The number of pixels that differ by more than the tolerance.
+    different: usize,
+    /// The fraction of all pixels that are different.
+    fraction: f64,
+    /// The reference image, faded, with the differences in red.
+    diff: Image,
+}
+
+impl Snapshot {
+    /// Create a snapshot with the given name, which is also the file name of
+    /// the reference image.
+    ///
+    /// Widgets are rendered at the [`Harness`]'s default window size and a
+    /// scale of `1.0`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Snapshot {
+            name: name.into(),
+            size: DEFAULT_SIZE,
+            scale: 1.0,
+            tolerance: DEFAULT_TOLERANCE,
+            threshold: DEFAULT_THRESHOLD,
+            reference_dir: Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/snapshots"),
+        }
+    }
+
+    /// Builder-style method for setting the window size, in display points.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Builder-style method for setting the number of pixels per display point.
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Builder-style method for setting the largest difference of a color
+    /// channel, from `0` to `255`, for pixels that are still equal.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Builder-style method for setting the largest fraction of pixels, from
+    /// `0.0` to `1.0`, that may differ in a render that still matches.
+    ///
+    /// The default is `0.0`, so that every pixel has to be within the tolerance.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Render `root` with `data`, and assert that it matches the reference image.
+    pub fn assert_widget<T: Data>(&self, data: T, root: impl Widget<T> + 'static) {
+        self.assert_widget_with(data, root, |_| {})
+    }
+
+    /// Render `root` with `data` after calling `f`, and assert that it matches
+    /// the reference image.
+    ///
+    /// `f` is called after the initial events and layout, and can be used to
+    /// send events to put the widget in the state to test.
+    pub fn assert_widget_with<T: Data>(
+        &self,
+        data: T,
+        root: impl Widget<T> + 'static,
+        mut f: impl FnMut(&mut Harness<T>),
+    ) {
+        Harness::create_with_render_scaled(
+            data,
+            root,
+            self.size,
+            self.scale,
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                f(harness);
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| self.assert_matches(&target.into_image_buf()),
+        )
+    }
+
+    /// Assert that `render` matches the reference image.
+    ///
+    /// If [`BLESS_VAR`] is set, `render` replaces the reference image instead,
+    /// unless they already match.
+    pub fn assert_matches(&self, render: &ImageBuf) {
+        let bless = env::var_os(BLESS_VAR).is_some();
+        if let Err(mismatch) = self.check(&Image::from_image_buf(render), bless) {
+            panic!("snapshot '{}' {mismatch}", self.name);
+        }
+    }
+
+    /// Compare `actual` with the reference image, or replace the reference
+    /// with it if `bless` is set and they don't match.
+    fn check(&self, actual: &Image, bless: bool) -> Result<(), String> {
+        let reference = self.reference_path();
+        match self.mismatch(&reference, actual) {
+            Some(_) if bless => {
+                fs::create_dir_all(&self.reference_dir).unwrap();
+                actual.save(&reference);
+                Ok(())
+            }
+            Some(mismatch) => Err(mismatch),
+            None => Ok(()),
+        }
+    }
+
+    /// Compare `actual` with the reference image, returning why they don't match.
+    ///
+    /// If they don't, this writes the images that explain why.
+    fn mismatch(&self, reference: &Path, actual: &Image) -> Option<String> {
+        if !reference.exists() {
+            let actual_path = self.write_failure("actual", actual);
+            return Some(format!(
+                "has no reference image {}; the render was written to {}, \
+                 set {BLESS_VAR} to use it as the reference",
+                reference.display(),
+                actual_path.display(),
+            ));
+        }
+
+        let expected = Image::load(reference);
+        if (expected.width, expected.height) != (actual.width, actual.height) {
+            let actual_path = self.write_failure("actual", actual);
+            return Some(format!(
+                "does not match: the render is {}x{} but the reference is {}x{}; \
+                 the render was written to {}",
+                actual.width,
+                actual.height,
+                expected.width,
+                expected.height,
+                actual_path.display(),
+            ));
+        }
+
+        let comparison = compare(&expected, actual, self.tolerance);
+        if comparison.different == 0 || comparison.fraction <= self.threshold {
+            return None;
+        }
+        let actual_path = self.write_failure("actual", actual);
+        self.write_failure("expected", &expected);
+        let diff_path = self.write_failure("diff", &comparison.diff);
+        Some(format!(
+            "does not match: {} pixels differ, a fraction of {:.5} (threshold {}); \
+             see {} and {}",
+            comparison.different,
+            comparison.fraction,
+            self.threshold,
+            actual_path.display(),
+            diff_path.display(),
+        ))
+    }
+
+    fn reference_path(&self) -> PathBuf {
+        self.reference_dir.join(format!("{}.png", self.name))
+    }
+
+    /// Where one of the images of a failed comparison is written, next to
+    /// the test binary.
+    fn failure_path(&self, kind: &str) -> PathBuf {
+        let current_exe_path = env::current_exe().unwrap();
+        let mut exe_dir = current_exe_path.parent().unwrap();
+        if exe_dir.ends_with("deps") {
+            exe_dir = exe_dir.parent().unwrap();
+        }
+        exe_dir
+            .parent()
+            .unwrap()
+            .join("snapshots")
+            .join(format!("{}.{kind}.png", self.name))
+    }
+
+    /// Write one of the images of a failed comparison.
+    fn write_failure(&self, kind: &str, image: &Image) -> PathBuf {
+        let path = self.failure_path(kind);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        image.save(&path);
+        path
+    }
+}
+
+impl Image {
+    fn from_image_buf(buf: &ImageBuf) -> Image {
+        let mut pixels = buf.raw_pixels().to_vec();
+        match buf.format() {
+            ImageFormat::RgbaSeparate => (),
+            ImageFormat::RgbaPremul => {
+                for pixel in pixels.chunks_exact_mut(4) {
+                    let alpha = pixel[3] as u32;
+                    if alpha != 0 {
+                        for channel in &mut pixel[..3] {
+                            *channel = ((*channel as u32 * 255 + alpha / 2) / alpha) as u8;
+                        }
+                    }
+                }
+            }
+            format => panic!("snapshots of {format:?} images are not supported"),
+        }
+        Image {
+            width: buf.width(),
+            height: buf.height(),
+            pixels,
+        }
+    }
+
+    fn load(path: &Path) -> Image {
+        let file = BufReader::new(File::open(path).unwrap());
+        let mut reader = png::Decoder::new(file).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert!(
+            info.color_type == png::ColorType::Rgba && info.bit_depth == png::BitDepth::Eight,
+            "reference image {} is not 8-bit RGBA",
+            path.display()
+        );
+        pixels.truncate(info.buffer_size());
+        Image {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        }
+    }
+
+    fn save(&self, path: &Path) {
+        let file = BufWriter::new(File::create(path).unwrap());
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .unwrap();
+    }
+}
+
+/// Compare two images of the same size.
+fn compare(expected: &Image, actual: &Image, tolerance: u8) -> Comparison {
+    let mut different = 0;
+    let mut diff = Vec::with_capacity(expected.pixels.len());
+    for (e, a) in expected
+        .pixels
+        .chunks_exact(4)
+        .zip(actual.pixels.chunks_exact(4))
+    {
+        let exceeds = e.iter().zip(a).any(|(e, a)| e.abs_diff(*a) > tolerance);
+        if exceeds {
+            different += 1;
+            let delta = yiq_delta(e, a);
+            // Stronger differences are a more opaque red.
+            diff.extend_from_slice(&[255, 0, 0, 128 + (delta * 127.0) as u8]);
+        } else {
+            let gray = (255.0 - (255.0 - luma(e)) * 0.1) as u8;
+            diff.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+    let pixel_count = (expected.width * expected.height).max(1);
+    Comparison {
+        different,
+        fraction: different as f64 / pixel_count as f64,
+        diff: Image {
+            width: expected.width,
+            height: expected.height,
+            pixels: diff,
+        },
+    }
+}
+
+/// The color of an RGBA pixel blended onto white.
+fn blend_white(pixel: &[u8]) -> (f64, f64, f64) {
+    let alpha = pixel[3] as f64 / 255.0;
+    let blend = |c: u8| 255.0 + (c as f64 - 255.0) * alpha;
+    (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]))
+}
+
+/// The brightness of an RGBA pixel blended onto white, from `0.0` to `255.0`.
+fn luma(pixel: &[u8]) -> f64 {
+    let (r, g, b) = blend_white(pixel);
+    r * 0.29889531 + g * 0.58662247 + b * 0.11448223
+}
+
+/// The perceptual difference of two pixels, from `0.0` to `1.0`.
+///
+/// This is the distance in the YIQ color space, weighted as in "Measuring
+/// perceived color difference using YIQ NTSC transmission color space in
+/// mobile applications" by Kotsarenko and Ramos.
+fn yiq_delta(a: &[u8], b: &[u8]) -> f64 {
+    let (r1, g1, b1) = blend_white(a);
+    let (r2, g2, b2) = blend_white(b);
+    let y = luma(a) - luma(b);
+    let i = (r1 - r2) * 0.59597799 - (g1 - g2) * 0.2741761 - (b1 - b2) * 0.32180189;
+    let q = (r1 - r2) * 0.21147017 - (g1 - g2) * 0.52261711 + (b1 - b2) * 0.31114694;
+    (0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_YIQ_DELTA
+}
+
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+
+    /// A white 20x20 image, with the first `red` pixels red.
+    fn image(red: usize) -> Image {
+        let pixels = (0..400)
+            .flat_map(|i| if i < red { RED } else { WHITE })
+            .collect();
+        Image {
+            width: 20,
+            height: 20,
+            pixels,
+        }
+    }
+
+    /// A snapshot whose reference image is `reference`, in a directory of its own.
+    fn snapshot(name: &str, reference: Option<&Image>) -> Snapshot {
+        let snapshot = Snapshot {
+            reference_dir: env::temp_dir().join(format!("druid-snapshot-{name}")),
+            ..Snapshot::new(name)
+        };
+        let _ = fs::remove_dir_all(&snapshot.reference_dir);
+        if let Some(reference) = reference {
+            fs::create_dir_all(&snapshot.reference_dir).unwrap();
+            reference.save(&snapshot.reference_path());
+        }
+        snapshot
+    }
+
+    fn clean_up(snapshot: &Snapshot) {
+        let _ = fs::remove_dir_all(&snapshot.reference_dir);
+        for kind in ["actual", "expected", "diff"] {
+            let _ = fs::remove_file(snapshot.failure_path(kind));
+        }
+    }
+
+    #[test]
+    fn differences_within_the_tolerance_match() {
+        let snapshot = snapshot("snapshot_tolerance", Some(&image(0)));
+        let mut actual = image(0);
+        actual.pixels[..8].copy_from_slice(&[253, 255, 254, 255, 255, 253, 255, 255]);
+        assert_eq!(snapshot.check(&actual, false), Ok(()));
+        clean_up(&snapshot);
+    }
+
+    #[test]
+    fn the_threshold_is_a_fraction_of_the_pixels() {
+        // 4 of 400 pixels may differ.
+        let snapshot = snapshot("snapshot_threshold", Some(&image(0))).threshold(0.01);
+        assert_eq!(snapshot.check(&image(4), false), Ok(()));
+        assert!(snapshot.check(&image(5), false).is_err());
+        clean_up(&snapshot);
+    }
+
+    #[test]
+    fn a_mismatch_writes_the_images() {
+        let snapshot = snapshot("snapshot_mismatch", Some(&image(0)));
+        let error = snapshot.check(&image(1), false).unwrap_err();
+        assert!(error.contains("1 pixels differ"), "{error}");
+
+        let actual = Image::load(&snapshot.failure_path("actual"));
+        assert_eq!(actual.pixels, image(1).pixels);
+        let expected = Image::load(&snapshot.failure_path("expected"));
+        assert_eq!(expected.pixels, image(0).pixels);
+        let diff = Image::load(&snapshot.failure_path("diff"));
+        assert_eq!(diff.pixels[..3], RED[..3]);
+        assert_ne!(diff.pixels[4..7], RED[..3]);
+        clean_up(&snapshot);
+    }
+
+    #[test]
+    fn blessing_replaces_the_reference() {
+        let snapshot = snapshot("snapshot_bless", None);
+        assert!(snapshot.check(&image(0), false).is_err());
+        assert_eq!(snapshot.check(&image(0), true), Ok(()));
+        assert_eq!(snapshot.check(&image(0), false), Ok(()));
+
+        assert_eq!(snapshot.check(&image(1), true), Ok(()));
+        let reference = Image::load(&snapshot.reference_path());
+        assert_eq!(reference.pixels, image(1).pixels);
+        clean_up(&snapshot);
+    }
+}
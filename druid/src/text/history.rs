@@ -0,0 +1,241 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The undo history of an edit session.
+
+use std::collections::VecDeque;
+
+use super::Selection;
+use crate::Data;
+
+/// The number of edits that can be undone, by default.
+pub(crate) const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// The kind of change an edit made to the text.
+///
+/// Consecutive edits of the same kind are merged into a single undo step,
+/// so that undoing reverts a whole word or run of deletions at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditKind {
+    /// Text was typed, or committed by an input method.
+    Typing,
+    /// Text was removed with backspace or delete.
+    Deleting,
+    /// Any other edit, such as a paste or a newline. These are never merged.
+    Other,
+}
+
+impl EditKind {
+    /// The kind of an edit that replaces the selection with `text`.
+    pub(crate) fn for_insertion(text: &str) -> Self {
+        if text.is_empty() {
+            EditKind::Deleting
+        } else if text.contains(['\n', '\r']) {
+            EditKind::Other
+        } else {
+            EditKind::Typing
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edit<T> {
+    kind: EditKind,
+    before: T,
+    selection_before: Selection,
+    after: T,
+    selection_after: Selection,
+}
+
+/// The undo and redo stacks of an [`EditSession`].
+///
+/// Edits are stored as snapshots of the text before and after the change;
+/// with cheaply cloned text storage such as `Arc<String>` this costs little
+/// more than the edited text itself.
+///
+/// [`EditSession`]: super::EditSession
+#[derive(Debug, Clone)]
+pub(crate) struct EditHistory<T> {
+    undo: VecDeque<Edit<T>>,
+    redo: Vec<Edit<T>>,
+    limit: usize,
+    /// Whether the next edit may be merged into the previous one.
+    can_merge: bool,
+}
+
+impl<T> EditHistory<T> {
+    pub(crate) fn new(limit: usize) -> Self {
+        EditHistory {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            can_merge: false,
+        }
+    }
+
+    /// Set the maximum number of edits that can be undone.
+    ///
+    /// A limit of `0` disables the history.
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.undo.len() > limit {
+            self.undo.pop_front();
+        }
+        self.redo.truncate(limit);
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forget all edits.
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.can_merge = false;
+    }
+
+    /// Make sure the next edit starts a new undo step.
+    pub(crate) fn break_merge(&mut self) {
+        self.can_merge = false;
+    }
+}
+
+impl<T: Data> EditHistory<T> {
+    /// Add an edit to the history, merging it with the previous one if possible.
+    ///
+    /// This discards anything that could have been redone.
+    pub(crate) fn record(
+        &mut self,
+        kind: EditKind,
+        before: T,
+        selection_before: Selection,
+        after: T,
+        selection_after: Selection,
+    ) {
+        if self.limit == 0 || before.same(&after) {
+            return;
+        }
+        self.redo.clear();
+
+        if self.can_merge && kind != EditKind::Other {
+            if let Some(last) = self.undo.back_mut() {
+                if last.kind == kind
+                    && same_position(last.selection_after, selection_before)
+                    && last.after.same(&before)
+                {
+                    last.after = after;
+                    last.selection_after = selection_after;
+                    return;
+                }
+            }
+        }
+
+        self.undo.push_back(Edit {
+            kind,
+            before,
+            selection_before,
+            after,
+            selection_after,
+        });
+        if self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+        self.can_merge = true;
+    }
+
+    /// Returns `true` if `text` is the result of the most recent edit.
+    pub(crate) fn is_latest(&self, text: &T) -> bool {
+        self.undo
+            .back()
+            .map(|edit| edit.after.same(text))
+            .unwrap_or(false)
+    }
+
+    /// Move the most recent edit to the redo stack, returning the text and
+    /// selection from before it was made.
+    pub(crate) fn undo(&mut self) -> Option<(T, Selection)> {
+        let edit = self.undo.pop_back()?;
+        let result = (edit.before.clone(), edit.selection_before);
+        self.redo.push(edit);
+        self.can_merge = false;
+        Some(result)
+    }
+
+    /// Move the most recently undone edit back to the undo stack, returning
+    /// the text and selection from after it was made.
+    pub(crate) fn redo(&mut self) -> Option<(T, Selection)> {
+        let edit = self.redo.pop()?;
+        let result = (edit.after.clone(), edit.selection_after);
+        self.undo.push_back(edit);
+        self.can_merge = false;
+        Some(result)
+    }
+}
+
+fn same_position(a: Selection, b: Selection) -> bool {
+    a.anchor == b.anchor && a.active == b.active
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caret(pos: usize) -> Selection {
+        Selection::caret(pos)
+    }
+
+    fn type_text(history: &mut EditHistory<String>, text: &mut String, new: &str) {
+        let before = text.clone();
+        let selection_before = caret(text.len());
+        text.push_str(new);
+        let kind = EditKind::for_insertion(new);
+        history.record(
+            kind,
+            before,
+            selection_before,
+            text.clone(),
+            caret(text.len()),
+        );
+    }
+
+    #[test]
+    fn typing_is_merged() {
+        let mut history = EditHistory::new(DEFAULT_UNDO_LIMIT);
+        let mut text = String::new();
+        for c in ["a", "b", "c"] {
+            type_text(&mut history, &mut text, c);
+        }
+        type_text(&mut history, &mut text, "\n");
+        type_text(&mut history, &mut text, "d");
+
+        assert_eq!(history.undo(), Some(("abc\n".to_string(), caret(4))));
+        assert_eq!(history.undo(), Some(("abc".to_string(), caret(3))));
+        assert_eq!(history.undo(), Some((String::new(), caret(0))));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(("abc".to_string(), caret(3))));
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn limit_drops_oldest_edits() {
+        let mut history = EditHistory::new(2);
+        let mut text = String::new();
+        for c in ["a", "\n", "b", "\n"] {
+            type_text(&mut history, &mut text, c);
+        }
+
+        assert_eq!(history.undo(), Some(("a\nb".to_string(), caret(3))));
+        assert_eq!(history.undo(), Some(("a\n".to_string(), caret(2))));
+        assert!(!history.can_undo());
+
+        history.set_limit(0);
+        type_text(&mut history, &mut text, "c");
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+    }
+}
@@ -9,6 +9,7 @@ use std::sync::{Arc, Weak};
 
 use tracing::instrument;
 
+use super::history::{EditHistory, EditKind, DEFAULT_UNDO_LIMIT};
 use super::{
     EditableText, ImeHandlerRef, ImeInvalidation, InputHandler, Movement, Selection, TextAction,
     TextLayout, TextStorage,
@@ -75,6 +76,8 @@ pub struct EditSession<T> {
     external_selection_change: Option<Selection>,
     external_scroll_to: Option<bool>,
     external_action: Option<TextAction>,
+    /// The kind of the edit in `external_text_change`, for the undo history.
+    external_edit_kind: Option<EditKind>,
     /// A flag set in `update` if the text has changed from a non-IME source.
     pending_ime_invalidation: Option<ImeInvalidation>,
     /// If `true`, the component will send the [`TextComponent::RETURN`]
@@ -91,6 +94,9 @@ pub struct EditSession<T> {
     alignment_offset: f64,
     /// The portion of the text that is currently marked by the IME.
     composition_range: Option<Range<usize>>,
+    /// The text and selection from before the current IME composition started.
+    composition_start: Option<(T, Selection)>,
+    history: EditHistory<T>,
     drag_granularity: DragGranularity,
     /// The origin of the textbox, relative to the origin of the window.
     pub origin: Point,
@@ -331,6 +337,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 }
                 let text = self.borrow_mut().take_external_text_change();
                 let selection = self.borrow_mut().take_external_selection_change();
+                let edit_kind = self.borrow_mut().take_external_edit_kind();
+                self.borrow_mut()
+                    .record_ime_edit(edit_kind, data, text.as_ref(), selection);
                 if let Some(text) = text {
                     self.borrow_mut().layout.set_text(text.clone());
                     *data = text;
@@ -341,6 +350,27 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 }
                 ctx.request_update();
             }
+            Event::Command(cmd)
+                if self.has_focus
+                    && self.can_write()
+                    && !self.is_composing()
+                    && (cmd.is(crate::commands::UNDO) || cmd.is(crate::commands::REDO)) =>
+            {
+                let inval = if cmd.is(crate::commands::UNDO) {
+                    self.borrow_mut().undo(data)
+                } else {
+                    self.borrow_mut().redo(data)
+                };
+                // With nothing to undo or redo, the command is left to an
+                // enclosing `UndoManager` or the delegate.
+                if let Some(inval) = inval {
+                    ctx.invalidate_text_input(inval);
+                    ctx.submit_notification(TextComponent::SCROLL_TO.with(true));
+                    ctx.request_update();
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
             _ => (),
         }
     }
@@ -517,6 +547,29 @@ impl<T> EditSession<T> {
         self.alignment
     }
 
+    /// Set the maximum number of edits that can be undone.
+    ///
+    /// The default is 100; a limit of `0` disables the undo history.
+    pub fn set_undo_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+
+    /// Returns `true` if there is an edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Returns `true` if there is an undone edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Forget all edits in the undo history.
+    pub fn clear_undo_history(&mut self) {
+        self.history.clear();
+        self.composition_start = None;
+    }
+
     /// Returns any invalidation action that should be passed to the platform.
     ///
     /// The user of this component *must* check this after calling `update`.
@@ -540,6 +593,10 @@ impl<T> EditSession<T> {
         self.external_action.take()
     }
 
+    fn take_external_edit_kind(&mut self) -> Option<EditKind> {
+        self.external_edit_kind.take()
+    }
+
     // we don't want to replace a more aggressive invalidation with a less aggressive one.
     fn update_pending_invalidation(&mut self, new_invalidation: ImeInvalidation) {
         self.pending_ime_invalidation = match self.pending_ime_invalidation.take() {
@@ -573,13 +630,94 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     /// text state, by calling [`EventCtx::invalidate_text_input`].
     #[must_use]
     pub fn insert_text(&mut self, data: &mut T, new_text: &str) -> ImeInvalidation {
+        let before = data.clone();
+        let selection_before = self.selection;
         let new_cursor_pos = self.selection.min() + new_text.len();
         data.edit(self.selection.range(), new_text);
         self.selection = Selection::caret(new_cursor_pos);
+        self.history.record(
+            EditKind::Other,
+            before,
+            selection_before,
+            data.clone(),
+            self.selection,
+        );
         self.scroll_to_selection_end(true);
         ImeInvalidation::Reset
     }
 
+    /// Revert the most recent edit, restoring the text and selection from
+    /// before it was made.
+    ///
+    /// Returns `None` if there is nothing to undo. Otherwise, the caller is
+    /// responsible for notifying the platform of the change in text state,
+    /// by passing the returned value to [`EventCtx::invalidate_text_input`].
+    #[must_use]
+    pub fn undo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let (text, selection) = self.history.undo()?;
+        self.restore(data, text, selection);
+        Some(ImeInvalidation::Reset)
+    }
+
+    /// Reapply the most recently undone edit.
+    ///
+    /// Returns `None` if there is nothing to redo. Otherwise, the caller is
+    /// responsible for notifying the platform of the change in text state,
+    /// by passing the returned value to [`EventCtx::invalidate_text_input`].
+    #[must_use]
+    pub fn redo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let (text, selection) = self.history.redo()?;
+        self.restore(data, text, selection);
+        Some(ImeInvalidation::Reset)
+    }
+
+    fn restore(&mut self, data: &mut T, text: T, selection: Selection) {
+        self.selection = selection.constrained(text.as_str());
+        self.layout.set_text(text.clone());
+        *data = text;
+    }
+
+    /// Record an edit made by the platform in the undo history.
+    ///
+    /// While the IME is composing, the intermediate states are not recorded;
+    /// the whole composition becomes a single edit once it is committed.
+    fn record_ime_edit(
+        &mut self,
+        kind: Option<EditKind>,
+        old_text: &T,
+        new_text: Option<&T>,
+        new_selection: Option<Selection>,
+    ) {
+        if self.composition_range.is_some() {
+            if new_text.is_some() && self.composition_start.is_none() {
+                self.composition_start = Some((old_text.clone(), self.selection));
+            }
+            return;
+        }
+
+        let new_selection = new_selection.unwrap_or(self.selection);
+        match (self.composition_start.take(), new_text) {
+            (Some((before, selection_before)), new_text) => {
+                let after = new_text.unwrap_or(old_text).clone();
+                self.history.record(
+                    EditKind::Typing,
+                    before,
+                    selection_before,
+                    after,
+                    new_selection,
+                );
+            }
+            (None, Some(new_text)) => self.history.record(
+                kind.unwrap_or(EditKind::Other),
+                old_text.clone(),
+                self.selection,
+                new_text.clone(),
+                new_selection,
+            ),
+            (None, None) => (),
+        }
+    }
+
     /// Sets the clipboard to the contents of the current selection.
     ///
     /// Returns `true` if the clipboard was set, and `false` if not (indicating)
@@ -695,6 +833,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     fn ime_insert_text(&mut self, buffer: &mut T, text: &str) {
         let new_cursor_pos = self.selection.min() + text.len();
         buffer.edit(self.selection.range(), text);
+        self.external_edit_kind = Some(EditKind::for_insertion(text));
         self.external_selection_change = Some(Selection::caret(new_cursor_pos));
         self.scroll_to_selection_end(true);
     }
@@ -707,6 +846,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             self.selection.range()
         };
         self.external_selection_change = Some(Selection::caret(to_del.start));
+        self.external_edit_kind = Some(EditKind::Deleting);
         buffer.edit(to_del, "");
         self.scroll_to_selection_end(true);
     }
@@ -714,6 +854,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     fn do_mouse_down(&mut self, point: Point, mods: Modifiers, count: u8) {
         let point = point - Vec2::new(self.alignment_offset, 0.0);
         let pos = self.layout.text_position_for_point(point);
+        self.history.break_merge();
         if mods.shift() {
             self.selection.active = pos;
         } else {
@@ -807,6 +948,11 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             .map(|t| !t.same(new_data))
             .unwrap_or(true)
         {
+            // Our own edits can be undone; a change from anywhere else
+            // invalidates everything in the history.
+            if !self.history.is_latest(new_data) {
+                self.clear_undo_history();
+            }
             self.update_pending_invalidation(ImeInvalidation::Reset);
             self.layout.set_text(new_data.clone());
         }
@@ -861,7 +1007,9 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
 
     fn replace_range(&mut self, range: Range<usize>, text: &str) {
         self.text.edit(range, text);
-        self.inner.borrow_mut().external_text_change = Some(self.text.clone());
+        let mut inner = self.inner.borrow_mut();
+        inner.external_text_change = Some(self.text.clone());
+        inner.external_edit_kind = Some(EditKind::for_insertion(text));
     }
 
     fn hit_test_point(&self, point: Point) -> crate::piet::HitTestPoint {
@@ -925,9 +1073,12 @@ impl<T> Default for TextComponent<T> {
             external_text_change: None,
             external_selection_change: None,
             external_action: None,
+            external_edit_kind: None,
             pending_ime_invalidation: None,
             selection: Selection::caret(0),
             composition_range: None,
+            composition_start: None,
+            history: EditHistory::new(DEFAULT_UNDO_LIMIT),
             send_notification_on_return: false,
             send_notification_on_cancel: false,
             accepts_newlines: false,
@@ -947,3 +1098,119 @@ impl<T> Default for TextComponent<T> {
         }
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::{commands, Target};
+    use test_log::test;
+
+    /// Edit the text through the input handler, the way the platform would.
+    fn ime_edit(
+        harness: &mut Harness<String>,
+        component: &TextComponent<String>,
+        edit: impl FnOnce(&mut dyn InputHandler),
+    ) {
+        let handler = component.input_handler();
+        let mut session = handler.acquire(true).unwrap();
+        edit(&mut *session);
+        drop(session);
+        handler.release();
+        harness.event(Event::ImeStateChange);
+    }
+
+    fn insert(session: &mut dyn InputHandler, text: &str) {
+        let selection = session.selection();
+        session.replace_range(selection.range(), text);
+        session.set_selection(Selection::caret(selection.min() + text.len()));
+    }
+
+    #[test]
+    fn undo_restores_text_and_selection() {
+        let component = TextComponent {
+            has_focus: true,
+            ..Default::default()
+        };
+        let text = component.clone();
+
+        Harness::create_simple(String::new(), component, |harness| {
+            harness.send_initial_events();
+            for c in ["h", "e", "y"] {
+                ime_edit(harness, &text, |session| insert(session, c));
+            }
+            ime_edit(harness, &text, |session| {
+                session.set_selection(Selection::new(0, 3))
+            });
+            ime_edit(harness, &text, |session| insert(session, ""));
+            assert_eq!(harness.data(), "");
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "hey");
+            assert_eq!(text.borrow().selection(), Selection::new(0, 3));
+
+            // Typing is undone as a single edit.
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "");
+            assert!(!text.borrow().can_undo());
+
+            harness.submit_command(commands::REDO);
+            assert_eq!(harness.data(), "hey");
+            assert_eq!(text.borrow().selection(), Selection::caret(3));
+        });
+    }
+
+    #[test]
+    fn empty_history_leaves_undo_unhandled() {
+        let component = TextComponent {
+            has_focus: true,
+            ..Default::default()
+        };
+        let text = component.clone();
+
+        Harness::create_simple(String::new(), component, |harness| {
+            harness.send_initial_events();
+            let undo = Event::Command(commands::UNDO.to(Target::Global));
+            let redo = Event::Command(commands::REDO.to(Target::Global));
+            assert!(!harness.event_handled(undo.clone()).is_handled());
+            assert!(!harness.event_handled(redo.clone()).is_handled());
+
+            ime_edit(harness, &text, |session| insert(session, "a"));
+            assert!(harness.event_handled(undo.clone()).is_handled());
+            assert_eq!(harness.data(), "");
+            // Everything is undone, so the next undo is left to others.
+            assert!(!harness.event_handled(undo).is_handled());
+            assert!(harness.event_handled(redo.clone()).is_handled());
+            assert!(!harness.event_handled(redo).is_handled());
+            assert_eq!(harness.data(), "a");
+        });
+    }
+
+    #[test]
+    fn composition_is_a_single_edit() {
+        let component = TextComponent {
+            has_focus: true,
+            ..Default::default()
+        };
+        let text = component.clone();
+
+        Harness::create_simple(String::new(), component, |harness| {
+            harness.send_initial_events();
+            ime_edit(harness, &text, |session| {
+                insert(session, "n");
+                session.set_composition_range(Some(0..1));
+            });
+            ime_edit(harness, &text, |session| {
+                session.replace_range(0..1, "ñ");
+                session.set_selection(Selection::caret(2));
+                session.set_composition_range(None);
+            });
+            assert_eq!(harness.data(), "ñ");
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "");
+            assert!(!text.borrow().can_undo());
+        });
+    }
+}
@@ -8,6 +8,7 @@ mod backspace;
 mod editable_text;
 mod font_descriptor;
 mod format;
+mod history;
 mod input_component;
 mod input_methods;
 mod layout;
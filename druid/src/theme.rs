@@ -111,6 +111,25 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+/// The color of the ring painted around a control that has keyboard focus.
+pub const FOCUS_RING_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.focus_ring_color");
+/// The width of the ring painted around a control that has keyboard focus.
+pub const FOCUS_RING_WIDTH: Key<f64> = Key::new("org.linebender.druid.theme.focus_ring_width");
+
+/// The color of the outline painted around a drop target that accepts the dragged payload.
+pub const DROP_ACCEPT_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.drop_accept_color");
+/// The color of the outline painted around a drop target that rejects the dragged payload.
+pub const DROP_REJECT_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.drop_reject_color");
+
+/// How long the mouse has to rest on a widget before its tooltip is shown, in milliseconds.
+pub const TOOLTIP_DELAY: Key<u64> = Key::new("org.linebender.druid.theme.tooltip_delay");
+pub const TOOLTIP_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.tooltip_background_color");
+pub const TOOLTIP_TEXT_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.tooltip_text_color");
+pub const TOOLTIP_BORDER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.tooltip_border_color");
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
     env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
@@ -158,6 +177,14 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SCROLLBAR_MIN_SIZE, 45.)
         .adding(SCROLLBAR_RADIUS, 5.)
         .adding(SCROLLBAR_EDGE_WIDTH, 1.)
+        .adding(FOCUS_RING_COLOR, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(FOCUS_RING_WIDTH, 2.)
+        .adding(DROP_ACCEPT_COLOR, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(DROP_REJECT_COLOR, Color::rgb8(0xe0, 0x4f, 0x4f))
+        .adding(TOOLTIP_DELAY, 600u64)
+        .adding(TOOLTIP_BACKGROUND_COLOR, Color::rgb8(0x40, 0x40, 0x40))
+        .adding(TOOLTIP_TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
+        .adding(TOOLTIP_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77))
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
@@ -20,8 +20,11 @@ impl<T> Align<T> {
     /// Create widget with alignment.
     ///
     /// Note that the `align` parameter is specified as a `UnitPoint` in
-    /// terms of left and right. This is inadequate for bidi-aware layout
-    /// and thus the API will change when Druid gains bidi capability.
+    /// terms of left and right, for a left-to-right layout. In a right-to-left
+    /// [`LayoutDirection`] it is mirrored, so that [`UnitPoint::LEFT`] aligns
+    /// the child to the right.
+    ///
+    /// [`LayoutDirection`]: crate::LayoutDirection
     pub fn new(align: UnitPoint, child: impl Widget<T> + 'static) -> Align<T> {
         Align {
             align,
@@ -36,12 +39,14 @@ impl<T> Align<T> {
         Align::new(UnitPoint::CENTER, child)
     }
 
-    /// Create right-aligned widget.
+    /// Create widget aligned to the right, or to the left in a right-to-left
+    /// layout.
     pub fn right(child: impl Widget<T> + 'static) -> Align<T> {
         Align::new(UnitPoint::RIGHT, child)
     }
 
-    /// Create left-aligned widget.
+    /// Create widget aligned to the left, or to the right in a right-to-left
+    /// layout.
     pub fn left(child: impl Widget<T> + 'static) -> Align<T> {
         Align::new(UnitPoint::LEFT, child)
     }
@@ -81,6 +86,9 @@ impl<T: Data> Widget<T> for Align<T> {
     #[instrument(name = "Align", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
         self.child.update(ctx, data, env);
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "Align", level = "trace", skip(self, ctx, bc, data, env))]
@@ -110,10 +118,14 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size = bc.constrain(my_size);
         let extra_width = (my_size.width - size.width).max(0.);
         let extra_height = (my_size.height - size.height).max(0.);
-        let origin = self
+        let mut origin = self
             .align
             .resolve(Rect::new(0., 0., extra_width, extra_height))
             .expand();
+        origin.x = env
+            .try_get(Env::LAYOUT_DIRECTION)
+            .unwrap_or_default()
+            .mirror_x(origin.x, size.width, my_size.width);
         self.child.set_origin(ctx, origin);
 
         let my_insets = self.child.compute_parent_paint_insets(my_size);
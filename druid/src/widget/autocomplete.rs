@@ -0,0 +1,123 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Completions for a text box.
+
+use crate::debug_state::DebugState;
+use crate::widget::popup_list::PopupHost;
+use crate::widget::prelude::*;
+use crate::widget::suggestion_list::{Source, SuggestionList, SuggestionRequest};
+use crate::widget::{ControllerHost, TextBox};
+
+/// The default for [`Autocomplete::with_max_suggestions`].
+const DEFAULT_MAX_SUGGESTIONS: usize = 8;
+
+/// A [`TextBox`] that suggests completions for the text as the user types.
+///
+/// Whenever the text changes, the suggestions are looked up and shown in a
+/// popup list below the cursor, in the order they were returned, so the
+/// best match should come first. While the list is open, the arrow keys
+/// highlight a suggestion, <kbd>Enter</kbd> accepts the highlighted
+/// suggestion, and <kbd>Tab</kbd> accepts the highlighted suggestion or,
+/// if none is highlighted, the first one. <kbd>Escape</kbd> closes the list
+/// and <kbd>Down</kbd> opens it again.
+///
+/// Suggestions can be computed right away with [`Autocomplete::new`], or
+/// on another thread with [`Autocomplete::new_async`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Autocomplete, TextBox};
+///
+/// const WORDS: &[&str] = &["apple", "apricot", "banana", "blackberry", "cherry"];
+///
+/// let search = Autocomplete::new(TextBox::new(), |query| {
+///     WORDS
+///         .iter()
+///         .filter(|word| word.starts_with(query))
+///         .map(|word| word.to_string())
+///         .collect()
+/// });
+/// ```
+pub struct Autocomplete {
+    host: PopupHost<String, ControllerHost<TextBox<String>, SuggestionList>>,
+}
+
+impl Autocomplete {
+    /// Create a new `Autocomplete` around `textbox`, that gets its
+    /// suggestions by calling `suggest` with the text.
+    pub fn new(textbox: TextBox<String>, suggest: impl Fn(&str) -> Vec<String> + 'static) -> Self {
+        Self::with_source(textbox, Source::Sync(Box::new(suggest)))
+    }
+
+    /// Create a new `Autocomplete` around `textbox`, that requests its
+    /// suggestions by calling `request` with a [`SuggestionRequest`].
+    ///
+    /// The request can be answered later, for instance from another thread.
+    pub fn new_async(
+        textbox: TextBox<String>,
+        request: impl Fn(SuggestionRequest) + 'static,
+    ) -> Self {
+        Self::with_source(textbox, Source::Async(Box::new(request)))
+    }
+
+    fn with_source(textbox: TextBox<String>, source: Source) -> Self {
+        let mut suggestions = SuggestionList::new(source)
+            .at_cursor()
+            .accept_with_tab()
+            .skip_empty();
+        suggestions.set_max_suggestions(DEFAULT_MAX_SUGGESTIONS);
+        Autocomplete {
+            host: PopupHost::new(ControllerHost::new(textbox, suggestions)),
+        }
+    }
+
+    /// Builder-style method to set the most suggestions that are shown at once.
+    ///
+    /// The default is 8.
+    pub fn with_max_suggestions(mut self, max: usize) -> Self {
+        self.host
+            .widget_mut()
+            .controller_mut()
+            .set_max_suggestions(max);
+        self
+    }
+}
+
+impl Widget<String> for Autocomplete {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        self.host.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, env: &Env) {
+        self.host.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &String, data: &String, env: &Env) {
+        self.host.update(ctx, old_data, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &String,
+        env: &Env,
+    ) -> Size {
+        self.host.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &String, env: &Env) {
+        self.host.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &String) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.clone(),
+            children: vec![self.host.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
@@ -4,6 +4,7 @@
 //! A button widget.
 
 use crate::debug_state::DebugState;
+use crate::widget::focus;
 use crate::widget::prelude::*;
 use crate::widget::{Click, ControllerHost, Label, LabelText};
 use crate::{theme, Affine, Data, Insets, LinearGradient, UnitPoint};
@@ -15,6 +16,9 @@ use tracing::{instrument, trace};
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
 /// A button with a text label.
+///
+/// A button can be focused from the keyboard, and is clicked by pressing
+/// Space or Enter while it has focus.
 pub struct Button<T> {
     label: Label<T>,
     label_size: Size,
@@ -91,11 +95,14 @@ impl<T: Data> Button<T> {
     }
 
     /// Provide a closure to be called when this button is clicked.
+    ///
+    /// The closure is also called when Space or Enter is pressed while the
+    /// button has keyboard focus.
     pub fn on_click(
         self,
         f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
     ) -> ControllerHost<Self, Click<T>> {
-        ControllerHost::new(self, Click::new(f))
+        ControllerHost::new(self, Click::new(f).with_keyboard_activation())
     }
 }
 
@@ -117,14 +124,21 @@ impl<T: Data> Widget<T> for Button<T> {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key) => {
+                focus::handle_tab(ctx, key);
+            }
             _ => (),
         }
     }
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
         self.label.lifecycle(ctx, event, data, env)
     }
@@ -198,6 +212,7 @@ impl<T: Data> Widget<T> for Button<T> {
         ctx.stroke(rounded_rect, &border_color, stroke_width);
 
         ctx.fill(rounded_rect, &bg_gradient);
+        focus::paint_focus_ring(ctx, rounded_rect, env);
 
         let label_offset = (size.to_vec2() - self.label_size.to_vec2()) / 2.0;
 
@@ -7,10 +7,12 @@ use crate::debug_state::DebugState;
 use crate::kurbo::{BezPath, Size};
 use crate::piet::{LineCap, LineJoin, LinearGradient, RenderContext, StrokeStyle, UnitPoint};
 use crate::theme;
-use crate::widget::{prelude::*, Label, LabelText};
+use crate::widget::{focus, prelude::*, Label, LabelText};
 use tracing::{instrument, trace};
 
 /// A checkbox that toggles a `bool`.
+///
+/// When focused, it is toggled with Space or Enter.
 pub struct Checkbox {
     child_label: Label<bool>,
 }
@@ -58,6 +60,16 @@ impl Widget<bool> for Checkbox {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key) if !ctx.is_disabled() => {
+                if focus::is_activation_key(key) && ctx.is_focused() {
+                    *data = !*data;
+                    trace!("Checkbox {:?} toggled - {}", ctx.widget_id(), *data);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                } else {
+                    focus::handle_tab(ctx, key);
+                }
+            }
             _ => (),
         }
     }
@@ -65,8 +77,12 @@ impl Widget<bool> for Checkbox {
     #[instrument(name = "CheckBox", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &bool, env: &Env) {
         self.child_label.lifecycle(ctx, event, data, env);
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -128,6 +144,7 @@ impl Widget<bool> for Checkbox {
         };
 
         ctx.stroke(rect, &border_color, border_width);
+        focus::paint_focus_ring(ctx, rect, env);
 
         if *data {
             // Paint the checkmark
@@ -3,7 +3,7 @@
 
 //! A clickable [`Controller`] widget.
 
-use crate::widget::Controller;
+use crate::widget::{focus, Controller};
 use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, MouseButton, Widget};
 use tracing::{instrument, trace};
 
@@ -18,6 +18,11 @@ use tracing::{instrument, trace};
 /// mouse down, which can be useful for painting based on `ctx.is_active()`
 /// and `ctx.is_hot()`.
 ///
+/// Only the mouse clicks the child, unless keyboard activation is enabled
+/// with [`with_keyboard_activation`], as [`Button::on_click`] does.
+///
+/// [`with_keyboard_activation`]: Click::with_keyboard_activation
+/// [`Button::on_click`]: super::Button::on_click
 /// [`ControllerHost`]: super::ControllerHost
 /// [`on_click`]: super::WidgetExt::on_click
 /// [`WidgetExt`]: super::WidgetExt
@@ -25,6 +30,8 @@ use tracing::{instrument, trace};
 pub struct Click<T> {
     /// A closure that will be invoked when the child widget is clicked.
     action: Box<dyn Fn(&mut EventCtx, &mut T, &Env)>,
+    /// Whether Space and Enter click the focused child.
+    keyboard_activation: bool,
 }
 
 impl<T: Data> Click<T> {
@@ -32,8 +39,19 @@ impl<T: Data> Click<T> {
     pub fn new(action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
         Click {
             action: Box::new(action),
+            keyboard_activation: false,
         }
     }
+
+    /// Builder-style method to also invoke the action when <kbd>Space</kbd>
+    /// or <kbd>Enter</kbd> is pressed while the child has keyboard focus.
+    ///
+    /// The child sees the key first; if it handles the key, as a text field
+    /// might, the action is not invoked.
+    pub fn with_keyboard_activation(mut self) -> Self {
+        self.keyboard_activation = true;
+        self
+    }
 }
 
 impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
@@ -61,6 +79,21 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
                     trace!("Widget {:?} released", ctx.widget_id());
                 }
             }
+            Event::KeyDown(key) if self.keyboard_activation => {
+                child.event(ctx, event, data, env);
+                // Only focusable children, such as `Button`, can get key events here.
+                if !ctx.is_handled()
+                    && focus::is_activation_key(key)
+                    && ctx.is_focused()
+                    && !ctx.is_disabled()
+                {
+                    (self.action)(ctx, data, env);
+                    ctx.set_handled();
+                    ctx.request_paint();
+                    trace!("Widget {:?} activated", ctx.widget_id());
+                }
+                return;
+            }
             _ => {}
         }
 
@@ -0,0 +1,206 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A text box that suggests values from a list.
+
+use crate::debug_state::DebugState;
+use crate::widget::popup_list::PopupHost;
+use crate::widget::prelude::*;
+use crate::widget::suggestion_list::{Source, SuggestionList};
+use crate::widget::{ControllerHost, LabelText, TextBox, WidgetWrapper};
+
+/// A [`TextBox`] that lets the user type any value, and suggests matching
+/// values from a list.
+///
+/// While the text box has keyboard focus, the options that contain the
+/// typed text (ignoring case) are shown in a popup list below it. Clicking
+/// an option, or highlighting it with the arrow keys and pressing
+/// <kbd>Enter</kbd>, replaces the text with that option. <kbd>Escape</kbd>
+/// closes the list, and <kbd>Down</kbd> opens it again.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::ComboBox;
+///
+/// let fruit = ComboBox::new(["Apple", "Banana", "Blackberry", "Cherry"])
+///     .with_placeholder("Pick a fruit, or type your own");
+/// ```
+pub struct ComboBox {
+    host: PopupHost<String, ControllerHost<TextBox<String>, SuggestionList>>,
+}
+
+impl ComboBox {
+    /// Create a new combo box that suggests `options`.
+    pub fn new(options: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let options: Vec<String> = options.into_iter().map(Into::into).collect();
+        let matching = move |text: &str| {
+            let query = text.to_lowercase();
+            options
+                .iter()
+                .filter(|option| option.to_lowercase().contains(&query))
+                .cloned()
+                .collect()
+        };
+        let suggestions = SuggestionList::new(Source::Sync(Box::new(matching)));
+        ComboBox {
+            host: PopupHost::new(ControllerHost::new(TextBox::new(), suggestions)),
+        }
+    }
+
+    /// Builder-style method to set the text box's placeholder text.
+    pub fn with_placeholder(mut self, placeholder: impl Into<LabelText<String>>) -> Self {
+        self.host
+            .widget_mut()
+            .wrapped_mut()
+            .set_placeholder(placeholder);
+        self
+    }
+}
+
+impl Widget<String> for ComboBox {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        self.host.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, env: &Env) {
+        self.host.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &String, data: &String, env: &Env) {
+        self.host.update(ctx, old_data, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &String,
+        env: &Env,
+    ) -> Size {
+        self.host.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &String, env: &Env) {
+        self.host.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &String) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.clone(),
+            children: vec![self.host.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::query::WidgetQuery;
+    use crate::widget::{Flex, WidgetExt};
+    use crate::{Data, KbKey, Lens, Modifiers, WindowId};
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        fruit: String,
+        other: String,
+    }
+
+    /// Start with the combo box focused.
+    fn form_harness(mut f: impl FnMut(&mut Harness<Form>)) {
+        let widget = Flex::column()
+            .with_child(
+                ComboBox::new(["Apple", "Banana", "Blackberry", "Cherry"]).lens(Form::fruit),
+            )
+            .with_child(TextBox::new().lens(Form::other));
+        let form = Form {
+            fruit: String::new(),
+            other: String::new(),
+        };
+        Harness::create_simple(form, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let combo = harness.assert_one(&WidgetQuery::new().type_name("ComboBox"));
+            harness.click(combo.id);
+            f(harness);
+        });
+    }
+
+    /// The options shown in the popup window `popup`.
+    fn suggestions(harness: &mut Harness<Form>, popup: WindowId) -> Vec<String> {
+        let main = harness.window_ids()[0];
+        harness.select_window(popup);
+        let list = harness.get_root_debug_state();
+        harness.select_window(main);
+        list.children
+            .into_iter()
+            .map(|item| item.main_value)
+            .collect()
+    }
+
+    #[test]
+    fn filters_options() {
+        form_harness(|harness| {
+            harness.type_text("b");
+            assert_eq!(harness.opened_windows().len(), 1);
+            let popup = harness.opened_windows()[0];
+            assert_eq!(suggestions(harness, popup), ["Banana", "Blackberry"]);
+
+            // Matching ignores case, and looks anywhere in the option.
+            harness.type_text("E");
+            assert_eq!(harness.data().fruit, "bE");
+            assert_eq!(suggestions(harness, popup), ["Blackberry"]);
+
+            harness.type_text("x");
+            assert_eq!(harness.closed_windows(), [popup]);
+        });
+    }
+
+    #[test]
+    fn keyboard_selection() {
+        form_harness(|harness| {
+            let none = Modifiers::empty();
+            harness.type_text("b");
+            let popup = harness.opened_windows()[0];
+            harness.press_key(none, KbKey::ArrowDown);
+            harness.press_key(none, KbKey::ArrowDown);
+            harness.press_key(none, KbKey::Enter);
+            assert_eq!(harness.data().fruit, "Blackberry");
+            assert_eq!(harness.closed_windows(), [popup]);
+
+            // Escape closes the list, and the down arrow shows it again.
+            harness.press_key(none, KbKey::Backspace);
+            assert_eq!(harness.opened_windows().len(), 2);
+            let popup = harness.opened_windows()[1];
+            harness.press_key(none, KbKey::Escape);
+            assert_eq!(harness.closed_windows().last(), Some(&popup));
+            harness.press_key(none, KbKey::ArrowDown);
+            assert_eq!(harness.opened_windows().len(), 3);
+            assert_eq!(harness.data().fruit, "Blackberr");
+        });
+    }
+
+    #[test]
+    fn commits_free_text() {
+        form_harness(|harness| {
+            harness.type_text("che");
+            let popup = harness.opened_windows()[0];
+            harness.type_text("stnut");
+            assert_eq!(harness.closed_windows(), [popup]);
+            assert_eq!(harness.data().fruit, "chestnut");
+
+            // Moving the focus away keeps the typed text.
+            let other = harness.assert_one(&WidgetQuery::new().type_name("TextBox").main_value(""));
+            harness.click(other.id);
+            harness.type_text("x");
+            assert_eq!(harness.data().fruit, "chestnut");
+            assert_eq!(harness.data().other, "x");
+            assert_eq!(harness.opened_windows().len(), 1);
+        });
+    }
+}
@@ -1,7 +1,8 @@
 // Copyright 2020 the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Affine, Data, Size};
+use crate::widget::prelude::*;
+use crate::{Affine, Point, Rect, WidgetPod};
 
 // These are based on https://api.flutter.dev/flutter/painting/BoxFit.html
 /// Strategies for inscribing a rectangle inside another rectangle.
@@ -58,3 +59,28 @@ impl FillStrat {
         Affine::new([scalex, 0., 0., scaley, origin_x, origin_y])
     }
 }
+
+/// Mirror children that were placed left to right in a container `width`
+/// wide, if the layout direction in `env` is right to left.
+///
+/// Returns the union of the children's paint rects after mirroring, or
+/// `None` if they were left where they were.
+pub(crate) fn mirror_children<'a, T: 'a>(
+    ctx: &mut LayoutCtx,
+    env: &Env,
+    children: impl Iterator<Item = &'a mut WidgetPod<T, Box<dyn Widget<T>>>>,
+    width: f64,
+) -> Option<Rect> {
+    let direction = env.try_get(Env::LAYOUT_DIRECTION).unwrap_or_default();
+    if !direction.is_rtl() {
+        return None;
+    }
+    let mut paint_rect = Rect::ZERO;
+    for child in children {
+        let rect = child.layout_rect();
+        let x = direction.mirror_x(rect.x0, rect.width(), width);
+        child.set_origin(ctx, Point::new(x, rect.y0));
+        paint_rect = paint_rect.union(child.paint_rect());
+    }
+    Some(paint_rect)
+}
@@ -83,6 +83,10 @@ impl<W, C> ControllerHost<W, C> {
     pub fn new(widget: W, controller: C) -> ControllerHost<W, C> {
         ControllerHost { widget, controller }
     }
+
+    pub(crate) fn controller_mut(&mut self) -> &mut C {
+        &mut self.controller
+    }
 }
 
 impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for ControllerHost<W, C> {
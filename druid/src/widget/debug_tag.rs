@@ -0,0 +1,90 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that tags a child, to find it in tests.
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Size;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::Data;
+use tracing::instrument;
+
+/// A wrapper that adds a tag to the [`DebugState`] of a widget.
+///
+/// The tag has no effect on the widget; it is a name that tests can use to
+/// find the widget, without knowing its [`WidgetId`] or its contents. This is
+/// also available, for convenience, as a [`debug_tag`] method via [`WidgetExt`].
+///
+/// [`debug_tag`]: super::WidgetExt::debug_tag
+/// [`WidgetExt`]: super::WidgetExt
+pub struct DebugTag<W> {
+    tag: String,
+    child: W,
+}
+
+impl<W> DebugTag<W> {
+    /// The key of the tag in the [`DebugState`]'s `other_values`.
+    pub const KEY: &'static str = "tag";
+
+    /// Add a tag to a widget.
+    pub fn new(child: W, tag: impl Into<String>) -> DebugTag<W> {
+        DebugTag {
+            tag: tag.into(),
+            child,
+        }
+    }
+
+    /// The tag of this widget.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for DebugTag<W> {
+    #[instrument(name = "DebugTag", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "DebugTag", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "DebugTag",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "DebugTag", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "DebugTag", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            other_values: [(Self::KEY.to_string(), self.tag.clone())].into(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<W> WidgetWrapper for DebugTag<W> {
+    widget_wrapper_body!(W, child);
+}
@@ -0,0 +1,345 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Widgets that start drags and accept drops.
+
+use std::rc::Rc;
+
+use crate::debug_state::DebugState;
+use crate::drag::DRAG_CANCELLED;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{theme, Affine, Command, Data, Point, Rect, Selector, Target};
+use tracing::{instrument, trace};
+
+/// How far the mouse has to move with the button held before a drag starts.
+pub(crate) const DRAG_THRESHOLD: f64 = 4.0;
+
+/// Paints the default drag preview: a translucent box the size of the dragged widget.
+pub(crate) fn paint_default_preview(ctx: &mut PaintCtx, size: Size, env: &Env) {
+    let rect = size
+        .to_rect()
+        .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+    ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT).with_alpha(0.7));
+    ctx.stroke(rect, &env.get(theme::BORDER_LIGHT), 1.0);
+}
+
+/// A wrapper that lets its child be dragged, carrying a typed payload.
+///
+/// A drag starts when the child is pressed with the left mouse button and the
+/// mouse moves a few pixels. The payload is created from the data at that
+/// moment, and is identified by a [`Selector`] in the same way as a
+/// [`Command`]'s payload; a [`DropTarget`] for the same selector can accept it.
+///
+/// While dragging, a preview follows the mouse above the window's content. By
+/// default this is a translucent box the size of the child; use
+/// [`preview`] to paint something else.
+///
+/// [`preview`]: DragSource::preview
+pub struct DragSource<T, P, W> {
+    child: W,
+    selector: Selector<P>,
+    payload: Box<dyn Fn(&T, &Env) -> P>,
+    preview: Option<Rc<dyn Fn(&mut PaintCtx, &P, &Env)>>,
+    press: Option<Point>,
+    dragging: bool,
+}
+
+impl<T: Data, P: 'static, W: Widget<T>> DragSource<T, P, W> {
+    /// Make `child` draggable, carrying the payload returned by `payload`.
+    pub fn new(child: W, selector: Selector<P>, payload: impl Fn(&T, &Env) -> P + 'static) -> Self {
+        DragSource {
+            child,
+            selector,
+            payload: Box::new(payload),
+            preview: None,
+            press: None,
+            dragging: false,
+        }
+    }
+
+    /// Builder-style method to set how the drag preview is painted.
+    ///
+    /// The preview is painted in the coordinate space of the child, moved along
+    /// with the mouse from where the drag started.
+    pub fn preview(mut self, paint: impl Fn(&mut PaintCtx, &P, &Env) + 'static) -> Self {
+        self.preview = Some(Rc::new(paint));
+        self
+    }
+
+    fn start_drag(&mut self, ctx: &mut EventCtx, press: Point, data: &T, env: &Env) {
+        let selector = self.selector;
+        let payload = Command::new(selector, (self.payload)(data, env), Target::Auto);
+        let size = ctx.size();
+        let offset = -press.to_vec2();
+        let bounds = Rect::from_origin_size(offset.to_point(), size);
+        let preview = self.preview.clone();
+        let env = env.clone();
+        let dragged = payload.clone();
+        ctx.start_drag_command(payload, bounds, move |ctx| {
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate(offset));
+                match (&preview, dragged.get(selector)) {
+                    (Some(paint), Some(payload)) => paint(ctx, payload, &env),
+                    _ => paint_default_preview(ctx, size, &env),
+                }
+            });
+        });
+        trace!("Drag started from {:?}", ctx.widget_id());
+        self.dragging = true;
+        ctx.request_paint();
+    }
+}
+
+impl<T: Data, P: 'static, W: Widget<T>> Widget<T> for DragSource<T, P, W> {
+    #[instrument(
+        name = "DragSource",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() && !ctx.is_disabled() => {
+                self.press = Some(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) => {
+                if self.dragging {
+                    // The preview follows the mouse; the child doesn't need to know.
+                    return;
+                }
+                if let Some(press) = self.press {
+                    if ctx.is_active() && mouse.pos.distance(press) >= DRAG_THRESHOLD {
+                        self.press = None;
+                        self.start_drag(ctx, press, data, env);
+                        return;
+                    }
+                }
+            }
+            Event::Command(cmd) if cmd.is(DRAG_CANCELLED) => {
+                self.dragging = false;
+                ctx.set_active(false);
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(_) => {
+                self.press = None;
+                if self.dragging {
+                    // The drop is handled by the target under the mouse, if any.
+                    self.dragging = false;
+                    ctx.set_active(false);
+                    ctx.request_paint();
+                    return;
+                }
+                self.child.event(ctx, event, data, env);
+                ctx.set_active(false);
+                return;
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "DragSource",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "DragSource",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "DragSource", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "DragSource", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, P, W> WidgetWrapper for DragSource<T, P, W> {
+    widget_wrapper_body!(W, child);
+}
+
+/// A wrapper that accepts payloads dropped on its child.
+///
+/// Only drags whose payload is identified by the same [`Selector`] are
+/// considered. While such a drag hovers over the child, an outline shows
+/// whether the payload would be accepted; by default every payload is, and
+/// [`accept_if`] can restrict that. Dropping an accepted payload calls the
+/// `on_drop` callback, and dropping a rejected one calls the callback set with
+/// [`on_reject`], if any.
+///
+/// [`accept_if`]: DropTarget::accept_if
+/// [`on_reject`]: DropTarget::on_reject
+pub struct DropTarget<T, P, W> {
+    child: W,
+    selector: Selector<P>,
+    accept: Box<dyn Fn(&P, &T, &Env) -> bool>,
+    on_drop: Box<dyn Fn(&mut EventCtx, &P, &mut T, &Env)>,
+    on_reject: Option<Box<dyn Fn(&mut EventCtx, &P, &mut T, &Env)>>,
+    /// Whether the payload being dragged over the child would be accepted.
+    hover: Option<bool>,
+}
+
+impl<T: Data, P: 'static, W: Widget<T>> DropTarget<T, P, W> {
+    /// Accept payloads identified by `selector` dropped on `child`, passing them to `on_drop`.
+    pub fn new(
+        child: W,
+        selector: Selector<P>,
+        on_drop: impl Fn(&mut EventCtx, &P, &mut T, &Env) + 'static,
+    ) -> Self {
+        DropTarget {
+            child,
+            selector,
+            accept: Box::new(|_, _, _| true),
+            on_drop: Box::new(on_drop),
+            on_reject: None,
+            hover: None,
+        }
+    }
+
+    /// Builder-style method to only accept the payloads for which `accept` returns `true`.
+    pub fn accept_if(mut self, accept: impl Fn(&P, &T, &Env) -> bool + 'static) -> Self {
+        self.accept = Box::new(accept);
+        self
+    }
+
+    /// Builder-style method to set a callback for payloads that are dropped but not accepted.
+    pub fn on_reject(
+        mut self,
+        on_reject: impl Fn(&mut EventCtx, &P, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_reject = Some(Box::new(on_reject));
+        self
+    }
+
+    fn set_hover(&mut self, hover: Option<bool>) -> bool {
+        let changed = self.hover != hover;
+        self.hover = hover;
+        changed
+    }
+}
+
+impl<T: Data, P: 'static, W: Widget<T>> Widget<T> for DropTarget<T, P, W> {
+    #[instrument(
+        name = "DropTarget",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(_) => {
+                let hover = match ctx.drag_payload(self.selector) {
+                    Some(payload) if ctx.is_hot() => Some((self.accept)(payload, data, env)),
+                    _ => None,
+                };
+                if self.set_hover(hover) {
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_hot() => {
+                if let Some(drag) = ctx.drag_command() {
+                    if let Some(payload) = drag.get(self.selector) {
+                        if (self.accept)(payload, data, env) {
+                            trace!("Payload dropped on {:?}", ctx.widget_id());
+                            (self.on_drop)(ctx, payload, data, env);
+                        } else if let Some(on_reject) = &self.on_reject {
+                            on_reject(ctx, payload, data, env);
+                        }
+                        ctx.set_handled();
+                    }
+                }
+                if self.set_hover(None) {
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "DropTarget",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.set_hover(None) {
+                ctx.request_paint();
+            }
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "DropTarget",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "DropTarget", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "DropTarget", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if let Some(accepted) = self.hover {
+            let color = if accepted {
+                env.get(theme::DROP_ACCEPT_COLOR)
+            } else {
+                env.get(theme::DROP_REJECT_COLOR)
+            };
+            let width = env.get(theme::FOCUS_RING_WIDTH);
+            let rect = ctx.size().to_rect().inset(-width / 2.);
+            ctx.stroke(rect, &color, width);
+        }
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, P, W> WidgetWrapper for DropTarget<T, P, W> {
+    widget_wrapper_body!(W, child);
+}
@@ -0,0 +1,578 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget for choosing one of a list of values.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::contexts::RequestCtx;
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::text::TextLayout;
+use crate::widget::popup_list::{ListState, Popup, PopupHost, PopupItem, ITEM_CHOSEN};
+use crate::widget::prelude::*;
+use crate::widget::LabelText;
+use crate::{theme, ArcStr, KbKey, LinearGradient, Point, TimerToken, UnitPoint};
+
+/// How long after the last key press a new type-to-search query is started.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(1);
+const ARROW_SIZE: f64 = 8.0;
+
+struct DropdownItem<T> {
+    label: LabelText<T>,
+    value: T,
+    disabled: bool,
+}
+
+/// A button that shows the current value, and opens a list of values to
+/// choose from when clicked.
+///
+/// The list is shown in a popup window. While the dropdown has keyboard
+/// focus, the arrow keys, <kbd>Home</kbd> and <kbd>End</kbd> move through
+/// the list, <kbd>Enter</kbd> or <kbd>Space</kbd> choose the highlighted
+/// value, and <kbd>Escape</kbd> closes the list. Typing the start of a
+/// label jumps to the first matching value, whether or not the list is open.
+/// While the list is open, clicking anywhere else in the window closes it.
+///
+/// Items added with [`with_disabled_item`] are shown, but cannot be chosen.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Dropdown;
+/// use druid::Data;
+///
+/// #[derive(Clone, Copy, Data, PartialEq)]
+/// enum Size {
+///     Small,
+///     Medium,
+///     Large,
+///     Huge,
+/// }
+///
+/// let dropdown = Dropdown::new(vec![
+///     ("Small", Size::Small),
+///     ("Medium", Size::Medium),
+///     ("Large", Size::Large),
+/// ])
+/// .with_disabled_item("Huge", Size::Huge);
+/// ```
+///
+/// [`with_disabled_item`]: Dropdown::with_disabled_item
+pub struct Dropdown<T> {
+    host: PopupHost<T, DropdownButton<T>>,
+}
+
+struct DropdownButton<T> {
+    items: Vec<DropdownItem<T>>,
+    layouts: Vec<TextLayout<ArcStr>>,
+    label_size: Size,
+    popup: Option<Popup>,
+    search: String,
+    /// Ends the type-to-search query when it fires.
+    search_timer: TimerToken,
+}
+
+impl<T: Data + PartialEq> Dropdown<T> {
+    /// Create a new dropdown from `(label_text, value)` pairs.
+    pub fn new(items: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>) -> Self {
+        let items = items
+            .into_iter()
+            .map(|(label, value)| DropdownItem {
+                label: label.into(),
+                value,
+                disabled: false,
+            })
+            .collect();
+        Dropdown {
+            host: PopupHost::new(DropdownButton {
+                items,
+                layouts: Vec::new(),
+                label_size: Size::ZERO,
+                popup: None,
+                search: String::new(),
+                search_timer: TimerToken::INVALID,
+            }),
+        }
+    }
+
+    /// Builder-style method to add an item.
+    pub fn with_item(mut self, label: impl Into<LabelText<T>>, value: T) -> Self {
+        self.add_item(label, value, false);
+        self
+    }
+
+    /// Builder-style method to add an item that is shown, but cannot be chosen.
+    pub fn with_disabled_item(mut self, label: impl Into<LabelText<T>>, value: T) -> Self {
+        self.add_item(label, value, true);
+        self
+    }
+
+    fn add_item(&mut self, label: impl Into<LabelText<T>>, value: T, disabled: bool) {
+        self.host.widget_mut().items.push(DropdownItem {
+            label: label.into(),
+            value,
+            disabled,
+        });
+    }
+}
+
+impl<T: Data + PartialEq> DropdownButton<T> {
+    fn selected(&self, data: &T) -> Option<usize> {
+        self.items.iter().position(|item| item.value == *data)
+    }
+
+    fn list_state(&self, data: &T) -> ListState {
+        ListState {
+            items: self
+                .items
+                .iter()
+                .map(|item| PopupItem {
+                    text: item.label.display_text(),
+                    disabled: item.disabled,
+                })
+                .collect(),
+            highlighted: self
+                .selected(data)
+                .filter(|&index| !self.items[index].disabled),
+        }
+    }
+
+    fn rebuild_layouts(&mut self) {
+        self.layouts = self
+            .items
+            .iter()
+            .map(|item| TextLayout::from_text(item.label.display_text()))
+            .collect();
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let list = self.list_state(data);
+        self.popup = Some(Popup::open(
+            ctx,
+            list,
+            Point::new(0., size.height),
+            size.width,
+            data,
+            env,
+        ));
+        ctx.request_paint();
+    }
+
+    fn close(&mut self, ctx: &mut impl RequestCtx) {
+        if let Some(popup) = self.popup.take() {
+            ctx.submit_command(popup.close());
+            ctx.request_paint();
+        }
+    }
+
+    fn choose(&mut self, ctx: &mut EventCtx, data: &mut T, index: usize) {
+        if let Some(item) = self.items.get(index).filter(|item| !item.disabled) {
+            trace!("Dropdown item {} chosen", index);
+            *data = item.value.clone();
+        }
+        self.close(ctx);
+    }
+
+    /// Returns `true` if a key was typed recently enough to continue the
+    /// type-to-search query.
+    fn is_searching(&self) -> bool {
+        !self.search.is_empty()
+    }
+
+    /// Add `c` to the type-to-search query, and return the matching item.
+    fn search(&mut self, ctx: &mut EventCtx, c: &str, data: &T) -> Option<usize> {
+        self.search_timer = ctx.request_timer(SEARCH_TIMEOUT);
+        self.search.push_str(c);
+        match &self.popup {
+            Some(popup) => popup.list().find_prefix(&self.search),
+            None => self.list_state(data).find_prefix(&self.search),
+        }
+    }
+
+    fn handle_key(&mut self, ctx: &mut EventCtx, key: &KbKey, data: &mut T, env: &Env) {
+        // Space chooses, unless it is part of a search.
+        let is_space = matches!(key, KbKey::Character(c) if c == " ") && !self.is_searching();
+        match (&self.popup, key) {
+            (None, KbKey::ArrowDown | KbKey::ArrowUp | KbKey::Enter) => self.open(ctx, data, env),
+            (None, _) if is_space => self.open(ctx, data, env),
+            (Some(_), KbKey::Escape) => self.close(ctx),
+            (Some(popup), KbKey::Enter) => {
+                let highlighted = popup.list().highlighted;
+                match highlighted {
+                    Some(index) => self.choose(ctx, data, index),
+                    None => self.close(ctx),
+                }
+            }
+            (Some(popup), _) if is_space => {
+                let highlighted = popup.list().highlighted;
+                if let Some(index) = highlighted {
+                    self.choose(ctx, data, index);
+                }
+            }
+            (Some(popup), KbKey::ArrowDown | KbKey::ArrowUp) => {
+                popup.list().move_highlight(*key == KbKey::ArrowDown);
+                ctx.submit_command(popup.changed());
+            }
+            (Some(popup), KbKey::Home | KbKey::End) => {
+                popup.list().highlight_edge(*key == KbKey::End);
+                ctx.submit_command(popup.changed());
+            }
+            (Some(_), KbKey::Tab) => {
+                // Let focus move on.
+                self.close(ctx);
+                return;
+            }
+            (_, KbKey::Character(c)) => {
+                let found = self.search(ctx, c, data);
+                match (&self.popup, found) {
+                    (Some(popup), Some(index)) => {
+                        popup.list().highlighted = Some(index);
+                        ctx.submit_command(popup.changed());
+                    }
+                    (None, Some(index)) => self.choose(ctx, data, index),
+                    _ => (),
+                }
+            }
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn paint_arrow(&self, ctx: &mut PaintCtx, env: &Env) {
+        let size = ctx.size();
+        let padding = env.get(theme::WIDGET_PADDING_HORIZONTAL);
+        let center = Point::new(size.width - padding - ARROW_SIZE / 2., size.height / 2.);
+        let mut arrow = BezPath::new();
+        arrow.move_to((center.x - ARROW_SIZE / 2., center.y - ARROW_SIZE / 4.));
+        arrow.line_to((center.x + ARROW_SIZE / 2., center.y - ARROW_SIZE / 4.));
+        arrow.line_to((center.x, center.y + ARROW_SIZE / 4.));
+        arrow.close_path();
+        let color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_TEXT_COLOR)
+        } else {
+            env.get(theme::TEXT_COLOR)
+        };
+        ctx.fill(arrow, &color);
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for DropdownButton<T> {
+    #[instrument(name = "Dropdown", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse)
+                if self.popup.is_some() && !ctx.size().to_rect().contains(mouse.pos) =>
+            {
+                // A click elsewhere in the window, seen because we are active.
+                self.close(ctx);
+                ctx.set_handled();
+            }
+            Event::MouseDown(_) if !ctx.is_disabled() => {
+                ctx.request_focus();
+                if self.popup.is_some() {
+                    self.close(ctx);
+                } else {
+                    self.open(ctx, data, env);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(ITEM_CHOSEN) => {
+                let index = *cmd.get_unchecked(ITEM_CHOSEN);
+                self.choose(ctx, data, index);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if !ctx.is_disabled() && !key.mods.ctrl() && !key.mods.meta() => {
+                self.handle_key(ctx, &key.key, data, env);
+            }
+            Event::Timer(token) if *token == self.search_timer => {
+                self.search.clear();
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+        // Stay active while the list is open, to see clicks outside of the
+        // dropdown. The list can also be closed from `lifecycle`, so this is
+        // checked on every event.
+        if ctx.is_active() != self.popup.is_some() {
+            ctx.set_active(self.popup.is_some());
+        }
+    }
+
+    #[instrument(name = "Dropdown", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                for item in &mut self.items {
+                    item.label.resolve(data, env);
+                }
+                self.rebuild_layouts();
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(false) | LifeCycle::DisabledChanged(true) => self.close(ctx),
+            LifeCycle::FocusChanged(true) => ctx.request_paint(),
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "Dropdown",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let mut labels_changed = false;
+        for item in &mut self.items {
+            labels_changed |= item.label.resolve(data, env);
+        }
+        if labels_changed {
+            self.rebuild_layouts();
+            if let Some(popup) = &self.popup {
+                let list = self.list_state(data);
+                *popup.list() = list;
+                ctx.submit_command(popup.changed());
+            }
+            ctx.request_layout();
+        } else if ctx.env_changed() {
+            for layout in &mut self.layouts {
+                if layout.needs_rebuild_after_update(ctx) {
+                    ctx.request_layout();
+                }
+            }
+        }
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Dropdown", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Dropdown");
+        // Size for the longest label, so that the dropdown does not change
+        // size when a different value is chosen.
+        self.label_size = Size::ZERO;
+        for layout in &mut self.layouts {
+            layout.rebuild_if_needed(ctx.text(), env);
+            let size = layout.size();
+            self.label_size.width = self.label_size.width.max(size.width);
+            self.label_size.height = self.label_size.height.max(size.height);
+        }
+        let padding = env.get(theme::WIDGET_PADDING_HORIZONTAL);
+        let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let size = bc.constrain(Size::new(
+            self.label_size.width + ARROW_SIZE + padding * 3.,
+            (self.label_size.height + env.get(theme::WIDGET_PADDING_VERTICAL)).max(min_height),
+        ));
+        trace!("Computed dropdown size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Dropdown", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let stroke_width = env.get(theme::BUTTON_BORDER_WIDTH);
+        let rounded_rect = size
+            .to_rect()
+            .inset(-stroke_width / 2.0)
+            .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+
+        let (light, dark) = if ctx.is_disabled() {
+            (theme::DISABLED_BUTTON_LIGHT, theme::DISABLED_BUTTON_DARK)
+        } else {
+            (theme::BUTTON_LIGHT, theme::BUTTON_DARK)
+        };
+        let bg_gradient = LinearGradient::new(
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+            (env.get(light), env.get(dark)),
+        );
+        ctx.fill(rounded_rect, &bg_gradient);
+
+        let border_color = if ctx.is_focused() || self.popup.is_some() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            env.get(theme::BORDER_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(rounded_rect, &border_color, stroke_width);
+
+        if let Some(index) = self.selected(data) {
+            let layout = &self.layouts[index];
+            let origin = Point::new(
+                env.get(theme::WIDGET_PADDING_HORIZONTAL),
+                (size.height - layout.size().height) / 2.,
+            );
+            layout.draw(ctx, origin);
+        }
+        self.paint_arrow(ctx, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Dropdown".to_string(),
+            main_value: self
+                .selected(data)
+                .map(|index| self.items[index].label.display_text().to_string())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for Dropdown<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.host.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.host.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.host.update(ctx, old_data, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.host.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.host.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.host.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::widget::{Flex, SizedBox, ViewSwitcher};
+    use crate::{KeyEvent, Modifiers, Selector, WidgetExt};
+    use test_log::test;
+
+    fn key_down(key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(Modifiers::default(), key))
+    }
+
+    #[test]
+    fn keyboard_selection() {
+        let dropdown = Dropdown::new(vec![("Small", 1), ("Medium", 2), ("Large", 3)])
+            .with_disabled_item("Huge", 4);
+
+        Harness::create_simple(1, dropdown, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // Clicking takes focus and opens the list; Escape closes it again.
+            harness.event(Event::MouseDown(move_mouse((5., 5.))));
+            harness.event(key_down(KbKey::Escape));
+
+            // Arrow keys reopen the list and move from the current value.
+            harness.event(key_down(KbKey::ArrowDown));
+            harness.event(key_down(KbKey::ArrowDown));
+            harness.event(key_down(KbKey::Enter));
+            assert_eq!(*harness.data(), 2);
+
+            // Typing picks the matching item, but never a disabled one.
+            harness.event(key_down(KbKey::Character("l".into())));
+            assert_eq!(*harness.data(), 3);
+            harness.advance_time(SEARCH_TIMEOUT);
+            harness.event(key_down(KbKey::Character("h".into())));
+            assert_eq!(*harness.data(), 3);
+        });
+    }
+
+    #[test]
+    fn typing_within_the_timeout_continues_the_search() {
+        let dropdown = Dropdown::new(vec![("Large", 1), ("Little", 2), ("Long", 3)]);
+
+        Harness::create_simple(1, dropdown, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((5., 5.))));
+            harness.event(key_down(KbKey::Escape));
+
+            harness.event(key_down(KbKey::Character("l".into())));
+            harness.event(key_down(KbKey::Character("o".into())));
+            assert_eq!(*harness.data(), 3);
+            harness.advance_time(SEARCH_TIMEOUT);
+            harness.event(key_down(KbKey::Character("l".into())));
+            assert_eq!(*harness.data(), 1);
+        });
+    }
+
+    #[test]
+    fn clicking_outside_closes_the_list() {
+        let [dropdown] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Dropdown::new(vec![("Small", 1), ("Large", 2)]).with_id(dropdown))
+            .with_flex_spacer(1.0);
+
+        Harness::create_simple(1, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.click(dropdown);
+            assert_eq!(harness.opened_windows().len(), 1);
+            let popup = harness.opened_windows()[0];
+
+            // Nothing else takes focus, but the list closes all the same.
+            harness.click_at((200., 300.));
+            assert_eq!(harness.closed_windows(), [popup]);
+            assert!(harness.get_state(dropdown).has_focus);
+
+            // The dropdown no longer holds on to the mouse.
+            harness.click(dropdown);
+            assert_eq!(harness.opened_windows().len(), 2);
+        });
+    }
+
+    #[test]
+    fn removing_the_dropdown_closes_the_list() {
+        const REMOVE: Selector = Selector::new("druid-test.remove-dropdown");
+
+        let [dropdown] = widget_ids();
+        let switcher = ViewSwitcher::new(
+            |removed: &bool, _| *removed,
+            move |removed, _, _| {
+                if *removed {
+                    SizedBox::empty().boxed()
+                } else {
+                    Dropdown::new(vec![("Yes", true), ("No", false)])
+                        .with_id(dropdown)
+                        .boxed()
+                }
+            },
+        );
+        let remover = ModularWidget::new(()).event_fn(|_, _ctx, event, data: &mut bool, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(REMOVE) {
+                    *data = true;
+                }
+            }
+        });
+        let widget = Flex::column().with_child(switcher).with_child(remover);
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.click(dropdown);
+            let popup = harness.opened_windows()[0];
+            harness.submit_command(REMOVE);
+            assert_eq!(harness.closed_windows(), [popup]);
+        });
+    }
+}
@@ -3,17 +3,26 @@
 
 //! A widget that switches dynamically between two child views.
 
+use super::transition::TransitionState;
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
+use crate::widget::Transition;
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
 
 /// A widget that switches between two possible child views.
+///
+/// By default the views are switched instantly; use [`with_transition`] to
+/// animate the change.
+///
+/// [`with_transition`]: Either::with_transition
 pub struct Either<T> {
     closure: Box<dyn Fn(&T, &Env) -> bool>,
     true_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     false_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     current: bool,
+    transition: Transition,
+    transition_state: Option<TransitionState>,
 }
 
 impl<T> Either<T> {
@@ -31,8 +40,21 @@ impl<T> Either<T> {
             true_branch: WidgetPod::new(true_branch).boxed(),
             false_branch: WidgetPod::new(false_branch).boxed(),
             current: false,
+            transition: Transition::Instant,
+            transition_state: None,
         }
     }
+
+    /// Builder-style method to set the [`Transition`] used when the branch changes.
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Set the [`Transition`] used when the branch changes.
+    pub fn set_transition(&mut self, transition: Transition) {
+        self.transition = transition;
+    }
 }
 
 impl<T: Data> Widget<T> for Either<T> {
@@ -44,6 +66,15 @@ impl<T: Data> Widget<T> for Either<T> {
         } else {
             self.current_widget().event(ctx, event, data, env)
         }
+
+        if let (Some(state), Event::AnimFrame(interval)) = (&mut self.transition_state, event) {
+            if state.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.transition_state = None;
+            }
+            ctx.request_paint();
+        }
     }
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, event, data, env), fields(branch = self.current))]
@@ -65,6 +96,10 @@ impl<T: Data> Widget<T> for Either<T> {
         let current = (self.closure)(data, env);
         if current != self.current {
             self.current = current;
+            self.transition_state = TransitionState::start(self.transition);
+            if self.transition_state.is_some() {
+                ctx.request_anim_frame();
+            }
             ctx.children_changed();
         }
         self.current_widget().update(ctx, data, env)
@@ -72,6 +107,11 @@ impl<T: Data> Widget<T> for Either<T> {
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, bc, data, env), fields(branch = self.current))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        if self.transition_state.is_some() {
+            let outgoing = self.outgoing_widget();
+            outgoing.layout(ctx, bc, data, env);
+            outgoing.set_origin(ctx, Point::ORIGIN);
+        }
         let current_widget = self.current_widget();
         let size = current_widget.layout(ctx, bc, data, env);
         current_widget.set_origin(ctx, Point::ORIGIN);
@@ -81,7 +121,20 @@ impl<T: Data> Widget<T> for Either<T> {
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, data, env), fields(branch = self.current))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        self.current_widget().paint(ctx, data, env)
+        if let Some(state) = &self.transition_state {
+            let (incoming, outgoing) = if self.current {
+                (&mut self.true_branch, &mut self.false_branch)
+            } else {
+                (&mut self.false_branch, &mut self.true_branch)
+            };
+            state.paint(
+                ctx,
+                |ctx| outgoing.paint_raw(ctx, data, env),
+                |ctx| incoming.paint_raw(ctx, data, env),
+            );
+        } else {
+            self.current_widget().paint(ctx, data, env)
+        }
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
@@ -106,4 +159,12 @@ impl<T> Either<T> {
             &mut self.false_branch
         }
     }
+
+    fn outgoing_widget(&mut self) -> &mut WidgetPod<T, Box<dyn Widget<T>>> {
+        if self.current {
+            &mut self.false_branch
+        } else {
+            &mut self.true_branch
+        }
+    }
 }
@@ -7,6 +7,7 @@ use std::ops::Add;
 
 use crate::debug_state::DebugState;
 use crate::kurbo::{common::FloatExt, Vec2};
+use crate::widget::common::mirror_children;
 use crate::widget::prelude::*;
 use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
 use tracing::{instrument, trace};
@@ -89,6 +90,10 @@ use tracing::{instrument, trace};
 ///
 /// Additional options can be set (or overridden) in the [`FlexParams`].
 ///
+/// In a right-to-left [`LayoutDirection`], the layout is mirrored: rows start
+/// on the right, and `CrossAxisAlignment::Start` aligns the children of a
+/// column to the right.
+///
 /// # Examples
 ///
 /// Construction with builder methods
@@ -119,6 +124,7 @@ use tracing::{instrument, trace};
 ///
 /// [`layout`]: Widget::layout
 /// [`must_fill_main_axis`]: Flex::must_fill_main_axis
+/// [`LayoutDirection`]: crate::LayoutDirection
 /// [`WidgetExt`]: super::WidgetExt
 /// [`expand_height`]: super::WidgetExt::expand_height
 /// [`expand_width`]: super::WidgetExt::expand_width
@@ -642,6 +648,9 @@ impl<T: Data> Widget<T> for Flex<T> {
                 _ => {}
             }
         }
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, bc, data, env))]
@@ -865,6 +874,11 @@ impl<T: Data> Widget<T> for Flex<T> {
             bc.constrain(my_size)
         };
 
+        let children = self.children.iter_mut().filter_map(|x| x.widget_mut());
+        if let Some(paint_rect) = mirror_children(ctx, env, children, my_size.width) {
+            child_paint_rect = paint_rect;
+        }
+
         let my_bounds = Rect::ZERO.with_size(my_size);
         let insets = child_paint_rect - my_bounds;
         ctx.set_paint_insets(insets);
@@ -1036,7 +1050,7 @@ impl CrossAxisAlignment {
     /// Given the difference between the size of the container and the size
     /// of the child (on their minor axis) return the necessary offset for
     /// this alignment.
-    fn align(self, val: f64) -> f64 {
+    pub(crate) fn align(self, val: f64) -> f64 {
         match self {
             CrossAxisAlignment::Start => 0.0,
             // in vertical layout, baseline is equivalent to center
@@ -1047,7 +1061,7 @@ impl CrossAxisAlignment {
     }
 }
 
-struct Spacing {
+pub(crate) struct Spacing {
     alignment: MainAxisAlignment,
     extra: f64,
     n_children: usize,
@@ -1061,7 +1075,7 @@ impl Spacing {
     /// this returns an iterator of `f64` spacing,
     /// where the first element is the spacing before any children
     /// and all subsequent elements are the spacing after children.
-    fn new(alignment: MainAxisAlignment, extra: f64, n_children: usize) -> Spacing {
+    pub(crate) fn new(alignment: MainAxisAlignment, extra: f64, n_children: usize) -> Spacing {
         let extra = if extra.is_finite() { extra } else { 0. };
         let equal_space = if n_children > 0 {
             match alignment {
@@ -0,0 +1,69 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keyboard handling shared by the focusable built-in controls.
+
+use crate::kurbo::Shape;
+use crate::widget::prelude::*;
+use crate::{theme, KbKey, KeyEvent};
+
+/// Moves focus to the next or previous widget in the focus chain on
+/// Tab or Shift+Tab.
+///
+/// Returns `true` if the key was handled.
+pub(crate) fn handle_tab(ctx: &mut EventCtx, key: &KeyEvent) -> bool {
+    if key.key != KbKey::Tab || !ctx.is_focused() {
+        return false;
+    }
+    if key.mods.shift() {
+        ctx.focus_prev();
+    } else {
+        ctx.focus_next();
+    }
+    ctx.set_handled();
+    true
+}
+
+/// Returns `true` if `key` should activate a focused button or toggle.
+///
+/// Keys held down don't activate again when they repeat.
+pub(crate) fn is_activation_key(key: &KeyEvent) -> bool {
+    if key.repeat {
+        return false;
+    }
+    match &key.key {
+        KbKey::Enter => true,
+        KbKey::Character(c) => c == " ",
+        _ => false,
+    }
+}
+
+/// Returns `Some(true)` if `key` is an arrow key moving forward through a
+/// sequence of items, and `Some(false)` if it moves backward.
+///
+/// Down and right move forward, except in a right-to-left layout, where left
+/// does.
+pub(crate) fn arrow_direction(key: &KeyEvent, env: &Env) -> Option<bool> {
+    let rtl = env
+        .try_get(Env::LAYOUT_DIRECTION)
+        .unwrap_or_default()
+        .is_rtl();
+    match key.key {
+        KbKey::ArrowDown => Some(true),
+        KbKey::ArrowUp => Some(false),
+        KbKey::ArrowRight => Some(!rtl),
+        KbKey::ArrowLeft => Some(rtl),
+        _ => None,
+    }
+}
+
+/// Paints the focus ring along `shape` if the widget has keyboard focus.
+pub(crate) fn paint_focus_ring(ctx: &mut PaintCtx, shape: impl Shape, env: &Env) {
+    if ctx.is_focused() && !ctx.is_disabled() {
+        ctx.stroke(
+            shape,
+            &env.get(theme::FOCUS_RING_COLOR),
+            env.get(theme::FOCUS_RING_WIDTH),
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Widgets that group focusable widgets and control their tab order.
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{Data, Point, WidgetPod};
+use tracing::instrument;
+
+/// A widget that groups the focusable widgets inside it.
+///
+/// The members of a scope are kept together in the focus chain, and ordered
+/// among themselves by the indices set with [`WidgetExt::tab_index`]. Widgets
+/// without an index come after the ones that have one, in tree order.
+///
+/// A scope can also [trap focus], which is useful for modal content: while the
+/// scope is in the widget tree, focus moves to its first member and can't be
+/// moved outside of it, by keyboard or by [`set_focus`]. When the scope is
+/// removed or hidden, focus goes back to the widget that had it before the
+/// scope was opened.
+///
+/// [`WidgetExt::tab_index`]: crate::WidgetExt::tab_index
+/// [trap focus]: FocusScope::trap_focus
+/// [`set_focus`]: crate::EventCtx::set_focus
+pub struct FocusScope<T, W> {
+    child: WidgetPod<T, W>,
+    trap: bool,
+}
+
+impl<T, W: Widget<T>> FocusScope<T, W> {
+    /// Create a new focus scope around `child`.
+    pub fn new(child: W) -> Self {
+        FocusScope {
+            child: WidgetPod::new(child),
+            trap: false,
+        }
+    }
+
+    /// Builder-style method to keep focus inside this scope while it is open.
+    pub fn trap_focus(mut self) -> Self {
+        self.trap = true;
+        self
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for FocusScope<T, W> {
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            let start = ctx.widget_state.focus_chain.len();
+            self.child.lifecycle(ctx, event, data, env);
+            let scope = ctx.widget_id();
+            ctx.widget_state.close_focus_scope(start, scope, self.trap);
+        } else {
+            self.child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "FocusScope", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("FocusScope");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "FocusScope", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for FocusScope<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+/// A wrapper that sets the position of its focusable descendants in the tab order.
+///
+/// Widgets with a lower index are focused first. Within the nearest enclosing
+/// [`FocusScope`], or the window, widgets with an index come before the ones
+/// without, and widgets with the same index keep their tree order. An index set
+/// closer to a widget takes precedence.
+///
+/// This is usually created with [`WidgetExt::tab_index`].
+///
+/// [`WidgetExt::tab_index`]: crate::WidgetExt::tab_index
+pub struct TabIndex<W> {
+    child: W,
+    index: u32,
+}
+
+impl<W> TabIndex<W> {
+    /// Give the focusable widgets in `child` the tab index `index`.
+    pub fn new(child: W, index: u32) -> Self {
+        TabIndex { child, index }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for TabIndex<W> {
+    #[instrument(name = "TabIndex", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "TabIndex", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            let start = ctx.widget_state.focus_chain.len();
+            self.child.lifecycle(ctx, event, data, env);
+            ctx.widget_state.assign_tab_index(start, self.index);
+        } else {
+            self.child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "TabIndex",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "TabIndex", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "TabIndex", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<W> WidgetWrapper for TabIndex<W> {
+    widget_wrapper_body!(W, child);
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [`Controller`] widget that recognizes gestures.
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, Gesture, GestureRecognizer, Widget};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] widget that recognizes touch and pen gestures. Pass this
+/// and a child widget to a [`ControllerHost`] to make the child respond to
+/// gestures. More conveniently, this is available as an [`on_gesture`] method
+/// via [`WidgetExt`].
+///
+/// The gestures that are recognized are configured on the [`GestureRecognizer`].
+///
+/// [`ControllerHost`]: super::ControllerHost
+/// [`on_gesture`]: super::WidgetExt::on_gesture
+/// [`WidgetExt`]: super::WidgetExt
+pub struct Gestures<T> {
+    recognizer: GestureRecognizer,
+    /// A closure that will be invoked for each recognized gesture.
+    action: Box<dyn Fn(&mut EventCtx, &Gesture, &mut T, &Env)>,
+}
+
+impl<T: Data> Gestures<T> {
+    /// Create a new gesture [`Controller`] widget.
+    pub fn new(
+        recognizer: GestureRecognizer,
+        action: impl Fn(&mut EventCtx, &Gesture, &mut T, &Env) + 'static,
+    ) -> Self {
+        Gestures {
+            recognizer,
+            action: Box::new(action),
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Gestures<T> {
+    #[instrument(
+        name = "Gestures",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for gesture in self.recognizer.event(ctx, event) {
+            trace!("Widget {:?} recognized {:?}", ctx.widget_id(), gesture);
+            (self.action)(ctx, &gesture, data, env);
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
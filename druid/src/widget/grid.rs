@@ -0,0 +1,683 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that arranges its children in rows and columns.
+
+use std::ops::Range;
+
+use crate::debug_state::DebugState;
+use crate::widget::common::mirror_children;
+use crate::widget::prelude::*;
+use crate::widget::{Axis, CrossAxisAlignment};
+use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+use tracing::{instrument, trace, warn};
+
+/// How the size of a row or column of a [`Grid`] is determined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    /// The track has this exact size.
+    Fixed(f64),
+    /// The track gets a share of the space that is left after the other
+    /// tracks are sized, proportional to this factor.
+    ///
+    /// If the grid is unbounded in this direction, the track is sized as if
+    /// it were [`Intrinsic`](GridTrack::Intrinsic).
+    Flex(f64),
+    /// The track is as large as the largest child in it.
+    Intrinsic,
+}
+
+/// Where a child is placed in a [`Grid`], and how it is aligned in its cell.
+///
+/// A `(row, column)` tuple can be passed anywhere `GridParams` are expected,
+/// for a child that occupies a single cell and uses the grid's alignment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridParams {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    horizontal: Option<CrossAxisAlignment>,
+    vertical: Option<CrossAxisAlignment>,
+}
+
+/// A container that arranges its children in rows and columns.
+///
+/// The size of each row and column, or *track*, is given by a [`GridTrack`]:
+/// it can be fixed, take a share of the leftover space like a flex child
+/// of a [`Flex`], or be as large as its largest child. Children are placed
+/// in a cell with [`GridParams`], and can span several rows and columns.
+///
+/// Children placed beyond the tracks that were added explicitly get extra
+/// [`Intrinsic`](GridTrack::Intrinsic) tracks, so a form with a column of
+/// labels and a column of fields only needs its columns declared:
+///
+/// ```
+/// use druid::widget::{Grid, GridParams, GridTrack, Label, TextBox};
+/// use druid::widget::CrossAxisAlignment;
+/// use druid::theme;
+///
+/// let form = Grid::new()
+///     .with_column(GridTrack::Intrinsic)
+///     .with_column(GridTrack::Flex(1.0))
+///     .row_gap(theme::WIDGET_PADDING_VERTICAL)
+///     .column_gap(theme::WIDGET_PADDING_HORIZONTAL)
+///     .cell_alignment(CrossAxisAlignment::Start, CrossAxisAlignment::Baseline)
+///     .with_child(Label::new("Name"), (0, 0))
+///     .with_child(TextBox::new(), GridParams::new(0, 1).align(CrossAxisAlignment::Fill, None))
+///     .with_child(Label::new("Notes"), (1, 0))
+///     .with_child(TextBox::multiline(), GridParams::new(1, 1).align(CrossAxisAlignment::Fill, None));
+/// ```
+///
+/// # Alignment
+///
+/// Each child is aligned in its cell with a [`CrossAxisAlignment`] on each
+/// axis, which defaults to the grid's [`cell_alignment`]. On the horizontal
+/// axis `Baseline` is the same as `Center`. On the vertical axis, the
+/// baselines of all `Baseline` aligned children that occupy a single row are
+/// lined up.
+///
+/// In a right-to-left [`LayoutDirection`], the grid is mirrored, so the first
+/// column is on the right.
+///
+/// [`Flex`]: super::Flex
+/// [`LayoutDirection`]: crate::LayoutDirection
+/// [`cell_alignment`]: Grid::cell_alignment
+pub struct Grid<T> {
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    column_gap: KeyOrValue<f64>,
+    row_gap: KeyOrValue<f64>,
+    horizontal: CrossAxisAlignment,
+    vertical: CrossAxisAlignment,
+    children: Vec<GridChild<T>>,
+}
+
+struct GridChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    params: GridParams,
+}
+
+impl GridParams {
+    /// Place a child in the cell at `row` and `column`, counting from zero.
+    pub fn new(row: usize, column: usize) -> Self {
+        GridParams {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            horizontal: None,
+            vertical: None,
+        }
+    }
+
+    /// Builder-style method to make the child span several rows and columns,
+    /// starting at its cell.
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        if rows == 0 || columns == 0 {
+            debug_panic!(
+                "Grid spans should be at least 1; got {} by {}",
+                rows,
+                columns
+            );
+        }
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    /// Builder-style method to align the child in its cell, instead of using
+    /// the grid's [`cell_alignment`](Grid::cell_alignment).
+    ///
+    /// Pass `None` for an axis to keep the grid's alignment on that axis.
+    pub fn align(
+        mut self,
+        horizontal: impl Into<Option<CrossAxisAlignment>>,
+        vertical: impl Into<Option<CrossAxisAlignment>>,
+    ) -> Self {
+        self.horizontal = horizontal.into();
+        self.vertical = vertical.into();
+        self
+    }
+
+    fn tracks(&self, axis: Axis) -> Range<usize> {
+        match axis {
+            Axis::Horizontal => self.column..self.column + self.column_span,
+            Axis::Vertical => self.row..self.row + self.row_span,
+        }
+    }
+}
+
+impl From<(usize, usize)> for GridParams {
+    fn from((row, column): (usize, usize)) -> Self {
+        GridParams::new(row, column)
+    }
+}
+
+impl<T: Data> Grid<T> {
+    /// Create a new, empty grid.
+    ///
+    /// Children are aligned to the start of their cell horizontally, and
+    /// centered vertically.
+    pub fn new() -> Self {
+        Grid {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            column_gap: 0.0.into(),
+            row_gap: 0.0.into(),
+            horizontal: CrossAxisAlignment::Start,
+            vertical: CrossAxisAlignment::Center,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a column.
+    pub fn with_column(mut self, track: GridTrack) -> Self {
+        self.add_column(track);
+        self
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_row(mut self, track: GridTrack) -> Self {
+        self.add_row(track);
+        self
+    }
+
+    /// Builder-style method to set the space between both rows and columns.
+    pub fn gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        let gap = gap.into();
+        self.row_gap = gap.clone();
+        self.column_gap = gap;
+        self
+    }
+
+    /// Builder-style method to set the space between rows.
+    pub fn row_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.row_gap = gap.into();
+        self
+    }
+
+    /// Builder-style method to set the space between columns.
+    pub fn column_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.column_gap = gap.into();
+        self
+    }
+
+    /// Builder-style method to set how children are aligned in their cells,
+    /// unless their [`GridParams`] say otherwise.
+    pub fn cell_alignment(
+        mut self,
+        horizontal: CrossAxisAlignment,
+        vertical: CrossAxisAlignment,
+    ) -> Self {
+        self.set_cell_alignment(horizontal, vertical);
+        self
+    }
+
+    /// Builder-style method to add a child.
+    ///
+    /// `params` can be a `(row, column)` tuple, or [`GridParams`] for a
+    /// child that spans several cells or has its own alignment.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        params: impl Into<GridParams>,
+    ) -> Self {
+        self.add_child(child, params);
+        self
+    }
+
+    /// Add a column.
+    pub fn add_column(&mut self, track: GridTrack) {
+        self.columns.push(track);
+    }
+
+    /// Add a row.
+    pub fn add_row(&mut self, track: GridTrack) {
+        self.rows.push(track);
+    }
+
+    /// Set the space between rows.
+    pub fn set_row_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        self.row_gap = gap.into();
+    }
+
+    /// Set the space between columns.
+    pub fn set_column_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        self.column_gap = gap.into();
+    }
+
+    /// Set how children are aligned in their cells, unless their
+    /// [`GridParams`] say otherwise.
+    pub fn set_cell_alignment(
+        &mut self,
+        horizontal: CrossAxisAlignment,
+        vertical: CrossAxisAlignment,
+    ) {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+    }
+
+    /// Add a child.
+    ///
+    /// See also [`with_child`](Grid::with_child).
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, params: impl Into<GridParams>) {
+        self.children.push(GridChild {
+            widget: WidgetPod::new(Box::new(child)),
+            params: params.into(),
+        });
+    }
+
+    /// The tracks along `axis`, including implicit tracks for children
+    /// placed beyond the explicit ones.
+    fn tracks(&self, axis: Axis) -> Vec<GridTrack> {
+        let mut tracks = match axis {
+            Axis::Horizontal => self.columns.clone(),
+            Axis::Vertical => self.rows.clone(),
+        };
+        let count = self
+            .children
+            .iter()
+            .map(|child| child.params.tracks(axis).end)
+            .max()
+            .unwrap_or(0);
+        if count > tracks.len() {
+            tracks.resize(count, GridTrack::Intrinsic);
+        }
+        tracks
+    }
+
+    fn resolved_gap(&self, axis: Axis, env: &Env) -> f64 {
+        let gap = match axis {
+            Axis::Horizontal => self.column_gap.resolve(env),
+            Axis::Vertical => self.row_gap.resolve(env),
+        };
+        if gap < 0.0 {
+            warn!("Grid gap was less than 0");
+        }
+        gap.max(0.0)
+    }
+}
+
+impl<T: Data> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Grid<T> {
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+        if ctx.env_key_changed(&self.row_gap)
+            || ctx.env_key_changed(&self.column_gap)
+            || ctx.env_key_changed(&Env::LAYOUT_DIRECTION)
+        {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Grid");
+        let columns = self.tracks(Axis::Horizontal);
+        let rows = self.tracks(Axis::Vertical);
+        let column_gap = self.resolved_gap(Axis::Horizontal, env);
+        let row_gap = self.resolved_gap(Axis::Vertical, env);
+        let (horizontal, vertical) = (self.horizontal, self.vertical);
+
+        // Size the columns from the intrinsic widths of the children.
+        let max_width = bc.max().width;
+        let child_bc = bc.loosen().unbound_max(Axis::Horizontal);
+        let mut measured = Vec::new();
+        for child in &mut self.children {
+            let range = child.params.tracks(Axis::Horizontal);
+            if !contributes(&columns[range.clone()], max_width) {
+                continue;
+            }
+            let width = child.widget.widget_mut().compute_max_intrinsic(
+                Axis::Horizontal,
+                ctx,
+                &child_bc,
+                data,
+                env,
+            );
+            measured.push((range, width));
+        }
+        let column_sizes = resolve_tracks(&columns, &measured, max_width, column_gap);
+
+        // Lay out the children that decide the row heights, leaving those in
+        // flex rows for when we know how much space is left over.
+        let max_height = bc.max().height;
+        let mut measured = Vec::new();
+        let mut baselines = vec![(0f64, 0f64); rows.len()];
+        let mut deferred = Vec::new();
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let range = child.params.tracks(Axis::Vertical);
+            if !contributes(&rows[range.clone()], max_height) {
+                deferred.push(i);
+                continue;
+            }
+            let fixed_height = rows[range.clone()]
+                .iter()
+                .map(|track| match track {
+                    GridTrack::Fixed(size) => Some(size.max(0.0)),
+                    _ => None,
+                })
+                .sum::<Option<f64>>()
+                .map(|height| height + row_gap * (range.len() - 1) as f64);
+            let size = layout_child(
+                ctx,
+                child,
+                &column_sizes,
+                column_gap,
+                fixed_height.unwrap_or(f64::INFINITY),
+                horizontal,
+                data,
+                env,
+            );
+            let alignment = child.params.vertical.unwrap_or(vertical);
+            if alignment == CrossAxisAlignment::Baseline && range.len() == 1 {
+                let baseline = child.widget.baseline_offset();
+                let (above, below) = &mut baselines[range.start];
+                *above = above.max(size.height - baseline);
+                *below = below.max(baseline);
+            } else {
+                measured.push((range, size.height));
+            }
+        }
+        for (row, (above, below)) in baselines.iter().enumerate() {
+            measured.push((row..row + 1, above + below));
+        }
+        let row_sizes = resolve_tracks(&rows, &measured, max_height, row_gap);
+        for i in deferred {
+            let child = &mut self.children[i];
+            let height = span_size(&row_sizes, child.params.tracks(Axis::Vertical), row_gap);
+            layout_child(
+                ctx,
+                child,
+                &column_sizes,
+                column_gap,
+                height,
+                horizontal,
+                data,
+                env,
+            );
+        }
+
+        // Place the children in their cells.
+        let column_starts = track_starts(&column_sizes, column_gap);
+        let row_starts = track_starts(&row_sizes, row_gap);
+        let mut child_paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let columns = child.params.tracks(Axis::Horizontal);
+            let rows = child.params.tracks(Axis::Vertical);
+            let origin = Point::new(column_starts[columns.start], row_starts[rows.start]);
+            let cell = Size::new(
+                span_size(&column_sizes, columns, column_gap),
+                span_size(&row_sizes, rows.clone(), row_gap),
+            );
+
+            let vertical = child.params.vertical.unwrap_or(vertical);
+            let mut size = child.widget.layout_rect().size();
+            if vertical == CrossAxisAlignment::Fill && size.height != cell.height {
+                let child_bc = BoxConstraints::new(
+                    Size::new(size.width, cell.height),
+                    Size::new(size.width, cell.height),
+                );
+                size = child.widget.layout(ctx, &child_bc, data, env);
+            }
+
+            let horizontal = child.params.horizontal.unwrap_or(horizontal);
+            let x = horizontal.align(cell.width - size.width);
+            let y = match vertical {
+                CrossAxisAlignment::Baseline if rows.len() == 1 => {
+                    let above = size.height - child.widget.baseline_offset();
+                    baselines[rows.start].0 - above
+                }
+                _ => vertical.align(cell.height - size.height),
+            };
+            child.widget.set_origin(ctx, origin + (x, y));
+            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
+        }
+
+        let content = Size::new(
+            total_size(&column_sizes, column_gap),
+            total_size(&row_sizes, row_gap),
+        );
+        let my_size = bc.constrain(content);
+
+        let children = self.children.iter_mut().map(|child| &mut child.widget);
+        if let Some(paint_rect) = mirror_children(ctx, env, children, my_size.width) {
+            child_paint_rect = paint_rect;
+        }
+        ctx.set_paint_insets(child_paint_rect - my_size.to_rect());
+        trace!("Computed layout: size={}", my_size);
+        my_size
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let tracks = self.tracks(axis);
+        let gap = self.resolved_gap(axis, env);
+        let child_bc = bc.unbound_max(axis);
+        let measured: Vec<_> = self
+            .children
+            .iter_mut()
+            .map(|child| {
+                let size = child
+                    .widget
+                    .widget_mut()
+                    .compute_max_intrinsic(axis, ctx, &child_bc, data, env);
+                (child.params.tracks(axis), size)
+            })
+            .collect();
+        let sizes = resolve_tracks(&tracks, &measured, f64::INFINITY, gap);
+        total_size(&sizes, gap)
+    }
+}
+
+/// Returns `true` if a child spanning `tracks` helps decide their size,
+/// given the space available on their axis.
+///
+/// Children in flex tracks only get whatever space is left over, unless the
+/// space is unbounded.
+fn contributes(tracks: &[GridTrack], available: f64) -> bool {
+    let any_flex = tracks
+        .iter()
+        .any(|track| matches!(track, GridTrack::Flex(_)));
+    let all_fixed = tracks
+        .iter()
+        .all(|track| matches!(track, GridTrack::Fixed(_)));
+    let gets_leftover_space = any_flex && available.is_finite();
+    !(all_fixed || gets_leftover_space)
+}
+
+/// Lay out a child to fit the width of its columns, and at most `max_height`.
+#[allow(clippy::too_many_arguments)]
+fn layout_child<T: Data>(
+    ctx: &mut LayoutCtx,
+    child: &mut GridChild<T>,
+    column_sizes: &[f64],
+    column_gap: f64,
+    max_height: f64,
+    horizontal: CrossAxisAlignment,
+    data: &T,
+    env: &Env,
+) -> Size {
+    let width = span_size(
+        column_sizes,
+        child.params.tracks(Axis::Horizontal),
+        column_gap,
+    );
+    let min_width = match child.params.horizontal.unwrap_or(horizontal) {
+        CrossAxisAlignment::Fill => width,
+        _ => 0.0,
+    };
+    let child_bc = BoxConstraints::new(Size::new(min_width, 0.0), Size::new(width, max_height));
+    child.widget.layout(ctx, &child_bc, data, env)
+}
+
+/// Compute the size of each track, given the sizes that children spanning
+/// some range of the tracks want, and the space available.
+fn resolve_tracks(
+    tracks: &[GridTrack],
+    measured: &[(Range<usize>, f64)],
+    available: f64,
+    gap: f64,
+) -> Vec<f64> {
+    let is_flex = |track: &GridTrack| matches!(track, GridTrack::Flex(_)) && available.is_finite();
+    let is_intrinsic = |track: &GridTrack| match track {
+        GridTrack::Fixed(_) => false,
+        GridTrack::Flex(_) => !available.is_finite(),
+        GridTrack::Intrinsic => true,
+    };
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+
+    // Children in a single track decide its size directly ...
+    for (range, size) in measured.iter().filter(|(range, _)| range.len() == 1) {
+        if is_intrinsic(&tracks[range.start]) {
+            sizes[range.start] = sizes[range.start].max(*size);
+        }
+    }
+    // ... while children spanning several tracks share any space they still
+    // need between the intrinsic tracks they span.
+    for (range, size) in measured.iter().filter(|(range, _)| range.len() > 1) {
+        let missing = size - span_size(&sizes, range.clone(), gap);
+        let intrinsic: Vec<usize> = range
+            .clone()
+            .filter(|&i| is_intrinsic(&tracks[i]))
+            .collect();
+        if missing > 0.0 && !intrinsic.is_empty() {
+            let share = missing / intrinsic.len() as f64;
+            for i in intrinsic {
+                sizes[i] += share;
+            }
+        }
+    }
+
+    let flex_sum: f64 = tracks
+        .iter()
+        .filter(|track| is_flex(track))
+        .map(|track| match track {
+            GridTrack::Flex(flex) => flex.max(0.0),
+            _ => 0.0,
+        })
+        .sum();
+    if flex_sum > 0.0 {
+        let remaining = (available - total_size(&sizes, gap)).max(0.0);
+        let px_per_flex = remaining / flex_sum;
+        let mut remainder: f64 = 0.0;
+        for (size, track) in sizes.iter_mut().zip(tracks) {
+            if let GridTrack::Flex(flex) = track {
+                let desired = flex.max(0.0) * px_per_flex + remainder;
+                *size = desired.round();
+                remainder = desired - *size;
+            }
+        }
+    }
+    sizes
+}
+
+/// The size of the tracks in `range`, including the gaps between them.
+fn span_size(sizes: &[f64], range: Range<usize>, gap: f64) -> f64 {
+    let count = range.len();
+    sizes[range].iter().sum::<f64>() + gap * count.saturating_sub(1) as f64
+}
+
+fn total_size(sizes: &[f64], gap: f64) -> f64 {
+    span_size(sizes, 0..sizes.len(), gap)
+}
+
+/// The position at which each track starts.
+fn track_starts(sizes: &[f64], gap: f64) -> Vec<f64> {
+    let mut position = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = position;
+            position += size + gap;
+            start
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn flex_tracks_share_leftover_space() {
+        let tracks = [
+            GridTrack::Fixed(50.),
+            GridTrack::Flex(1.),
+            GridTrack::Flex(3.),
+        ];
+        let sizes = resolve_tracks(&tracks, &[], 270., 10.);
+        assert_eq!(sizes, [50., 50., 150.]);
+        // Without a bound, flex tracks fit their children.
+        let sizes = resolve_tracks(&tracks, &[(1..2, 20.)], f64::INFINITY, 10.);
+        assert_eq!(sizes, [50., 20., 0.]);
+    }
+
+    #[test]
+    fn spanning_children_grow_intrinsic_tracks() {
+        let tracks = [
+            GridTrack::Intrinsic,
+            GridTrack::Fixed(20.),
+            GridTrack::Intrinsic,
+        ];
+        let measured = [(0..1, 30.), (0..3, 100.)];
+        let sizes = resolve_tracks(&tracks, &measured, f64::INFINITY, 0.);
+        // 50 is missing, and is split between the two intrinsic tracks.
+        assert_eq!(sizes, [55., 20., 25.]);
+    }
+}
@@ -6,22 +6,28 @@
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::f64;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::sync::Arc;
 
-use tracing::{instrument, trace};
+use tracing::{error, instrument, trace};
 
 #[cfg(feature = "im")]
 use crate::im::{OrdMap, Vector};
 
-use crate::kurbo::{Point, Rect, Size};
+use crate::kurbo::{Affine, Line, Point, Rect, Size};
 
 use crate::debug_state::DebugState;
+use crate::drag::DRAG_CANCELLED;
+use crate::widget::drag_drop::{paint_default_preview, DRAG_THRESHOLD};
 use crate::{
-    widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetPod,
+    theme, widget::Axis, BoxConstraints, Command, Data, Env, Event, EventCtx, KeyOrValue,
+    LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, RenderContext, Selector, Target, UpdateCtx,
+    Widget, WidgetPod,
 };
 
+/// The payload of a drag started by a reorderable [`List`]: the index of the dragged child.
+const LIST_REORDER: Selector<usize> = Selector::new("druid-builtin.list-reorder");
+
 /// A list widget for a variable-size collection of items.
 pub struct List<T> {
     closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
@@ -29,6 +35,11 @@ pub struct List<T> {
     axis: Axis,
     spacing: KeyOrValue<f64>,
     old_bc: BoxConstraints,
+    reorder: bool,
+    /// The child pressed with the mouse, and where, before a reorder drag starts.
+    press: Option<(usize, Point)>,
+    /// Where the dragged child would be inserted if it was dropped now.
+    drop_index: Option<usize>,
 }
 
 impl<T: Data> List<T> {
@@ -41,6 +52,9 @@ impl<T: Data> List<T> {
             axis: Axis::Vertical,
             spacing: KeyOrValue::Concrete(0.),
             old_bc: BoxConstraints::tight(Size::ZERO),
+            reorder: false,
+            press: None,
+            drop_index: None,
         }
     }
 
@@ -62,6 +76,106 @@ impl<T: Data> List<T> {
         self
     }
 
+    /// Builder-style method to let the user reorder the items by dragging them.
+    ///
+    /// Items are moved with [`ListIter::move_item`], so the data has to
+    /// implement it. All the collections supported by druid do, except
+    /// `OrdMap`, which is always ordered by its keys.
+    pub fn reorderable(mut self) -> Self {
+        self.reorder = true;
+        self
+    }
+
+    /// The index of the child at `pos`, if any.
+    fn child_at(&self, pos: Point) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|child| child.layout_rect().contains(pos))
+    }
+
+    /// Where a child dropped at `pos` would be inserted.
+    fn insertion_index(&self, pos: Point) -> usize {
+        let axis = self.axis;
+        self.children
+            .iter()
+            .take_while(|child| axis.major_pos(child.layout_rect().center()) < axis.major_pos(pos))
+            .count()
+    }
+
+    /// Handle the mouse events that drag children around.
+    fn reorder_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut impl ListIter<T>,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() && !ctx.is_handled() => {
+                if let Some(index) = self.child_at(mouse.pos) {
+                    self.press = Some((index, mouse.pos));
+                    ctx.set_active(true);
+                }
+            }
+            Event::MouseMove(mouse) => {
+                if let Some((index, press)) = self.press {
+                    if ctx.is_active() && mouse.pos.distance(press) >= DRAG_THRESHOLD {
+                        self.press = None;
+                        let rect = self.children[index].layout_rect();
+                        let size = rect.size();
+                        let payload = Command::new(LIST_REORDER, index, Target::Auto);
+                        let bounds = rect - press.to_vec2();
+                        let env = env.clone();
+                        ctx.start_drag_command(payload, bounds, move |ctx| {
+                            ctx.with_save(|ctx| {
+                                ctx.transform(Affine::translate(bounds.origin().to_vec2()));
+                                paint_default_preview(ctx, size, &env);
+                            });
+                        });
+                    }
+                }
+                let drop_index = match ctx.drag_payload(LIST_REORDER) {
+                    Some(_) if ctx.is_drag_source() && ctx.is_hot() => {
+                        Some(self.insertion_index(mouse.pos))
+                    }
+                    _ => None,
+                };
+                if drop_index != self.drop_index {
+                    self.drop_index = drop_index;
+                    ctx.request_paint();
+                }
+            }
+            Event::Command(cmd) if cmd.is(DRAG_CANCELLED) => {
+                self.press = None;
+                ctx.set_active(false);
+                if self.drop_index.take().is_some() {
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
+            }
+            Event::MouseUp(_) => {
+                let from = ctx
+                    .drag_payload(LIST_REORDER)
+                    .copied()
+                    .filter(|_| ctx.is_drag_source());
+                if let (Some(from), Some(to)) = (from, self.drop_index) {
+                    let to = if to > from { to - 1 } else { to };
+                    if to != from {
+                        trace!("Moving list item {} to {}", from, to);
+                        data.move_item(from, to);
+                    }
+                    ctx.set_handled();
+                }
+                self.press = None;
+                ctx.set_active(false);
+                if self.drop_index.take().is_some() {
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+    }
+
     /// When the widget is created or the data changes, create or remove children as needed
     ///
     /// Returns `true` if children were added or removed.
@@ -91,7 +205,56 @@ pub trait ListIter<T>: Data {
 
     /// Return data length.
     fn data_len(&self) -> usize;
+
+    /// Move the data child at index `from` so that it ends up at index `to`.
+    ///
+    /// This is used by [`List::reorderable`]. The default implementation logs
+    /// an error and leaves the data unchanged, for collections that can't be
+    /// reordered.
+    fn move_item(&mut self, from: usize, to: usize) {
+        error!(
+            "Can't move list item {} to {}: the data doesn't support reordering",
+            from, to
+        );
+    }
+
+    /// Iterate over the data children with an index in `range`.
+    ///
+    /// The default implementation walks the whole collection; implementors with
+    /// cheap random access should override it.
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        self.for_each(|child, i| {
+            if range.contains(&i) {
+                cb(child, i);
+            }
+        });
+    }
+
+    /// Iterate over the data children with an index in `range`. Keep track of
+    /// changed data and update self.
+    ///
+    /// The default implementation walks the whole collection; implementors with
+    /// cheap random access should override it.
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        self.for_each_mut(|child, i| {
+            if range.contains(&i) {
+                cb(child, i);
+            }
+        });
+    }
+}
+
+/// Clamp `range` so that it can be used to index a collection of length `len`.
+fn clamp_range(range: Range<usize>, len: usize) -> Range<usize> {
+    let end = range.end.min(len);
+    range.start.min(end)..end
 }
+
+/// Whether an item can be moved from `from` to `to` in a collection of length `len`.
+fn can_move(from: usize, to: usize, len: usize) -> bool {
+    from != to && from < len && to < len
+}
+
 #[cfg(feature = "im")]
 impl<T: Data> ListIter<T> for Vector<T> {
     fn for_each(&self, mut cb: impl FnMut(&T, usize)) {
@@ -113,6 +276,29 @@ impl<T: Data> ListIter<T> for Vector<T> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.len()) {
+            let item = self.remove(from);
+            self.insert(to, item);
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            cb(&self[i], i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            let mut new_element = self[i].clone();
+            cb(&mut new_element, i);
+            if !new_element.same(&self[i]) {
+                self[i] = new_element;
+            }
+        }
+    }
 }
 
 //An implementation for ListIter<(K, V)> has been omitted due to problems
@@ -173,6 +359,34 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Vector<T>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.1.len()) {
+            let item = self.1.remove(from);
+            self.1.insert(to, item);
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&(S, T), usize)) {
+        for i in clamp_range(range, self.1.len()) {
+            let d = (self.0.to_owned(), self.1[i].to_owned());
+            cb(&d, i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut (S, T), usize)) {
+        for i in clamp_range(range, self.1.len()) {
+            let mut d = (self.0.clone(), self.1[i].to_owned());
+            cb(&mut d, i);
+
+            if !self.0.same(&d.0) {
+                self.0 = d.0;
+            }
+            if !self.1[i].same(&d.1) {
+                self.1[i] = d.1;
+            }
+        }
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<Vec<T>> {
@@ -210,6 +424,45 @@ impl<T: Data> ListIter<T> for Arc<Vec<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.len()) {
+            let vec = Arc::make_mut(self);
+            let item = vec.remove(from);
+            vec.insert(to, item);
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            cb(&self[i], i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        let mut new_data: Option<Vec<T>> = None;
+
+        for i in clamp_range(range, self.len()) {
+            let mut d = self[i].to_owned();
+            cb(&mut d, i);
+
+            if !self[i].same(&d) {
+                match &mut new_data {
+                    Some(vec) => {
+                        vec[i] = d;
+                    }
+                    None => {
+                        let mut new = (**self).clone();
+                        new[i] = d;
+                        new_data = Some(new);
+                    }
+                }
+            }
+        }
+        if let Some(vec) = new_data {
+            *self = Arc::new(vec);
+        }
+    }
 }
 
 // S == shared data type
@@ -251,6 +504,48 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<Vec<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.1.len()) {
+            let vec = Arc::make_mut(&mut self.1);
+            let item = vec.remove(from);
+            vec.insert(to, item);
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&(S, T), usize)) {
+        for i in clamp_range(range, self.1.len()) {
+            let d = (self.0.clone(), self.1[i].to_owned());
+            cb(&d, i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut (S, T), usize)) {
+        let mut new_data: Option<Vec<T>> = None;
+
+        for i in clamp_range(range, self.1.len()) {
+            let mut d = (self.0.clone(), self.1[i].to_owned());
+            cb(&mut d, i);
+
+            self.0 = d.0;
+
+            if !self.1[i].same(&d.1) {
+                match &mut new_data {
+                    Some(vec) => {
+                        vec[i] = d.1;
+                    }
+                    None => {
+                        let mut new = self.1.deref().clone();
+                        new[i] = d.1;
+                        new_data = Some(new);
+                    }
+                }
+            }
+        }
+        if let Some(vec) = new_data {
+            self.1 = Arc::new(vec);
+        }
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
@@ -288,6 +583,46 @@ impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.len()) {
+            let deque = Arc::make_mut(self);
+            if let Some(item) = deque.remove(from) {
+                deque.insert(to, item);
+            }
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            cb(&self[i], i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        let mut new_data: Option<VecDeque<T>> = None;
+
+        for i in clamp_range(range, self.len()) {
+            let mut d = self[i].to_owned();
+            cb(&mut d, i);
+
+            if !self[i].same(&d) {
+                match &mut new_data {
+                    Some(vec) => {
+                        vec[i] = d;
+                    }
+                    None => {
+                        let mut new = (**self).clone();
+                        new[i] = d;
+                        new_data = Some(new);
+                    }
+                }
+            }
+        }
+        if let Some(vec) = new_data {
+            *self = Arc::new(vec);
+        }
+    }
 }
 
 // S == shared data type
@@ -329,6 +664,49 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<VecDeque<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        if can_move(from, to, self.1.len()) {
+            let deque = Arc::make_mut(&mut self.1);
+            if let Some(item) = deque.remove(from) {
+                deque.insert(to, item);
+            }
+        }
+    }
+
+    fn for_each_in(&self, range: Range<usize>, mut cb: impl FnMut(&(S, T), usize)) {
+        for i in clamp_range(range, self.1.len()) {
+            let d = (self.0.clone(), self.1[i].to_owned());
+            cb(&d, i);
+        }
+    }
+
+    fn for_each_mut_in(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut (S, T), usize)) {
+        let mut new_data: Option<VecDeque<T>> = None;
+
+        for i in clamp_range(range, self.1.len()) {
+            let mut d = (self.0.clone(), self.1[i].to_owned());
+            cb(&mut d, i);
+
+            self.0 = d.0;
+
+            if !self.1[i].same(&d.1) {
+                match &mut new_data {
+                    Some(vec) => {
+                        vec[i] = d.1;
+                    }
+                    None => {
+                        let mut new = self.1.deref().clone();
+                        new[i] = d.1;
+                        new_data = Some(new);
+                    }
+                }
+            }
+        }
+        if let Some(vec) = new_data {
+            self.1 = Arc::new(vec);
+        }
+    }
 }
 
 impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
@@ -340,6 +718,10 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
                 child.event(ctx, event, child_data, env);
             }
         });
+
+        if self.reorder {
+            self.reorder_event(ctx, event, data, env);
+        }
     }
 
     #[instrument(name = "List", level = "trace", skip(self, ctx, event, data, env))]
@@ -431,6 +813,20 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
                 child.paint(ctx, child_data, env);
             }
         });
+
+        if let Some(index) = self.drop_index {
+            // Draw the insertion line halfway between the neighbouring children.
+            let axis = self.axis;
+            let spacing = self.spacing.resolve(env);
+            let major = match (self.children.get(index), self.children.last()) {
+                (Some(child), _) => axis.major_span(child.layout_rect()).0 - spacing / 2.,
+                (None, Some(last)) => axis.major_span(last.layout_rect()).1 + spacing / 2.,
+                (None, None) => return,
+            };
+            let minor = axis.minor(ctx.size());
+            let line = Line::new(axis.pack(major, 0.), axis.pack(major, minor));
+            ctx.stroke(line, &env.get(theme::DROP_ACCEPT_COLOR), 2.0);
+        }
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
@@ -0,0 +1,319 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that shows a different child for each variant of its data.
+
+use std::marker::PhantomData;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, Point, Prism, WidgetPod};
+
+type CaseBuilder<T> = dyn Fn() -> Box<dyn Widget<T>>;
+
+struct Case<T> {
+    matches: Box<dyn Fn(&T) -> bool>,
+    build: Box<CaseBuilder<T>>,
+}
+
+/// A widget that switches between child views depending on which variant
+/// its data currently is.
+///
+/// Each case is a [`Prism`], usually created with
+/// [`#[derive(Prism)]`](druid_derive::Prism), together with a closure that
+/// builds the widget for it. That widget gets the data inside the variant.
+/// The first case whose prism matches the data is shown; if none match, the
+/// default widget is shown, or nothing at all if there is no default.
+///
+/// Like [`Maybe`], which this generalizes, the child is rebuilt whenever a
+/// different case starts to match, so children do not keep state across
+/// variants.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Matcher, Spinner};
+/// use druid::{Data, Prism, Widget};
+///
+/// #[derive(Clone, Data, Prism)]
+/// enum Download {
+///     Pending,
+///     InProgress(f64),
+///     Failed(String),
+/// }
+///
+/// fn download_widget() -> impl Widget<Download> {
+///     Matcher::new()
+///         .with_case(Download::in_progress, Spinner::new)
+///         .with_case(Download::failed, || {
+///             Label::dynamic(|error: &String, _| format!("Failed: {error}"))
+///         })
+///         .with_default(|| Label::new("Waiting…"))
+/// }
+/// ```
+///
+/// [`Maybe`]: super::Maybe
+pub struct Matcher<T> {
+    cases: Vec<Case<T>>,
+    default: Option<Box<CaseBuilder<T>>>,
+    /// The index of the case being shown, or `cases.len()` for the default,
+    /// and its widget.
+    current: Option<(usize, WidgetPod<T, Box<dyn Widget<T>>>)>,
+}
+
+/// Shows a widget for the part of the data focused on by a prism.
+struct PrismWrap<U, P, W> {
+    child: W,
+    prism: P,
+    phantom_u: PhantomData<U>,
+}
+
+impl<T: Data> Matcher<T> {
+    /// Create a new `Matcher` without any cases.
+    pub fn new() -> Self {
+        Matcher {
+            cases: Vec::new(),
+            default: None,
+            current: None,
+        }
+    }
+
+    /// Builder-style method to add a case.
+    ///
+    /// When `prism` is the first prism to match the data, the widget built by
+    /// `build` is shown, with the data it focuses on.
+    pub fn with_case<U, P, W>(mut self, prism: P, build: impl Fn() -> W + 'static) -> Self
+    where
+        U: Data,
+        P: Prism<T, U> + Clone + 'static,
+        W: Widget<U> + 'static,
+    {
+        let matches = prism.clone();
+        self.cases.push(Case {
+            matches: Box::new(move |data| matches.matches(data)),
+            build: Box::new(move || {
+                Box::new(PrismWrap {
+                    child: build(),
+                    prism: prism.clone(),
+                    phantom_u: PhantomData,
+                })
+            }),
+        });
+        self
+    }
+
+    /// Builder-style method to set the widget that is shown when no case matches.
+    pub fn with_default<W: Widget<T> + 'static>(mut self, build: impl Fn() -> W + 'static) -> Self {
+        self.default = Some(Box::new(move || Box::new(build())));
+        self
+    }
+
+    /// The index of the case that should be shown for `data`.
+    fn case_for(&self, data: &T) -> Option<usize> {
+        self.cases
+            .iter()
+            .position(|case| (case.matches)(data))
+            .or_else(|| self.default.as_ref().map(|_| self.cases.len()))
+    }
+
+    fn rebuild(&mut self, case: Option<usize>) {
+        trace!("Showing case {:?}", case);
+        self.current = case.map(|index| {
+            let widget = match self.cases.get(index) {
+                Some(case) => (case.build)(),
+                None => (self.default.as_ref().unwrap())(),
+            };
+            (index, WidgetPod::new(widget))
+        });
+    }
+
+    fn current_case(&self) -> Option<usize> {
+        self.current.as_ref().map(|(index, _)| *index)
+    }
+}
+
+impl<T: Data> Default for Matcher<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Matcher<T> {
+    #[instrument(name = "Matcher", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some((_, child)) = &mut self.current {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Matcher", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild(self.case_for(data));
+        }
+        if let Some((_, child)) = &mut self.current {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Matcher",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let case = self.case_for(data);
+        if case != self.current_case() {
+            self.rebuild(case);
+            ctx.children_changed();
+        } else if let Some((_, child)) = &mut self.current {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Matcher", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        match &mut self.current {
+            Some((_, child)) => {
+                let size = child.layout(ctx, bc, data, env);
+                child.set_origin(ctx, Point::ORIGIN);
+                ctx.set_paint_insets(child.paint_insets());
+                size
+            }
+            None => bc.min(),
+        }
+    }
+
+    #[instrument(name = "Matcher", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if let Some((_, child)) = &mut self.current {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", self.current_case()),
+            children: self
+                .current
+                .iter()
+                .map(|(_, child)| child.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+// Unlike `LensWrap`, the data may not always be there; while the `Matcher`
+// has not caught up with a change of variant, the child is simply skipped.
+impl<T, U, P, W> Widget<T> for PrismWrap<U, P, W>
+where
+    T: Data,
+    U: Data,
+    P: Prism<T, U>,
+    W: Widget<U>,
+{
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let child = &mut self.child;
+        self.prism
+            .with_mut(data, |data| child.event(ctx, event, data, env));
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let child = &mut self.child;
+        self.prism
+            .with(data, |data| child.lifecycle(ctx, event, data, env));
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let child = &mut self.child;
+        let prism = &self.prism;
+        prism.with(data, |data| {
+            match prism.with(old_data, |old_data| old_data.clone()) {
+                Some(old_data) => child.update(ctx, &old_data, data, env),
+                None => child.update(ctx, data, data, env),
+            }
+        });
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let child = &mut self.child;
+        self.prism
+            .with(data, |data| child.layout(ctx, bc, data, env))
+            .unwrap_or_else(|| bc.min())
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let child = &mut self.child;
+        self.prism.with(data, |data| child.paint(ctx, data, env));
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = self
+            .prism
+            .with(data, |data| self.child.debug_state(data))
+            .into_iter()
+            .collect();
+        DebugState {
+            display_name: "PrismWrap".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::Label;
+    use test_log::test;
+
+    #[derive(Clone, Data, Prism)]
+    enum State {
+        Loading,
+        Ready(String),
+    }
+
+    fn matcher() -> Matcher<State> {
+        Matcher::new()
+            .with_case(State::ready, || {
+                Label::dynamic(|text: &String, _| text.clone())
+            })
+            .with_case(State::loading, || Label::new("Loading"))
+    }
+
+    #[test]
+    fn shows_the_matching_case() {
+        let data = State::Ready("hello".to_string());
+        Harness::create_simple(data, matcher(), |harness| {
+            harness.send_initial_events();
+            let state = harness.get_root_debug_state();
+            assert_eq!(state.main_value, "Some(0)");
+            assert_eq!(state.children[0].children[0].main_value, "hello");
+        });
+
+        Harness::create_simple(State::Loading, matcher(), |harness| {
+            harness.send_initial_events();
+            let state = harness.get_root_debug_state();
+            assert_eq!(state.main_value, "Some(1)");
+            assert_eq!(state.children[0].children[0].main_value, "Loading");
+        });
+    }
+
+    #[test]
+    fn nothing_matches_without_default() {
+        let widget = Matcher::new().with_case(State::ready, || Label::new("Ready"));
+        Harness::create_simple(State::Loading, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let state = harness.get_root_debug_state();
+            assert_eq!(state.main_value, "None");
+            assert!(state.children.is_empty());
+        });
+    }
+}
@@ -10,17 +10,26 @@ mod widget_wrapper;
 mod added;
 mod align;
 mod aspect_ratio_box;
+mod autocomplete;
 mod button;
 mod checkbox;
 mod click;
 mod clip_box;
+mod combo_box;
 mod common;
 mod container;
 mod controller;
+mod debug_tag;
 mod disable_if;
+mod drag_drop;
+mod dropdown;
 mod either;
 mod env_scope;
 mod flex;
+mod focus;
+mod focus_scope;
+mod gestures;
+mod grid;
 mod identity_wrapper;
 mod image;
 mod intrinsic_width;
@@ -28,10 +37,12 @@ mod invalidation;
 mod label;
 mod lens_wrap;
 mod list;
+mod matcher;
 mod maybe;
 mod padding;
 mod painter;
 mod parse;
+mod popup_list;
 mod progress_bar;
 mod radio;
 mod scope;
@@ -41,39 +52,56 @@ mod slider;
 mod spinner;
 mod split;
 mod stepper;
+mod suggestion_list;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 mod svg;
 mod switch;
+mod table;
 mod tabs;
 mod textbox;
+mod tooltip;
+mod transition;
+mod tree;
+mod undo;
 mod value_textbox;
 mod view_switcher;
+mod virtual_list;
 #[allow(clippy::module_inception)]
 mod widget;
 mod widget_ext;
+mod wrap;
 mod z_stack;
 
 pub use self::image::Image;
 pub use added::Added;
 pub use align::Align;
 pub use aspect_ratio_box::AspectRatioBox;
+pub use autocomplete::Autocomplete;
 pub use button::Button;
 pub use checkbox::Checkbox;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
+pub use combo_box::ComboBox;
 pub use common::FillStrat;
 pub use container::Container;
 pub use controller::{Controller, ControllerHost};
+pub use debug_tag::DebugTag;
 pub use disable_if::DisabledIf;
+pub use drag_drop::{DragSource, DropTarget};
+pub use dropdown::Dropdown;
 pub use either::Either;
 pub use env_scope::EnvScope;
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_scope::{FocusScope, TabIndex};
+pub use gestures::Gestures;
+pub use grid::{Grid, GridParams, GridTrack};
 pub use identity_wrapper::IdentityWrapper;
 pub use intrinsic_width::IntrinsicWidth;
 pub use label::{Label, LabelText, LineBreaking, RawLabel};
 pub use lens_wrap::LensWrap;
 pub use list::{List, ListIter};
+pub use matcher::Matcher;
 pub use maybe::Maybe;
 pub use padding::Padding;
 pub use painter::{BackgroundBrush, Painter};
@@ -88,16 +116,24 @@ pub use slider::{KnobStyle, RangeSlider, Slider};
 pub use spinner::Spinner;
 pub use split::Split;
 pub use stepper::Stepper;
+pub use suggestion_list::SuggestionRequest;
 #[cfg(feature = "svg")]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
+pub use table::{ColumnWidth, SortDirection, Table, TableColumn, TableState};
 pub use tabs::{AddTab, TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
 pub use textbox::TextBox;
+pub use tooltip::Tooltip;
+pub use transition::{SlideDirection, Transition};
+pub use tree::{Tree, TreeNode, TreePath, TreeState};
+pub use undo::UndoManager;
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
+pub use virtual_list::{RowSize, VirtualList};
 pub use widget::{Widget, WidgetId};
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
+pub use wrap::Wrap;
 pub use z_stack::ZStack;
 
 /// The types required to implement a [`Widget`].
@@ -0,0 +1,497 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The list of choices shown in the popup of a [`Dropdown`], [`ComboBox`]
+//! or [`Autocomplete`].
+//!
+//! The popup is shown in a sub-window that does not take keyboard focus, so
+//! the widget that opened it handles the keyboard and shares the highlighted
+//! item with the popup through a shared [`ListState`].
+//!
+//! [`Dropdown`]: super::Dropdown
+//! [`ComboBox`]: super::ComboBox
+//! [`Autocomplete`]: super::Autocomplete
+
+use std::cell::{RefCell, RefMut};
+use std::rc::Rc;
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::{CLOSE_WINDOW, CONFIGURE_WINDOW};
+use crate::debug_state::DebugState;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::{
+    theme, ArcStr, Command, ExtEventSink, Point, Rect, Selector, Target, WidgetId, WidgetPod,
+    WindowConfig, WindowId, WindowLevel, WindowSizePolicy,
+};
+
+/// Sent to the owner of the list when an item is clicked, with the item's index.
+pub(crate) const ITEM_CHOSEN: Selector<usize> =
+    Selector::new("druid-builtin.popup-list-item-chosen");
+
+/// Sent to the popup window when the owner has changed the [`ListState`].
+pub(crate) const LIST_CHANGED: Selector = Selector::new("druid-builtin.popup-list-changed");
+
+/// The most rows shown at once; longer lists scroll.
+const MAX_VISIBLE_ROWS: usize = 10;
+
+/// One entry in the list.
+pub(crate) struct PopupItem {
+    pub text: ArcStr,
+    pub disabled: bool,
+}
+
+/// The items of a popup list, and which one is highlighted.
+#[derive(Default)]
+pub(crate) struct ListState {
+    pub items: Vec<PopupItem>,
+    pub highlighted: Option<usize>,
+}
+
+type SharedListState = Rc<RefCell<ListState>>;
+
+impl ListState {
+    fn is_enabled(&self, index: usize) -> bool {
+        self.items.get(index).is_some_and(|item| !item.disabled)
+    }
+
+    /// Highlight the next enabled item after the current one, or before it
+    /// if `forward` is `false`, stopping at either end of the list.
+    pub fn move_highlight(&mut self, forward: bool) {
+        let next = match (self.highlighted, forward) {
+            (None, true) => (0..self.items.len()).find(|&i| self.is_enabled(i)),
+            (None, false) => (0..self.items.len()).rev().find(|&i| self.is_enabled(i)),
+            (Some(current), true) => (current + 1..self.items.len()).find(|&i| self.is_enabled(i)),
+            (Some(current), false) => (0..current).rev().find(|&i| self.is_enabled(i)),
+        };
+        if next.is_some() {
+            self.highlighted = next;
+        }
+    }
+
+    /// Highlight the first enabled item, or the last if `last` is `true`.
+    pub fn highlight_edge(&mut self, last: bool) {
+        self.highlighted = None;
+        self.move_highlight(!last);
+    }
+
+    /// Find the first enabled item whose text starts with `prefix`, ignoring case.
+    ///
+    /// The search starts at the highlighted item, or just after it if
+    /// `prefix` is a single character, so that typing the same letter
+    /// repeatedly cycles through the items starting with it.
+    pub fn find_prefix(&self, prefix: &str) -> Option<usize> {
+        let len = self.items.len();
+        if len == 0 || prefix.is_empty() {
+            return None;
+        }
+        let prefix = prefix.to_lowercase();
+        let start = match self.highlighted {
+            Some(current) if prefix.chars().count() == 1 => current + 1,
+            Some(current) => current,
+            None => 0,
+        };
+        (start..start + len)
+            .map(|i| i % len)
+            .find(|&i| self.is_enabled(i) && self.items[i].text.to_lowercase().starts_with(&prefix))
+    }
+}
+
+/// An open popup window showing a [`PopupList`].
+///
+/// A popup that is dropped without being closed, because its owner was
+/// removed from the tree, closes its window.
+pub(crate) struct Popup {
+    window_id: WindowId,
+    state: SharedListState,
+    /// Used to close the window on drop; `None` once it has been closed.
+    sink: Option<ExtEventSink>,
+}
+
+impl Popup {
+    /// Open a popup showing `list`, with its top left corner at `position`
+    /// in the coordinates of the widget handling the event.
+    ///
+    /// That widget is notified with [`ITEM_CHOSEN`] when an item is clicked.
+    /// The popup is at least `min_width` wide.
+    ///
+    /// The popup window shares its data with the nearest [`WidgetPod`], so
+    /// that pod must have data of type `T`.
+    ///
+    /// [`WidgetPod`]: crate::WidgetPod
+    pub fn open<T: Data>(
+        ctx: &mut EventCtx,
+        list: ListState,
+        position: Point,
+        min_width: f64,
+        data: &T,
+        env: &Env,
+    ) -> Self {
+        let state = Rc::new(RefCell::new(list));
+        let widget = PopupList::new(state.clone(), ctx.widget_id(), min_width);
+        let config = WindowConfig::default()
+            .show_titlebar(false)
+            .resizable(false)
+            .window_size_policy(WindowSizePolicy::Content)
+            .set_level(WindowLevel::DropDown(ctx.window().clone()))
+            .set_position(ctx.to_screen(position));
+        let window_id = ctx.new_sub_window(config, widget, data.clone(), env.clone());
+        trace!("Opened popup list in {:?}", window_id);
+        Popup {
+            window_id,
+            state,
+            sink: Some(ctx.get_external_handle()),
+        }
+    }
+
+    /// The list shown in the popup.
+    ///
+    /// After changing it, submit [`Popup::changed`] so that the popup is repainted.
+    pub fn list(&self) -> RefMut<'_, ListState> {
+        self.state.borrow_mut()
+    }
+
+    /// The command that tells the popup its list has changed.
+    pub fn changed(&self) -> Command {
+        LIST_CHANGED.to(self.window_id)
+    }
+
+    /// The command that moves the popup to `position`, in screen coordinates.
+    pub fn move_to(&self, position: Point) -> Command {
+        CONFIGURE_WINDOW
+            .with(WindowConfig::default().set_position(position))
+            .to(self.window_id)
+    }
+
+    /// The command that closes the popup.
+    pub fn close(mut self) -> Command {
+        trace!("Closing popup list in {:?}", self.window_id);
+        self.sink = None;
+        CLOSE_WINDOW.to(self.window_id)
+    }
+}
+
+impl Drop for Popup {
+    fn drop(&mut self) {
+        // The owner is being removed from the tree, and can't close us itself.
+        if let Some(sink) = self.sink.take() {
+            trace!("Closing popup list in {:?} on drop", self.window_id);
+            if sink
+                .submit_command(CLOSE_WINDOW, (), Target::Window(self.window_id))
+                .is_err()
+            {
+                warn!("failed to close popup list in {:?}", self.window_id);
+            }
+        }
+    }
+}
+
+/// A widget that opens a [`Popup`], in a [`WidgetPod`] of its own.
+///
+/// The popup window shares its data with the nearest `WidgetPod`, so the
+/// widgets that open popups keep one of their own in case they are used
+/// behind a lens. When the host is removed from the tree, the [`Popup`] of
+/// its widget is dropped along with it, which closes the popup window.
+pub(crate) struct PopupHost<T, W> {
+    inner: WidgetPod<T, W>,
+}
+
+impl<T, W: Widget<T>> PopupHost<T, W> {
+    pub fn new(widget: W) -> Self {
+        PopupHost {
+            inner: WidgetPod::new(widget),
+        }
+    }
+
+    /// The widget that opens the popup.
+    pub fn widget_mut(&mut self) -> &mut W {
+        self.inner.widget_mut()
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for PopupHost<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.inner.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.widget().debug_state(data)
+    }
+}
+
+/// The widget shown in the popup window.
+struct PopupList {
+    state: SharedListState,
+    owner: WidgetId,
+    min_width: f64,
+    layouts: Vec<TextLayout<ArcStr>>,
+    row_height: f64,
+    first_row: usize,
+    pressed: Option<usize>,
+}
+
+impl PopupList {
+    /// Create a list showing `state`, that notifies `owner` when an item is clicked.
+    ///
+    /// The list is at least `min_width` wide, so that it can line up with its owner.
+    fn new(state: SharedListState, owner: WidgetId, min_width: f64) -> Self {
+        PopupList {
+            state,
+            owner,
+            min_width,
+            layouts: Vec::new(),
+            row_height: 0.0,
+            first_row: 0,
+            pressed: None,
+        }
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.layouts.len().min(MAX_VISIBLE_ROWS)
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        if self.row_height <= 0.0 || pos.y < 0.0 {
+            return None;
+        }
+        let row = self.first_row + (pos.y / self.row_height) as usize;
+        (row < self.layouts.len()).then_some(row)
+    }
+
+    /// Scroll so that the highlighted row is visible.
+    fn scroll_to_highlight(&mut self) {
+        let visible = self.visible_rows();
+        if let Some(row) = self.state.borrow().highlighted {
+            if row < self.first_row {
+                self.first_row = row;
+            } else if visible > 0 && row >= self.first_row + visible {
+                self.first_row = row + 1 - visible;
+            }
+        }
+        self.first_row = self.first_row.min(self.layouts.len() - visible);
+    }
+
+    fn rebuild_layouts(&mut self) {
+        let state = self.state.borrow();
+        self.layouts = state
+            .items
+            .iter()
+            .map(|item| {
+                let mut layout = TextLayout::from_text(item.text.clone());
+                if item.disabled {
+                    layout.set_text_color(theme::DISABLED_TEXT_COLOR);
+                }
+                layout
+            })
+            .collect();
+    }
+}
+
+impl<T: Data> Widget<T> for PopupList {
+    #[instrument(
+        name = "PopupList",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(LIST_CHANGED) => {
+                let item_count = self.state.borrow().items.len();
+                if item_count != self.layouts.len() {
+                    self.first_row = 0;
+                }
+                self.rebuild_layouts();
+                ctx.request_layout();
+            }
+            Event::MouseMove(mouse) => {
+                let mut state = self.state.borrow_mut();
+                let row = self.row_at(mouse.pos).filter(|&row| state.is_enabled(row));
+                if row.is_some() && row != state.highlighted {
+                    state.highlighted = row;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) => {
+                self.pressed = self.row_at(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                let pressed = self.pressed.take();
+                if let Some(row) = self.row_at(mouse.pos) {
+                    if pressed == Some(row) && self.state.borrow().is_enabled(row) {
+                        trace!("Popup list item {} chosen", row);
+                        ctx.submit_command(ITEM_CHOSEN.with(row).to(self.owner));
+                    }
+                }
+            }
+            Event::Wheel(wheel) => {
+                let max_first = self.layouts.len() - self.visible_rows();
+                if wheel.wheel_delta.y > 0.0 {
+                    self.first_row = (self.first_row + 1).min(max_first);
+                } else if wheel.wheel_delta.y < 0.0 {
+                    self.first_row = self.first_row.saturating_sub(1);
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "PopupList",
+        level = "trace",
+        skip(self, _ctx, event, _data, _env)
+    )]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild_layouts();
+        }
+    }
+
+    #[instrument(
+        name = "PopupList",
+        level = "trace",
+        skip(self, _ctx, _old_data, _data, _env)
+    )]
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+
+    #[instrument(name = "PopupList", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        let padding = env.get(theme::WIDGET_PADDING_VERTICAL) / 2.;
+        let mut width = self.min_width;
+        let mut text_height: f64 = 0.0;
+        for layout in &mut self.layouts {
+            layout.rebuild_if_needed(ctx.text(), env);
+            let size = layout.size();
+            width = width.max(size.width + env.get(theme::WIDGET_PADDING_HORIZONTAL) * 2.);
+            text_height = text_height.max(size.height);
+        }
+        self.row_height = text_height + padding * 2.;
+        self.scroll_to_highlight();
+        let size = bc.constrain(Size::new(
+            width,
+            self.row_height * self.visible_rows() as f64,
+        ));
+        trace!("Computed popup list size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PopupList", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_LIGHT));
+
+        let highlighted = self.state.borrow().highlighted;
+        let padding = Point::new(
+            env.get(theme::WIDGET_PADDING_HORIZONTAL),
+            env.get(theme::WIDGET_PADDING_VERTICAL) / 2.,
+        );
+        let visible = self.first_row..self.first_row + self.visible_rows();
+        for (y, row) in visible.enumerate() {
+            let top = y as f64 * self.row_height;
+            if highlighted == Some(row) {
+                let rect = Rect::new(0., top, size.width, top + self.row_height);
+                ctx.fill(rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+            }
+            self.layouts[row].draw(ctx, (padding.x, top + padding.y));
+        }
+
+        let border = size.to_rect().inset(-0.5);
+        ctx.stroke(border, &env.get(theme::BORDER_DARK), 1.0);
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        let state = self.state.borrow();
+        DebugState {
+            display_name: "PopupList".to_string(),
+            main_value: format!("{:?}", state.highlighted),
+            children: state
+                .items
+                .iter()
+                .map(|item| DebugState {
+                    display_name: "PopupItem".to_string(),
+                    main_value: item.text.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn state(items: &[(&str, bool)]) -> ListState {
+        ListState {
+            items: items
+                .iter()
+                .map(|(text, disabled)| PopupItem {
+                    text: (*text).into(),
+                    disabled: *disabled,
+                })
+                .collect(),
+            highlighted: None,
+        }
+    }
+
+    #[test]
+    fn highlight_skips_disabled_items() {
+        let mut list = state(&[("a", true), ("b", false), ("c", true), ("d", false)]);
+        list.move_highlight(true);
+        assert_eq!(list.highlighted, Some(1));
+        list.move_highlight(true);
+        assert_eq!(list.highlighted, Some(3));
+        list.move_highlight(true);
+        assert_eq!(list.highlighted, Some(3));
+        list.move_highlight(false);
+        assert_eq!(list.highlighted, Some(1));
+        list.move_highlight(false);
+        assert_eq!(list.highlighted, Some(1));
+        list.highlight_edge(true);
+        assert_eq!(list.highlighted, Some(3));
+    }
+
+    #[test]
+    fn prefix_search() {
+        let mut list = state(&[
+            ("Apple", false),
+            ("Banana", false),
+            ("blueberry", true),
+            ("Blackberry", false),
+        ]);
+        assert_eq!(list.find_prefix("b"), Some(1));
+        list.highlighted = Some(1);
+        // A single letter moves on to the next match, skipping disabled items.
+        assert_eq!(list.find_prefix("b"), Some(3));
+        list.highlighted = Some(3);
+        assert_eq!(list.find_prefix("b"), Some(1));
+        // A longer prefix may keep the current item.
+        assert_eq!(list.find_prefix("BLA"), Some(3));
+        assert_eq!(list.find_prefix("c"), None);
+    }
+}
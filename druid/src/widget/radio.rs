@@ -6,7 +6,9 @@
 use crate::debug_state::DebugState;
 use crate::kurbo::Circle;
 use crate::widget::prelude::*;
-use crate::widget::{Axis, CrossAxisAlignment, Flex, Label, LabelText};
+use crate::widget::{
+    focus, Axis, Controller, CrossAxisAlignment, Flex, Label, LabelText, WidgetExt,
+};
 use crate::{theme, Data, LinearGradient, UnitPoint};
 use tracing::{instrument, trace};
 
@@ -35,25 +37,65 @@ impl RadioGroup {
 
     /// Given a vector of `(label_text, enum_variant)` tuples, create a group of Radio buttons
     /// along the specified axis.
+    ///
+    /// When one of the buttons is focused, the arrow keys select the next or
+    /// previous variant and move focus along with the selection.
     pub fn for_axis<T: Data + PartialEq>(
         axis: Axis,
         variants: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>,
     ) -> impl Widget<T> {
         let mut col = Flex::for_axis(axis).cross_axis_alignment(CrossAxisAlignment::Start);
+        let mut keys = RadioGroupKeys {
+            variants: Vec::new(),
+            ids: Vec::new(),
+        };
         let mut is_first = true;
         for (label, variant) in variants.into_iter() {
             if !is_first {
                 col.add_default_spacer();
             }
+            let id = WidgetId::next();
+            keys.variants.push(variant.clone());
+            keys.ids.push(id);
             let radio = Radio::new(label, variant);
-            col.add_child(radio);
+            col.add_child(radio.with_id(id));
             is_first = false;
         }
-        col
+        col.controller(keys)
+    }
+}
+
+/// Moves the selection of a [`RadioGroup`] with the arrow keys.
+struct RadioGroupKeys<T> {
+    variants: Vec<T>,
+    ids: Vec<WidgetId>,
+}
+
+impl<T: Data + PartialEq, W: Widget<T>> Controller<T, W> for RadioGroupKeys<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            if let Some(forward) = focus::arrow_direction(key, env) {
+                if !ctx.is_disabled() && !self.variants.is_empty() {
+                    let len = self.variants.len();
+                    let next = match self.variants.iter().position(|v| v == data) {
+                        Some(idx) if forward => (idx + 1) % len,
+                        Some(idx) => (idx + len - 1) % len,
+                        None => 0,
+                    };
+                    *data = self.variants[next].clone();
+                    ctx.set_focus(self.ids[next]);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+        }
+        child.event(ctx, event, data, env);
     }
 }
 
 /// A single radio button
+///
+/// When focused, it is selected with Space or Enter.
 pub struct Radio<T> {
     variant: T,
     child_label: Label<T>,
@@ -90,6 +132,15 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key) if !ctx.is_disabled() => {
+                if focus::is_activation_key(key) && ctx.is_focused() {
+                    *data = self.variant.clone();
+                    ctx.request_paint();
+                    ctx.set_handled();
+                } else {
+                    focus::handle_tab(ctx, key);
+                }
+            }
             _ => (),
         }
     }
@@ -97,8 +148,12 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
     #[instrument(name = "Radio", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         self.child_label.lifecycle(ctx, event, data, env);
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -153,6 +208,7 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
         };
 
         ctx.stroke(circle, &border_color, 1.);
+        focus::paint_focus_ring(ctx, circle, env);
 
         // Check if data enum matches our variant
         if *data == self.variant {
@@ -21,6 +21,10 @@ use tracing::{instrument, trace};
 /// When restricted to scrolling on a specific axis the child's size is
 /// locked on the opposite axis.
 ///
+/// Under a right-to-left [`LayoutDirection`] the vertical scrollbar is drawn
+/// along the left edge.
+///
+/// [`LayoutDirection`]: crate::LayoutDirection
 /// [`vertical`]: struct.Scroll.html#method.vertical
 /// [`horizontal`]: struct.Scroll.html#method.horizontal
 pub struct Scroll<T, W> {
@@ -211,6 +215,9 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         self.clip.update(ctx, old_data, data, env);
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_paint();
+        }
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, bc, data, env))]
@@ -7,8 +7,11 @@ use crate::debug_state::DebugState;
 use crate::kurbo::{Circle, Line};
 use crate::theme::TEXT_COLOR;
 use crate::widget::prelude::*;
-use crate::widget::Axis;
-use crate::{theme, Color, KeyOrValue, LinearGradient, Point, Rect, UnitPoint, Vec2, WidgetPod};
+use crate::widget::{focus, Axis};
+use crate::{
+    theme, Color, KbKey, KeyEvent, KeyOrValue, LinearGradient, Point, Rect, UnitPoint, Vec2,
+    WidgetPod,
+};
 use druid::kurbo::{PathEl, Shape};
 use druid::piet::{PietText, PietTextLayout, Text, TextLayout, TextLayoutBuilder};
 use tracing::{instrument, trace, warn};
@@ -21,6 +24,15 @@ const KNOB_STROKE_WIDTH: f64 = 2.0;
 ///
 /// This slider implements `Widget<f64>`, and works on values clamped
 /// in the range `min..max`.
+///
+/// In a right-to-left [`LayoutDirection`], a horizontal slider has its
+/// lowest value on the right.
+///
+/// When focused, the arrow keys move the value by one step, or by a hundredth
+/// of the range if the slider has no stepping. Home and End move it to the
+/// minimum and maximum.
+///
+/// [`LayoutDirection`]: crate::LayoutDirection
 #[derive(Debug, Clone, Default)]
 pub struct Slider {
     mapping: SliderValueMapping,
@@ -33,6 +45,9 @@ pub struct Slider {
 ///
 /// This slider implements `Widget<(f64, f64)>`, and works on value pairs clamped
 /// in the range `min..max`, where the left value is always smaller than the right.
+///
+/// Like [`Slider`], a horizontal range slider is mirrored in a right-to-left
+/// layout.
 #[derive(Debug, Clone, Default)]
 pub struct RangeSlider {
     mapping: SliderValueMapping,
@@ -59,6 +74,8 @@ pub struct SliderValueMapping {
     max: f64,
     step: Option<f64>,
     axis: Axis,
+    /// The slider is horizontal, and its values increase to the left.
+    mirrored: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -151,6 +168,30 @@ impl Slider {
         let mapping = self.mapping;
         Annotated::new(self, mapping, named_steps, unnamed_steps)
     }
+
+    fn handle_key(&mut self, ctx: &mut EventCtx, key: &KeyEvent, data: &mut f64) {
+        if !ctx.is_focused() {
+            return;
+        }
+        let step = self.mapping.key_step();
+        let value = match &key.key {
+            KbKey::ArrowUp => *data + step,
+            KbKey::ArrowDown => *data - step,
+            KbKey::ArrowRight if self.mapping.mirrored => *data - step,
+            KbKey::ArrowRight => *data + step,
+            KbKey::ArrowLeft if self.mapping.mirrored => *data + step,
+            KbKey::ArrowLeft => *data - step,
+            KbKey::Home => self.mapping.min,
+            KbKey::End => self.mapping.max,
+            _ => {
+                focus::handle_tab(ctx, key);
+                return;
+            }
+        };
+        *data = value.clamp(self.mapping.min, self.mapping.max);
+        ctx.request_paint();
+        ctx.set_handled();
+    }
 }
 
 impl Widget<f64> for Slider {
@@ -173,6 +214,10 @@ impl Widget<f64> for Slider {
                     ctx.set_active(true);
                 }
             }
+
+            if let Event::KeyDown(key) = event {
+                self.handle_key(ctx, key, data);
+            }
         }
     }
 
@@ -181,7 +226,8 @@ impl Widget<f64> for Slider {
         match event {
             // checked in LifeCycle::WidgetAdded because logging may not be setup in with_range
             LifeCycle::WidgetAdded => self.mapping.check_range(),
-            LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
             _ => (),
         }
     }
@@ -192,12 +238,16 @@ impl Widget<f64> for Slider {
         skip(self, ctx, _old_data, _data, _env)
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, _data: &f64, _env: &Env) {
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
         ctx.request_paint();
     }
 
     #[instrument(name = "Slider", level = "trace", skip(self, ctx, bc, _data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &f64, env: &Env) -> Size {
         bc.debug_check("Slider");
+        self.mapping.update_direction(env);
         slider_layout(ctx, bc, env, self.mapping)
     }
 
@@ -207,6 +257,8 @@ impl Widget<f64> for Slider {
 
         self.knob
             .paint(ctx, *data, env, self.mapping, self.knob_style);
+        self.knob
+            .paint_focus_ring(ctx, *data, env, self.mapping, self.knob_style);
     }
 
     fn debug_state(&self, data: &f64) -> DebugState {
@@ -383,6 +435,9 @@ impl Widget<(f64, f64)> for RangeSlider {
         _data: &(f64, f64),
         _env: &Env,
     ) {
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
         ctx.request_paint();
     }
 
@@ -395,6 +450,7 @@ impl Widget<(f64, f64)> for RangeSlider {
         env: &Env,
     ) -> Size {
         bc.debug_check("Slider");
+        self.mapping.update_direction(env);
         slider_layout(ctx, bc, env, self.mapping)
     }
 
@@ -502,11 +558,15 @@ impl<T: Data, W: Widget<T>> Widget<T> for Annotated<T, W> {
             self.build_labels(ctx.text(), env.get(TEXT_COLOR));
             ctx.request_paint();
         }
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "Annotated", level = "trace", skip(self, bc, ctx, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         let label_size = Size::new(40.0, 20.0);
+        self.mapping.update_direction(env);
 
         match self.mapping.axis {
             Axis::Vertical => {
@@ -607,9 +667,19 @@ impl SliderValueMapping {
             max: 1.0,
             step: None,
             axis: Axis::Horizontal,
+            mirrored: false,
         }
     }
 
+    /// Mirror horizontal sliders in right-to-left layouts.
+    fn update_direction(&mut self, env: &Env) {
+        self.mirrored = self.axis == Axis::Horizontal
+            && env
+                .try_get(Env::LAYOUT_DIRECTION)
+                .unwrap_or_default()
+                .is_rtl();
+    }
+
     fn calculate_value(
         &self,
         mouse_pos: Point,
@@ -617,8 +687,12 @@ impl SliderValueMapping {
         slider_size: Size,
         offset: f64,
     ) -> f64 {
-        // The vertical slider has its lowest value at the bottom.
-        let mouse_pos = Point::new(mouse_pos.x, slider_size.height - mouse_pos.y);
+        // The vertical slider has its lowest value at the bottom, and a mirrored
+        // slider at the right.
+        let mut mouse_pos = Point::new(mouse_pos.x, slider_size.height - mouse_pos.y);
+        if self.mirrored {
+            mouse_pos.x = slider_size.width - mouse_pos.x;
+        }
 
         let scalar = (self.axis.major_pos(mouse_pos) - knob_size / 2.)
             / (self.axis.major(slider_size) - knob_size);
@@ -648,7 +722,10 @@ impl SliderValueMapping {
     fn get_point(&self, value: f64, knob_size: f64, widget_size: Size) -> Point {
         let knob_major =
             (self.axis.major(widget_size) - knob_size) * self.normalize(value) + knob_size / 2.;
-        let (w, h) = self.axis.pack(knob_major, knob_size / 2.);
+        let (mut w, h) = self.axis.pack(knob_major, knob_size / 2.);
+        if self.mirrored {
+            w = widget_size.width - w;
+        }
         Point::new(w, widget_size.height - h)
     }
 
@@ -671,6 +748,11 @@ impl SliderValueMapping {
     fn range(&self) -> f64 {
         self.max - self.min
     }
+
+    /// The amount the arrow keys change the value by.
+    fn key_step(&self) -> f64 {
+        self.step.unwrap_or(self.range() / 100.)
+    }
 }
 
 impl Default for SliderValueMapping {
@@ -680,6 +762,7 @@ impl Default for SliderValueMapping {
             max: 1.0,
             step: None,
             axis: Axis::Horizontal,
+            mirrored: false,
         }
     }
 }
@@ -832,6 +915,27 @@ impl SliderKnob {
             }
         }
     }
+
+    fn paint_focus_ring(
+        &self,
+        ctx: &mut PaintCtx,
+        value: f64,
+        env: &Env,
+        settings: SliderValueMapping,
+        knob_style: KnobStyle,
+    ) {
+        let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let center = settings.get_point(value, knob_size, ctx.size());
+        match knob_style {
+            KnobStyle::Circle => {
+                focus::paint_focus_ring(ctx, Circle::new(center, knob_size / 2.), env);
+            }
+            KnobStyle::Wedge => {
+                let knob_wedge = knob_wedge(center, knob_size, settings.axis);
+                focus::paint_focus_ring(ctx, &knob_wedge[..], env);
+            }
+        }
+    }
 }
 
 fn knob_wedge(center: Point, knob_size: f64, axis: Axis) -> [PathEl; 6] {
@@ -29,6 +29,10 @@ pub struct Split<T> {
     /// bar was clicked. This is used to ensure a click without mouse move is a no-op,
     /// instead of re-centering the bar on the mouse.
     click_offset: f64,
+    /// The children of a horizontal split are swapped, because the layout
+    /// direction is right-to-left. Positions along the split axis are then
+    /// measured from the right edge.
+    mirrored: bool,
     child1: WidgetPod<T, Box<dyn Widget<T>>>,
     old_bc_1: BoxConstraints,
     child2: WidgetPod<T, Box<dyn Widget<T>>>,
@@ -56,6 +60,7 @@ impl<T> Split<T> {
             draggable: false,
             is_bar_hover: false,
             click_offset: 0.0,
+            mirrored: false,
             child1: WidgetPod::new(child1).boxed(),
             old_bc_1: BoxConstraints::tight(Size::ZERO),
             child2: WidgetPod::new(child2).boxed(),
@@ -64,6 +69,10 @@ impl<T> Split<T> {
     }
 
     /// Create a new split panel, with the horizontal axis split in two by a vertical bar.
+    ///
+    /// In a right-to-left [`LayoutDirection`], the first child is on the right.
+    ///
+    /// [`LayoutDirection`]: crate::LayoutDirection
     pub fn columns(
         left_child: impl Widget<T> + 'static,
         right_child: impl Widget<T> + 'static,
@@ -195,8 +204,29 @@ impl<T> Split<T> {
         }
     }
 
+    /// Returns the edges of the splitter bar area as they are painted, which
+    /// are swapped around if the split is mirrored.
+    fn painted_bar_edges(&self, size: Size) -> (f64, f64) {
+        let (edge1, edge2) = self.bar_edges(size);
+        if self.mirrored {
+            (size.width - edge2, size.width - edge1)
+        } else {
+            (edge1, edge2)
+        }
+    }
+
+    /// Converts a mouse position to one measured from the leading edge.
+    fn logical_pos(&self, size: Size, mouse_pos: Point) -> Point {
+        if self.mirrored {
+            Point::new(size.width - mouse_pos.x, mouse_pos.y)
+        } else {
+            mouse_pos
+        }
+    }
+
     /// Returns true if the provided mouse position is inside the splitter bar area.
     fn bar_hit_test(&self, size: Size, mouse_pos: Point) -> bool {
+        let mouse_pos = self.logical_pos(size, mouse_pos);
         let (edge1, edge2) = self.bar_edges(size);
         match self.split_axis {
             Axis::Horizontal => mouse_pos.x >= edge1 && mouse_pos.x <= edge2,
@@ -239,7 +269,7 @@ impl<T> Split<T> {
 
     fn paint_solid_bar(&mut self, ctx: &mut PaintCtx, env: &Env) {
         let size = ctx.size();
-        let (edge1, edge2) = self.bar_edges(size);
+        let (edge1, edge2) = self.painted_bar_edges(size);
         let padding = self.bar_padding();
         let rect = match self.split_axis {
             Axis::Horizontal => Rect::from_points(
@@ -261,7 +291,7 @@ impl<T> Split<T> {
         // because we'll paint two equal lines at the edges.
         let line_width = (self.bar_size / 3.0).floor();
         let line_midpoint = line_width / 2.0;
-        let (edge1, edge2) = self.bar_edges(size);
+        let (edge1, edge2) = self.painted_bar_edges(size);
         let padding = self.bar_padding();
         let (line1, line2) = match self.split_axis {
             Axis::Horizontal => (
@@ -313,9 +343,10 @@ impl<T: Data> Widget<T> for Split<T> {
                         ctx.set_handled();
                         ctx.set_active(true);
                         // Save the delta between the mouse click position and the split point
+                        let mouse_pos = self.logical_pos(ctx.size(), mouse.pos);
                         self.click_offset = match self.split_axis {
-                            Axis::Horizontal => mouse.pos.x,
-                            Axis::Vertical => mouse.pos.y,
+                            Axis::Horizontal => mouse_pos.x,
+                            Axis::Vertical => mouse_pos.y,
                         } - self.bar_position(ctx.size());
                         // If not already hovering, force and change cursor appropriately
                         if !self.is_bar_hover {
@@ -343,12 +374,13 @@ impl<T: Data> Widget<T> for Split<T> {
                 Event::MouseMove(mouse) => {
                     if ctx.is_active() {
                         // If active, assume always hover/hot
+                        let mouse_pos = self.logical_pos(ctx.size(), mouse.pos);
                         let effective_pos = match self.split_axis {
                             Axis::Horizontal => {
-                                Point::new(mouse.pos.x - self.click_offset, mouse.pos.y)
+                                Point::new(mouse_pos.x - self.click_offset, mouse_pos.y)
                             }
                             Axis::Vertical => {
-                                Point::new(mouse.pos.x, mouse.pos.y - self.click_offset)
+                                Point::new(mouse_pos.x, mouse_pos.y - self.click_offset)
                             }
                         };
                         self.update_split_point(ctx.size(), effective_pos);
@@ -390,6 +422,9 @@ impl<T: Data> Widget<T> for Split<T> {
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
         self.child1.update(ctx, data, env);
         self.child2.update(ctx, data, env);
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "Split", level = "trace", skip(self, ctx, bc, data, env))]
@@ -409,6 +444,9 @@ impl<T: Data> Widget<T> for Split<T> {
             }
         }
 
+        let direction = env.try_get(Env::LAYOUT_DIRECTION).unwrap_or_default();
+        self.mirrored = self.split_axis == Axis::Horizontal && direction.is_rtl();
+
         let mut my_size = bc.max();
         let bar_area = self.bar_area();
         let reduced_size = Size::new(
@@ -478,8 +516,8 @@ impl<T: Data> Widget<T> for Split<T> {
 
         // Top-left align for both children, out of laziness.
         // Reduce our unsplit direction to the larger of the two widgets
-        let child1_pos = Point::ORIGIN;
-        let child2_pos = match self.split_axis {
+        let mut child1_pos = Point::ORIGIN;
+        let mut child2_pos = match self.split_axis {
             Axis::Horizontal => {
                 my_size.height = child1_size.height.max(child2_size.height);
                 Point::new(child1_size.width + bar_area, 0.0)
@@ -489,6 +527,10 @@ impl<T: Data> Widget<T> for Split<T> {
                 Point::new(0.0, child1_size.height + bar_area)
             }
         };
+        if self.mirrored {
+            child1_pos.x = direction.mirror_x(child1_pos.x, child1_size.width, my_size.width);
+            child2_pos.x = direction.mirror_x(child2_pos.x, child2_size.width, my_size.width);
+        }
         self.child1.set_origin(ctx, child1_pos);
         self.child2.set_origin(ctx, child2_pos);
 
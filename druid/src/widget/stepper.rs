@@ -9,8 +9,9 @@ use tracing::{instrument, trace};
 use crate::debug_state::DebugState;
 use crate::kurbo::BezPath;
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
+use crate::widget::focus;
 use crate::widget::prelude::*;
-use crate::{theme, Point, Rect, TimerToken};
+use crate::{theme, KbKey, Point, Rect, TimerToken};
 
 // Delay until stepper starts automatically changing value when one of the buttons is held down.
 const STEPPER_REPEAT_DELAY: Duration = Duration::from_millis(500);
@@ -18,6 +19,8 @@ const STEPPER_REPEAT_DELAY: Duration = Duration::from_millis(500);
 const STEPPER_REPEAT: Duration = Duration::from_millis(200);
 
 /// A stepper widget for step-wise increasing and decreasing a value.
+///
+/// When focused, the up and down arrow keys step the value.
 pub struct Stepper {
     max: f64,
     min: f64,
@@ -120,6 +123,7 @@ impl Widget<f64> for Stepper {
         let button_size = Size::new(width, height / 2.);
 
         ctx.stroke(rounded_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        focus::paint_focus_ring(ctx, rounded_rect, env);
         ctx.clip(rounded_rect);
 
         // draw buttons for increase/decrease
@@ -252,13 +256,28 @@ impl Widget<f64> for Stepper {
                     ctx.set_active(false);
                 }
             }
+            Event::KeyDown(key) if !ctx.is_disabled() => match key.key {
+                KbKey::ArrowUp if ctx.is_focused() => {
+                    self.increment(data);
+                    ctx.set_handled();
+                }
+                KbKey::ArrowDown if ctx.is_focused() => {
+                    self.decrement(data);
+                    ctx.set_handled();
+                }
+                _ => {
+                    focus::handle_tab(ctx, key);
+                }
+            },
             _ => (),
         }
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
-        if let LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -0,0 +1,413 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The suggestions shown by a [`ComboBox`] or an [`Autocomplete`].
+//!
+//! [`ComboBox`]: super::ComboBox
+//! [`Autocomplete`]: super::Autocomplete
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::contexts::RequestCtx;
+use crate::text::Selection;
+use crate::widget::popup_list::{ListState, Popup, PopupItem, ITEM_CHOSEN};
+use crate::widget::prelude::*;
+use crate::widget::{Controller, TextBox};
+use crate::{ExtEventError, ExtEventSink, KbKey, Point, Selector, SingleUse, Target, WidgetId};
+
+/// The suggestions for a query, with the generation of the query.
+const SUGGESTIONS: Selector<SingleUse<(u64, Vec<String>)>> =
+    Selector::new("druid-builtin.suggestion-list-suggestions");
+
+/// Where a [`SuggestionList`] gets its suggestions from.
+pub(crate) enum Source {
+    /// Suggestions that are computed right away.
+    Sync(Box<dyn Fn(&str) -> Vec<String>>),
+    /// Suggestions that are requested, and sent back later.
+    Async(Box<dyn Fn(SuggestionRequest)>),
+}
+
+/// A request for suggestions, made by an [`Autocomplete`] created with
+/// [`Autocomplete::new_async`].
+///
+/// The request can be sent to another thread, and answered with
+/// [`respond`](SuggestionRequest::respond) when the suggestions are ready.
+/// A request becomes stale as soon as the text changes again; the results
+/// of stale requests are ignored, so long-running lookups should check
+/// [`is_stale`](SuggestionRequest::is_stale) and give up early.
+///
+/// [`Autocomplete`]: super::Autocomplete
+/// [`Autocomplete::new_async`]: super::Autocomplete::new_async
+pub struct SuggestionRequest {
+    query: String,
+    generation: u64,
+    latest: Arc<AtomicU64>,
+    sink: ExtEventSink,
+    target: WidgetId,
+}
+
+/// A [`Controller`] that shows suggestions for the text of a [`TextBox`] in
+/// a popup list.
+///
+/// Whenever the text changes while the text box has focus, the suggestions
+/// are looked up and shown in the order they were returned. While the list
+/// is open, the arrow keys highlight a suggestion, <kbd>Enter</kbd> accepts
+/// the highlighted suggestion and <kbd>Escape</kbd> closes the list;
+/// <kbd>Down</kbd> opens it again.
+pub(crate) struct SuggestionList {
+    source: Source,
+    max_suggestions: usize,
+    at_cursor: bool,
+    accept_with_tab: bool,
+    skip_empty: bool,
+    suggestions: Vec<String>,
+    popup: Option<Popup>,
+    /// Incremented with every query, so that stale results can be recognized.
+    generation: Arc<AtomicU64>,
+}
+
+impl SuggestionRequest {
+    /// The text to suggest completions for.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns `true` if the text has changed since this request was made,
+    /// so that its results would be ignored.
+    pub fn is_stale(&self) -> bool {
+        self.latest.load(Ordering::Relaxed) != self.generation
+    }
+
+    /// Send the suggestions, best first, back to the [`Autocomplete`].
+    ///
+    /// Nothing is sent if the request is stale.
+    ///
+    /// [`Autocomplete`]: super::Autocomplete
+    pub fn respond(self, suggestions: Vec<String>) -> Result<(), ExtEventError> {
+        if self.is_stale() {
+            return Ok(());
+        }
+        self.sink.submit_command(
+            SUGGESTIONS,
+            SingleUse::new((self.generation, suggestions)),
+            Target::Widget(self.target),
+        )
+    }
+}
+
+impl SuggestionList {
+    /// Create a list that gets its suggestions from `source`.
+    ///
+    /// By default the list is shown below the text box and as wide as it,
+    /// and shows every suggestion, even for empty text.
+    pub fn new(source: Source) -> Self {
+        SuggestionList {
+            source,
+            max_suggestions: usize::MAX,
+            at_cursor: false,
+            accept_with_tab: false,
+            skip_empty: false,
+            suggestions: Vec::new(),
+            popup: None,
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Builder-style method to show the list below the cursor, as narrow as
+    /// the suggestions allow.
+    pub fn at_cursor(mut self) -> Self {
+        self.at_cursor = true;
+        self
+    }
+
+    /// Builder-style method to let <kbd>Tab</kbd> accept the highlighted
+    /// suggestion, or the first one if none is highlighted.
+    pub fn accept_with_tab(mut self) -> Self {
+        self.accept_with_tab = true;
+        self
+    }
+
+    /// Builder-style method to not look up suggestions for empty text.
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
+        self
+    }
+
+    /// Set the most suggestions that are shown at once.
+    pub fn set_max_suggestions(&mut self, max: usize) {
+        self.max_suggestions = max;
+    }
+
+    /// Look up suggestions for `data`, cancelling any earlier lookups.
+    fn query(&mut self, child: &TextBox<String>, ctx: &mut EventCtx, data: &String, env: &Env) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.skip_empty && data.is_empty() {
+            self.close(ctx);
+            return;
+        }
+        match &self.source {
+            Source::Sync(suggest) => {
+                let suggestions = suggest(data);
+                self.show(child, ctx, suggestions, data, env);
+            }
+            Source::Async(request) => {
+                trace!("Requesting suggestions for {:?}", data);
+                request(SuggestionRequest {
+                    query: data.clone(),
+                    generation,
+                    latest: self.generation.clone(),
+                    sink: ctx.get_external_handle(),
+                    target: ctx.widget_id(),
+                });
+            }
+        }
+    }
+
+    /// Show `suggestions` in the popup, or close it if there are none.
+    fn show(
+        &mut self,
+        child: &TextBox<String>,
+        ctx: &mut EventCtx,
+        mut suggestions: Vec<String>,
+        data: &String,
+        env: &Env,
+    ) {
+        suggestions.retain(|suggestion| suggestion != data);
+        suggestions.truncate(self.max_suggestions);
+        self.suggestions = suggestions;
+        if self.suggestions.is_empty() {
+            self.close(ctx);
+            return;
+        }
+
+        let list = ListState {
+            items: self
+                .suggestions
+                .iter()
+                .map(|suggestion| PopupItem {
+                    text: suggestion.as_str().into(),
+                    disabled: false,
+                })
+                .collect(),
+            highlighted: None,
+        };
+        let size = ctx.size();
+        let (position, min_width) = if self.at_cursor {
+            let cursor = child.cursor_line(data, env);
+            (Point::new(cursor.p1.x, size.height.max(cursor.p1.y)), 0.)
+        } else {
+            (Point::new(0., size.height), size.width)
+        };
+        match &self.popup {
+            Some(popup) => {
+                *popup.list() = list;
+                ctx.submit_command(popup.changed());
+                if self.at_cursor {
+                    ctx.submit_command(popup.move_to(ctx.to_screen(position)));
+                }
+            }
+            None => self.popup = Some(Popup::open(ctx, list, position, min_width, data, env)),
+        }
+    }
+
+    fn close(&mut self, ctx: &mut impl RequestCtx) {
+        if let Some(popup) = self.popup.take() {
+            ctx.submit_command(popup.close());
+        }
+    }
+
+    /// Replace the text with the suggestion at `index`.
+    fn accept(
+        &mut self,
+        child: &mut TextBox<String>,
+        ctx: &mut EventCtx,
+        data: &mut String,
+        index: usize,
+    ) {
+        if let Some(suggestion) = self.suggestions.get(index) {
+            trace!("Suggestion {:?} accepted", suggestion);
+            *data = suggestion.clone();
+            let caret = Selection::caret(data.len());
+            if let Some(inval) = child.text_mut().borrow_mut().set_selection(caret) {
+                ctx.invalidate_text_input(inval);
+            }
+        }
+        // Results for the old text are no longer wanted.
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.close(ctx);
+    }
+}
+
+impl Controller<String, TextBox<String>> for SuggestionList {
+    #[instrument(
+        name = "SuggestionList",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(
+        &mut self,
+        child: &mut TextBox<String>,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut String,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(SUGGESTIONS) => {
+                if let Some((generation, suggestions)) = cmd.get_unchecked(SUGGESTIONS).take() {
+                    if generation == self.generation.load(Ordering::Relaxed) && ctx.has_focus() {
+                        self.show(child, ctx, suggestions, data, env);
+                    } else {
+                        trace!("Ignoring stale suggestions");
+                    }
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(ITEM_CHOSEN) => {
+                let index = *cmd.get_unchecked(ITEM_CHOSEN);
+                self.accept(child, ctx, data, index);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if self.popup.is_some() => {
+                let popup = self.popup.as_ref().unwrap();
+                let highlighted = popup.list().highlighted;
+                match (&key.key, highlighted) {
+                    (KbKey::ArrowDown | KbKey::ArrowUp, _) => {
+                        popup.list().move_highlight(key.key == KbKey::ArrowDown);
+                        ctx.submit_command(popup.changed());
+                    }
+                    (KbKey::Enter, Some(index)) => self.accept(child, ctx, data, index),
+                    (KbKey::Tab, highlighted) if self.accept_with_tab => {
+                        self.accept(child, ctx, data, highlighted.unwrap_or(0))
+                    }
+                    (KbKey::Escape, _) => self.close(ctx),
+                    _ => return child.event(ctx, event, data, env),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if key.key == KbKey::ArrowDown => {
+                self.query(child, ctx, data, env);
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        let old_data = data.clone();
+        child.event(ctx, event, data, env);
+        if !old_data.same(data) && ctx.has_focus() {
+            self.query(child, ctx, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "SuggestionList",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut TextBox<String>,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &String,
+        env: &Env,
+    ) {
+        if let LifeCycle::FocusChanged(false) | LifeCycle::DisabledChanged(true) = event {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+            self.close(ctx);
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Autocomplete, SizedBox, ViewSwitcher};
+    use crate::{KeyEvent, Modifiers, WidgetExt};
+    use test_log::test;
+
+    fn key_down(key: KbKey) -> Event {
+        Event::KeyDown(KeyEvent::for_test(Modifiers::default(), key))
+    }
+
+    #[test]
+    fn stale_suggestions_are_ignored() {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let widget = Autocomplete::new_async(TextBox::new(), {
+            let requests = requests.clone();
+            move |request| requests.borrow_mut().push(request)
+        });
+
+        Harness::create_simple("ap".to_string(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((5., 5.))));
+
+            // Asking again makes the first request stale.
+            harness.event(key_down(KbKey::ArrowDown));
+            harness.event(key_down(KbKey::ArrowDown));
+            let (old, new) = {
+                let mut requests = requests.borrow_mut();
+                let new = requests.pop().unwrap();
+                (requests.pop().unwrap(), new)
+            };
+            assert!(old.is_stale());
+            assert!(!new.is_stale());
+            assert_eq!(new.query(), "ap");
+
+            let response = |request: &SuggestionRequest, suggestions: &[&str]| {
+                let suggestions = suggestions.iter().map(|s| s.to_string()).collect();
+                SUGGESTIONS
+                    .with(SingleUse::new((request.generation, suggestions)))
+                    .to(request.target)
+            };
+            harness.submit_command(response(&new, &["apple", "application"]));
+            harness.submit_command(response(&old, &["apricot"]));
+
+            // Tab accepts the best suggestion from the current request.
+            harness.event(key_down(KbKey::Tab));
+            assert_eq!(harness.data(), "apple");
+        });
+    }
+
+    #[test]
+    fn removing_the_owner_closes_the_popup() {
+        let widget = ViewSwitcher::new(
+            |text: &String, _| text.len() > 2,
+            |too_long, _, _| {
+                if *too_long {
+                    SizedBox::empty().boxed()
+                } else {
+                    Autocomplete::new(TextBox::new(), |query| vec![format!("{query}!")]).boxed()
+                }
+            },
+        );
+
+        Harness::create_simple(String::new(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((5., 5.))));
+            harness.type_text("ap");
+            assert_eq!(harness.opened_windows().len(), 1);
+            let popup = harness.opened_windows()[0];
+            assert!(harness.closed_windows().is_empty());
+
+            harness.type_text("r");
+            assert_eq!(harness.closed_windows(), [popup]);
+        });
+    }
+}
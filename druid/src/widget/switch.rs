@@ -9,6 +9,7 @@ use tracing::{instrument, trace};
 use crate::debug_state::DebugState;
 use crate::kurbo::{Circle, Shape};
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
+use crate::widget::focus;
 use crate::widget::prelude::*;
 use crate::{theme, ArcStr, Point, TextLayout};
 
@@ -17,6 +18,8 @@ const SWITCH_PADDING: f64 = 3.;
 const SWITCH_WIDTH_RATIO: f64 = 2.75;
 
 /// A switch that toggles a `bool`.
+///
+/// When focused, it is toggled with Space or Enter.
 #[derive(Debug, Clone)]
 pub struct Switch {
     knob_pos: Point,
@@ -149,6 +152,15 @@ impl Widget<bool> for Switch {
                     ctx.request_paint();
                 }
             }
+            Event::KeyDown(key) if !ctx.is_disabled() => {
+                if focus::is_activation_key(key) && ctx.is_focused() {
+                    // The knob is animated to its new position in `update`.
+                    *data = !*data;
+                    ctx.set_handled();
+                } else {
+                    focus::handle_tab(ctx, key);
+                }
+            }
             _ => (),
         }
     }
@@ -176,6 +188,8 @@ impl Widget<bool> for Switch {
                 self.on_text.set_text_color(color);
                 ctx.request_paint();
             }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
             _ => {}
         }
     }
@@ -271,6 +285,7 @@ impl Widget<bool> for Switch {
         ctx.stroke(background_rect, &env.get(theme::BORDER_DARK), stroke_width);
         ctx.fill(background_rect, &background_gradient_on_state);
         ctx.fill(background_rect, &background_gradient_off_state);
+        focus::paint_focus_ring(ctx, background_rect, env);
         ctx.clip(background_rect);
 
         // paint the knob
@@ -0,0 +1,993 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A table widget with sortable, resizable and reorderable columns.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::prelude::*;
+use crate::widget::{Label, LabelText, ListIter, Scroll};
+use crate::{theme, Cursor, Data, Lens, Point, Rect, WidgetExt, WidgetPod};
+
+type CellBuilder<T> = dyn Fn() -> Box<dyn Widget<T>>;
+type Compare<T> = dyn Fn(&T, &T) -> Ordering;
+
+/// The distance from a column divider in which it can be grabbed for resizing.
+const DIVIDER_HIT_WIDTH: f64 = 4.0;
+/// How far the mouse needs to move before pressing a header starts moving the column.
+const DRAG_THRESHOLD: f64 = 4.0;
+/// The size of the arrow indicating the sort direction.
+const SORT_ARROW_SIZE: f64 = 6.0;
+
+/// The direction in which a [`Table`] is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum SortDirection {
+    /// Smallest items first.
+    Ascending,
+    /// Largest items first.
+    Descending,
+}
+
+/// The part of a [`Table`]'s state that is stored in application data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Data, Lens)]
+pub struct TableState {
+    /// The index of the selected row in the table's data, if any.
+    ///
+    /// This is an index into the data, not a position on screen, so it doesn't
+    /// change when the table is sorted.
+    pub selection: Option<usize>,
+    /// The column the table is sorted by, and the direction of the sort.
+    ///
+    /// Columns are identified by the order in which they were added to the table,
+    /// which doesn't change when the user reorders them.
+    pub sort: Option<(usize, SortDirection)>,
+}
+
+/// How the width of a [`TableColumn`] is determined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    /// The column has this exact width.
+    Fixed(f64),
+    /// The column gets a share of the width that is left after the fixed columns
+    /// are laid out, proportional to this factor.
+    Flex(f64),
+}
+
+/// A description of a column in a [`Table`].
+pub struct TableColumn<T> {
+    header: LabelText<()>,
+    cell: Box<CellBuilder<T>>,
+    width: ColumnWidth,
+    min_width: f64,
+    resizable: bool,
+    compare: Option<Box<Compare<T>>>,
+}
+
+impl<T: Data> TableColumn<T> {
+    /// Create a new column with the given header. The closure will be called to
+    /// build the widget for each cell in this column.
+    pub fn new<W: Widget<T> + 'static>(
+        header: impl Into<LabelText<()>>,
+        cell: impl Fn() -> W + 'static,
+    ) -> Self {
+        TableColumn {
+            header: header.into(),
+            cell: Box::new(move || Box::new(cell())),
+            width: ColumnWidth::Flex(1.0),
+            min_width: 20.0,
+            resizable: true,
+            compare: None,
+        }
+    }
+
+    /// Create a new column that shows the value found through `lens` as a label.
+    pub fn with_lens<U, L>(header: impl Into<LabelText<()>>, lens: L) -> Self
+    where
+        U: Data + Display,
+        L: Lens<T, U> + Clone + 'static,
+    {
+        Self::new(header, move || {
+            Label::dynamic(|value: &U, _| value.to_string()).lens(lens.clone())
+        })
+    }
+
+    /// Builder-style method to set how the width of this column is determined.
+    ///
+    /// The default is [`ColumnWidth::Flex`] with a factor of `1.0`.
+    pub fn with_width(mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Builder-style method to set the width this column will never shrink below.
+    pub fn with_min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width.max(0.);
+        self
+    }
+
+    /// Builder-style method to set whether the user can resize this column
+    /// by dragging the right edge of its header. Columns are resizable by default.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Builder-style method to make the table sortable by this column, using
+    /// `compare` to order the rows.
+    pub fn sort_by(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+}
+
+/// The header of a column, as kept by the [`Table`] itself.
+struct Header {
+    label: WidgetPod<(), Label<()>>,
+    width: ColumnWidth,
+    min_width: f64,
+    resizable: bool,
+    sortable: bool,
+}
+
+/// What the user is doing with the header.
+#[derive(Debug, Clone, Copy)]
+enum HeaderDrag {
+    /// The header of `column` was pressed, but the mouse hasn't moved far yet.
+    Pressed { column: usize, start_x: f64 },
+    /// The header of `column` is being dragged to a new position.
+    Moving { column: usize },
+    /// The right edge of `column` is being dragged.
+    Resizing {
+        column: usize,
+        start_x: f64,
+        start_width: f64,
+    },
+}
+
+/// A widget that shows a collection as rows, with one column per [`TableColumn`].
+///
+/// The data of a `Table` is a tuple of the collection, which can be anything
+/// that implements [`ListIter`], and a [`TableState`] that keeps the selection
+/// and the sort order. Both are usually combined from the fields of the
+/// application state with a tuple of lenses:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::{Table, TableColumn, TableState};
+/// # use druid::{Data, Lens, Widget, WidgetExt};
+/// #[derive(Clone, Data, Lens)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     people: Arc<Vec<Person>>,
+///     table: TableState,
+/// }
+///
+/// fn people_table() -> impl Widget<AppState> {
+///     Table::new()
+///         .with_column(
+///             TableColumn::with_lens("Name", Person::name).sort_by(|a, b| a.name.cmp(&b.name)),
+///         )
+///         .with_column(
+///             TableColumn::with_lens("Age", Person::age).sort_by(|a, b| a.age.cmp(&b.age)),
+///         )
+///         .lens((AppState::people, AppState::table))
+/// }
+/// ```
+///
+/// The header stays in place while the rows are scrolled. Clicking the header
+/// of a sortable column sorts the rows by that column, dragging a header moves
+/// the column and dragging the edge of a header resizes the column. Sorting
+/// only changes the order in which rows are shown; the collection itself is
+/// left as it is.
+pub struct Table<T, L> {
+    headers: Vec<Header>,
+    /// The columns in the order in which they are shown.
+    order: Vec<usize>,
+    /// The current width of each column.
+    widths: Vec<f64>,
+    header_height: f64,
+    drag: Option<HeaderDrag>,
+    /// The divider of a resizable column is hovered by the mouse.
+    is_divider_hover: bool,
+    body: WidgetPod<(L, TableState), Scroll<(L, TableState), TableBody<T>>>,
+}
+
+/// The rows of a [`Table`].
+struct TableBody<T> {
+    cells: Vec<Box<CellBuilder<T>>>,
+    compares: Vec<Option<Box<Compare<T>>>>,
+    /// The cells of each row, in the order of the data.
+    rows: Vec<Vec<WidgetPod<T, Box<dyn Widget<T>>>>>,
+    /// The indices of the rows in the order in which they are shown.
+    display_order: Vec<usize>,
+    /// The vertical extent of each row, in the order of the data.
+    row_spans: Vec<(f64, f64)>,
+    widths: Vec<f64>,
+    order: Vec<usize>,
+    columns_changed: bool,
+    old_bc: BoxConstraints,
+}
+
+impl<T: Data, L: ListIter<T>> Table<T, L> {
+    /// Create a new table without columns.
+    pub fn new() -> Self {
+        let body = TableBody {
+            cells: Vec::new(),
+            compares: Vec::new(),
+            rows: Vec::new(),
+            display_order: Vec::new(),
+            row_spans: Vec::new(),
+            widths: Vec::new(),
+            order: Vec::new(),
+            columns_changed: false,
+            old_bc: BoxConstraints::tight(Size::ZERO),
+        };
+        Table {
+            headers: Vec::new(),
+            order: Vec::new(),
+            widths: Vec::new(),
+            header_height: 0.,
+            drag: None,
+            is_divider_hover: false,
+            body: WidgetPod::new(Scroll::new(body)),
+        }
+    }
+
+    /// Builder-style method to add a column to the right of the existing ones.
+    pub fn with_column(mut self, column: TableColumn<T>) -> Self {
+        self.add_column(column);
+        self
+    }
+
+    /// Add a column to the right of the existing ones.
+    ///
+    /// This should only be called before the table is added to the widget tree.
+    pub fn add_column(&mut self, column: TableColumn<T>) {
+        self.order.push(self.headers.len());
+        self.widths.push(0.);
+        self.headers.push(Header {
+            label: WidgetPod::new(Label::new(column.header)),
+            width: column.width,
+            min_width: column.min_width,
+            resizable: column.resizable,
+            sortable: column.compare.is_some(),
+        });
+        let body = self.body.widget_mut().child_mut();
+        body.cells.push(column.cell);
+        body.compares.push(column.compare);
+    }
+
+    /// Compute the width of each column, given the width of the table.
+    fn resolve_widths(&mut self, available: f64) {
+        let mut fixed = 0.;
+        let mut flex = 0.;
+        for header in &self.headers {
+            match header.width {
+                ColumnWidth::Fixed(width) => fixed += width.max(header.min_width),
+                ColumnWidth::Flex(factor) => flex += factor,
+            }
+        }
+        let remaining = if available.is_finite() {
+            (available - fixed).max(0.)
+        } else {
+            0.
+        };
+        for (header, width) in self.headers.iter().zip(&mut self.widths) {
+            *width = match header.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Flex(factor) if flex > 0. => remaining * factor / flex,
+                ColumnWidth::Flex(_) => 0.,
+            }
+            .max(header.min_width);
+        }
+    }
+
+    /// The horizontal extent of each column in the header, in visual order.
+    fn column_spans(&self) -> impl Iterator<Item = (usize, f64, f64)> + '_ {
+        let mut x = 0.;
+        self.order.iter().map(move |&column| {
+            let start = x;
+            x += self.widths[column];
+            (column, start, x)
+        })
+    }
+
+    /// Convert a position in the table to a position in the (scrolled) header.
+    fn header_x(&self, pos: Point) -> f64 {
+        pos.x + self.body.widget().offset().x
+    }
+
+    fn column_at(&self, x: f64) -> Option<usize> {
+        self.column_spans()
+            .find(|(_, start, end)| (*start..*end).contains(&x))
+            .map(|(column, _, _)| column)
+    }
+
+    fn divider_at(&self, x: f64) -> Option<usize> {
+        self.column_spans()
+            .find(|(column, _, end)| {
+                self.headers[*column].resizable && (x - end).abs() <= DIVIDER_HIT_WIDTH
+            })
+            .map(|(column, _, _)| column)
+    }
+
+    /// Swap the moving column with its neighbour if the mouse is past the neighbour's middle.
+    fn move_column(&mut self, column: usize, x: f64) -> bool {
+        let pos = match self.order.iter().position(|c| *c == column) {
+            Some(pos) => pos,
+            None => return false,
+        };
+        let spans: Vec<_> = self.column_spans().collect();
+        if pos > 0 {
+            let (_, start, end) = spans[pos - 1];
+            if x < (start + end) / 2. {
+                self.order.swap(pos, pos - 1);
+                return true;
+            }
+        }
+        if pos + 1 < spans.len() {
+            let (_, start, end) = spans[pos + 1];
+            if x > (start + end) / 2. {
+                self.order.swap(pos, pos + 1);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn set_divider_hover(&mut self, ctx: &mut EventCtx, hover: bool) {
+        if hover != self.is_divider_hover {
+            self.is_divider_hover = hover;
+            if hover {
+                ctx.set_cursor(&Cursor::ResizeLeftRight);
+            } else {
+                ctx.clear_cursor();
+            }
+        }
+    }
+
+    fn paint_header(
+        &mut self,
+        ctx: &mut PaintCtx,
+        sort: Option<(usize, SortDirection)>,
+        env: &Env,
+    ) {
+        let header_rect = Size::new(ctx.size().width, self.header_height).to_rect();
+        ctx.fill(header_rect, &env.get(theme::BACKGROUND_LIGHT));
+
+        let offset = self.body.widget().offset().x;
+        let height = self.header_height;
+        let spans: Vec<_> = self.column_spans().collect();
+        ctx.with_save(|ctx| {
+            ctx.clip(header_rect);
+            for (column, start, end) in spans {
+                // The header scrolls along with the body.
+                let (start, end) = (start - offset, end - offset);
+                let label = &mut self.headers[column].label;
+                ctx.with_save(|ctx| {
+                    ctx.clip(Rect::new(start, 0., end, height));
+                    label.paint(ctx, &(), env);
+                });
+                let divider = Line::new((end - 0.5, 0.), (end - 0.5, height));
+                ctx.stroke(divider, &env.get(theme::BORDER_LIGHT), 1.0);
+
+                if let Some((_, direction)) = sort.filter(|(c, _)| *c == column) {
+                    let center = Point::new(end - SORT_ARROW_SIZE - DIVIDER_HIT_WIDTH, height / 2.);
+                    let half = SORT_ARROW_SIZE / 2.;
+                    let (tip, base) = match direction {
+                        SortDirection::Ascending => (-half, half),
+                        SortDirection::Descending => (half, -half),
+                    };
+                    let mut arrow = BezPath::new();
+                    arrow.move_to((center.x - half, center.y + base));
+                    arrow.line_to((center.x + half, center.y + base));
+                    arrow.line_to((center.x, center.y + tip));
+                    arrow.close_path();
+                    ctx.fill(arrow, &env.get(theme::FOREGROUND_DARK));
+                }
+            }
+        });
+
+        let border = Line::new((0., height - 0.5), (header_rect.width(), height - 0.5));
+        ctx.stroke(border, &env.get(theme::BORDER_DARK), 1.0);
+    }
+}
+
+impl<T: Data, L: ListIter<T>> Default for Table<T, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data, L: ListIter<T>> Widget<(L, TableState)> for Table<T, L> {
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (L, TableState), env: &Env) {
+        match event {
+            Event::MouseDown(mouse)
+                if mouse.button.is_left() && mouse.pos.y < self.header_height =>
+            {
+                let x = self.header_x(mouse.pos);
+                if let Some(column) = self.divider_at(x) {
+                    self.drag = Some(HeaderDrag::Resizing {
+                        column,
+                        start_x: x,
+                        start_width: self.widths[column],
+                    });
+                } else if let Some(column) = self.column_at(x) {
+                    self.drag = Some(HeaderDrag::Pressed { column, start_x: x });
+                }
+                if self.drag.is_some() {
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                let x = self.header_x(mouse.pos);
+                let drag = self.drag;
+                match drag {
+                    Some(HeaderDrag::Pressed { column, start_x })
+                        if (x - start_x).abs() > DRAG_THRESHOLD =>
+                    {
+                        self.drag = Some(HeaderDrag::Moving { column });
+                    }
+                    Some(HeaderDrag::Moving { column }) if self.move_column(column, x) => {
+                        ctx.request_layout();
+                    }
+                    Some(HeaderDrag::Resizing {
+                        column,
+                        start_x,
+                        start_width,
+                    }) => {
+                        let header = &mut self.headers[column];
+                        let width = (start_width + x - start_x).max(header.min_width);
+                        header.width = ColumnWidth::Fixed(width);
+                        ctx.request_layout();
+                    }
+                    _ => (),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && ctx.is_active() => {
+                if let Some(HeaderDrag::Pressed { column, .. }) = self.drag {
+                    if self.headers[column].sortable {
+                        data.1.sort = match data.1.sort {
+                            Some((sorted, SortDirection::Ascending)) if sorted == column => {
+                                Some((column, SortDirection::Descending))
+                            }
+                            _ => Some((column, SortDirection::Ascending)),
+                        };
+                    }
+                }
+                self.drag = None;
+                ctx.set_active(false);
+                let hover = ctx.is_hot()
+                    && mouse.pos.y < self.header_height
+                    && self.divider_at(self.header_x(mouse.pos)).is_some();
+                self.set_divider_hover(ctx, hover);
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                let hover = ctx.is_hot()
+                    && mouse.pos.y < self.header_height
+                    && self.divider_at(self.header_x(mouse.pos)).is_some();
+                self.set_divider_hover(ctx, hover);
+            }
+            _ => (),
+        }
+        let offset = self.body.widget().offset();
+        self.body.event(ctx, event, data, env);
+        if self.body.widget().offset() != offset {
+            // The header labels are laid out at the scrolled position.
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(L, TableState),
+        env: &Env,
+    ) {
+        for header in &mut self.headers {
+            header.label.lifecycle(ctx, event, &(), env);
+        }
+        self.body.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(L, TableState),
+        data: &(L, TableState),
+        env: &Env,
+    ) {
+        for header in &mut self.headers {
+            header.label.update(ctx, &(), env);
+        }
+        self.body.update(ctx, data, env);
+        if old_data.1.sort != data.1.sort {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(L, TableState),
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Table");
+        self.resolve_widths(bc.max().width);
+
+        let padding = env.get(theme::WIDGET_PADDING_HORIZONTAL) / 2.;
+        let mut label_height: f64 = 0.;
+        for (header, width) in self.headers.iter_mut().zip(&self.widths) {
+            let label_bc = BoxConstraints::new(
+                Size::ZERO,
+                Size::new((width - 2. * padding).max(0.), f64::INFINITY),
+            );
+            let size = header.label.layout(ctx, &label_bc, &(), env);
+            label_height = label_height.max(size.height);
+        }
+        self.header_height = label_height + env.get(theme::WIDGET_PADDING_VERTICAL) * 2.;
+
+        let offset = self.body.widget().offset().x;
+        let spans: Vec<_> = self.column_spans().collect();
+        for (column, start, _) in spans {
+            let label = &mut self.headers[column].label;
+            let y = (self.header_height - label.layout_rect().height()) / 2.;
+            label.set_origin(ctx, Point::new(start - offset + padding, y));
+        }
+
+        self.body
+            .widget_mut()
+            .child_mut()
+            .set_columns(&self.widths, &self.order);
+        let body_bc = bc.shrink((0., self.header_height));
+        let body_size = self.body.layout(ctx, &body_bc, data, env);
+        self.body
+            .set_origin(ctx, Point::new(0., self.header_height));
+
+        let size = bc.constrain(Size::new(
+            body_size.width,
+            body_size.height + self.header_height,
+        ));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(L, TableState), env: &Env) {
+        self.body.paint(ctx, data, env);
+        self.paint_header(ctx, data.1.sort, env);
+    }
+
+    fn debug_state(&self, data: &(L, TableState)) -> DebugState {
+        let headers = self
+            .order
+            .iter()
+            .map(|column| self.headers[*column].label.widget().debug_state(&()));
+        DebugState {
+            display_name: "Table".to_string(),
+            children: headers
+                .chain(std::iter::once(self.body.widget().debug_state(data)))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data> TableBody<T> {
+    fn set_columns(&mut self, widths: &[f64], order: &[usize]) {
+        if self.widths != widths || self.order != order {
+            self.widths = widths.to_vec();
+            self.order = order.to_vec();
+            self.columns_changed = true;
+        }
+    }
+
+    /// Create or remove rows so that there is one for each item.
+    ///
+    /// Returns `true` if rows were added or removed.
+    fn update_row_count(&mut self, len: usize) -> bool {
+        let old_len = self.rows.len();
+        if old_len > len {
+            self.rows.truncate(len);
+        }
+        while self.rows.len() < len {
+            let row = self
+                .cells
+                .iter()
+                .map(|cell| WidgetPod::new(cell()))
+                .collect();
+            self.rows.push(row);
+        }
+        old_len != len
+    }
+
+    /// Recompute the order in which rows are shown.
+    fn sort(&mut self, data: &impl ListIter<T>, sort: Option<(usize, SortDirection)>) {
+        self.display_order = (0..data.data_len()).collect();
+        let (column, direction) = match sort {
+            Some(sort) => sort,
+            None => return,
+        };
+        if let Some(Some(compare)) = self.compares.get(column) {
+            let mut items = Vec::with_capacity(data.data_len());
+            data.for_each(|item, _| items.push(item.clone()));
+            self.display_order.sort_by(|a, b| {
+                let ordering = compare(&items[*a], &items[*b]);
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+    }
+
+    fn row_at(&self, y: f64) -> Option<usize> {
+        self.row_spans
+            .iter()
+            .position(|(start, end)| (*start..*end).contains(&y))
+    }
+}
+
+impl<T: Data, L: ListIter<T>> Widget<(L, TableState)> for TableBody<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (L, TableState), env: &Env) {
+        let mut rows = self.rows.iter_mut();
+        data.0.for_each_mut(|item, _| {
+            if let Some(row) = rows.next() {
+                for cell in row {
+                    cell.event(ctx, event, item, env);
+                }
+            }
+        });
+
+        if let Event::MouseDown(mouse) = event {
+            if mouse.button.is_left() && !ctx.is_handled() {
+                if let Some(row) = self.row_at(mouse.pos.y) {
+                    data.1.selection = Some(row);
+                    ctx.request_paint();
+                }
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(L, TableState),
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            if self.update_row_count(data.0.data_len()) {
+                ctx.children_changed();
+            }
+            self.sort(&data.0, data.1.sort);
+        }
+
+        let mut rows = self.rows.iter_mut();
+        data.0.for_each(|item, _| {
+            if let Some(row) = rows.next() {
+                for cell in row {
+                    cell.lifecycle(ctx, event, item, env);
+                }
+            }
+        });
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(L, TableState),
+        data: &(L, TableState),
+        env: &Env,
+    ) {
+        let mut rows = self.rows.iter_mut();
+        data.0.for_each(|item, _| {
+            if let Some(row) = rows.next() {
+                for cell in row {
+                    cell.update(ctx, item, env);
+                }
+            }
+        });
+
+        if self.update_row_count(data.0.data_len()) {
+            ctx.children_changed();
+        }
+
+        if !old_data.0.same(&data.0) || old_data.1.sort != data.1.sort {
+            self.sort(&data.0, data.1.sort);
+            ctx.request_layout();
+        }
+        if old_data.1.selection != data.1.selection {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(L, TableState),
+        env: &Env,
+    ) -> Size {
+        let padding_h = env.get(theme::WIDGET_PADDING_HORIZONTAL) / 2.;
+        let padding_v = env.get(theme::WIDGET_PADDING_VERTICAL) / 2.;
+        let relayout = self.columns_changed || self.old_bc != *bc;
+        self.columns_changed = false;
+        self.old_bc = *bc;
+
+        let mut heights = Vec::with_capacity(self.rows.len());
+        let mut rows = self.rows.iter_mut();
+        let widths = &self.widths;
+        data.0.for_each(|item, _| {
+            let row = match rows.next() {
+                Some(row) => row,
+                None => return,
+            };
+            let mut height: f64 = 0.;
+            for (cell, width) in row.iter_mut().zip(widths) {
+                let size = if relayout || cell.layout_requested() {
+                    let cell_bc = BoxConstraints::new(
+                        Size::ZERO,
+                        Size::new((width - 2. * padding_h).max(0.), f64::INFINITY),
+                    );
+                    cell.layout(ctx, &cell_bc, item, env)
+                } else {
+                    cell.layout_rect().size()
+                };
+                height = height.max(size.height);
+            }
+            heights.push(height + 2. * padding_v);
+        });
+
+        let mut column_starts = vec![0.; self.widths.len()];
+        let mut width = 0.;
+        for column in &self.order {
+            column_starts[*column] = width;
+            width += self.widths[*column];
+        }
+
+        self.row_spans = vec![(0., 0.); heights.len()];
+        let mut y = 0.;
+        for index in &self.display_order {
+            if let (Some(row), Some(height)) = (self.rows.get_mut(*index), heights.get(*index)) {
+                for (cell, start) in row.iter_mut().zip(&column_starts) {
+                    cell.set_origin(ctx, Point::new(start + padding_h, y + padding_v));
+                }
+                self.row_spans[*index] = (y, y + height);
+                y += height;
+            }
+        }
+
+        bc.constrain(Size::new(width, y))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(L, TableState), env: &Env) {
+        if let Some((start, end)) = data.1.selection.and_then(|i| self.row_spans.get(i)) {
+            let rect = Rect::new(0., *start, ctx.size().width, *end);
+            ctx.fill(rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+        }
+
+        let mut rows = self.rows.iter_mut();
+        data.0.for_each(|item, _| {
+            if let Some(row) = rows.next() {
+                for cell in row {
+                    cell.paint(ctx, item, env);
+                }
+            }
+        });
+    }
+
+    fn debug_state(&self, data: &(L, TableState)) -> DebugState {
+        let mut rows = self.rows.iter();
+        let mut children = Vec::with_capacity(self.rows.len());
+        data.0.for_each(|item, _| {
+            if let Some(row) = rows.next() {
+                children.push(DebugState {
+                    display_name: "TableRow".to_string(),
+                    children: self
+                        .order
+                        .iter()
+                        .filter_map(|column| row.get(*column))
+                        .map(|cell| cell.widget().debug_state(item))
+                        .collect(),
+                    ..Default::default()
+                });
+            }
+        });
+        DebugState {
+            display_name: "TableBody".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::query::WidgetQuery;
+    use crate::Vec2;
+    use std::sync::Arc;
+    use test_log::test;
+
+    type Numbers = (Arc<Vec<u32>>, TableState);
+
+    /// A table of numbers and their squares, with columns of the given width.
+    fn numbers_table(width: f64) -> Table<u32, Arc<Vec<u32>>> {
+        Table::new()
+            .with_column(
+                TableColumn::new("Number", || Label::dynamic(|n: &u32, _| n.to_string()))
+                    .with_width(ColumnWidth::Fixed(width))
+                    .sort_by(|a, b| a.cmp(b)),
+            )
+            .with_column(
+                TableColumn::new("Square", || {
+                    Label::dynamic(|n: &u32, _| (n * n).to_string())
+                })
+                .with_width(ColumnWidth::Fixed(width)),
+            )
+    }
+
+    fn numbers() -> Numbers {
+        (Arc::new(vec![6, 5, 7]), TableState::default())
+    }
+
+    fn label_rect(harness: &mut Harness<Numbers>, text: &str) -> Rect {
+        harness
+            .assert_one(&WidgetQuery::new().label(text))
+            .window_rect
+    }
+
+    #[test]
+    fn flex_columns_share_remaining_width() {
+        let mut table: Table<u32, Arc<Vec<u32>>> = Table::new()
+            .with_column(
+                TableColumn::new("A", || Label::new("")).with_width(ColumnWidth::Fixed(100.)),
+            )
+            .with_column(TableColumn::new("B", || Label::new("")).with_width(ColumnWidth::Flex(1.)))
+            .with_column(
+                TableColumn::new("C", || Label::new("")).with_width(ColumnWidth::Flex(3.)),
+            );
+
+        table.resolve_widths(500.);
+        assert_eq!(table.widths, vec![100., 100., 300.]);
+
+        // Flex columns don't shrink below their minimum width.
+        table.resolve_widths(110.);
+        assert_eq!(table.widths, vec![100., 20., 20.]);
+    }
+
+    #[test]
+    fn moving_a_column_past_the_middle_of_its_neighbour() {
+        let mut table: Table<u32, Arc<Vec<u32>>> = Table::new()
+            .with_column(TableColumn::new("A", || Label::new("")))
+            .with_column(TableColumn::new("B", || Label::new("")));
+        table.resolve_widths(200.);
+
+        assert!(!table.move_column(0, 40.));
+        assert!(table.move_column(0, 160.));
+        assert_eq!(table.order, vec![1, 0]);
+        assert!(table.move_column(0, 40.));
+        assert_eq!(table.order, vec![0, 1]);
+    }
+
+    #[test]
+    fn clicking_a_header_toggles_the_sort() {
+        Harness::create_simple(numbers(), numbers_table(100.), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let header = label_rect(harness, "Number").center();
+
+            harness.click_at(header);
+            assert_eq!(harness.data().1.sort, Some((0, SortDirection::Ascending)));
+            assert!(label_rect(harness, "5").y0 < label_rect(harness, "6").y0);
+            assert!(label_rect(harness, "6").y0 < label_rect(harness, "7").y0);
+
+            harness.click_at(header);
+            assert_eq!(harness.data().1.sort, Some((0, SortDirection::Descending)));
+            assert!(label_rect(harness, "7").y0 < label_rect(harness, "6").y0);
+
+            // Columns without a comparison can't be sorted by.
+            let square = label_rect(harness, "Square").center();
+            harness.click_at(square);
+            assert_eq!(harness.data().1.sort, Some((0, SortDirection::Descending)));
+        });
+    }
+
+    #[test]
+    fn clicking_a_row_selects_its_item() {
+        let data = (
+            numbers().0,
+            TableState {
+                selection: None,
+                sort: Some((0, SortDirection::Ascending)),
+            },
+        );
+        Harness::create_simple(data, numbers_table(100.), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // The selection is the index in the data, not the sorted position.
+            let row = label_rect(harness, "36").center();
+            harness.click_at(row);
+            assert_eq!(harness.data().1.selection, Some(0));
+            let row = label_rect(harness, "7").center();
+            harness.click_at(row);
+            assert_eq!(harness.data().1.selection, Some(2));
+        });
+    }
+
+    #[test]
+    fn dragging_a_divider_resizes_the_column() {
+        Harness::create_simple(numbers(), numbers_table(100.), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let header = label_rect(harness, "Number");
+            let square = label_rect(harness, "Square");
+
+            let divider = Point::new(100., header.center().y);
+            harness.drag(divider, divider + Vec2::new(50., 0.));
+            harness.run_requested_passes();
+            assert_eq!(label_rect(harness, "Square").x0, square.x0 + 50.);
+            assert_eq!(label_rect(harness, "25").x0, square.x0 + 50.);
+            // Clicking the divider doesn't sort.
+            assert_eq!(harness.data().1.sort, None);
+        });
+    }
+
+    #[test]
+    fn dragging_a_header_moves_the_column() {
+        Harness::create_simple(numbers(), numbers_table(100.), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let number = label_rect(harness, "Number");
+            let square = label_rect(harness, "Square");
+
+            harness.drag(number.center(), number.center() + Vec2::new(150., 0.));
+            harness.run_requested_passes();
+            assert_eq!(label_rect(harness, "Square").x0, number.x0);
+            assert_eq!(label_rect(harness, "Number").x0, square.x0);
+            assert_eq!(label_rect(harness, "36").x0, number.x0);
+            // Moving a column doesn't sort by it.
+            assert_eq!(harness.data().1.sort, None);
+        });
+    }
+
+    #[test]
+    fn header_scrolls_with_the_body() {
+        Harness::create_simple(numbers(), numbers_table(300.), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let square = label_rect(harness, "Square");
+
+            harness.scroll((100., 100.), (100., 0.));
+            harness.run_requested_passes();
+            assert_eq!(label_rect(harness, "Square").x0, square.x0 - 100.);
+            assert_eq!(label_rect(harness, "36").x0, square.x0 - 100.);
+        });
+    }
+}
@@ -14,7 +14,9 @@ use tracing::{instrument, trace};
 use crate::commands::SCROLL_TO_VIEW;
 use crate::kurbo::{Circle, Line};
 use crate::widget::prelude::*;
-use crate::widget::{Axis, Flex, Label, LabelText, LensScopeTransfer, Painter, Scope, ScopePolicy};
+use crate::widget::{
+    focus, Axis, Flex, Label, LabelText, LensScopeTransfer, Painter, Scope, ScopePolicy,
+};
 use crate::{theme, Affine, Data, Insets, Lens, Point, SingleUse, WidgetExt, WidgetPod};
 
 type TabsScope<TP> = Scope<TabsScopePolicy<TP>, Box<dyn Widget<TabsState<TP>>>>;
@@ -247,18 +249,12 @@ impl<TP: TabsPolicy> TabBar<TP> {
     fn find_idx(&self, pos: Point) -> Option<TabIndex> {
         let major_pix = self.axis.major_pos(pos);
         let axis = self.axis;
-        let res = self
-            .tabs
-            .binary_search_by_key(&((major_pix * 10.) as i64), |(_, tab)| {
-                let rect = tab.layout_rect();
-                let far_pix = axis.major_pos(rect.origin()) + axis.major(rect.size());
-                (far_pix * 10.) as i64
-            });
-        match res {
-            Ok(idx) => Some(idx),
-            Err(idx) if idx < self.tabs.len() => Some(idx),
-            _ => None,
-        }
+        // Tabs may be laid out in reverse under a right-to-left layout direction,
+        // so we can't rely on their rects being sorted.
+        self.tabs.iter().position(|(_, tab)| {
+            let (near, far) = axis.major_span(tab.layout_rect());
+            major_pix >= near && major_pix < far
+        })
     }
 
     fn ensure_tabs(&mut self, data: &TabsState<TP>) {
@@ -345,6 +341,22 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
                     ctx.request_paint();
                 }
             }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let len = self.tabs.len();
+                match focus::arrow_direction(key, env) {
+                    Some(forward) if len > 0 => {
+                        data.selected = if forward {
+                            (data.selected + 1) % len
+                        } else {
+                            (data.selected + len - 1) % len
+                        };
+                        ctx.set_handled();
+                    }
+                    _ => {
+                        focus::handle_tab(ctx, key);
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -361,9 +373,14 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         data: &TabsState<TP>,
         env: &Env,
     ) {
-        if let LifeCycle::WidgetAdded = event {
-            self.ensure_tabs(data);
-            ctx.children_changed();
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.ensure_tabs(data);
+                ctx.children_changed();
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
 
         for (_, tab) in self.tabs.iter_mut() {
@@ -389,6 +406,10 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         } else if old_data.selected != data.selected {
             ctx.request_paint();
         }
+
+        if ctx.env_key_changed(&Env::LAYOUT_DIRECTION) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(name = "TabBar", level = "trace", skip(self, ctx, bc, data, env))]
@@ -409,6 +430,15 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         }
         let wanted = self.axis.pack(major.max(self.axis.major(bc.max())), minor);
         let size = bc.constrain(wanted);
+
+        let direction = env.try_get(Env::LAYOUT_DIRECTION).unwrap_or_default();
+        if self.axis == Axis::Horizontal && direction.is_rtl() {
+            for (_, tab) in self.tabs.iter_mut() {
+                let rect = tab.layout_rect();
+                let x = direction.mirror_x(rect.x0, rect.width(), size.width);
+                tab.set_origin(ctx, Point::new(x, rect.y0));
+            }
+        }
         trace!("Computed size: {}", size);
         size
     }
@@ -448,7 +478,9 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
                     ),
                     &highlight,
                     hl_thickness,
-                )
+                );
+                let ring_width = env.get(theme::FOCUS_RING_WIDTH);
+                focus::paint_focus_ring(ctx, rect.inset(-ring_width / 2.), env);
             }
         }
     }
@@ -692,7 +724,16 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
         if let Some(trans) = &self.transition_state {
             let axis = self.axis;
             let size = ctx.size();
-            let major = axis.major(size);
+            let mut major = axis.major(size);
+            // Slide the other way when the tab bar is mirrored.
+            if axis == Axis::Horizontal
+                && env
+                    .try_get(Env::LAYOUT_DIRECTION)
+                    .unwrap_or_default()
+                    .is_rtl()
+            {
+                major = -major;
+            }
             ctx.clip(size.to_rect());
 
             let children = &mut self.children;
@@ -818,6 +859,14 @@ enum TabsContent<TP: TabsPolicy> {
 /// Or, the tabs can be derived from the input data by implementing TabsPolicy, and providing it to
 /// Tabs::from_policy()
 ///
+/// A horizontal tab bar is laid out from the right under a right-to-left
+/// [`LayoutDirection`], and slide transitions move the other way.
+///
+/// The tab bar can be focused from the keyboard, and the arrow keys then
+/// select the next or previous tab.
+///
+/// [`LayoutDirection`]: crate::LayoutDirection
+///
 /// ```
 /// use druid::widget::{Tabs, Label, WidgetExt};
 /// use druid::{Data, Lens};
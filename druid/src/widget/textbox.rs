@@ -8,7 +8,7 @@ use tracing::{instrument, trace};
 
 use crate::contexts::ChangeCtx;
 use crate::debug_state::DebugState;
-use crate::kurbo::Insets;
+use crate::kurbo::{Insets, Line};
 use crate::piet::TextLayout as _;
 use crate::text::{
     EditableText, ImeInvalidation, Selection, TextComponent, TextLayout, TextStorage,
@@ -325,6 +325,15 @@ impl<T> TextBox<T> {
         self
     }
 
+    /// Builder-style method for setting the maximum number of edits that
+    /// can be undone.
+    ///
+    /// The default is 100; a limit of `0` disables undo for this text box.
+    pub fn with_undo_limit(mut self, limit: usize) -> Self {
+        self.set_undo_limit(limit);
+        self
+    }
+
     /// Set the text size.
     ///
     /// The argument can be either an `f64` or a [`Key<f64>`].
@@ -402,6 +411,17 @@ impl<T> TextBox<T> {
         self.text_mut().borrow_mut().layout.set_text_color(color);
     }
 
+    /// Set the maximum number of edits that can be undone.
+    ///
+    /// Lowering the limit discards the oldest edits.
+    pub fn set_undo_limit(&mut self, limit: usize) {
+        if !self.text().can_write() {
+            tracing::warn!("set_undo_limit called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_undo_limit(limit);
+    }
+
     /// The point, relative to the origin, where this text box draws its
     /// [`TextLayout`].
     ///
@@ -461,6 +481,26 @@ impl<T> TextBox<T> {
 }
 
 impl<T: TextStorage + EditableText> TextBox<T> {
+    /// The line where the cursor is drawn, relative to the origin of the text box.
+    ///
+    /// This can be used to place something next to the cursor, such as a
+    /// list of completions.
+    ///
+    /// This is not valid until `layout` has been called.
+    pub fn cursor_line(&self, data: &T, env: &Env) -> Line {
+        let text = self.text().borrow();
+        let cursor_line = text.cursor_line_for_text_position(text.selection().active);
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        let padding_offset = Vec2::new(insets.x0, insets.y0);
+        // if there's no data, we always draw the cursor based on
+        // our alignment.
+        if data.is_empty() {
+            cursor_line + padding_offset
+        } else {
+            cursor_line + padding_offset - self.inner.offset()
+        }
+    }
+
     fn rect_for_selection_end(&self) -> Rect {
         let text = self.text().borrow();
         let layout = text.layout.layout().unwrap();
@@ -787,21 +827,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
 
         // Paint the cursor if focused and there's no selection
         if is_focused && self.should_draw_cursor() {
-            // if there's no data, we always draw the cursor based on
-            // our alignment.
-            let cursor_pos = self.text().borrow().selection().active;
-            let cursor_line = self
-                .text()
-                .borrow()
-                .cursor_line_for_text_position(cursor_pos);
-
-            let padding_offset = Vec2::new(textbox_insets.x0, textbox_insets.y0);
-
-            let mut cursor = if data.is_empty() {
-                cursor_line + padding_offset
-            } else {
-                cursor_line + padding_offset - self.inner.offset()
-            };
+            let mut cursor = self.cursor_line(data, env);
 
             // Snap the cursor to the pixel grid so it stays sharp.
             cursor.p0.x = cursor.p0.x.trunc() + 0.5;
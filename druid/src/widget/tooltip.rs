@@ -0,0 +1,282 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [`Controller`] that shows a tooltip when its child is hovered.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::widget::{Controller, Label, LabelText, WidgetExt};
+use crate::{
+    theme, Data, Env, Event, EventCtx, ExtEventSink, LifeCycle, LifeCycleCtx, Point, Target,
+    TimerToken, Vec2, Widget, WindowConfig, WindowId, WindowLevel, WindowSizePolicy,
+};
+
+/// How far below the mouse the tooltip is placed, so that it is not covered by the cursor.
+const CURSOR_OFFSET: Vec2 = Vec2::new(0.0, 20.0);
+
+enum TooltipState {
+    Idle,
+    Waiting {
+        token: TimerToken,
+        /// The last mouse position, in the widget's coordinates.
+        position: Point,
+    },
+    /// The tooltip is shown in a window, which is closed through the sink if
+    /// the controller is dropped.
+    Showing(WindowId, ExtEventSink),
+}
+
+/// A [`Controller`] that shows a tooltip when the mouse rests on its child.
+///
+/// The tooltip is shown in its own window after the mouse has been still for
+/// [`TOOLTIP_DELAY`], and is dismissed when the mouse leaves the child, or
+/// on a mouse click, scroll, or a key press reaching the child. It is also
+/// dismissed when the child is disabled or removed from the tree. Its colors
+/// come from the [`TOOLTIP_BACKGROUND_COLOR`], [`TOOLTIP_TEXT_COLOR`] and
+/// [`TOOLTIP_BORDER_COLOR`] theme keys.
+///
+/// This is most conveniently used through [`WidgetExt::tooltip`].
+///
+/// [`TOOLTIP_DELAY`]: crate::theme::TOOLTIP_DELAY
+/// [`TOOLTIP_BACKGROUND_COLOR`]: crate::theme::TOOLTIP_BACKGROUND_COLOR
+/// [`TOOLTIP_TEXT_COLOR`]: crate::theme::TOOLTIP_TEXT_COLOR
+/// [`TOOLTIP_BORDER_COLOR`]: crate::theme::TOOLTIP_BORDER_COLOR
+pub struct Tooltip<T> {
+    text: LabelText<T>,
+    state: TooltipState,
+}
+
+impl<T: Data> Tooltip<T> {
+    /// Create a new tooltip [`Controller`] that shows `text`.
+    pub fn new(text: impl Into<LabelText<T>>) -> Self {
+        Tooltip {
+            text: text.into(),
+            state: TooltipState::Idle,
+        }
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, position: Point, data: &T, env: &Env) {
+        let label = Label::new(self.text.clone())
+            .with_text_color(theme::TOOLTIP_TEXT_COLOR)
+            .padding((6.0, 3.0))
+            .background(theme::TOOLTIP_BACKGROUND_COLOR)
+            .border(theme::TOOLTIP_BORDER_COLOR, 1.0);
+        let config = WindowConfig::default()
+            .show_titlebar(false)
+            .resizable(false)
+            .window_size_policy(WindowSizePolicy::Content)
+            .set_level(WindowLevel::Tooltip(ctx.window().clone()))
+            .set_position(ctx.to_screen(position + CURSOR_OFFSET));
+        let window_id = ctx.new_sub_window(config, label, data.clone(), env.clone());
+        trace!("Showing tooltip in {:?}", window_id);
+        self.state = TooltipState::Showing(window_id, ctx.get_external_handle());
+    }
+
+    /// Stop waiting for or showing the tooltip, returning the window that should be closed.
+    fn dismiss(&mut self) -> Option<WindowId> {
+        match std::mem::replace(&mut self.state, TooltipState::Idle) {
+            TooltipState::Showing(window_id, _) => {
+                trace!("Closing tooltip in {:?}", window_id);
+                Some(window_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Tooltip<T> {
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(_)
+                if ctx.is_hot() && matches!(self.state, TooltipState::Showing(..)) => {}
+            Event::MouseMove(mouse) if ctx.is_hot() => {
+                // Every move restarts the delay; earlier timers are ignored.
+                let delay = Duration::from_millis(env.get(theme::TOOLTIP_DELAY));
+                self.state = TooltipState::Waiting {
+                    token: ctx.request_timer(delay),
+                    position: mouse.pos,
+                };
+            }
+            Event::Timer(timer) => match self.state {
+                TooltipState::Waiting { token, position } if token == *timer => {
+                    self.show(ctx, position, data, env);
+                    ctx.set_handled();
+                    return;
+                }
+                _ => (),
+            },
+            Event::MouseMove(_) | Event::MouseDown(_) | Event::Wheel(_) | Event::KeyDown(_) => {
+                if let Some(window_id) = self.dismiss() {
+                    ctx.submit_command(CLOSE_WINDOW.to(window_id));
+                }
+            }
+            _ => (),
+        }
+        child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(false) | LifeCycle::DisabledChanged(true) = event {
+            if let Some(window_id) = self.dismiss() {
+                ctx.submit_command(CLOSE_WINDOW.to(window_id));
+            }
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+impl<T> Drop for Tooltip<T> {
+    fn drop(&mut self) {
+        // We are being removed from the tree, and won't see the mouse leave.
+        if let TooltipState::Showing(window_id, sink) = &self.state {
+            trace!("Closing tooltip in {:?} on drop", window_id);
+            if sink
+                .submit_command(CLOSE_WINDOW, (), Target::Window(*window_id))
+                .is_err()
+            {
+                warn!("failed to close tooltip in {:?}", window_id);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::{Flex, SizedBox, ViewSwitcher};
+    use crate::{Selector, Size, WidgetId};
+    use test_log::test;
+
+    const SET: Selector<bool> = Selector::new("druid-test.tooltip-set");
+
+    /// The default [`TOOLTIP_DELAY`](theme::TOOLTIP_DELAY).
+    const DELAY: Duration = Duration::from_millis(600);
+
+    /// A widget that sets the data to the payload of [`SET`].
+    fn setter() -> ModularWidget<(), bool> {
+        ModularWidget::new(()).event_fn(|_, _ctx, event, data, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(SET) {
+                    *data = *value;
+                }
+            }
+        })
+    }
+
+    fn target() -> impl Widget<bool> {
+        ModularWidget::new(()).tooltip("tip")
+    }
+
+    /// Rest the mouse on `id` until its tooltip is shown, returning the tooltip window.
+    fn show_tooltip(harness: &mut Harness<bool>, id: WidgetId) -> WindowId {
+        harness.send_initial_events();
+        harness.just_layout();
+        let pos = harness.window_rect(id).center();
+        harness.mouse_move(pos);
+        harness.advance_time(DELAY - Duration::from_millis(1));
+        assert!(harness.opened_windows().is_empty());
+        harness.advance_time(Duration::from_millis(1));
+        assert_eq!(harness.opened_windows().len(), 1);
+        harness.opened_windows()[0]
+    }
+
+    #[test]
+    fn shows_after_delay_and_hides_on_leave() {
+        let [id] = widget_ids();
+        let widget = Flex::row()
+            .with_child(target().with_id(id))
+            .with_child(setter());
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.set_initial_size(Size::new(400., 400.));
+            let tooltip = show_tooltip(harness, id);
+            assert!(harness.closed_windows().is_empty());
+
+            harness.mouse_move((390., 390.));
+            assert_eq!(harness.closed_windows(), [tooltip]);
+        });
+    }
+
+    #[test]
+    fn moving_restarts_the_delay() {
+        let [id] = widget_ids();
+        let widget = Flex::row()
+            .with_child(target().with_id(id))
+            .with_child(setter());
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.set_initial_size(Size::new(400., 400.));
+            harness.send_initial_events();
+            harness.just_layout();
+            let pos = harness.window_rect(id).center();
+            harness.mouse_move(pos);
+            harness.advance_time(Duration::from_millis(400));
+            harness.mouse_move(pos + Vec2::new(1., 1.));
+            harness.advance_time(Duration::from_millis(400));
+            assert!(harness.opened_windows().is_empty());
+            harness.advance_time(Duration::from_millis(200));
+            assert_eq!(harness.opened_windows().len(), 1);
+        });
+    }
+
+    #[test]
+    fn hides_when_disabled() {
+        let [id] = widget_ids();
+        let widget = Flex::row()
+            .with_child(target().disabled_if(|data, _| *data).with_id(id))
+            .with_child(setter());
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.set_initial_size(Size::new(400., 400.));
+            let tooltip = show_tooltip(harness, id);
+            harness.submit_command(SET.with(true));
+            assert_eq!(harness.closed_windows(), [tooltip]);
+        });
+    }
+
+    #[test]
+    fn hides_when_removed() {
+        let [id] = widget_ids();
+        let switcher = ViewSwitcher::new(
+            |data: &bool, _| *data,
+            move |removed, _, _| {
+                if *removed {
+                    SizedBox::empty().boxed()
+                } else {
+                    target().with_id(id).boxed()
+                }
+            },
+        );
+        let widget = Flex::row().with_child(switcher).with_child(setter());
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.set_initial_size(Size::new(400., 400.));
+            let tooltip = show_tooltip(harness, id);
+            harness.submit_command(SET.with(true));
+            assert_eq!(harness.closed_windows(), [tooltip]);
+        });
+    }
+}
@@ -0,0 +1,341 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Animated transitions between child widgets.
+
+use std::time::Duration;
+
+use crate::animation::{Animation, Easing, Tween};
+use crate::kurbo::Vec2;
+use crate::widget::prelude::*;
+use crate::{Affine, Color};
+
+/// The animation used by [`Either`] and [`ViewSwitcher`] when they change
+/// which child is shown.
+///
+/// While a transition runs, both the outgoing and the incoming child are
+/// painted, but only the incoming child receives input.
+///
+/// [`Either`]: super::Either
+/// [`ViewSwitcher`]: super::ViewSwitcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Change children instantly, with no animation.
+    #[default]
+    Instant,
+    /// Cross-fade from the outgoing child to the incoming child, over the
+    /// given backdrop color.
+    ///
+    /// Widgets cannot be painted with partial opacity, so both children are
+    /// faded to and from the backdrop, with the one that is more visible
+    /// painted on top. The backdrop should be the color behind the two
+    /// children, such as the [`WINDOW_BACKGROUND_COLOR`]; anything else shows
+    /// through as a flash of that color. Where both children paint the same
+    /// area, the child on top hides the other one.
+    ///
+    /// [`WINDOW_BACKGROUND_COLOR`]: crate::theme::WINDOW_BACKGROUND_COLOR
+    Fade(Duration, Color),
+    /// Slide the outgoing child out and the incoming child in, moving in
+    /// the given direction.
+    Slide(Duration, SlideDirection),
+    /// Grow the incoming child from the center, over the outgoing child.
+    Scale(Duration),
+}
+
+/// The direction that children move in during a [`Transition::Slide`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideDirection {
+    /// The incoming child enters from the right.
+    Left,
+    /// The incoming child enters from the left.
+    Right,
+    /// The incoming child enters from the bottom.
+    Up,
+    /// The incoming child enters from the top.
+    Down,
+}
+
+impl Transition {
+    fn duration(self) -> Duration {
+        match self {
+            Transition::Instant => Duration::ZERO,
+            Transition::Fade(duration, _)
+            | Transition::Slide(duration, _)
+            | Transition::Scale(duration) => duration,
+        }
+    }
+}
+
+/// The smallest scale the incoming child of a [`Transition::Scale`] is
+/// painted at; below this it would not be visible anyway.
+const MIN_SCALE: f64 = 0.01;
+
+/// The progress of a running [`Transition`].
+pub(crate) struct TransitionState {
+    transition: Transition,
+    progress: Tween<f64>,
+    first_frame: bool,
+}
+
+impl TransitionState {
+    /// Start `transition`, or return `None` if it is not animated.
+    pub fn start(transition: Transition) -> Option<Self> {
+        let duration = transition.duration();
+        if duration.is_zero() {
+            return None;
+        }
+        Some(TransitionState {
+            transition,
+            progress: Tween::new(0.0, 1.0, duration).with_easing(Easing::EaseInOut),
+            first_frame: true,
+        })
+    }
+
+    /// Advance the transition, returning `true` if it is still running.
+    pub fn advance(&mut self, interval: u64) -> bool {
+        // We can get a high interval on the first frame due to other widgets or old animations.
+        if std::mem::take(&mut self.first_frame) {
+            self.progress.advance(1);
+        } else {
+            self.progress.advance(interval);
+        }
+        !self.progress.is_finished()
+    }
+
+    /// Paint both children, with the appropriate effect for the current progress.
+    pub fn paint(
+        &self,
+        ctx: &mut PaintCtx,
+        paint_outgoing: impl FnOnce(&mut PaintCtx),
+        paint_incoming: impl FnOnce(&mut PaintCtx),
+    ) {
+        let t = self.progress.value();
+        let size = ctx.size();
+        ctx.clip(size.to_rect());
+        match self.transition {
+            Transition::Instant => paint_incoming(ctx),
+            Transition::Fade(_, backdrop) => {
+                // Each child is faded after it is painted, and again with the
+                // child on top, so that the incoming child ends up with an
+                // opacity of `t` and the outgoing one with `1 - t`.
+                let fade = |ctx: &mut PaintCtx, alpha: f64| {
+                    ctx.fill(size.to_rect(), &backdrop.with_alpha(alpha));
+                };
+                if t < 0.5 {
+                    paint_incoming(ctx);
+                    fade(ctx, 1.0 - t / (1.0 - t));
+                    paint_outgoing(ctx);
+                    fade(ctx, t);
+                } else {
+                    paint_outgoing(ctx);
+                    fade(ctx, 1.0 - (1.0 - t) / t);
+                    paint_incoming(ctx);
+                    fade(ctx, 1.0 - t);
+                }
+            }
+            Transition::Slide(_, direction) => {
+                let offset = match direction {
+                    SlideDirection::Left => Vec2::new(-size.width, 0.0),
+                    SlideDirection::Right => Vec2::new(size.width, 0.0),
+                    SlideDirection::Up => Vec2::new(0.0, -size.height),
+                    SlideDirection::Down => Vec2::new(0.0, size.height),
+                };
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate(offset * t));
+                    paint_outgoing(ctx);
+                });
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate(-offset * (1.0 - t)));
+                    paint_incoming(ctx);
+                });
+            }
+            Transition::Scale(_) => {
+                paint_outgoing(ctx);
+                // Scaling by zero can't be inverted, which some backends reject.
+                if t < MIN_SCALE {
+                    return;
+                }
+                let center = size.to_rect().center().to_vec2();
+                ctx.with_save(|ctx| {
+                    ctx.transform(
+                        Affine::translate(center) * Affine::scale(t) * Affine::translate(-center),
+                    );
+                    paint_incoming(ctx);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::widget::{Either, ViewSwitcher};
+    use crate::{Selector, WidgetExt};
+    use test_log::test;
+
+    const SWITCH: Selector = Selector::new("druid-test.switch-branch");
+    const SET: Selector<u32> = Selector::new("druid-test.set-value");
+
+    /// A branch of an `Either` that logs which branch was painted or clicked.
+    fn branch(name: &'static str, log: Rc<RefCell<Vec<String>>>) -> impl Widget<bool> {
+        let paint_log = log.clone();
+        ModularWidget::new(())
+            .event_fn(move |_, _ctx, event, data: &mut bool, _| match event {
+                Event::Command(cmd) if cmd.is(SWITCH) => *data = true,
+                Event::MouseDown(_) => log.borrow_mut().push(format!("click {name}")),
+                _ => (),
+            })
+            .paint_fn(move |_, _ctx, _data, _env| {
+                paint_log.borrow_mut().push(format!("paint {name}"))
+            })
+    }
+
+    #[test]
+    fn either_paints_both_children_during_transition() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let widget = Either::new(
+            |data, _| *data,
+            branch("new", log.clone()),
+            branch("old", log.clone()),
+        )
+        .with_transition(Transition::Slide(
+            Duration::from_millis(100),
+            SlideDirection::Left,
+        ));
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SWITCH);
+            harness.just_layout();
+            log.borrow_mut().clear();
+
+            harness.paint();
+            harness.event(Event::MouseDown(move_mouse((10., 10.))));
+            assert_eq!(*log.borrow(), ["paint old", "paint new", "click new"]);
+            log.borrow_mut().clear();
+
+            // The first frame only starts the clock.
+            harness.event(Event::AnimFrame(1));
+            harness.event(Event::AnimFrame(200_000_000));
+            harness.paint();
+            assert_eq!(*log.borrow(), ["paint new"]);
+        });
+    }
+
+    /// Switch branches, and then run the transition for `elapsed` before painting.
+    fn paint_switch_after(
+        transition: Transition,
+        elapsed: Duration,
+        log: &Rc<RefCell<Vec<String>>>,
+    ) -> Vec<String> {
+        let widget = Either::new(
+            |data, _| *data,
+            branch("new", log.clone()),
+            branch("old", log.clone()),
+        )
+        .with_transition(transition);
+
+        let mut painted = Vec::new();
+        Harness::create_simple(false, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SWITCH);
+            harness.just_layout();
+            if !elapsed.is_zero() {
+                // The first frame only starts the clock.
+                harness.event(Event::AnimFrame(1));
+                harness.event(Event::AnimFrame(elapsed.as_nanos() as u64));
+            }
+            log.borrow_mut().clear();
+            harness.paint();
+            painted = log.borrow_mut().drain(..).collect();
+        });
+        painted
+    }
+
+    #[test]
+    fn fade_paints_both_children() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let fade = Transition::Fade(Duration::from_millis(100), Color::BLACK);
+
+        // The more visible child is painted on top.
+        let painted = paint_switch_after(fade, Duration::ZERO, &log);
+        assert_eq!(painted, ["paint new", "paint old"]);
+        let painted = paint_switch_after(fade, Duration::from_millis(70), &log);
+        assert_eq!(painted, ["paint old", "paint new"]);
+        let painted = paint_switch_after(fade, Duration::from_millis(200), &log);
+        assert_eq!(painted, ["paint new"]);
+    }
+
+    #[test]
+    fn scale_skips_the_incoming_child_at_zero() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let scale = Transition::Scale(Duration::from_millis(100));
+
+        let painted = paint_switch_after(scale, Duration::ZERO, &log);
+        assert_eq!(painted, ["paint old"]);
+        let painted = paint_switch_after(scale, Duration::from_millis(50), &log);
+        assert_eq!(painted, ["paint old", "paint new"]);
+    }
+
+    #[test]
+    fn view_switcher_keeps_outgoing_child_until_transition_ends() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let [low, high] = widget_ids();
+        let builder_log = log.clone();
+        let widget = ViewSwitcher::new(
+            |data: &u32, _| *data >= 10,
+            move |is_high, _, _| {
+                let (name, id) = if *is_high {
+                    ("high", high)
+                } else {
+                    ("low", low)
+                };
+                let log = builder_log.clone();
+                ModularWidget::new(())
+                    .event_fn(|_, _ctx, event, data: &mut u32, _| {
+                        if let Event::Command(cmd) = event {
+                            if let Some(value) = cmd.get(SET) {
+                                *data = *value;
+                            }
+                        }
+                    })
+                    .update_fn(move |_, _ctx, _old, data: &u32, _| {
+                        log.borrow_mut().push(format!("update {name} {data}"))
+                    })
+                    .with_id(id)
+                    .boxed()
+            },
+        )
+        .with_transition(Transition::Fade(Duration::from_millis(100), Color::BLACK));
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SET.with(10));
+            harness.just_layout();
+            assert!(harness.try_get_state(low).is_some());
+
+            // The outgoing child still gets the new data.
+            log.borrow_mut().clear();
+            harness.submit_command(SET.with(11));
+            assert_eq!(*log.borrow(), ["update high 11", "update low 11"]);
+
+            harness.event(Event::AnimFrame(1));
+            harness.event(Event::AnimFrame(200_000_000));
+            assert!(harness.try_get_state(low).is_none());
+            log.borrow_mut().clear();
+            harness.submit_command(SET.with(12));
+            assert_eq!(*log.borrow(), ["update high 12"]);
+        });
+    }
+}
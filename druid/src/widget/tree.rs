@@ -0,0 +1,844 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A tree widget for hierarchical data.
+
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::prelude::*;
+use crate::{theme, Data, KbKey, Lens, Point, Rect, Selector, WidgetPod};
+
+/// The size of the disclosure arrow of a branch.
+const ARROW_SIZE: f64 = 8.0;
+/// The indentation of each level of the tree, if none was provided.
+const DEFAULT_INDENT: f64 = 16.0;
+
+/// A path from the root of a tree to one of its nodes, as the index of each
+/// child along the way. The root itself has an empty path.
+pub type TreePath = Arc<[usize]>;
+
+/// Hierarchical data that can be shown by a [`Tree`].
+pub trait TreeNode: Data {
+    /// Returns how many children this node currently has.
+    fn children_count(&self) -> usize;
+
+    /// Returns a reference to the child at `index`.
+    ///
+    /// This is only called with indices below [`children_count`](Self::children_count).
+    fn get_child(&self, index: usize) -> &Self;
+
+    /// Call `cb` with a mutable reference to the child at `index`.
+    ///
+    /// This is only called with indices below [`children_count`](Self::children_count).
+    fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Self, usize));
+
+    /// Returns `true` if this node can be expanded.
+    ///
+    /// The default is to treat nodes with children as branches. Nodes whose
+    /// children are loaded lazily should return `true` before they are loaded.
+    fn is_branch(&self) -> bool {
+        self.children_count() > 0
+    }
+
+    /// Returns `true` if the children of this node still have to be loaded.
+    ///
+    /// When such a node is expanded, the [`Tree`] submits a
+    /// [`Tree::LOAD_CHILDREN`] command with the path to the node.
+    fn needs_loading(&self) -> bool {
+        false
+    }
+}
+
+/// The part of a [`Tree`]'s state that is stored in application data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Data, Lens)]
+pub struct TreeState {
+    /// The path to the selected node, if any.
+    pub selection: Option<TreePath>,
+}
+
+/// A widget that shows a [`TreeNode`] and its descendants as an outline.
+///
+/// The data of a `Tree` is a tuple of the root node and a [`TreeState`] that
+/// keeps the selection. Whether a node is expanded is part of the node itself,
+/// and is accessed through the lens passed to [`Tree::new`].
+///
+/// Each node is shown with the widget returned by the closure passed to
+/// [`Tree::new`], indented according to its depth, next to an arrow that
+/// expands or collapses it. The root node is shown as well.
+///
+/// The tree can be navigated with the keyboard once it has focus: the up and
+/// down arrows move the selection, the right arrow expands the selected node or
+/// moves to its first child, the left arrow collapses it or moves to its parent,
+/// and enter or space toggles it.
+///
+/// Nodes whose children are loaded on demand return `true` from
+/// [`TreeNode::needs_loading`]; when one of them is expanded the tree submits a
+/// [`Tree::LOAD_CHILDREN`] command, which is usually handled by an
+/// [`AppDelegate`] that fills in the children.
+///
+/// [`AppDelegate`]: crate::AppDelegate
+pub struct Tree<T, L> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    expanded: L,
+    indent: f64,
+    root: Option<NodeWidget<T>>,
+    /// The nodes that are currently shown, in order.
+    rows: Vec<Row>,
+    /// The lines connecting the children of each expanded node.
+    guides: Vec<Line>,
+    old_bc: BoxConstraints,
+}
+
+/// The widgets of a node and its (shown) descendants.
+struct NodeWidget<T> {
+    row: WidgetPod<T, Box<dyn Widget<T>>>,
+    children: Vec<NodeWidget<T>>,
+    is_expanded: bool,
+    load_requested: bool,
+}
+
+/// A node that is shown, as computed during layout.
+struct Row {
+    path: TreePath,
+    /// The vertical extent of the row.
+    span: (f64, f64),
+    is_branch: bool,
+    is_expanded: bool,
+}
+
+/// State that is carried along while laying out the tree.
+struct LayoutState {
+    y: f64,
+    width: f64,
+    max_width: f64,
+    bc_changed: bool,
+}
+
+impl Tree<(), ()> {
+    /// A command submitted by a [`Tree`] when a node that [needs loading] is expanded.
+    ///
+    /// The payload is the path to the node, relative to the root of the tree.
+    ///
+    /// [needs loading]: TreeNode::needs_loading
+    pub const LOAD_CHILDREN: Selector<TreePath> = Selector::new("druid-builtin.tree-load-children");
+}
+
+impl<T: TreeNode, L: Lens<T, bool>> Tree<T, L> {
+    /// Create a new tree widget.
+    ///
+    /// The closure will be called every time a widget is needed for a node, and
+    /// `expanded` is a lens to the flag that says whether a node is expanded.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static, expanded: L) -> Self {
+        Tree {
+            closure: Box::new(move || Box::new(closure())),
+            expanded,
+            indent: DEFAULT_INDENT,
+            root: None,
+            rows: Vec::new(),
+            guides: Vec::new(),
+            old_bc: BoxConstraints::tight(Size::ZERO),
+        }
+    }
+
+    /// Builder-style method to set the indentation of each level of the tree.
+    pub fn with_indent(mut self, indent: f64) -> Self {
+        self.indent = indent.max(ARROW_SIZE);
+        self
+    }
+
+    /// Create or drop widgets so that there is one for each shown node.
+    ///
+    /// Returns `true` if widgets were added or removed.
+    fn sync(&mut self, data: &T, loads: &mut Vec<TreePath>) -> bool {
+        let mut changed = false;
+        let root = self.root.get_or_insert_with(|| {
+            changed = true;
+            NodeWidget::new(&self.closure)
+        });
+        let mut path = Vec::new();
+        changed |= root.sync(data, &self.closure, &self.expanded, &mut path, loads);
+        changed
+    }
+
+    fn row_index(&self, path: &[usize]) -> Option<usize> {
+        self.rows.iter().position(|row| &*row.path == path)
+    }
+
+    fn row_at(&self, y: f64) -> Option<&Row> {
+        self.rows
+            .iter()
+            .find(|row| (row.span.0..row.span.1).contains(&y))
+    }
+
+    fn select(&self, ctx: &mut EventCtx, state: &mut TreeState, path: TreePath) {
+        if let Some(row) = self.row_index(&path).map(|i| &self.rows[i]) {
+            let rect = Rect::new(0., row.span.0, ctx.size().width, row.span.1);
+            ctx.scroll_area_to_view(rect);
+        }
+        state.selection = Some(path);
+        ctx.request_paint();
+    }
+
+    fn set_expanded(
+        &self,
+        ctx: &mut EventCtx,
+        data: &mut (T, TreeState),
+        path: &[usize],
+        expand: bool,
+    ) {
+        with_node_mut(&mut data.0, path, &mut |node| {
+            self.expanded.with_mut(node, |expanded| *expanded = expand);
+        });
+        // Don't leave the selection inside a collapsed node.
+        if !expand {
+            if let Some(selection) = &data.1.selection {
+                if selection.len() > path.len() && selection.starts_with(path) {
+                    data.1.selection = Some(path.into());
+                }
+            }
+        }
+        ctx.request_layout();
+    }
+
+    fn handle_key(&self, ctx: &mut EventCtx, key: &KbKey, data: &mut (T, TreeState)) {
+        let current = data
+            .1
+            .selection
+            .as_ref()
+            .and_then(|path| self.row_index(path));
+        let current = match current {
+            Some(current) => current,
+            None => {
+                if let Some(row) = self.rows.first() {
+                    self.select(ctx, &mut data.1, row.path.clone());
+                    ctx.set_handled();
+                }
+                return;
+            }
+        };
+        let row = &self.rows[current];
+        match key {
+            KbKey::ArrowUp if current > 0 => {
+                self.select(ctx, &mut data.1, self.rows[current - 1].path.clone());
+            }
+            KbKey::ArrowDown if current + 1 < self.rows.len() => {
+                self.select(ctx, &mut data.1, self.rows[current + 1].path.clone());
+            }
+            KbKey::ArrowRight if row.is_branch && !row.is_expanded => {
+                self.set_expanded(ctx, data, &row.path, true);
+            }
+            KbKey::ArrowRight if row.is_branch => {
+                // The first child is the next row, if the children are loaded.
+                if let Some(next) = self.rows.get(current + 1) {
+                    if next.path.len() > row.path.len() {
+                        self.select(ctx, &mut data.1, next.path.clone());
+                    }
+                }
+            }
+            KbKey::ArrowLeft if row.is_expanded => {
+                self.set_expanded(ctx, data, &row.path, false);
+            }
+            KbKey::ArrowLeft if !row.path.is_empty() => {
+                let parent = &row.path[..row.path.len() - 1];
+                self.select(ctx, &mut data.1, parent.into());
+            }
+            _ if is_toggle_key(key) && row.is_branch => {
+                self.set_expanded(ctx, data, &row.path, !row.is_expanded);
+            }
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn paint_arrow(&self, ctx: &mut PaintCtx, row: &Row, env: &Env) {
+        let depth = row.path.len() as f64;
+        let center = Point::new(
+            depth * self.indent + self.indent / 2.,
+            (row.span.0 + row.span.1) / 2.,
+        );
+        let half = ARROW_SIZE / 2.;
+        let mut arrow = BezPath::new();
+        if row.is_expanded {
+            arrow.move_to((center.x - half, center.y - half / 2.));
+            arrow.line_to((center.x + half, center.y - half / 2.));
+            arrow.line_to((center.x, center.y + half / 2.));
+        } else {
+            arrow.move_to((center.x - half / 2., center.y - half));
+            arrow.line_to((center.x - half / 2., center.y + half));
+            arrow.line_to((center.x + half / 2., center.y));
+        }
+        arrow.close_path();
+        ctx.fill(arrow, &env.get(theme::FOREGROUND_DARK));
+    }
+}
+
+impl<T: TreeNode, L: Lens<T, bool>> Widget<(T, TreeState)> for Tree<T, L> {
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut (T, TreeState), env: &Env) {
+        if let Some(root) = &mut self.root {
+            root.event(ctx, event, &mut data.0, env);
+        }
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.request_focus();
+                let row = match self.row_at(mouse.pos.y) {
+                    Some(row) => row,
+                    None => return,
+                };
+                let arrow_start = (row.path.len() as f64) * self.indent;
+                let on_arrow = (arrow_start..arrow_start + self.indent).contains(&mouse.pos.x);
+                if row.is_branch && (on_arrow || mouse.count == 2) {
+                    let (path, expand) = (row.path.clone(), !row.is_expanded);
+                    self.set_expanded(ctx, data, &path, expand);
+                } else {
+                    self.select(ctx, &mut data.1, row.path.clone());
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.has_focus() => {
+                self.handle_key(ctx, &key.key, data);
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(T, TreeState),
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_for_focus();
+                let mut loads = Vec::new();
+                if self.sync(&data.0, &mut loads) {
+                    ctx.children_changed();
+                }
+                for path in loads {
+                    ctx.submit_command(Tree::LOAD_CHILDREN.with(path));
+                }
+            }
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+
+        if let Some(root) = &mut self.root {
+            root.lifecycle(ctx, event, &data.0, env);
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(T, TreeState),
+        data: &(T, TreeState),
+        env: &Env,
+    ) {
+        if let Some(root) = &mut self.root {
+            root.update(ctx, &data.0, env);
+        }
+
+        let mut loads = Vec::new();
+        if self.sync(&data.0, &mut loads) {
+            ctx.children_changed();
+            ctx.request_layout();
+        }
+        for path in loads {
+            ctx.submit_command(Tree::LOAD_CHILDREN.with(path));
+        }
+
+        if old_data.1.selection != data.1.selection {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(T, TreeState),
+        env: &Env,
+    ) -> Size {
+        let mut state = LayoutState {
+            y: 0.,
+            width: 0.,
+            max_width: bc.max().width,
+            bc_changed: self.old_bc != *bc,
+        };
+        self.old_bc = *bc;
+        self.rows.clear();
+        self.guides.clear();
+        if let Some(root) = &mut self.root {
+            let mut path = Vec::new();
+            root.layout(
+                ctx,
+                &data.0,
+                env,
+                self.indent,
+                &mut state,
+                &mut path,
+                &mut self.rows,
+                &mut self.guides,
+            );
+        }
+
+        let size = bc.constrain(Size::new(state.width, state.y));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(T, TreeState), env: &Env) {
+        let selected = data
+            .1
+            .selection
+            .as_ref()
+            .and_then(|path| self.row_index(path));
+        if let Some(row) = selected.map(|i| &self.rows[i]) {
+            let rect = Rect::new(0., row.span.0, ctx.size().width, row.span.1);
+            let color = if ctx.has_focus() {
+                env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            };
+            ctx.fill(rect, &color);
+        }
+
+        let guide_color = env.get(theme::BORDER_LIGHT);
+        for guide in &self.guides {
+            ctx.stroke(*guide, &guide_color, 1.0);
+        }
+        for row in self.rows.iter().filter(|row| row.is_branch) {
+            self.paint_arrow(ctx, row, env);
+        }
+
+        if let Some(root) = &mut self.root {
+            root.paint(ctx, &data.0, env);
+        }
+    }
+
+    fn debug_state(&self, data: &(T, TreeState)) -> DebugState {
+        DebugState {
+            display_name: "Tree".to_string(),
+            children: self
+                .root
+                .iter()
+                .map(|root| root.debug_state(&data.0))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: TreeNode> NodeWidget<T> {
+    fn new(closure: &dyn Fn() -> Box<dyn Widget<T>>) -> Self {
+        NodeWidget {
+            row: WidgetPod::new(closure()),
+            children: Vec::new(),
+            is_expanded: false,
+            load_requested: false,
+        }
+    }
+
+    /// The number of children that have widgets and still exist in the data.
+    fn shown_children(&self, data: &T) -> usize {
+        self.children.len().min(data.children_count())
+    }
+
+    fn sync(
+        &mut self,
+        data: &T,
+        closure: &dyn Fn() -> Box<dyn Widget<T>>,
+        expanded: &impl Lens<T, bool>,
+        path: &mut Vec<usize>,
+        loads: &mut Vec<TreePath>,
+    ) -> bool {
+        let is_expanded = expanded.with(data, |expanded| *expanded);
+        self.is_expanded = is_expanded;
+        if is_expanded && data.needs_loading() {
+            if !self.load_requested {
+                self.load_requested = true;
+                loads.push(path.as_slice().into());
+            }
+        } else {
+            self.load_requested = false;
+        }
+
+        let wanted = if is_expanded {
+            data.children_count()
+        } else {
+            0
+        };
+        let mut changed = self.children.len() != wanted;
+        self.children.truncate(wanted);
+        while self.children.len() < wanted {
+            self.children.push(NodeWidget::new(closure));
+        }
+
+        for (i, child) in self.children.iter_mut().enumerate() {
+            path.push(i);
+            changed |= child.sync(data.get_child(i), closure, expanded, path, loads);
+            path.pop();
+        }
+        changed
+    }
+
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.row.event(ctx, event, data, env);
+        for i in 0..self.shown_children(data) {
+            let child = &mut self.children[i];
+            data.for_child_mut(i, |child_data, _| child.event(ctx, event, child_data, env));
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.row.lifecycle(ctx, event, data, env);
+        for i in 0..self.shown_children(data) {
+            self.children[i].lifecycle(ctx, event, data.get_child(i), env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        // Widgets that were just created get their data with `WidgetAdded`.
+        if self.row.is_initialized() {
+            self.row.update(ctx, data, env);
+        }
+        for i in 0..self.shown_children(data) {
+            self.children[i].update(ctx, data.get_child(i), env);
+        }
+    }
+
+    /// Lay out this node and its descendants, returning the center of this node's row.
+    #[allow(clippy::too_many_arguments)]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        data: &T,
+        env: &Env,
+        indent: f64,
+        state: &mut LayoutState,
+        path: &mut Vec<usize>,
+        rows: &mut Vec<Row>,
+        guides: &mut Vec<Line>,
+    ) -> f64 {
+        let depth = path.len() as f64;
+        let x = (depth + 1.) * indent;
+        let row_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new((state.max_width - x).max(0.), f64::INFINITY),
+        );
+        let size = if state.bc_changed || self.row.layout_requested() {
+            self.row.layout(ctx, &row_bc, data, env)
+        } else {
+            self.row.layout_rect().size()
+        };
+        let height = size.height.max(indent);
+        let top = state.y;
+        self.row
+            .set_origin(ctx, Point::new(x, top + (height - size.height) / 2.));
+        state.y += height;
+        state.width = state.width.max(x + size.width);
+
+        rows.push(Row {
+            path: path.as_slice().into(),
+            span: (top, top + height),
+            is_branch: data.is_branch(),
+            is_expanded: self.is_expanded,
+        });
+
+        let mut last_child_center = None;
+        for i in 0..self.shown_children(data) {
+            path.push(i);
+            last_child_center = Some(self.children[i].layout(
+                ctx,
+                data.get_child(i),
+                env,
+                indent,
+                state,
+                path,
+                rows,
+                guides,
+            ));
+            path.pop();
+        }
+        if let Some(end) = last_child_center {
+            let guide_x = depth * indent + indent / 2.;
+            guides.push(Line::new((guide_x, top + height), (guide_x, end)));
+        }
+
+        top + height / 2.
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.row.paint(ctx, data, env);
+        for i in 0..self.shown_children(data) {
+            self.children[i].paint(ctx, data.get_child(i), env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "TreeNode".to_string(),
+            children: std::iter::once(self.row.widget().debug_state(data))
+                .chain(
+                    (0..self.shown_children(data))
+                        .map(|i| self.children[i].debug_state(data.get_child(i))),
+                )
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Call `f` with the node at `path` below `node`, if it exists.
+fn with_node_mut<T: TreeNode>(node: &mut T, path: &[usize], f: &mut dyn FnMut(&mut T)) {
+    match path.split_first() {
+        None => f(node),
+        Some((first, rest)) => {
+            if *first < node.children_count() {
+                node.for_child_mut(*first, |child, _| with_node_mut(child, rest, f));
+            }
+        }
+    }
+}
+
+fn is_toggle_key(key: &KbKey) -> bool {
+    match key {
+        KbKey::Enter => true,
+        KbKey::Character(c) => c == " ",
+        _ => false,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::query::WidgetQuery;
+    use crate::widget::Label;
+    use crate::{AppDelegate, Command, DelegateCtx, Handled, Modifiers, Target};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Node {
+        name: String,
+        expanded: bool,
+        lazy: bool,
+        children: Arc<Vec<Node>>,
+    }
+
+    impl Node {
+        fn new(name: &str, children: Vec<Node>) -> Self {
+            Node {
+                name: name.to_string(),
+                expanded: false,
+                lazy: false,
+                children: Arc::new(children),
+            }
+        }
+
+        fn lazy(name: &str) -> Self {
+            Node {
+                lazy: true,
+                ..Node::new(name, Vec::new())
+            }
+        }
+    }
+
+    impl TreeNode for Node {
+        fn children_count(&self) -> usize {
+            self.children.len()
+        }
+
+        fn get_child(&self, index: usize) -> &Self {
+            &self.children[index]
+        }
+
+        fn for_child_mut(&mut self, index: usize, cb: impl FnOnce(&mut Self, usize)) {
+            let mut child = self.children[index].clone();
+            cb(&mut child, index);
+            if !child.same(&self.children[index]) {
+                Arc::make_mut(&mut self.children)[index] = child;
+            }
+        }
+
+        fn is_branch(&self) -> bool {
+            self.lazy || !self.children.is_empty()
+        }
+
+        fn needs_loading(&self) -> bool {
+            self.lazy && self.children.is_empty()
+        }
+    }
+
+    fn tree_data() -> (Node, TreeState) {
+        let root = Node::new(
+            "root",
+            vec![
+                Node::new("a", vec![Node::new("a1", vec![]), Node::new("a2", vec![])]),
+                Node::lazy("b"),
+            ],
+        );
+        (root, TreeState::default())
+    }
+
+    fn tree() -> impl Widget<(Node, TreeState)> {
+        Tree::new(
+            || Label::dynamic(|node: &Node, _| node.name.clone()),
+            Node::expanded,
+        )
+    }
+
+    fn selection(harness: &Harness<(Node, TreeState)>) -> Option<Vec<usize>> {
+        harness
+            .data()
+            .1
+            .selection
+            .as_ref()
+            .map(|path| path.to_vec())
+    }
+
+    /// Click the disclosure arrow of the row showing `name`, at `depth`.
+    fn click_arrow(harness: &mut Harness<(Node, TreeState)>, name: &str, depth: usize) {
+        let row = harness.assert_one(&WidgetQuery::new().label(name));
+        let x = depth as f64 * DEFAULT_INDENT + DEFAULT_INDENT / 2.;
+        harness.click_at((x, row.window_rect.center().y));
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        Harness::create_simple(tree_data(), tree(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.assert_one(&WidgetQuery::new().label("root"));
+            harness.assert_none(&WidgetQuery::new().label("a"));
+
+            click_arrow(harness, "root", 0);
+            assert!(harness.data().0.expanded);
+            harness.assert_one(&WidgetQuery::new().label("a"));
+            harness.assert_one(&WidgetQuery::new().label("b"));
+
+            click_arrow(harness, "a", 1);
+            let a1 = harness.assert_one(&WidgetQuery::new().label("a1"));
+            let a2 = harness.assert_one(&WidgetQuery::new().label("a2"));
+            assert!(a1.window_rect.y1 <= a2.window_rect.y0);
+            assert_eq!(a1.window_rect.x0, 3. * DEFAULT_INDENT);
+
+            // Collapsing a node moves the selection out of it.
+            harness.click(a2.id);
+            assert_eq!(selection(harness), Some(vec![0, 1]));
+            click_arrow(harness, "root", 0);
+            assert!(!harness.data().0.expanded);
+            assert_eq!(selection(harness), Some(vec![]));
+            harness.assert_none(&WidgetQuery::new().label("a"));
+            harness.assert_none(&WidgetQuery::new().label("a2"));
+        });
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        Harness::create_simple(tree_data(), tree(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let root = harness.assert_one(&WidgetQuery::new().label("root"));
+            harness.click(root.id);
+            assert_eq!(selection(harness), Some(vec![]));
+
+            let none = Modifiers::empty();
+            // Right expands the root, then moves to its first child.
+            harness.press_key(none, KbKey::ArrowRight);
+            assert!(harness.data().0.expanded);
+            assert_eq!(selection(harness), Some(vec![]));
+            harness.press_key(none, KbKey::ArrowRight);
+            assert_eq!(selection(harness), Some(vec![0]));
+
+            harness.press_key(none, KbKey::ArrowDown);
+            assert_eq!(selection(harness), Some(vec![1]));
+            harness.press_key(none, KbKey::ArrowUp);
+            assert_eq!(selection(harness), Some(vec![0]));
+
+            harness.press_key(none, KbKey::Enter);
+            assert!(harness.data().0.children[0].expanded);
+            harness.press_key(none, KbKey::ArrowDown);
+            harness.press_key(none, KbKey::ArrowDown);
+            assert_eq!(selection(harness), Some(vec![0, 1]));
+
+            // Left moves to the parent, then collapses it.
+            harness.press_key(none, KbKey::ArrowLeft);
+            assert_eq!(selection(harness), Some(vec![0]));
+            harness.press_key(none, KbKey::ArrowLeft);
+            assert!(!harness.data().0.children[0].expanded);
+            harness.press_key(none, " ");
+            assert!(harness.data().0.children[0].expanded);
+        });
+    }
+
+    /// Fills in the children of lazy nodes, counting how often it was asked to.
+    struct Loader(Rc<Cell<usize>>);
+
+    impl AppDelegate<(Node, TreeState)> for Loader {
+        fn command(
+            &mut self,
+            _: &mut DelegateCtx,
+            _: Target,
+            cmd: &Command,
+            data: &mut (Node, TreeState),
+            _: &Env,
+        ) -> Handled {
+            match cmd.get(Tree::LOAD_CHILDREN) {
+                Some(path) => {
+                    self.0.set(self.0.get() + 1);
+                    let mut node = &mut data.0;
+                    for i in path.iter() {
+                        node = &mut Arc::make_mut(&mut node.children)[*i];
+                    }
+                    node.children = Arc::new(vec![Node::new("b1", vec![])]);
+                    Handled::Yes
+                }
+                None => Handled::No,
+            }
+        }
+    }
+
+    #[test]
+    fn lazy_loading() {
+        let loads = Rc::new(Cell::new(0));
+        let mut data = tree_data();
+        data.0.expanded = true;
+
+        Harness::create_simple(data, tree(), |harness| {
+            harness.set_delegate(Loader(loads.clone()));
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(loads.get(), 0);
+            harness.assert_none(&WidgetQuery::new().label("b1"));
+
+            click_arrow(harness, "b", 1);
+            assert_eq!(loads.get(), 1);
+            harness.assert_one(&WidgetQuery::new().label("b1"));
+
+            // Once loaded, the children are kept when the node is collapsed.
+            click_arrow(harness, "b", 1);
+            harness.assert_none(&WidgetQuery::new().label("b1"));
+            click_arrow(harness, "b", 1);
+            assert_eq!(loads.get(), 1);
+            harness.assert_one(&WidgetQuery::new().label("b1"));
+        });
+    }
+}
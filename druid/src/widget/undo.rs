@@ -0,0 +1,444 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Document-style undo and redo for application data.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{commands, Data, Selector};
+
+/// The number of steps that can be undone, by default.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Changes recorded this close together are merged into one step, by default.
+const DEFAULT_COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// A [`Controller`] that keeps an undo history of its data.
+///
+/// Because [`Data`] is cheap to clone, the history is simply a list of
+/// snapshots. A snapshot is taken at the end of each *transaction*; by
+/// default every change to the data is a transaction, but with
+/// [`UndoManager::manual`] only the points marked by submitting
+/// [`UndoManager::CHECKPOINT`] are. Transactions that end within a short
+/// time of the first one of a step are merged into that step, so that, for
+/// instance, dragging a slider can be undone all at once.
+///
+/// The manager handles [`commands::UNDO`] and [`commands::REDO`] after its
+/// child has had a chance to handle them. A focused [`TextBox`] therefore
+/// undoes its own edits first, and leaves the commands to the manager once
+/// it has nothing left to undo or redo. What the text box undoes or redoes
+/// itself isn't recorded as a new step, and doesn't discard the steps the
+/// manager can redo.
+///
+/// Clones of an `UndoManager` share the same history. This can be used to
+/// enable and disable the corresponding menu items:
+///
+/// ```
+/// # use druid::widget::{TextBox, UndoManager};
+/// # use druid::{platform_menus, Menu, Widget, WidgetExt};
+/// let undo = UndoManager::<String>::new();
+/// let root = TextBox::new().controller(undo.clone());
+///
+/// let (can_undo, can_redo) = (undo.clone(), undo);
+/// let menu: Menu<String> = Menu::new("Edit")
+///     .entry(platform_menus::common::undo().enabled_if(move |_, _| can_undo.can_undo()))
+///     .entry(platform_menus::common::redo().enabled_if(move |_, _| can_redo.can_redo()));
+/// ```
+///
+/// An `UndoManager` should be placed around the root widget of a window;
+/// it only sees changes to the data it is given.
+///
+/// [`TextBox`]: crate::widget::TextBox
+pub struct UndoManager<T> {
+    history: Rc<RefCell<History<T>>>,
+    /// Whether every change to the data ends a transaction.
+    automatic: bool,
+}
+
+struct History<T> {
+    /// The state before each step, oldest first.
+    undo: VecDeque<T>,
+    /// The state after each undone step, most recently undone last.
+    redo: Vec<T>,
+    /// The state at the end of the last transaction.
+    current: Option<T>,
+    /// When the first transaction of the newest step ended, if later ones
+    /// may still be merged into it.
+    step_start: Option<Instant>,
+    limit: usize,
+    coalesce_window: Duration,
+}
+
+impl UndoManager<()> {
+    /// Ends the current transaction, recording a step in the undo history
+    /// if the data has changed since the previous one.
+    ///
+    /// This is only needed with a manager created by [`UndoManager::manual`].
+    pub const CHECKPOINT: Selector = Selector::new("druid-builtin.undo-checkpoint");
+}
+
+impl<T: Data> UndoManager<T> {
+    /// Create a manager that records every change to the data.
+    pub fn new() -> Self {
+        UndoManager {
+            history: Rc::new(RefCell::new(History {
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                current: None,
+                step_start: None,
+                limit: DEFAULT_LIMIT,
+                coalesce_window: DEFAULT_COALESCE_WINDOW,
+            })),
+            automatic: true,
+        }
+    }
+
+    /// Create a manager that only records changes when it receives
+    /// [`UndoManager::CHECKPOINT`].
+    ///
+    /// Use this if the data contains state that should not be undone, like
+    /// a selection, or if a single user action changes the data several times.
+    pub fn manual() -> Self {
+        UndoManager {
+            automatic: false,
+            ..UndoManager::new()
+        }
+    }
+
+    /// Builder-style method to set the maximum number of steps that can be undone.
+    ///
+    /// The default is 100.
+    pub fn with_limit(self, limit: usize) -> Self {
+        self.history.borrow_mut().limit = limit;
+        self
+    }
+
+    /// Builder-style method to set the time, measured from the first
+    /// transaction of a step, within which later transactions are merged
+    /// into that step.
+    ///
+    /// The default is half a second; [`Duration::ZERO`] disables merging.
+    pub fn with_coalesce_window(self, window: Duration) -> Self {
+        self.history.borrow_mut().coalesce_window = window;
+        self
+    }
+
+    /// Returns `true` if there is a step that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.history.borrow().undo.is_empty()
+    }
+
+    /// Returns `true` if there is an undone step that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.history.borrow().redo.is_empty()
+    }
+
+    /// Forget all steps, keeping the current data as the starting point.
+    pub fn clear(&self) {
+        let mut history = self.history.borrow_mut();
+        history.undo.clear();
+        history.redo.clear();
+        history.step_start = None;
+    }
+}
+
+impl<T: Data> Default for UndoManager<T> {
+    fn default() -> Self {
+        UndoManager::new()
+    }
+}
+
+impl<T> Clone for UndoManager<T> {
+    fn clone(&self) -> Self {
+        UndoManager {
+            history: self.history.clone(),
+            automatic: self.automatic,
+        }
+    }
+}
+
+impl<T: Data> History<T> {
+    /// Ends a transaction at time `now`, merging it into the newest step if
+    /// that started less than the coalesce window ago.
+    fn end_transaction(&mut self, data: &T, now: Instant) {
+        let current = match &self.current {
+            Some(current) if !current.same(data) => current.clone(),
+            Some(_) => return,
+            None => {
+                self.current = Some(data.clone());
+                return;
+            }
+        };
+
+        let merge = !self.undo.is_empty()
+            && self
+                .step_start
+                .map(|start| now.duration_since(start) < self.coalesce_window)
+                .unwrap_or(false);
+        if !merge {
+            self.undo.push_back(current);
+            if self.undo.len() > self.limit {
+                self.undo.pop_front();
+            }
+            self.step_start = Some(now);
+        }
+        self.redo.clear();
+        self.current = Some(data.clone());
+    }
+
+    fn undo(&mut self, data: &T) -> Option<T> {
+        // Changes that are not part of a transaction yet can still be redone.
+        self.step_start = None;
+        self.end_transaction(data, Instant::now());
+        let previous = self.undo.pop_back()?;
+        self.redo.extend(self.current.replace(previous.clone()));
+        Some(previous)
+    }
+
+    /// Follows an undo or redo that the child handled itself, without
+    /// recording a step or discarding what can be redone.
+    ///
+    /// Undoing back to the state before the newest step removes that step,
+    /// and redoing away from it adds it again.
+    fn follow_child(&mut self, data: &T, is_undo: bool) {
+        if is_undo {
+            if matches!(self.undo.back(), Some(previous) if previous.same(data)) {
+                self.undo.pop_back();
+            }
+        } else if let Some(current) = self.current.take() {
+            if !current.same(data) {
+                self.undo.push_back(current);
+            }
+        }
+        self.current = Some(data.clone());
+        self.step_start = None;
+    }
+
+    fn redo(&mut self, data: &T) -> Option<T> {
+        // Changes made since the last undo discard what could be redone.
+        self.end_transaction(data, Instant::now());
+        let next = self.redo.pop()?;
+        self.undo.extend(self.current.replace(next.clone()));
+        self.step_start = None;
+        Some(next)
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for UndoManager<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        child.event(ctx, event, data, env);
+        if ctx.is_handled() {
+            if let Event::Command(cmd) = event {
+                if cmd.is(commands::UNDO) || cmd.is(commands::REDO) {
+                    self.history
+                        .borrow_mut()
+                        .follow_child(data, cmd.is(commands::UNDO));
+                }
+            }
+            return;
+        }
+        if let Event::Command(cmd) = event {
+            if cmd.is(commands::UNDO) {
+                if let Some(previous) = self.history.borrow_mut().undo(data) {
+                    *data = previous;
+                }
+                ctx.set_handled();
+            } else if cmd.is(commands::REDO) {
+                if let Some(next) = self.history.borrow_mut().redo(data) {
+                    *data = next;
+                }
+                ctx.set_handled();
+            } else if cmd.is(UndoManager::CHECKPOINT) {
+                self.history
+                    .borrow_mut()
+                    .end_transaction(data, Instant::now());
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.history.borrow_mut().current = Some(data.clone());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if self.automatic {
+            self.history
+                .borrow_mut()
+                .end_transaction(data, Instant::now());
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::widget::{Flex, TextBox};
+    use crate::{WidgetExt, WidgetId};
+    use test_log::test;
+
+    const SET: Selector<u32> = Selector::new("druid-tests.undo-set");
+
+    fn setter() -> impl Widget<u32> {
+        ModularWidget::new(()).event_fn(|_, _ctx, event, data, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(SET) {
+                    *data = *value;
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn undo_and_redo_snapshots() {
+        let undo = UndoManager::new().with_coalesce_window(Duration::ZERO);
+        let widget = setter().controller(undo.clone());
+
+        Harness::create_simple(0, widget, |harness| {
+            harness.send_initial_events();
+            assert!(!undo.can_undo());
+
+            harness.submit_command(SET.with(1));
+            harness.submit_command(SET.with(2));
+            assert!(undo.can_undo());
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(*harness.data(), 1);
+            harness.submit_command(commands::UNDO);
+            assert_eq!(*harness.data(), 0);
+            assert!(!undo.can_undo());
+
+            harness.submit_command(commands::REDO);
+            assert_eq!(*harness.data(), 1);
+            assert!(undo.can_redo());
+
+            // A new change discards what could have been redone.
+            harness.submit_command(SET.with(3));
+            assert!(!undo.can_redo());
+        });
+    }
+
+    #[test]
+    fn manual_checkpoints_are_merged_within_window() {
+        let undo = UndoManager::manual().with_coalesce_window(Duration::from_secs(60));
+        let widget = setter().controller(undo.clone());
+
+        Harness::create_simple(0, widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(SET.with(1));
+            assert!(!undo.can_undo());
+            harness.submit_command(UndoManager::CHECKPOINT);
+            harness.submit_command(SET.with(2));
+            harness.submit_command(UndoManager::CHECKPOINT);
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(*harness.data(), 0);
+            assert!(!undo.can_undo());
+        });
+    }
+
+    #[test]
+    fn text_box_undoes_its_own_edits_first() {
+        let undo = UndoManager::new().with_coalesce_window(Duration::from_secs(60));
+        let text_box = WidgetId::next();
+        let widget = TextBox::new().controller(undo.clone()).with_id(text_box);
+
+        Harness::create_simple(String::new(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.click(text_box);
+            harness.type_text("ab");
+            assert!(undo.can_undo());
+
+            // The text box's undo restores the state before the manager's
+            // only step, so that step is gone instead of a new one recorded.
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "");
+            assert!(!undo.can_undo());
+            harness.submit_command(commands::REDO);
+            assert_eq!(harness.data(), "ab");
+            assert!(undo.can_undo());
+            assert!(!undo.can_redo());
+        });
+    }
+
+    #[test]
+    fn manager_undoes_once_text_box_has_nothing_left() {
+        const SET_TEXT: Selector<String> = Selector::new("druid-tests.undo-set-text");
+
+        let undo = UndoManager::manual();
+        let text_box = WidgetId::next();
+        let setter = ModularWidget::new(()).event_fn(|_, _ctx, event, data: &mut String, _env| {
+            if let Event::Command(cmd) = event {
+                if let Some(text) = cmd.get(SET_TEXT) {
+                    *data = text.clone();
+                }
+            }
+        });
+        let widget = Flex::column()
+            .with_child(setter)
+            .with_child(TextBox::new().with_id(text_box))
+            .controller(undo.clone());
+
+        Harness::create_simple(String::new(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SET_TEXT.with("hi".to_string()));
+            harness.submit_command(UndoManager::CHECKPOINT);
+            harness.click(text_box);
+            harness.type_text("!");
+            assert_eq!(harness.data(), "hi!");
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "hi");
+            assert!(undo.can_undo());
+            assert!(!undo.can_redo());
+
+            harness.submit_command(commands::UNDO);
+            assert_eq!(harness.data(), "");
+            assert!(undo.can_redo());
+        });
+    }
+
+    #[test]
+    fn coalesce_window_starts_with_first_change() {
+        let undo = UndoManager::<u32>::new();
+        let mut history = undo.history.borrow_mut();
+        let start = Instant::now();
+        history.current = Some(0);
+
+        // Each change is within the window of the previous one, but the third
+        // is past the window of the first.
+        history.end_transaction(&1, start);
+        history.end_transaction(&2, start + Duration::from_millis(300));
+        history.end_transaction(&3, start + Duration::from_millis(600));
+        assert_eq!(history.undo, [0, 2]);
+
+        history.end_transaction(&4, start + Duration::from_millis(900));
+        assert_eq!(history.undo, [0, 2]);
+        history.end_transaction(&5, start + Duration::from_millis(1200));
+        assert_eq!(history.undo, [0, 2, 4]);
+    }
+}
@@ -3,7 +3,9 @@
 
 //! A widget that can dynamically switch between one of many views.
 
+use super::transition::TransitionState;
 use crate::widget::prelude::*;
+use crate::widget::Transition;
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
 
@@ -11,11 +13,21 @@ type ChildPicker<T, U> = dyn Fn(&T, &Env) -> U;
 type ChildBuilder<T, U> = dyn Fn(&U, &T, &Env) -> Box<dyn Widget<T>>;
 
 /// A widget that switches dynamically between multiple children.
+///
+/// By default the children are switched instantly; use [`with_transition`]
+/// to animate the change.
+///
+/// [`with_transition`]: ViewSwitcher::with_transition
 pub struct ViewSwitcher<T, U> {
     child_picker: Box<ChildPicker<T, U>>,
     child_builder: Box<ChildBuilder<T, U>>,
     active_child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
     active_child_id: Option<U>,
+    /// The previous child, kept in the widget tree until the transition that
+    /// hides it has finished.
+    outgoing_child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    transition: Transition,
+    transition_state: Option<TransitionState>,
 }
 
 impl<T: Data, U: Data> ViewSwitcher<T, U> {
@@ -62,8 +74,22 @@ impl<T: Data, U: Data> ViewSwitcher<T, U> {
             child_builder: Box::new(child_builder),
             active_child: None,
             active_child_id: None,
+            outgoing_child: None,
+            transition: Transition::Instant,
+            transition_state: None,
         }
     }
+
+    /// Builder-style method to set the [`Transition`] used when the child changes.
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Set the [`Transition`] used when the child changes.
+    pub fn set_transition(&mut self, transition: Transition) {
+        self.transition = transition;
+    }
 }
 
 impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
@@ -76,6 +102,24 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
         if let Some(child) = self.active_child.as_mut() {
             child.event(ctx, event, data, env);
         }
+        // The outgoing child is still part of the tree, but doesn't receive input.
+        if let Some(outgoing) = self.outgoing_child.as_mut() {
+            if event.should_propagate_to_hidden() {
+                outgoing.event(ctx, event, data, env);
+            }
+        }
+
+        if let (Some(state), Event::AnimFrame(interval)) = (&mut self.transition_state, event) {
+            if state.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.transition_state = None;
+                if self.outgoing_child.take().is_some() {
+                    ctx.children_changed();
+                }
+            }
+            ctx.request_paint();
+        }
     }
 
     #[instrument(
@@ -92,6 +136,11 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
         if let Some(child) = self.active_child.as_mut() {
             child.lifecycle(ctx, event, data, env);
         }
+        if let Some(outgoing) = self.outgoing_child.as_mut() {
+            if event.should_propagate_to_hidden() {
+                outgoing.lifecycle(ctx, event, data, env);
+            }
+        }
     }
 
     #[instrument(
@@ -103,17 +152,32 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
         let child_id = (self.child_picker)(data, env);
         // Safe to unwrap because self.active_child_id should not be empty
         if !child_id.same(self.active_child_id.as_ref().unwrap()) {
-            self.active_child = Some(WidgetPod::new((self.child_builder)(&child_id, data, env)));
+            let new_child = WidgetPod::new((self.child_builder)(&child_id, data, env));
+            let old_child = self.active_child.replace(new_child);
             self.active_child_id = Some(child_id);
+            self.transition_state = TransitionState::start(self.transition);
+            if self.transition_state.is_some() {
+                self.outgoing_child = old_child;
+                ctx.request_anim_frame();
+            } else {
+                self.outgoing_child = None;
+            }
             ctx.children_changed();
         // Because the new child has not yet been initialized, we have to skip the update after switching.
         } else if let Some(child) = self.active_child.as_mut() {
             child.update(ctx, data, env);
         }
+        if let Some(outgoing) = self.outgoing_child.as_mut() {
+            outgoing.update(ctx, data, env);
+        }
     }
 
     #[instrument(name = "ViewSwitcher", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        if let Some(outgoing) = self.outgoing_child.as_mut() {
+            outgoing.layout(ctx, bc, data, env);
+            outgoing.set_origin(ctx, Point::ORIGIN);
+        }
         match self.active_child {
             Some(ref mut child) => {
                 let size = child.layout(ctx, bc, data, env);
@@ -126,8 +190,24 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
 
     #[instrument(name = "ViewSwitcher", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        if let Some(ref mut child) = self.active_child {
-            child.paint_raw(ctx, data, env);
+        match (&self.transition_state, &mut self.outgoing_child) {
+            (Some(state), Some(outgoing)) => {
+                let incoming = &mut self.active_child;
+                state.paint(
+                    ctx,
+                    |ctx| outgoing.paint_raw(ctx, data, env),
+                    |ctx| {
+                        if let Some(child) = incoming {
+                            child.paint_raw(ctx, data, env);
+                        }
+                    },
+                );
+            }
+            _ => {
+                if let Some(ref mut child) = self.active_child {
+                    child.paint_raw(ctx, data, env);
+                }
+            }
         }
     }
 }
@@ -0,0 +1,596 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A list view that only builds widgets for the visible items.
+
+use std::collections::VecDeque;
+use std::f64;
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::{Axis, ListIter};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Selector, UpdateCtx, Widget, WidgetPod,
+};
+
+/// Sent by a [`VirtualList`] to itself when the set of items it should show has changed.
+const REALIZE_VISIBLE: Selector = Selector::new("druid.builtin.virtual-list-realize-visible");
+
+/// The size used for rows that have not been measured yet, if none was provided.
+const DEFAULT_ROW_ESTIMATE: f64 = 24.0;
+
+/// The distance past each edge of the viewport in which rows are kept alive, by default.
+const DEFAULT_OVERSCAN: f64 = 100.0;
+
+/// How a [`VirtualList`] determines the size of its rows along the list's axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowSize {
+    /// Every row has exactly this size.
+    ///
+    /// This is the cheapest option; children are laid out with tight constraints
+    /// along the list's axis.
+    Fixed(f64),
+    /// Rows choose their own size.
+    ///
+    /// Rows that have never been laid out are assumed to have this size, which
+    /// means the total size of the list, and therefore the scroll bars, may shift
+    /// as new rows become visible.
+    Estimated(f64),
+}
+
+/// A list widget for very large collections.
+///
+/// Unlike [`List`], which creates a widget for every item, `VirtualList` only
+/// creates widgets for the items that are inside (or close to) the visible area,
+/// and reuses the widgets of items that scroll out of view for the items that
+/// scroll into it. This keeps memory use and the cost of each pass proportional
+/// to the size of the viewport instead of the size of the collection.
+///
+/// `VirtualList` is meant to be placed inside a [`Scroll`]. Because widgets are
+/// reused, any state they keep that is not derived from their data will carry
+/// over from one item to another.
+///
+/// The size of the list across its axis is the largest size of the rows that are
+/// currently shown.
+///
+/// [`List`]: super::List
+/// [`Scroll`]: super::Scroll
+pub struct VirtualList<T> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    /// The realized children, in order, starting with the item at `first`.
+    children: VecDeque<WidgetPod<T, Box<dyn Widget<T>>>>,
+    first: usize,
+    /// Children that are not in use, kept around to avoid rebuilding widgets while scrolling.
+    pool: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    axis: Axis,
+    spacing: KeyOrValue<f64>,
+    row_size: RowSize,
+    overscan: f64,
+    data_len: usize,
+    /// The measured size of each row, only used with [`RowSize::Estimated`].
+    measured: Vec<Option<f64>>,
+    /// The start of each row along the axis, plus the end of the last one.
+    /// Only used with [`RowSize::Estimated`].
+    offsets: Vec<f64>,
+    /// The visible part of the list, in its own coordinate space.
+    viewport: Rect,
+    window_size: Size,
+    old_bc: BoxConstraints,
+}
+
+impl<T: Data> VirtualList<T> {
+    /// Create a new virtual list widget. Closure will be called every time when a new child
+    /// needs to be constructed.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        VirtualList {
+            closure: Box::new(move || Box::new(closure())),
+            children: VecDeque::new(),
+            first: 0,
+            pool: Vec::new(),
+            axis: Axis::Vertical,
+            spacing: KeyOrValue::Concrete(0.),
+            row_size: RowSize::Estimated(DEFAULT_ROW_ESTIMATE),
+            overscan: DEFAULT_OVERSCAN,
+            data_len: 0,
+            measured: Vec::new(),
+            offsets: vec![0.],
+            viewport: Rect::ZERO,
+            window_size: Size::ZERO,
+            old_bc: BoxConstraints::tight(Size::ZERO),
+        }
+    }
+
+    /// Sets the widget to display the list horizontally, not vertically.
+    pub fn horizontal(mut self) -> Self {
+        self.axis = Axis::Horizontal;
+        self
+    }
+
+    /// Set the spacing between elements.
+    pub fn with_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Set the spacing between elements.
+    pub fn set_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) -> &mut Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Builder-style method to set how the size of the rows is determined.
+    ///
+    /// The default is [`RowSize::Estimated`] with an estimate of 24 pixels.
+    pub fn with_row_size(mut self, row_size: RowSize) -> Self {
+        self.row_size = row_size;
+        self
+    }
+
+    /// Builder-style method to set the distance past each edge of the viewport
+    /// in which rows are created ahead of time.
+    ///
+    /// Larger values make fast scrolling smoother at the cost of more widgets.
+    /// The default is 100 pixels.
+    pub fn with_overscan(mut self, overscan: f64) -> Self {
+        self.overscan = overscan.max(0.);
+        self
+    }
+
+    /// The range of items that currently have a widget.
+    fn realized_range(&self) -> Range<usize> {
+        self.first..self.first + self.children.len()
+    }
+
+    fn set_data_len(&mut self, len: usize, spacing: f64) {
+        self.data_len = len;
+        if let RowSize::Estimated(_) = self.row_size {
+            self.measured.resize(len, None);
+            self.update_offsets(spacing);
+        }
+    }
+
+    fn update_offsets(&mut self, spacing: f64) {
+        if let RowSize::Estimated(estimate) = self.row_size {
+            self.offsets.clear();
+            let mut pos = 0.;
+            for size in &self.measured {
+                self.offsets.push(pos);
+                pos += size.unwrap_or(estimate) + spacing;
+            }
+            self.offsets.push(pos);
+        }
+    }
+
+    /// The position of the start of the row at `index` along the list's axis.
+    fn row_start(&self, index: usize, spacing: f64) -> f64 {
+        match self.row_size {
+            RowSize::Fixed(size) => index as f64 * (size + spacing),
+            RowSize::Estimated(_) => self.offsets[index.min(self.offsets.len() - 1)],
+        }
+    }
+
+    /// The index of the row at position `pos` along the list's axis.
+    fn row_at(&self, pos: f64, spacing: f64) -> usize {
+        let index = match self.row_size {
+            RowSize::Fixed(size) => (pos / (size + spacing).max(1.)).floor().max(0.) as usize,
+            RowSize::Estimated(_) => self
+                .offsets
+                .partition_point(|start| *start <= pos)
+                .saturating_sub(1),
+        };
+        index.min(self.data_len)
+    }
+
+    /// The total size of all rows along the list's axis.
+    fn extent(&self, spacing: f64) -> f64 {
+        if self.data_len == 0 {
+            0.
+        } else {
+            self.row_start(self.data_len, spacing) - spacing
+        }
+    }
+
+    /// The range of items that should have a widget, given the current viewport.
+    fn wanted_range(&self, spacing: f64) -> Range<usize> {
+        // We are only told about our viewport when it moves, not when it grows,
+        // so always assume that at least a window's worth of rows may be visible.
+        let (start, end) = self.axis.major_span(self.viewport);
+        let end = end.max(start + self.axis.major(self.window_size));
+        let first = self.row_at(start - self.overscan, spacing);
+        let last = self.row_at(end + self.overscan, spacing) + 1;
+        first..last.min(self.data_len)
+    }
+
+    /// Create, recycle or reuse children so that exactly the items in `range` have a widget.
+    ///
+    /// Returns `true` if any children were added or removed, in which case
+    /// the caller should request `children_changed`.
+    fn realize(&mut self, range: Range<usize>) -> bool {
+        let old = self.realized_range();
+        if old == range {
+            return false;
+        }
+
+        // Retire the children that are no longer wanted, keeping the overlap.
+        let keep_start = old.start.max(range.start);
+        let keep_end = old.end.min(range.end).max(keep_start);
+        let mut retired = Vec::new();
+        while self.first + self.children.len() > keep_end && !self.children.is_empty() {
+            retired.extend(self.children.pop_back());
+        }
+        while self.first < keep_start && !self.children.is_empty() {
+            retired.extend(self.children.pop_front());
+            self.first += 1;
+        }
+        if self.children.is_empty() {
+            self.first = range.start;
+        }
+
+        let mut changed = !retired.is_empty();
+        while self.first > range.start {
+            let child = self.take_child(&mut retired);
+            self.children.push_front(child);
+            self.first -= 1;
+            changed = true;
+        }
+        while self.first + self.children.len() < range.end {
+            let child = self.take_child(&mut retired);
+            self.children.push_back(child);
+            changed = true;
+        }
+
+        self.pool.append(&mut retired);
+        // Don't hold on to more spare widgets than we would ever need at once.
+        self.pool.truncate(self.children.len().max(1));
+        trace!(
+            "Realized items {:?}, {} spare",
+            self.realized_range(),
+            self.pool.len()
+        );
+        changed
+    }
+
+    /// Realize the rows in the current viewport, and request the passes they need.
+    fn realize_visible(&mut self, ctx: &mut EventCtx, env: &Env) {
+        let spacing = self.spacing.resolve(env);
+        if self.realize(self.wanted_range(spacing)) {
+            ctx.children_changed();
+        }
+        // Reused children need to be told about their new data.
+        ctx.request_update();
+        ctx.request_layout();
+    }
+
+    fn take_child(
+        &mut self,
+        retired: &mut Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    ) -> WidgetPod<T, Box<dyn Widget<T>>> {
+        retired
+            .pop()
+            .or_else(|| self.pool.pop())
+            .unwrap_or_else(|| WidgetPod::new((self.closure)()))
+    }
+}
+
+impl<C: Data, T: ListIter<C>> Widget<T> for VirtualList<C> {
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::WindowSize(size) = event {
+            // A larger window may show more rows, even if the viewport didn't move.
+            self.window_size = *size;
+            self.realize_visible(ctx, env);
+        }
+        if let Event::Command(cmd) = event {
+            if cmd.is(REALIZE_VISIBLE) {
+                ctx.set_handled();
+                self.realize_visible(ctx, env);
+                return;
+            }
+        }
+
+        // Children that were just created haven't received `WidgetAdded` yet.
+        let mut children = self.children.iter_mut();
+        data.for_each_mut_in(self.first..self.first + children.len(), |child_data, _| {
+            if let Some(child) = children.next() {
+                if child.is_initialized() {
+                    child.event(ctx, event, child_data, env);
+                }
+            }
+        });
+    }
+
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                let spacing = self.spacing.resolve(env);
+                self.window_size = ctx.window().get_size();
+                self.set_data_len(data.data_len(), spacing);
+                if self.realize(self.wanted_range(spacing)) {
+                    ctx.children_changed();
+                }
+            }
+            LifeCycle::ViewContextChanged(view_context) => {
+                self.viewport = view_context.clip;
+                if self.wanted_range(self.spacing.resolve(env)) != self.realized_range() {
+                    ctx.submit_command(REALIZE_VISIBLE.to(ctx.widget_id()));
+                }
+            }
+            _ => (),
+        }
+
+        let mut children = self.children.iter_mut();
+        data.for_each_in(self.first..self.first + children.len(), |child_data, _| {
+            if let Some(child) = children.next() {
+                child.lifecycle(ctx, event, child_data, env);
+            }
+        });
+    }
+
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let spacing = self.spacing.resolve(env);
+        if data.data_len() != self.data_len {
+            self.set_data_len(data.data_len(), spacing);
+            if self.realize(self.wanted_range(spacing)) {
+                ctx.children_changed();
+            }
+            ctx.request_layout();
+        }
+
+        // Children that were just created haven't received `WidgetAdded` yet,
+        // and will get their data when they do.
+        let mut children = self.children.iter_mut();
+        data.for_each_in(self.first..self.first + children.len(), |child_data, _| {
+            if let Some(child) = children.next() {
+                if child.is_initialized() {
+                    child.update(ctx, child_data, env);
+                }
+            }
+        });
+
+        if ctx.env_key_changed(&self.spacing) {
+            self.update_offsets(spacing);
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "VirtualList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let axis = self.axis;
+        let spacing = self.spacing.resolve(env);
+        let mut minor = axis.minor(bc.min());
+        let mut paint_rect = Rect::ZERO;
+
+        let bc_changed = self.old_bc != *bc;
+        self.old_bc = *bc;
+
+        let child_bc = match self.row_size {
+            RowSize::Fixed(size) => axis.constraints(bc, size, size),
+            RowSize::Estimated(_) => axis.constraints(bc, 0., f64::INFINITY),
+        };
+
+        let range = self.realized_range();
+        let mut measured_changed = false;
+        let mut children = self.children.iter_mut();
+        let measured = &mut self.measured;
+        let row_size = self.row_size;
+        data.for_each_in(range.clone(), |child_data, i| {
+            let child = match children.next() {
+                Some(child) => child,
+                None => {
+                    return;
+                }
+            };
+
+            let child_size = if bc_changed || child.layout_requested() {
+                child.layout(ctx, &child_bc, child_data, env)
+            } else {
+                child.layout_rect().size()
+            };
+            minor = minor.max(axis.minor(child_size));
+
+            if let RowSize::Estimated(_) = row_size {
+                let size = Some(axis.major(child_size));
+                if measured[i] != size {
+                    measured[i] = size;
+                    measured_changed = true;
+                }
+            }
+        });
+
+        if measured_changed {
+            self.update_offsets(spacing);
+        }
+
+        let starts: Vec<f64> = range.map(|i| self.row_start(i, spacing)).collect();
+        for (child, start) in self.children.iter_mut().zip(starts) {
+            let child_pos: Point = axis.pack(start, 0.).into();
+            child.set_origin(ctx, child_pos);
+            paint_rect = paint_rect.union(child.paint_rect());
+        }
+
+        // Measuring rows may have moved other rows into or out of view.
+        if self.wanted_range(spacing) != self.realized_range() {
+            ctx.submit_command(REALIZE_VISIBLE.to(ctx.widget_id()));
+        }
+
+        let major = self.extent(spacing);
+        let my_size = bc.constrain(Size::from(axis.pack(major, minor)));
+        let insets = paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "VirtualList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let mut children = self.children.iter_mut();
+        data.for_each_in(self.first..self.first + children.len(), |child_data, _| {
+            if let Some(child) = children.next() {
+                child.paint(ctx, child_data, env);
+            }
+        });
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = self.children.iter();
+        let mut children_state = Vec::with_capacity(self.children.len());
+        data.for_each_in(self.realized_range(), |child_data, _| {
+            if let Some(child) = children.next() {
+                children_state.push(child.widget().debug_state(child_data));
+            }
+        });
+
+        DebugState {
+            display_name: "VirtualList".to_string(),
+            main_value: format!("{:?}", self.realized_range()),
+            children: children_state,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::tests::query::WidgetQuery;
+    use crate::widget::{Label, Scroll, WidgetExt};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[test]
+    fn only_visible_rows_are_built() {
+        let data: Arc<Vec<u32>> = Arc::new((0..100_000).collect());
+        let list = VirtualList::new(|| Label::dynamic(|item: &u32, _| item.to_string()))
+            .with_row_size(RowSize::Fixed(20.))
+            .with_overscan(0.);
+        let widget = Scroll::new(list).vertical();
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(200., 200.));
+            harness.send_initial_events();
+            harness.just_layout();
+
+            let state = harness.get_root_debug_state();
+            let list = &state.children[0].children[0];
+            assert_eq!(list.main_value, format!("{:?}", 0..11));
+            assert_eq!(list.children[3].main_value, "3");
+        });
+    }
+
+    #[test]
+    fn estimated_rows_are_measured() {
+        let data: Arc<Vec<u32>> = Arc::new((0..1000).collect());
+        let list =
+            VirtualList::new(|| Label::dynamic(|item: &u32, _| item.to_string()).fix_height(40.))
+                .with_row_size(RowSize::Estimated(20.))
+                .with_overscan(0.);
+        let widget = Scroll::new(list).vertical();
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(200., 200.));
+            harness.send_initial_events();
+            // The first layout measures the rows, which are larger than the
+            // estimate, so fewer of them fit in the window.
+            harness.run_requested_passes();
+
+            let list = harness.assert_one(&WidgetQuery::new().type_name("VirtualList"));
+            assert_eq!(list.state.main_value, format!("{:?}", 0..6));
+            // The eleven rows built before measuring keep their size, the
+            // rest still use the estimate.
+            assert_eq!(list.layout_rect.height(), 11. * 40. + 989. * 20.);
+
+            let row = harness.assert_one(&WidgetQuery::new().label("5"));
+            assert_eq!(row.layout_rect.y0, 200.);
+            assert_eq!(row.layout_rect.height(), 40.);
+        });
+    }
+
+    #[test]
+    fn rows_are_recycled_while_scrolling() {
+        let built = Rc::new(Cell::new(0));
+        let built_in_closure = built.clone();
+        let data: Arc<Vec<u32>> = Arc::new((0..1000).collect());
+        let list = VirtualList::new(move || {
+            built_in_closure.set(built_in_closure.get() + 1);
+            Label::dynamic(|item: &u32, _| item.to_string())
+        })
+        .with_row_size(RowSize::Fixed(20.))
+        .with_overscan(0.);
+        let widget = Scroll::new(list).vertical();
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(200., 200.));
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(built.get(), 11);
+
+            harness.scroll((100., 100.), (0., 400.));
+            let state = harness.get_root_debug_state();
+            let list = &state.children[0].children[0];
+            assert_eq!(list.main_value, format!("{:?}", 20..31));
+            assert_eq!(list.children[0].main_value, "20");
+            assert_eq!(list.children[10].main_value, "30");
+            assert_eq!(built.get(), 11);
+
+            // Scrolling partly back keeps the rows that are still visible.
+            harness.scroll((100., 100.), (0., -100.));
+            let state = harness.get_root_debug_state();
+            let list = &state.children[0].children[0];
+            assert_eq!(list.main_value, format!("{:?}", 15..26));
+            assert_eq!(list.children[0].main_value, "15");
+            assert_eq!(built.get(), 11);
+        });
+    }
+
+    #[test]
+    fn retired_rows_leave_the_focus_chain() {
+        let data: Arc<Vec<u32>> = Arc::new((0..1000).collect());
+        let list = VirtualList::new(|| {
+            ModularWidget::new(()).lifecycle_fn(|_, ctx, event, _: &u32, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+        })
+        .with_row_size(RowSize::Fixed(20.))
+        .with_overscan(0.);
+        let widget = Scroll::new(list).vertical();
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.set_initial_size(Size::new(200., 200.));
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.window().focus_chain().len(), 11);
+
+            // A smaller window only retires rows, without adding any.
+            harness.event(Event::WindowSize(Size::new(200., 100.)));
+            harness.run_requested_passes();
+            let list = harness.assert_one(&WidgetQuery::new().type_name("VirtualList"));
+            assert_eq!(list.state.main_value, format!("{:?}", 0..6));
+            assert_eq!(harness.window().focus_chain().len(), 6);
+        });
+    }
+}
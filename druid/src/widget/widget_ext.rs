@@ -7,12 +7,14 @@ use super::invalidation::DebugInvalidation;
 #[allow(deprecated)]
 use super::Parse;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, SizedBox, WidgetId,
+    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, DebugTag,
+    DragSource, DropTarget, EnvScope, Gestures, IdentityWrapper, LabelText, LensWrap, Padding,
+    SizedBox, TabIndex, Tooltip, WidgetId,
 };
 use crate::widget::{DisabledIf, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    Color, Data, Env, EventCtx, Gesture, GestureRecognizer, Insets, KeyOrValue, LayoutDirection,
+    Lens, LifeCycleCtx, Selector, UnitPoint, Widget,
 };
 
 /// A trait that provides extra methods for combining `Widget`s.
@@ -131,6 +133,21 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         EnvScope::new(f, self)
     }
 
+    /// Lay out this widget and its descendants in the given direction,
+    /// instead of the direction of the current locale.
+    ///
+    /// This sets [`Env::LAYOUT_DIRECTION`]; use it with
+    /// [`LayoutDirection::LeftToRight`] for content that should not be
+    /// mirrored in right-to-left locales, such as a media timeline.
+    ///
+    /// [`LayoutDirection::LeftToRight`]: crate::LayoutDirection::LeftToRight
+    fn layout_direction(self, direction: LayoutDirection) -> EnvScope<T, Self> {
+        EnvScope::new(
+            move |env, _| env.set(Env::LAYOUT_DIRECTION, direction),
+            self,
+        )
+    }
+
     /// Wrap this widget with the provided [`Controller`].
     fn controller<C: Controller<T, Self>>(self, controller: C) -> ControllerHost<Self, C> {
         ControllerHost::new(self, controller)
@@ -153,7 +170,8 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
 
     /// Control the events of this widget with a [`Click`] widget. The closure
     /// provided will be called when the widget is clicked with the left mouse
-    /// button.
+    /// button. Keys don't click the widget, unless the [`Click`] is created
+    /// with [`Click::with_keyboard_activation`].
     ///
     /// The child widget will also be updated on [`LifeCycle::HotChanged`] and
     /// mouse down, which can be useful for painting based on `ctx.is_active()`
@@ -167,6 +185,38 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Control the events of this widget with a [`Gestures`] widget. The
+    /// closure provided will be called for each gesture that `recognizer`
+    /// recognizes in the touch and pen input.
+    ///
+    /// ```no_run
+    /// use druid::widget::{Label, WidgetExt};
+    /// use druid::{Gesture, GestureRecognizer};
+    ///
+    /// let label = Label::new("Pinch me").on_gesture(
+    ///     GestureRecognizer::new().pinch(),
+    ///     |_ctx, gesture, zoom: &mut f64, _env| {
+    ///         if let Gesture::Pinch { scale, .. } = gesture {
+    ///             *zoom = *scale;
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    fn on_gesture(
+        self,
+        recognizer: GestureRecognizer,
+        f: impl Fn(&mut EventCtx, &Gesture, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, Gestures<T>> {
+        ControllerHost::new(self, Gestures::new(recognizer, f))
+    }
+
+    /// Show a tooltip with the given text when the mouse rests on this widget.
+    ///
+    /// See [`Tooltip`] for details.
+    fn tooltip(self, text: impl Into<LabelText<T>>) -> ControllerHost<Self, Tooltip<T>> {
+        ControllerHost::new(self, Tooltip::new(text))
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
     /// [`layout`]: Widget::layout
@@ -191,6 +241,13 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         DebugInvalidation::new(self)
     }
 
+    /// Tag this widget, so that tests can find it by the tag.
+    ///
+    /// The tag has no effect otherwise; see [`DebugTag`] for details.
+    fn debug_tag(self, tag: impl Into<String>) -> DebugTag<Self> {
+        DebugTag::new(self, tag)
+    }
+
     /// Set the [`DEBUG_WIDGET`] env variable for this widget (and its descendants).
     ///
     /// This does nothing by default, but you can use this variable while
@@ -238,6 +295,35 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         Scroll::new(self)
     }
 
+    /// Set the position of this widget's focusable descendants in the tab order.
+    ///
+    /// Widgets with a lower index are focused first; see [`TabIndex`] for details.
+    fn tab_index(self, index: u32) -> TabIndex<Self> {
+        TabIndex::new(self, index)
+    }
+
+    /// Let this widget be dragged, carrying the payload returned by `payload`.
+    ///
+    /// See [`DragSource`] for details.
+    fn drag_source<P: 'static>(
+        self,
+        selector: Selector<P>,
+        payload: impl Fn(&T, &Env) -> P + 'static,
+    ) -> DragSource<T, P, Self> {
+        DragSource::new(self, selector, payload)
+    }
+
+    /// Accept payloads identified by `selector` that are dropped on this widget.
+    ///
+    /// See [`DropTarget`] for details.
+    fn drop_target<P: 'static>(
+        self,
+        selector: Selector<P>,
+        on_drop: impl Fn(&mut EventCtx, &P, &mut T, &Env) + 'static,
+    ) -> DropTarget<T, P, Self> {
+        DropTarget::new(self, selector, on_drop)
+    }
+
     /// Wrap this widget in a [`DisabledIf`] widget.
     ///
     /// The provided closure will determine if the widget is disabled.
@@ -0,0 +1,407 @@
+// Copyright 2024 the Druid Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that arranges its children in lines, wrapping when a line is full.
+
+use std::ops::Range;
+
+use crate::debug_state::DebugState;
+use crate::widget::common::mirror_children;
+use crate::widget::flex::Spacing;
+use crate::widget::prelude::*;
+use crate::widget::{Axis, CrossAxisAlignment, MainAxisAlignment};
+use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+use tracing::{instrument, trace, warn};
+
+/// A container that lays out its children in a row or column, starting a new
+/// *run* whenever the next child does not fit on the main axis.
+///
+/// Where a [`Flex`] row overflows when its children are too wide, a `Wrap`
+/// row moves the children that do not fit onto a new row below, which makes
+/// it a good fit for lists of tags or toolbars. Children are always laid out
+/// at their own size; there are no flex children.
+///
+/// # Options
+///
+/// - [`spacing`] is the space between neighbouring children in a run, and
+///   [`run_spacing`] the space between runs.
+///
+/// - [`MainAxisAlignment`] determines how the children of each run are
+///   positioned on the main axis, when the run is shorter than the container.
+///   The default is `MainAxisAlignment::Start`.
+///
+/// - [`CrossAxisAlignment`] determines how children are positioned on the
+///   cross axis within their run, which is as large as its largest child.
+///   In a row, `CrossAxisAlignment::Baseline` lines up the baselines of the
+///   children in each run. The default is `CrossAxisAlignment::Start`.
+///
+/// - [`must_fill_main_axis`] determines whether the container is as large as
+///   its constraints allow on the main axis, or only as large as its longest
+///   run.
+///
+/// In a right-to-left [`LayoutDirection`], the layout is mirrored, so the runs
+/// of a row start on the right.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, CrossAxisAlignment, MainAxisAlignment, Wrap};
+///
+/// let tags = Wrap::row()
+///     .spacing(4.0)
+///     .run_spacing(4.0)
+///     .main_axis_alignment(MainAxisAlignment::Center)
+///     .cross_axis_alignment(CrossAxisAlignment::Baseline)
+///     .with_child(Button::<()>::new("rust"))
+///     .with_child(Button::new("gui"))
+///     .with_child(Button::new("layout"));
+/// ```
+///
+/// [`Flex`]: super::Flex
+/// [`LayoutDirection`]: crate::LayoutDirection
+/// [`spacing`]: Wrap::spacing
+/// [`run_spacing`]: Wrap::run_spacing
+/// [`must_fill_main_axis`]: Wrap::must_fill_main_axis
+pub struct Wrap<T> {
+    direction: Axis,
+    main_alignment: MainAxisAlignment,
+    cross_alignment: CrossAxisAlignment,
+    fill_major_axis: bool,
+    spacing: KeyOrValue<f64>,
+    run_spacing: KeyOrValue<f64>,
+    children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+}
+
+/// A line of children.
+struct Run {
+    children: Range<usize>,
+    major: f64,
+    minor: f64,
+    above_baseline: f64,
+    below_baseline: f64,
+}
+
+impl<T: Data> Wrap<T> {
+    /// Create a new `Wrap` oriented along the provided axis.
+    pub fn for_axis(axis: Axis) -> Self {
+        Wrap {
+            direction: axis,
+            main_alignment: MainAxisAlignment::Start,
+            cross_alignment: CrossAxisAlignment::Start,
+            fill_major_axis: false,
+            spacing: 0.0.into(),
+            run_spacing: 0.0.into(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a new horizontal `Wrap`, whose runs are stacked vertically.
+    pub fn row() -> Self {
+        Self::for_axis(Axis::Horizontal)
+    }
+
+    /// Create a new vertical `Wrap`, whose runs are stacked horizontally.
+    pub fn column() -> Self {
+        Self::for_axis(Axis::Vertical)
+    }
+
+    /// Builder-style method for specifying the childrens' [`MainAxisAlignment`]
+    /// within their run.
+    pub fn main_axis_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.main_alignment = alignment;
+        self
+    }
+
+    /// Builder-style method for specifying the childrens' [`CrossAxisAlignment`]
+    /// within their run.
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_alignment = alignment;
+        self
+    }
+
+    /// Builder-style method for setting whether the container must expand
+    /// to fill the available space on its main axis.
+    pub fn must_fill_main_axis(mut self, fill: bool) -> Self {
+        self.fill_major_axis = fill;
+        self
+    }
+
+    /// Builder-style method to set the space between children in a run.
+    pub fn spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Builder-style method to set the space between runs.
+    pub fn run_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.run_spacing = spacing.into();
+        self
+    }
+
+    /// Builder-style variant of [`add_child`](Wrap::add_child).
+    pub fn with_child(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.add_child(child);
+        self
+    }
+
+    /// Set the childrens' [`MainAxisAlignment`] within their run.
+    pub fn set_main_axis_alignment(&mut self, alignment: MainAxisAlignment) {
+        self.main_alignment = alignment;
+    }
+
+    /// Set the childrens' [`CrossAxisAlignment`] within their run.
+    pub fn set_cross_axis_alignment(&mut self, alignment: CrossAxisAlignment) {
+        self.cross_alignment = alignment;
+    }
+
+    /// Set whether the container must expand to fill the available space on
+    /// its main axis.
+    pub fn set_must_fill_main_axis(&mut self, fill: bool) {
+        self.fill_major_axis = fill;
+    }
+
+    /// Set the space between children in a run.
+    pub fn set_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.spacing = spacing.into();
+    }
+
+    /// Set the space between runs.
+    pub fn set_run_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.run_spacing = spacing.into();
+    }
+
+    /// Add a child widget.
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static) {
+        self.children.push(WidgetPod::new(Box::new(child)));
+    }
+
+    fn resolve_spacing(spacing: &KeyOrValue<f64>, env: &Env) -> f64 {
+        let spacing = spacing.resolve(env);
+        if spacing < 0.0 {
+            warn!("Wrap spacing was less than 0");
+        }
+        spacing.max(0.0)
+    }
+}
+
+impl Run {
+    fn new(start: usize) -> Self {
+        Run {
+            children: start..start,
+            major: 0.0,
+            minor: 0.0,
+            above_baseline: 0.0,
+            below_baseline: 0.0,
+        }
+    }
+
+    /// Returns `true` if a child of size `major` would not fit in this run.
+    fn is_full(&self, major: f64, spacing: f64, max_major: f64) -> bool {
+        !self.children.is_empty() && self.major + spacing + major > max_major
+    }
+
+    fn push(&mut self, major: f64, spacing: f64) {
+        if !self.children.is_empty() {
+            self.major += spacing;
+        }
+        self.major += major;
+        self.children.end += 1;
+    }
+}
+
+impl<T: Data> Widget<T> for Wrap<T> {
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.update(ctx, data, env);
+        }
+        if ctx.env_key_changed(&self.spacing)
+            || ctx.env_key_changed(&self.run_spacing)
+            || ctx.env_key_changed(&Env::LAYOUT_DIRECTION)
+        {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Wrap");
+        let direction = self.direction;
+        let spacing = Self::resolve_spacing(&self.spacing, env);
+        let run_spacing = Self::resolve_spacing(&self.run_spacing, env);
+        let use_baseline =
+            direction == Axis::Horizontal && self.cross_alignment == CrossAxisAlignment::Baseline;
+
+        // Measure the children, and break them into runs.
+        let max_major = direction.major(bc.max());
+        let child_bc = direction.constraints(&bc.loosen(), 0.0, max_major);
+        let mut runs = Vec::new();
+        let mut run = Run::new(0);
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let size = child.layout(ctx, &child_bc, data, env);
+            let major = direction.major(size);
+            if run.is_full(major, spacing, max_major) {
+                runs.push(std::mem::replace(&mut run, Run::new(i)));
+            }
+            run.push(major, spacing);
+            let baseline_offset = child.baseline_offset();
+            run.minor = run.minor.max(direction.minor(size));
+            run.above_baseline = run.above_baseline.max(size.height - baseline_offset);
+            run.below_baseline = run.below_baseline.max(baseline_offset);
+        }
+        if !run.children.is_empty() {
+            runs.push(run);
+        }
+        if use_baseline {
+            for run in &mut runs {
+                run.minor = run.above_baseline + run.below_baseline;
+            }
+        }
+
+        let longest_run = runs.iter().map(|run| run.major).fold(0.0, f64::max);
+        let major = if self.fill_major_axis && max_major.is_finite() {
+            max_major
+        } else {
+            longest_run.max(direction.major(bc.min()))
+        };
+        let minor = runs.iter().map(|run| run.minor).sum::<f64>()
+            + run_spacing * runs.len().saturating_sub(1) as f64;
+
+        // Position the children in their runs.
+        let mut run_start = 0.0;
+        let mut child_paint_rect = Rect::ZERO;
+        for run in &runs {
+            let extra = (major - run.major).max(0.0);
+            let mut space = Spacing::new(self.main_alignment, extra, run.children.len());
+            let mut child_major = space.next().unwrap_or(0.);
+            for child in &mut self.children[run.children.clone()] {
+                let child_size = child.layout_rect().size();
+                let child_minor = match self.cross_alignment {
+                    CrossAxisAlignment::Baseline if use_baseline => {
+                        let above_baseline = child_size.height - child.baseline_offset();
+                        run.above_baseline - above_baseline
+                    }
+                    CrossAxisAlignment::Fill => {
+                        let fill_size: Size = direction
+                            .pack(direction.major(child_size), run.minor)
+                            .into();
+                        if child_size != fill_size {
+                            child.layout(ctx, &BoxConstraints::tight(fill_size), data, env);
+                        }
+                        0.0
+                    }
+                    alignment => alignment.align(run.minor - direction.minor(child_size)),
+                };
+                let origin: Point = direction.pack(child_major, run_start + child_minor).into();
+                child.set_origin(ctx, origin);
+                child_paint_rect = child_paint_rect.union(child.paint_rect());
+                child_major += direction.major(child_size) + spacing;
+                child_major += space.next().unwrap_or(0.);
+            }
+            run_start += run.minor + run_spacing;
+        }
+
+        let my_size = bc.constrain(Size::from(direction.pack(major, minor)));
+
+        if let Some(paint_rect) = mirror_children(ctx, env, self.children.iter_mut(), my_size.width)
+        {
+            child_paint_rect = paint_rect;
+        }
+        ctx.set_paint_insets(child_paint_rect - my_size.to_rect());
+
+        // Our baseline is the lowest baseline in the last run.
+        let baseline = runs
+            .last()
+            .map(|run| {
+                self.children[run.children.clone()]
+                    .iter()
+                    .map(|child| child.layout_rect().max_y() - child.baseline_offset())
+                    .fold(0.0, f64::max)
+            })
+            .map_or(0.0, |baseline_y| my_size.height - baseline_y);
+        ctx.set_baseline_offset(baseline);
+        trace!(
+            "Computed layout: size={}, runs={}, baseline_offset={}",
+            my_size,
+            runs.len(),
+            baseline
+        );
+        my_size
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let direction = self.direction;
+        let spacing = Self::resolve_spacing(&self.spacing, env);
+        let child_bc = bc.unbound_max(axis);
+        if axis == direction {
+            // With unlimited space, all the children fit in a single run.
+            let total: f64 = self
+                .children
+                .iter_mut()
+                .map(|child| {
+                    child
+                        .widget_mut()
+                        .compute_max_intrinsic(axis, ctx, &child_bc, data, env)
+                })
+                .sum();
+            total + spacing * self.children.len().saturating_sub(1) as f64
+        } else {
+            let run_spacing = Self::resolve_spacing(&self.run_spacing, env);
+            let max_major = direction.major(bc.max());
+            let mut total = 0.0;
+            let mut run = Run::new(0);
+            for (i, child) in self.children.iter_mut().enumerate() {
+                let child = child.widget_mut();
+                let major = child.compute_max_intrinsic(direction, ctx, &child_bc, data, env);
+                let minor = child.compute_max_intrinsic(axis, ctx, &child_bc, data, env);
+                if run.is_full(major, spacing, max_major) {
+                    total += run.minor + run_spacing;
+                    run = Run::new(i);
+                }
+                run.push(major, spacing);
+                run.minor = run.minor.max(minor);
+            }
+            total + run.minor
+        }
+    }
+}
@@ -11,8 +11,8 @@ use std::rc::Rc;
 use crate::kurbo::Size;
 use crate::piet::Piet;
 use crate::shell::{
-    text::InputHandler, Application, FileDialogToken, FileInfo, IdleToken, MouseEvent, Region,
-    Scale, TextFieldToken, WinHandler, WindowHandle,
+    text::InputHandler, Application, DropEvent, FileDialogToken, FileInfo, IdleToken, MouseEvent,
+    PointerEvent, Region, Scale, TextFieldToken, WinHandler, WindowHandle,
 };
 
 use crate::app_delegate::{AppDelegate, DelegateCtx};
@@ -21,8 +21,8 @@ use crate::ext_event::{ExtEventHost, ExtEventSink};
 use crate::menu::{ContextMenu, MenuItemId, MenuManager};
 use crate::window::{ImeUpdateFn, Window};
 use crate::{
-    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError, Selector, Target,
-    TimerToken, WidgetId, WindowDesc, WindowId,
+    Command, Data, Env, Event, ExternalDrag, Handled, InternalEvent, KeyEvent, PlatformError,
+    Selector, Target, TimerToken, WidgetId, WindowDesc, WindowId,
 };
 
 use crate::app::{PendingWindow, WindowConfig};
@@ -256,7 +256,7 @@ impl<T: Data> InnerAppState<T> {
                 self.root_menu = win.menu.take();
                 // If there are even no pending windows, we quit the run loop.
                 if self.windows.count() == 0 {
-                    #[cfg(any(target_os = "windows", feature = "x11"))]
+                    #[cfg(any(target_os = "windows", feature = "x11", feature = "headless"))]
                     self.app.quit();
                 }
             }
@@ -997,6 +997,49 @@ impl<T: Data> WinHandler for DruidHandler<T> {
             .do_window_event(Event::Internal(InternalEvent::MouseLeave), self.window_id);
     }
 
+    fn pointer_down(&mut self, event: &PointerEvent) {
+        let event = Event::PointerDown(event.clone().into());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn pointer_move(&mut self, event: &PointerEvent) {
+        let event = Event::PointerMove(event.clone().into());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn pointer_up(&mut self, event: &PointerEvent) {
+        let event = Event::PointerUp(event.clone().into());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn pointer_cancel(&mut self, event: &PointerEvent) {
+        let event = Event::PointerCancel(event.clone().into());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn drag_enter(&mut self, event: &DropEvent) -> bool {
+        self.drag_over(event)
+    }
+
+    fn drag_over(&mut self, event: &DropEvent) -> bool {
+        let event = Event::ExternalDrag(ExternalDrag::Over(event.clone()));
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
+    fn drag_leave(&mut self) {
+        let event = Event::ExternalDrag(ExternalDrag::Leave);
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn drag_drop(&mut self, event: &DropEvent) -> bool {
+        let event = Event::ExternalDrag(ExternalDrag::Drop(event.clone()));
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
     fn key_down(&mut self, event: KeyEvent) -> bool {
         self.app_state
             .do_window_event(Event::KeyDown(event), self.window_id)
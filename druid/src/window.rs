@@ -5,6 +5,7 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::mem;
+use std::time::Duration;
 use tracing::{error, info, trace_span};
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
@@ -14,17 +15,22 @@ use crate::piet::{Color, Piet, RenderContext};
 use crate::shell::{text::InputHandler, Counter, Cursor, Region, TextFieldToken, WindowHandle};
 
 use crate::app::{PendingWindow, WindowSizePolicy};
+use crate::command::sys as sys_cmd;
 use crate::contexts::ContextState;
-use crate::core::{CommandQueue, FocusChange, WidgetState};
+use crate::core::{
+    sort_by_tab_index, CommandQueue, FocusChange, FocusDirection, FocusTrap, WidgetState,
+};
 use crate::debug_state::DebugState;
+use crate::drag::{DragSession, DRAG_CANCELLED};
+use crate::event::FocusRectsCell;
 use crate::menu::{MenuItemId, MenuManager};
 use crate::text::TextFieldRegistration;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, TimerToken,
-    UpdateCtx, ViewContext, Widget, WidgetId, WidgetPod,
+    Affine, BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
+    InternalLifeCycle, KbKey, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Rect,
+    Size, Target, TimerToken, UpdateCtx, ViewContext, Widget, WidgetId, WidgetPod,
 };
 
 pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
@@ -47,13 +53,38 @@ pub struct Window<T> {
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<Point>,
     pub(crate) focus: Option<WidgetId>,
+    /// The focus chain, ordered by tab index.
+    focus_chain: Vec<WidgetId>,
+    /// The open focus traps, innermost last, with the widget to focus when each closes.
+    focus_traps: Vec<(WidgetId, Option<WidgetId>)>,
     pub(crate) handle: WindowHandle,
-    pub(crate) timers: HashMap<TimerToken, WidgetId>,
+    pub(crate) timers: Timers,
     pub(crate) pending_text_registrations: Vec<TextFieldRegistration>,
     pub(crate) transparent: bool,
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    /// The drag in progress in this window, if any.
+    pub(crate) drag: Option<DragSession>,
+}
+
+/// The timers requested by the widgets of a window.
+#[derive(Debug, Default)]
+pub(crate) struct Timers {
+    /// The widget that requested each pending timer.
+    widgets: HashMap<TimerToken, WidgetId>,
+    /// The clock that keeps the timers instead of the platform, in tests.
+    virtual_clock: Option<VirtualClock>,
+}
+
+/// A clock that only moves forward when told to, so that tests can fire
+/// timers deterministically.
+#[derive(Debug, Default)]
+pub(crate) struct VirtualClock {
+    /// The time that has passed since the clock was started.
+    now: Duration,
+    /// The pending timers, with their deadlines.
+    deadlines: Vec<(Duration, TimerToken)>,
 }
 
 impl<T> Window<T> {
@@ -76,16 +107,85 @@ impl<T> Window<T> {
             last_anim: None,
             last_mouse_pos: None,
             focus: None,
+            focus_chain: Vec::new(),
+            focus_traps: Vec::new(),
             handle,
-            timers: HashMap::new(),
+            timers: Timers::default(),
             ext_handle,
             ime_handlers: Vec::new(),
             ime_focus_change: None,
             pending_text_registrations: Vec::new(),
+            drag: None,
         }
     }
 }
 
+impl Timers {
+    /// Request a timer for `widget_id`, returning its token.
+    pub(crate) fn request(
+        &mut self,
+        handle: &WindowHandle,
+        widget_id: WidgetId,
+        deadline: Duration,
+    ) -> TimerToken {
+        let token = match &mut self.virtual_clock {
+            Some(clock) => {
+                let token = TimerToken::next();
+                clock.deadlines.push((clock.now + deadline, token));
+                token
+            }
+            None => handle.request_timer(deadline),
+        };
+        self.widgets.insert(token, widget_id);
+        token
+    }
+
+    /// Remove a timer that fired, returning the widget that requested it.
+    pub(crate) fn remove(&mut self, token: TimerToken) -> Option<WidgetId> {
+        self.widgets.remove(&token)
+    }
+
+    /// Keep the timers requested from now on with a [`VirtualClock`], instead
+    /// of the platform, and return that clock.
+    // Only the test harness uses the virtual clock, and it isn't built for wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn virtual_clock(&mut self) -> &mut VirtualClock {
+        self.virtual_clock.get_or_insert_with(VirtualClock::default)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl VirtualClock {
+    /// The time that has passed since the clock was started.
+    pub(crate) fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// The deadline of the next timer, if any.
+    pub(crate) fn next_deadline(&self) -> Option<Duration> {
+        self.deadlines.iter().map(|(deadline, _)| *deadline).min()
+    }
+
+    /// Move the clock to the deadline of the next timer that is due at `time`,
+    /// and remove and return that timer.
+    pub(crate) fn pop_due(&mut self, time: Duration) -> Option<TimerToken> {
+        let (idx, &(deadline, token)) = self
+            .deadlines
+            .iter()
+            .enumerate()
+            .filter(|(_, (deadline, _))| *deadline <= time)
+            .min_by_key(|(_, (deadline, _))| *deadline)?;
+        self.deadlines.remove(idx);
+        self.now = self.now.max(deadline);
+        Some(token)
+    }
+
+    /// Move the clock forward to `time`.
+    pub(crate) fn set_now(&mut self, time: Duration) {
+        self.now = self.now.max(time);
+    }
+}
+
 impl<T: Data> Window<T> {
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     pub(crate) fn wants_animation_frame(&self) -> bool {
@@ -93,7 +193,35 @@ impl<T: Data> Window<T> {
     }
 
     pub(crate) fn focus_chain(&self) -> &[WidgetId] {
-        &self.root.state().focus_chain
+        &self.focus_chain
+    }
+
+    /// The part of the focus chain that focus can currently move in: the members
+    /// of the innermost open focus trap, or the whole chain.
+    fn active_focus_chain(&self) -> &[WidgetId] {
+        self.active_focus_trap()
+            .map(|trap| trap.members.as_slice())
+            .unwrap_or(&self.focus_chain)
+    }
+
+    /// The innermost open focus trap, if any.
+    fn active_focus_trap(&self) -> Option<&FocusTrap> {
+        self.focus_traps.last().and_then(|(scope, _)| {
+            self.root
+                .state()
+                .focus_traps
+                .iter()
+                .find(|trap| trap.scope == *scope)
+        })
+    }
+
+    /// Returns `true` if `widget` may be given focus while the innermost focus
+    /// trap is open, which is when it is inside the trap's scope.
+    fn is_in_focus_trap(&self, widget: WidgetId) -> bool {
+        match self.active_focus_trap() {
+            Some(trap) => trap.descendants.may_contain(&widget),
+            None => true,
+        }
     }
 
     /// Returns `true` if the provided widget may be in this window,
@@ -193,6 +321,7 @@ impl<T: Data> Window<T> {
         if widget_state.update_focus_chain {
             let event = LifeCycle::BuildFocusChain;
             self.lifecycle(queue, &event, data, env, false);
+            self.update_focus_traps(widget_state);
         }
 
         self.update_focus(widget_state, queue, data, env);
@@ -238,7 +367,7 @@ impl<T: Data> Window<T> {
 
         let event = match event {
             Event::Timer(token) => {
-                if let Some(widget_id) = self.timers.remove(&token) {
+                if let Some(widget_id) = self.timers.remove(token) {
                     Event::Internal(InternalEvent::RouteTimer(token, widget_id))
                 } else {
                     error!("No widget found for timer {:?}", token);
@@ -258,6 +387,16 @@ impl<T: Data> Window<T> {
             );
         }
 
+        if let (Event::KeyDown(key), Some(drag)) = (&event, &self.drag) {
+            if key.key == KbKey::Escape {
+                self.invalid.add_rect(drag.preview_rect());
+                queue.push_back(DRAG_CANCELLED.to(drag.source));
+                self.drag = None;
+                return Handled::Yes;
+            }
+        }
+        let old_preview = self.drag.as_ref().map(DragSession::preview_rect);
+
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
         let is_handled = {
             let mut state = ContextState::new::<T>(
@@ -268,6 +407,7 @@ impl<T: Data> Window<T> {
                 self.focus,
                 &mut self.timers,
                 &mut self.pending_text_registrations,
+                &mut self.drag,
             );
             let mut notifications = VecDeque::new();
             let mut ctx = EventCtx {
@@ -297,6 +437,28 @@ impl<T: Data> Window<T> {
             Handled::from(ctx.is_handled)
         };
 
+        // The drag preview follows the mouse, and the drag ends when the button is released.
+        if let Some(rect) = old_preview {
+            self.invalid.add_rect(rect);
+        }
+        if let Some(drag) = &mut self.drag {
+            if let Some(pos) = self.last_mouse_pos {
+                drag.mouse_pos = pos;
+            }
+            self.invalid.add_rect(drag.preview_rect());
+            if matches!(event, Event::MouseUp(_)) {
+                self.drag = None;
+            }
+        }
+
+        if let Event::Internal(InternalEvent::TargetedCommand(cmd)) = &event {
+            if let Some(direction) = cmd.get(sys_cmd::MOVE_FOCUS) {
+                if cmd.target() == Target::Window(self.id) && !is_handled.is_handled() {
+                    widget_state.request_focus = Some(FocusChange::Direction(*direction));
+                }
+            }
+        }
+
         if let Some(cursor) = &widget_state.cursor {
             self.handle.set_cursor(cursor);
         } else if matches!(
@@ -337,6 +499,7 @@ impl<T: Data> Window<T> {
             self.focus,
             &mut self.timers,
             &mut self.pending_text_registrations,
+            &mut self.drag,
         );
         let mut ctx = LifeCycleCtx {
             state: &mut state,
@@ -364,6 +527,7 @@ impl<T: Data> Window<T> {
             self.focus,
             &mut self.timers,
             &mut self.pending_text_registrations,
+            &mut self.drag,
         );
         let mut update_ctx = UpdateCtx {
             widget_state: &mut widget_state,
@@ -457,6 +621,7 @@ impl<T: Data> Window<T> {
             self.focus,
             &mut self.timers,
             &mut self.pending_text_registrations,
+            &mut self.drag,
         );
         let mut layout_ctx = LayoutCtx {
             state: &mut state,
@@ -500,6 +665,10 @@ impl<T: Data> Window<T> {
         data: &T,
         env: &Env,
     ) {
+        let drag_preview = self
+            .drag
+            .as_ref()
+            .map(|drag| (drag.preview.clone(), drag.mouse_pos));
         let widget_state = WidgetState::new(self.root.id(), Some(self.size));
         let mut state = ContextState::new::<T>(
             queue,
@@ -509,6 +678,7 @@ impl<T: Data> Window<T> {
             self.focus,
             &mut self.timers,
             &mut self.pending_text_registrations,
+            &mut self.drag,
         );
         let mut ctx = PaintCtx {
             render_ctx: piet,
@@ -536,6 +706,15 @@ impl<T: Data> Window<T> {
             });
         }
 
+        if let Some((paint_preview, pos)) = drag_preview {
+            ctx.with_child_ctx(invalid.clone(), |ctx| {
+                ctx.with_save(|ctx| {
+                    ctx.render_ctx.transform(Affine::translate(pos.to_vec2()));
+                    paint_preview(ctx);
+                });
+            });
+        }
+
         if self.wants_animation_frame() {
             self.handle.request_anim_frame();
         }
@@ -586,7 +765,7 @@ impl<T: Data> Window<T> {
     ) {
         if let Some(focus_req) = widget_state.request_focus.take() {
             let old = self.focus;
-            let new = self.widget_for_focus_request(focus_req);
+            let new = self.widget_for_focus_request(focus_req, queue, data, env);
             // Only send RouteFocusChanged in case there's actual change
             if old != new {
                 let event = LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { old, new });
@@ -648,42 +827,161 @@ impl<T: Data> Window<T> {
             .and_then(|(_, reg)| reg.document.release().then_some(reg.widget_id))
     }
 
-    fn widget_for_focus_request(&self, focus: FocusChange) -> Option<WidgetId> {
+    fn widget_for_focus_request(
+        &mut self,
+        focus: FocusChange,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> Option<WidgetId> {
         match focus {
             FocusChange::Resign => None,
-            FocusChange::Focus(id) => Some(id),
+            // While a focus trap is open, focus can't be moved out of it.
+            FocusChange::Focus(id) if self.is_in_focus_trap(id) => Some(id),
+            FocusChange::Focus(_) => self.focus,
             FocusChange::Next => self.widget_from_focus_chain(true),
             FocusChange::Previous => self.widget_from_focus_chain(false),
+            FocusChange::Direction(direction) => {
+                self.widget_in_direction(direction, queue, data, env)
+            }
         }
     }
 
     fn widget_from_focus_chain(&self, forward: bool) -> Option<WidgetId> {
+        let chain = self.active_focus_chain();
         self.focus.and_then(|focus| {
-            self.focus_chain()
+            chain
                 .iter()
                 // Find where the focused widget is in the focus chain
                 .position(|id| id == &focus)
                 .map(|idx| {
                     // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
+                    let len = chain.len();
                     let new_idx = if forward {
                         (idx + 1) % len
                     } else {
                         (idx + len - 1) % len
                     };
-                    self.focus_chain()[new_idx]
+                    chain[new_idx]
                 })
                 .or_else(|| {
                     // If the currently focused widget isn't in the focus chain,
                     // then we'll just return the first/last entry of the chain, if any.
                     if forward {
-                        self.focus_chain().first().copied()
+                        chain.first().copied()
                     } else {
-                        self.focus_chain().last().copied()
+                        chain.last().copied()
                     }
                 })
         })
     }
+
+    /// Find the focusable widget nearest to the focused one in `direction`.
+    ///
+    /// Returns the focused widget if there is no candidate in that direction.
+    fn widget_in_direction(
+        &mut self,
+        direction: FocusDirection,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> Option<WidgetId> {
+        let focus = self.focus?;
+        let rects = FocusRectsCell::default();
+        let event = LifeCycle::Internal(InternalLifeCycle::CollectFocusRects(rects.clone()));
+        self.lifecycle(queue, &event, data, env, false);
+        let rects = rects.take();
+
+        let rect_of = |widget: WidgetId| {
+            rects
+                .iter()
+                .find(|(id, _)| *id == widget)
+                .map(|(_, rect)| *rect)
+        };
+        let from = match rect_of(focus) {
+            Some(rect) => rect,
+            None => return Some(focus),
+        };
+        let nearest = self
+            .active_focus_chain()
+            .iter()
+            .filter(|id| **id != focus)
+            .filter_map(|id| {
+                let distance = focus_distance(direction, from, rect_of(*id)?)?;
+                Some((*id, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id);
+        nearest.or(Some(focus))
+    }
+
+    /// Sync the ordered focus chain and the open focus traps with a rebuilt focus chain.
+    ///
+    /// Newly opened traps take focus, and closed ones give it back to the widget that
+    /// was focused when they opened.
+    fn update_focus_traps(&mut self, widget_state: &mut WidgetState) {
+        let root = self.root.state();
+        let mut chain = root.focus_chain.clone();
+        sort_by_tab_index(&mut chain, &root.tab_indices);
+        self.focus_chain = chain;
+
+        let is_open = |scope: &WidgetId| root.focus_traps.iter().any(|t| t.scope == *scope);
+        let mut restore = None;
+        while let Some((scope, focus)) = self.focus_traps.last().copied() {
+            if is_open(&scope) {
+                break;
+            }
+            self.focus_traps.pop();
+            restore = Some(focus);
+        }
+        self.focus_traps.retain(|(scope, _)| is_open(scope));
+
+        // Traps are listed innermost first, so open the outer ones first.
+        let mut opened = None;
+        for trap in root.focus_traps.iter().rev() {
+            if !self
+                .focus_traps
+                .iter()
+                .any(|(scope, _)| *scope == trap.scope)
+            {
+                self.focus_traps
+                    .push((trap.scope, restore.unwrap_or(self.focus)));
+                opened = Some(trap);
+            }
+        }
+
+        if let Some(trap) = opened {
+            let pending = match widget_state.request_focus {
+                Some(FocusChange::Focus(id)) => Some(id),
+                _ => self.focus,
+            };
+            if !pending.map_or(false, |id| trap.members.contains(&id)) {
+                widget_state.request_focus = trap.members.first().copied().map(FocusChange::Focus);
+            }
+        } else if let Some(restore) = restore {
+            if widget_state.request_focus.is_none() {
+                widget_state.request_focus = restore
+                    .filter(|id| self.focus_chain.contains(id))
+                    .map(FocusChange::Focus);
+            }
+        }
+    }
+}
+
+/// The distance from `from` to `to` when moving focus in `direction`, or `None` if
+/// `to` does not lie in that direction.
+///
+/// Offsets across the direction of movement weigh more than offsets along it, so that
+/// aligned widgets are preferred.
+fn focus_distance(direction: FocusDirection, from: Rect, to: Rect) -> Option<f64> {
+    let offset = to.center() - from.center();
+    let (along, across) = match direction {
+        FocusDirection::Up => (-offset.y, offset.x),
+        FocusDirection::Down => (offset.y, offset.x),
+        FocusDirection::Left => (-offset.x, offset.y),
+        FocusDirection::Right => (offset.x, offset.y),
+    };
+    (along > 0.0).then_some(along + 2.0 * across.abs())
 }
 
 impl WindowId {